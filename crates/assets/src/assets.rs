@@ -3,6 +3,7 @@ use anyhow::anyhow;
 
 use gpui::{AppContext, AssetSource, Result, SharedString};
 use rust_embed::RustEmbed;
+use std::path::Path;
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
@@ -17,9 +18,18 @@ pub struct Assets;
 
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<std::borrow::Cow<'static, [u8]>> {
-        Self::get(path)
-            .map(|f| f.data)
-            .ok_or_else(|| anyhow!("could not find asset at path \"{}\"", path))
+        if let Some(file) = Self::get(path) {
+            return Ok(file.data);
+        }
+
+        // Assets outside of Zed, like the icons of extensions, are loaded by their absolute paths.
+        if Path::new(path).is_absolute() {
+            return std::fs::read(path)
+                .map(Into::into)
+                .map_err(|err| anyhow!("could not load asset at path \"{}\": {}", path, err));
+        }
+
+        Err(anyhow!("could not find asset at path \"{}\"", path))
     }
 
     fn list(&self, path: &str) -> Result<Vec<SharedString>> {
@@ -88,7 +88,10 @@ impl CurrentProjectContext {
         ContextUpdated::Updating
     }
 
-    async fn build_message(fs: Arc<dyn Fs>, path_to_cargo_toml: &Path) -> Result<String> {
+    pub(crate) async fn build_message(
+        fs: Arc<dyn Fs>,
+        path_to_cargo_toml: &Path,
+    ) -> Result<String> {
         let buffer = fs.load(path_to_cargo_toml).await?;
         let cargo_toml: cargo_toml::Manifest = toml::from_str(&buffer)?;
 
@@ -146,7 +149,7 @@ impl CurrentProjectContext {
         Ok(message)
     }
 
-    fn path_to_cargo_toml(
+    pub(crate) fn path_to_cargo_toml(
         project: WeakModel<Project>,
         cx: &mut AsyncAppContext,
     ) -> Result<Option<PathBuf>> {
@@ -7,11 +7,13 @@ mod prompt_library;
 mod prompts;
 mod saved_conversation;
 mod search;
+mod slash_command;
 mod streaming_diff;
 
 use ambient_context::AmbientContextSnapshot;
 pub use assistant_panel::AssistantPanel;
 use assistant_settings::{AnthropicModel, AssistantSettings, OpenAiModel, ZedDotDevModel};
+use assistant_slash_command::SlashCommandRegistry;
 use client::{proto, Client};
 use command_palette_hooks::CommandPaletteFilter;
 pub(crate) use completion_provider::*;
@@ -37,7 +39,8 @@ actions!(
         InsertActivePrompt,
         ToggleIncludeConversation,
         ToggleHistory,
-        ApplyEdit
+        ApplyEdit,
+        ConfirmCommand
     ]
 );
 
@@ -236,6 +239,7 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     cx.set_global(Assistant::default());
     AssistantSettings::register(cx);
     completion_provider::init(client, cx);
+    register_slash_commands(cx);
     assistant_panel::init(cx);
 
     CommandPaletteFilter::update_global(cx, |filter, _cx| {
@@ -256,6 +260,12 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     .detach();
 }
 
+fn register_slash_commands(cx: &mut AppContext) {
+    let slash_command_registry = SlashCommandRegistry::default_global(cx);
+    slash_command_registry.register_command(slash_command::file_command::FileSlashCommand);
+    slash_command_registry.register_command(slash_command::project_command::ProjectSlashCommand);
+}
+
 #[cfg(test)]
 #[ctor::ctor]
 fn init_logger() {
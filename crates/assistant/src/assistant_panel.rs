@@ -5,12 +5,15 @@ use crate::{
     prompt_library::{PromptLibrary, PromptManager},
     prompts::generate_content_prompt,
     search::*,
-    ApplyEdit, Assist, CompletionProvider, CycleMessageRole, InlineAssist, InsertActivePrompt,
-    LanguageModel, LanguageModelRequest, LanguageModelRequestMessage, MessageId, MessageMetadata,
-    MessageStatus, QuoteSelection, ResetKey, Role, SavedConversation, SavedConversationMetadata,
-    SavedMessage, Split, ToggleFocus, ToggleHistory, ToggleIncludeConversation,
+    slash_command::{SlashCommandCompletionProvider, SlashCommandLine},
+    ApplyEdit, Assist, CompletionProvider, ConfirmCommand, CycleMessageRole, InlineAssist,
+    InsertActivePrompt, LanguageModel, LanguageModelRequest, LanguageModelRequestMessage,
+    MessageId, MessageMetadata, MessageStatus, QuoteSelection, ResetKey, Role, SavedConversation,
+    SavedConversationMetadata, SavedMessage, Split, ToggleFocus, ToggleHistory,
+    ToggleIncludeConversation,
 };
 use anyhow::{anyhow, Result};
+use assistant_slash_command::{SlashCommandOutputSection, SlashCommandRegistry};
 use client::telemetry::Telemetry;
 use collections::{hash_map, HashMap, HashSet, VecDeque};
 use editor::{
@@ -39,7 +42,7 @@ use language::{
 };
 use multi_buffer::MultiBufferRow;
 use parking_lot::Mutex;
-use project::{Project, ProjectTransaction};
+use project::{Project, ProjectLspAdapterDelegate, ProjectTransaction};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use settings::Settings;
 use std::{
@@ -2386,6 +2389,7 @@ struct ConversationEditor {
     conversation: Model<Conversation>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
+    slash_command_registry: Arc<SlashCommandRegistry>,
     editor: View<Editor>,
     blocks: HashSet<BlockId>,
     scroll_position: Option<ScrollPosition>,
@@ -2412,11 +2416,21 @@ impl ConversationEditor {
         workspace: View<Workspace>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
+        let slash_command_registry = SlashCommandRegistry::global(cx);
+        let buffer = conversation.read(cx).buffer.clone();
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_completion_triggers(vec!["/".into()], cx)
+        });
+
         let editor = cx.new_view(|cx| {
             let mut editor = Editor::for_buffer(conversation.read(cx).buffer.clone(), None, cx);
             editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
             editor.set_show_gutter(false, cx);
             editor.set_show_wrap_guides(false, cx);
+            editor.set_completion_provider(Box::new(SlashCommandCompletionProvider::new(
+                slash_command_registry.clone(),
+                workspace.downgrade(),
+            )));
             editor
         });
 
@@ -2430,6 +2444,7 @@ impl ConversationEditor {
         let mut this = Self {
             conversation,
             editor,
+            slash_command_registry,
             blocks: Default::default(),
             scroll_position: None,
             fs,
@@ -2483,6 +2498,149 @@ impl ConversationEditor {
         }
     }
 
+    fn confirm_command(&mut self, _: &ConfirmCommand, cx: &mut ViewContext<Self>) {
+        let Some((line_range, name, argument)) = self.slash_command_at_cursor(cx) else {
+            self.editor.update(cx, |editor, cx| {
+                editor.newline(&editor::actions::Newline, cx)
+            });
+            return;
+        };
+
+        self.run_slash_command(line_range, &name, argument, cx);
+    }
+
+    /// Returns the slash command on the line containing the newest cursor, if any.
+    fn slash_command_at_cursor(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<(Range<language::Anchor>, String, Option<String>)> {
+        let selection = self.editor.read(cx).selections.newest::<Point>(cx);
+        if !selection.is_empty() {
+            return None;
+        }
+
+        let buffer = self.conversation.read(cx).buffer.read(cx);
+        let row = selection.head().row;
+        let line_range = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        let line = buffer
+            .text_for_range(line_range.clone())
+            .collect::<String>();
+        let call = SlashCommandLine::parse(&line)?;
+        let name = line[call.name].to_string();
+        self.slash_command_registry.command(&name)?;
+
+        let argument = call
+            .argument
+            .map(|argument| line[argument].to_string())
+            .filter(|argument| !argument.is_empty());
+        let line_range =
+            buffer.anchor_after(line_range.start)..buffer.anchor_before(line_range.end);
+        Some((line_range, name, argument))
+    }
+
+    fn run_slash_command(
+        &mut self,
+        line_range: Range<language::Anchor>,
+        name: &str,
+        argument: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(command) = self.slash_command_registry.command(name) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        if command.requires_argument() && argument.is_none() {
+            return;
+        }
+
+        let project = workspace.read(cx).project().clone();
+        let Some(delegate) = project.update(cx, |project, cx| {
+            let worktree = project.visible_worktrees(cx).next()?;
+            Some(ProjectLspAdapterDelegate::new(project, &worktree, cx))
+        }) else {
+            return;
+        };
+
+        let output = command.run(argument.as_deref(), self.workspace.clone(), delegate, cx);
+        cx.spawn(|this, mut cx| async move {
+            let output = output.await;
+            this.update(&mut cx, |this, cx| match output {
+                Ok(output) => this.insert_slash_command_output(line_range, output, cx),
+                Err(error) => {
+                    struct SlashCommandError;
+
+                    this.workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.show_toast(
+                                Toast::new(
+                                    NotificationId::unique::<SlashCommandError>(),
+                                    format!("failed to run slash command: {error}"),
+                                ),
+                                cx,
+                            );
+                        })
+                        .ok();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn insert_slash_command_output(
+        &mut self,
+        line_range: Range<language::Anchor>,
+        output: assistant_slash_command::SlashCommandOutput,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let sections = self.conversation.update(cx, |conversation, cx| {
+            conversation.buffer.update(cx, |buffer, cx| {
+                let start = line_range.start.to_offset(buffer);
+                buffer.edit([(line_range, output.text)], None, cx);
+                output
+                    .sections
+                    .into_iter()
+                    .map(|section| SlashCommandOutputSection {
+                        range: buffer.anchor_after(start + section.range.start)
+                            ..buffer.anchor_before(start + section.range.end),
+                        label: section.label,
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let blocks = sections
+                .iter()
+                .filter_map(|section| {
+                    let label = section.label.clone();
+                    Some(BlockProperties {
+                        position: buffer.anchor_in_excerpt(excerpt_id, section.range.start)?,
+                        height: 1,
+                        style: BlockStyle::Sticky,
+                        render: Box::new(move |_| {
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Icon::new(IconName::Hash)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                                .child(Label::new(label.clone()).size(LabelSize::Small))
+                                .into_any_element()
+                        }),
+                        disposition: BlockDisposition::Above,
+                    })
+                })
+                .collect::<Vec<_>>();
+            editor.insert_blocks(blocks, None, cx);
+        });
+    }
+
     fn cycle_message_role(&mut self, _: &CycleMessageRole, cx: &mut ViewContext<Self>) {
         let cursors = self.cursors(cx);
         self.conversation.update(cx, |conversation, cx| {
@@ -3150,6 +3308,7 @@ impl Render for ConversationEditor {
             .capture_action(cx.listener(ConversationEditor::copy))
             .capture_action(cx.listener(ConversationEditor::cycle_message_role))
             .on_action(cx.listener(ConversationEditor::assist))
+            .on_action(cx.listener(ConversationEditor::confirm_command))
             .on_action(cx.listener(ConversationEditor::split))
             .on_action(cx.listener(ConversationEditor::apply_edit))
             .size_full()
@@ -0,0 +1,256 @@
+use anyhow::Result;
+use assistant_slash_command::SlashCommandRegistry;
+use editor::{CompletionProvider, Editor};
+use gpui::{Model, Task, ViewContext, WeakView, WindowContext};
+use language::{Anchor, Buffer, CodeLabel, Documentation, LanguageServerId, Point, ToPoint};
+use parking_lot::{Mutex, RwLock};
+use project::Completion;
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Arc,
+    },
+};
+use workspace::Workspace;
+
+pub mod file_command;
+pub mod project_command;
+
+pub(crate) struct SlashCommandCompletionProvider {
+    commands: Arc<SlashCommandRegistry>,
+    cancel_flag: Mutex<Arc<AtomicBool>>,
+    workspace: WeakView<Workspace>,
+}
+
+/// A `/command argument` line within a conversation.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SlashCommandLine {
+    /// The range within the line containing the command name.
+    pub name: Range<usize>,
+    /// The range within the line containing the command argument.
+    pub argument: Option<Range<usize>>,
+}
+
+impl SlashCommandCompletionProvider {
+    pub fn new(commands: Arc<SlashCommandRegistry>, workspace: WeakView<Workspace>) -> Self {
+        Self {
+            commands,
+            cancel_flag: Mutex::new(Arc::new(AtomicBool::new(false))),
+            workspace,
+        }
+    }
+
+    fn complete_command_name(
+        &self,
+        command_name: &str,
+        range: Range<Anchor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let completions = self
+            .commands
+            .command_names()
+            .into_iter()
+            .filter(|name| name.starts_with(command_name))
+            .filter_map(|name| {
+                let command = self.commands.command(&name)?;
+                let mut new_text = name.to_string();
+                if command.requires_argument() {
+                    new_text.push(' ');
+                }
+
+                Some(Completion {
+                    old_range: range.clone(),
+                    documentation: Some(Documentation::SingleLine(command.description())),
+                    new_text,
+                    label: CodeLabel::plain(name.to_string(), None),
+                    server_id: LanguageServerId(0),
+                    lsp_completion: Default::default(),
+                })
+            })
+            .collect();
+        Task::ready(Ok(completions))
+    }
+
+    fn complete_command_argument(
+        &self,
+        command_name: &str,
+        argument: String,
+        range: Range<Anchor>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<Vec<Completion>>> {
+        let new_cancel_flag = Arc::new(AtomicBool::new(false));
+        let mut flag = self.cancel_flag.lock();
+        flag.store(true, SeqCst);
+        *flag = new_cancel_flag.clone();
+
+        let Some(command) = self.commands.command(command_name) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let completions =
+            command.complete_argument(argument, new_cancel_flag, self.workspace.clone(), cx);
+        cx.background_executor().spawn(async move {
+            Ok(completions
+                .await?
+                .into_iter()
+                .map(|arg| Completion {
+                    old_range: range.clone(),
+                    label: CodeLabel::plain(arg.clone(), None),
+                    new_text: arg,
+                    documentation: None,
+                    server_id: LanguageServerId(0),
+                    lsp_completion: Default::default(),
+                })
+                .collect())
+        })
+    }
+}
+
+impl CompletionProvider for SlashCommandCompletionProvider {
+    fn completions(
+        &self,
+        buffer: &Model<Buffer>,
+        buffer_position: Anchor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let task = buffer.update(cx, |buffer, _| {
+            let position = buffer_position.to_point(buffer);
+            let line_start = Point::new(position.row, 0);
+            let line = buffer
+                .text_for_range(line_start..position)
+                .collect::<String>();
+            let call = SlashCommandLine::parse(&line)?;
+
+            let name = line[call.name.clone()].to_string();
+            Some(if let Some(argument) = call.argument {
+                let start = buffer.anchor_after(Point::new(position.row, argument.start as u32));
+                let argument = line[argument].to_string();
+                (name, Some(argument), start..buffer_position)
+            } else {
+                let start = buffer.anchor_after(Point::new(position.row, call.name.start as u32));
+                (name, None, start..buffer_position)
+            })
+        });
+
+        let Some((name, argument, range)) = task else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        if let Some(argument) = argument {
+            self.complete_command_argument(&name, argument, range, cx)
+        } else {
+            self.complete_command_name(&name, range)
+        }
+    }
+
+    fn resolve_completions(
+        &self,
+        _buffer: Model<Buffer>,
+        _completion_indices: Vec<usize>,
+        _completions: Arc<RwLock<Box<[Completion]>>>,
+        _cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<bool>> {
+        Task::ready(Ok(false))
+    }
+
+    fn apply_additional_edits_for_completion(
+        &self,
+        _buffer: Model<Buffer>,
+        _completion: Completion,
+        _push_to_history: bool,
+        _cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Option<language::Transaction>>> {
+        Task::ready(Ok(None))
+    }
+}
+
+impl SlashCommandLine {
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let mut call: Option<Self> = None;
+        let mut ix = 0;
+        for c in line.chars() {
+            let next_ix = ix + c.len_utf8();
+            if let Some(call) = &mut call {
+                // The command arguments start at the first non-whitespace character
+                // after the command name, and continue until the end of the line.
+                if let Some(argument) = &mut call.argument {
+                    if (*argument).is_empty() && c.is_whitespace() {
+                        argument.start = next_ix;
+                    }
+                    argument.end = next_ix;
+                }
+                // The command name ends at the first whitespace character.
+                else if !call.name.is_empty() && c.is_whitespace() {
+                    call.argument = Some(next_ix..next_ix);
+                }
+                // The command name must begin with a letter, and may contain digits,
+                // dashes, and underscores after that.
+                else if c.is_alphabetic()
+                    || (!call.name.is_empty() && (c.is_alphanumeric() || c == '-' || c == '_'))
+                {
+                    call.name.end = next_ix;
+                } else {
+                    return None;
+                }
+            }
+            // Commands start with a slash.
+            else if c == '/' {
+                call = Some(SlashCommandLine {
+                    name: next_ix..next_ix,
+                    argument: None,
+                });
+            }
+            // The line can't contain anything before the slash except for whitespace.
+            else if !c.is_whitespace() {
+                return None;
+            }
+            ix = next_ix;
+        }
+        call
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slash_command_line() {
+        assert_eq!(
+            SlashCommandLine::parse("/file"),
+            Some(SlashCommandLine {
+                name: 1..5,
+                argument: None,
+            })
+        );
+        assert_eq!(
+            SlashCommandLine::parse("  /file src/main.rs"),
+            Some(SlashCommandLine {
+                name: 3..7,
+                argument: Some(8..19),
+            })
+        );
+        assert_eq!(
+            SlashCommandLine::parse("/file   main.rs "),
+            Some(SlashCommandLine {
+                name: 1..5,
+                argument: Some(8..16),
+            })
+        );
+        assert_eq!(
+            SlashCommandLine::parse("/file "),
+            Some(SlashCommandLine {
+                name: 1..5,
+                argument: Some(6..6),
+            })
+        );
+        assert_eq!(SlashCommandLine::parse("text /file"), None);
+        assert_eq!(
+            SlashCommandLine::parse("/docs-rs serde"),
+            Some(SlashCommandLine {
+                name: 1..8,
+                argument: Some(9..14),
+            })
+        );
+        assert_eq!(SlashCommandLine::parse("/-file"), None);
+    }
+}
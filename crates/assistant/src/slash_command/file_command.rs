@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
+use fuzzy::PathMatch;
+use gpui::{AppContext, Model, SharedString, Task, WeakView, WindowContext};
+use language::LspAdapterDelegate;
+use project::{PathMatchCandidateSet, Project, ProjectPath};
+use std::{
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+use workspace::Workspace;
+
+/// Inserts the contents of a file from the project into the conversation.
+pub(crate) struct FileSlashCommand;
+
+impl FileSlashCommand {
+    fn search_paths(
+        &self,
+        query: String,
+        cancellation_flag: Arc<AtomicBool>,
+        project: &Model<Project>,
+        cx: &mut AppContext,
+    ) -> Task<Vec<PathMatch>> {
+        let worktrees = project.read(cx).visible_worktrees(cx).collect::<Vec<_>>();
+        let candidate_sets = worktrees
+            .into_iter()
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                PathMatchCandidateSet {
+                    snapshot: worktree.snapshot(),
+                    include_ignored: worktree
+                        .root_entry()
+                        .map_or(false, |entry| entry.is_ignored),
+                    include_root_name: true,
+                    directories_only: false,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let executor = cx.background_executor().clone();
+        cx.foreground_executor().spawn(async move {
+            fuzzy::match_path_sets(
+                candidate_sets.as_slice(),
+                query.as_str(),
+                None,
+                false,
+                100,
+                &cancellation_flag,
+                executor,
+            )
+            .await
+        })
+    }
+}
+
+impl SlashCommand for FileSlashCommand {
+    fn name(&self) -> String {
+        "file".into()
+    }
+
+    fn description(&self) -> String {
+        "insert a file".into()
+    }
+
+    fn tooltip_text(&self) -> String {
+        "insert file".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        true
+    }
+
+    fn complete_argument(
+        &self,
+        query: String,
+        cancellation_flag: Arc<AtomicBool>,
+        workspace: WeakView<Workspace>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let paths = self.search_paths(query, cancellation_flag, &project, cx);
+        cx.background_executor().spawn(async move {
+            Ok(paths
+                .await
+                .into_iter()
+                .map(|path_match| {
+                    format!(
+                        "{}{}",
+                        path_match.path_prefix,
+                        path_match.path.to_string_lossy()
+                    )
+                })
+                .collect())
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let Some(argument) = argument else {
+            return Task::ready(Err(anyhow!("missing path")));
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let Some(project_path) = project_path_for_argument(&project, argument, cx) else {
+            return Task::ready(Err(anyhow!("no such file in the project: {argument}")));
+        };
+
+        let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        let label = SharedString::from(argument.to_string());
+        cx.spawn(|cx| async move {
+            let buffer = buffer.await?;
+            let (text, language_name) = buffer.read_with(&cx, |buffer, _| {
+                (
+                    buffer.text(),
+                    buffer
+                        .language()
+                        .map(|language| language.code_fence_block_name()),
+                )
+            })?;
+
+            let mut output = String::new();
+            output.push_str("```");
+            if let Some(language_name) = language_name {
+                output.push_str(&language_name);
+            }
+            output.push('\n');
+            output.push_str(&text);
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str("```\n");
+
+            Ok(SlashCommandOutput {
+                sections: vec![SlashCommandOutputSection {
+                    range: 0..output.len(),
+                    label,
+                }],
+                text: output,
+            })
+        })
+    }
+}
+
+/// Resolves a path of the form `{worktree root name}/{path}` to a [`ProjectPath`].
+fn project_path_for_argument(
+    project: &Model<Project>,
+    argument: &str,
+    cx: &AppContext,
+) -> Option<ProjectPath> {
+    let path = Path::new(argument);
+    project.read(cx).visible_worktrees(cx).find_map(|worktree| {
+        let worktree = worktree.read(cx);
+        let relative_path = path.strip_prefix(worktree.root_name()).ok()?;
+        let entry = worktree.entry_for_path(relative_path)?;
+        entry.is_file().then(|| ProjectPath {
+            worktree_id: worktree.id(),
+            path: entry.path.clone(),
+        })
+    })
+}
@@ -0,0 +1,72 @@
+use crate::ambient_context::CurrentProjectContext;
+use anyhow::{anyhow, Result};
+use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
+use gpui::{AppContext, Task, WeakView, WindowContext};
+use language::LspAdapterDelegate;
+use std::sync::{atomic::AtomicBool, Arc};
+use workspace::Workspace;
+
+/// Inserts a summary of the current project into the conversation.
+pub(crate) struct ProjectSlashCommand;
+
+impl SlashCommand for ProjectSlashCommand {
+    fn name(&self) -> String {
+        "project".into()
+    }
+
+    fn description(&self) -> String {
+        "insert current project context".into()
+    }
+
+    fn tooltip_text(&self) -> String {
+        "insert current project context".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        false
+    }
+
+    fn complete_argument(
+        &self,
+        _query: String,
+        _cancel: Arc<AtomicBool>,
+        _workspace: WeakView<Workspace>,
+        _cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        Task::ready(Err(anyhow!("this command does not require argument")))
+    }
+
+    fn run(
+        self: Arc<Self>,
+        _argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+
+        let workspace = workspace.read(cx);
+        let project = workspace.project().downgrade();
+        let fs = workspace.app_state().fs.clone();
+        cx.spawn(|mut cx| async move {
+            let path_to_cargo_toml = CurrentProjectContext::path_to_cargo_toml(project, &mut cx)?
+                .ok_or_else(|| anyhow!("no Cargo.toml"))?;
+            let text = cx
+                .background_executor()
+                .spawn(async move {
+                    CurrentProjectContext::build_message(fs, &path_to_cargo_toml).await
+                })
+                .await?;
+
+            Ok(SlashCommandOutput {
+                sections: vec![SlashCommandOutputSection {
+                    range: 0..text.len(),
+                    label: "Current Project".into(),
+                }],
+                text,
+            })
+        })
+    }
+}
@@ -0,0 +1,56 @@
+mod slash_command_registry;
+
+use anyhow::Result;
+use gpui::{AppContext, SharedString, Task, WeakView, WindowContext};
+use language::LspAdapterDelegate;
+pub use slash_command_registry::*;
+use std::{
+    ops::Range,
+    sync::{atomic::AtomicBool, Arc},
+};
+use workspace::Workspace;
+
+pub fn init(cx: &mut AppContext) {
+    SlashCommandRegistry::default_global(cx);
+}
+
+/// A command that can be invoked from the Assistant by typing `/name` followed by an
+/// optional argument.
+pub trait SlashCommand: 'static + Send + Sync {
+    /// The name of the command, without the leading `/`.
+    fn name(&self) -> String;
+    fn description(&self) -> String;
+    fn tooltip_text(&self) -> String;
+    /// Returns the completions for the given (possibly partial) argument.
+    fn complete_argument(
+        &self,
+        query: String,
+        cancel: Arc<AtomicBool>,
+        workspace: WeakView<Workspace>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>>;
+    fn requires_argument(&self) -> bool;
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        // We reuse the `LspAdapterDelegate` here, as it is what the extension API
+        // already expects for accessing a worktree.
+        delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>>;
+}
+
+/// The text produced by running a [`SlashCommand`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SlashCommandOutput {
+    pub text: String,
+    pub sections: Vec<SlashCommandOutputSection<usize>>,
+}
+
+/// A labeled region of a [`SlashCommandOutput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlashCommandOutputSection<T> {
+    pub range: Range<T>,
+    pub label: SharedString,
+}
@@ -0,0 +1,74 @@
+use collections::{BTreeSet, HashMap};
+use derive_more::{Deref, DerefMut};
+use gpui::Global;
+use gpui::{AppContext, ReadGlobal};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+use crate::SlashCommand;
+
+#[derive(Default, Deref, DerefMut)]
+struct GlobalSlashCommandRegistry(Arc<SlashCommandRegistry>);
+
+impl Global for GlobalSlashCommandRegistry {}
+
+#[derive(Default)]
+struct SlashCommandRegistryState {
+    commands: HashMap<Arc<str>, Arc<dyn SlashCommand>>,
+}
+
+/// The registry of the slash commands available in the Assistant.
+///
+/// Built-in commands and commands contributed by extensions are both registered here.
+#[derive(Default)]
+pub struct SlashCommandRegistry {
+    state: RwLock<SlashCommandRegistryState>,
+}
+
+impl SlashCommandRegistry {
+    /// Returns the global [`SlashCommandRegistry`].
+    pub fn global(cx: &AppContext) -> Arc<Self> {
+        GlobalSlashCommandRegistry::global(cx).0.clone()
+    }
+
+    /// Returns the global [`SlashCommandRegistry`].
+    ///
+    /// Inserts a default [`SlashCommandRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalSlashCommandRegistry>().0.clone()
+    }
+
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registers the provided [`SlashCommand`], replacing any existing command with the same name.
+    pub fn register_command(&self, command: impl SlashCommand) {
+        self.state
+            .write()
+            .commands
+            .insert(command.name().into(), Arc::new(command));
+    }
+
+    /// Unregisters the [`SlashCommand`] with the given name.
+    pub fn unregister_command(&self, name: &str) {
+        self.state.write().commands.remove(name);
+    }
+
+    /// Returns the names of the registered slash commands, in sorted order.
+    pub fn command_names(&self) -> Vec<Arc<str>> {
+        let state = self.state.read();
+        state
+            .commands
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the [`SlashCommand`] with the given name.
+    pub fn command(&self, name: &str) -> Option<Arc<dyn SlashCommand>> {
+        self.state.read().commands.get(name).cloned()
+    }
+}
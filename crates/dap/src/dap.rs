@@ -0,0 +1,1011 @@
+pub mod events;
+pub mod requests;
+mod types;
+
+pub use types::*;
+
+use anyhow::{anyhow, Context, Result};
+use collections::HashMap;
+use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, Future, FutureExt};
+use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Task};
+use parking_lot::Mutex;
+use postage::{barrier, prelude::Stream};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{value::RawValue, Value};
+use smol::{
+    channel,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    process::{self, Child},
+};
+
+#[cfg(target_os = "windows")]
+use smol::process::windows::CommandExt;
+
+use std::{
+    ffi::OsString,
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+    str,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc,
+    },
+    time::Duration,
+};
+use util::{ResultExt, TryFutureExt};
+
+use crate::{events::Event, requests::Request};
+
+const HEADER_DELIMITER: &'static [u8; 4] = b"\r\n\r\n";
+const CONTENT_LEN_HEADER: &str = "Content-Length: ";
+const DAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+const ADAPTER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+type EventHandler = Box<dyn Send + FnMut(&str, AsyncAppContext)>;
+type RequestHandler = Box<dyn Send + FnMut(u64, &str, AsyncAppContext)>;
+type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
+
+/// Represents a launchable debug adapter: either a standalone binary or a runtime
+/// with the arguments to start the actual adapter with.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DebugAdapterBinary {
+    pub path: PathBuf,
+    pub arguments: Vec<OsString>,
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Identifies a running debug adapter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct DebugAdapterId(pub usize);
+
+/// A running debug adapter process, talking the Debug Adapter Protocol over its stdio.
+pub struct DebugAdapterClient {
+    adapter_id: DebugAdapterId,
+    next_seq: Arc<AtomicU64>,
+    outbound_tx: channel::Sender<String>,
+    name: Arc<str>,
+    capabilities: Capabilities,
+    event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+    response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    executor: BackgroundExecutor,
+    #[allow(clippy::type_complexity)]
+    io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
+    output_done_rx: Mutex<Option<barrier::Receiver>>,
+    adapter: Arc<Mutex<Option<Child>>>,
+}
+
+/// Handle to a debug adapter event or reverse request subscription.
+pub struct Subscription {
+    key: &'static str,
+    handlers: Option<SubscriptionHandlers>,
+}
+
+enum SubscriptionHandlers {
+    Event(Arc<Mutex<HashMap<&'static str, EventHandler>>>),
+    Request(Arc<Mutex<HashMap<&'static str, RequestHandler>>>),
+}
+
+/// Debug Adapter Protocol request message.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Request)
+#[derive(Serialize)]
+struct RequestMessage<'a> {
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    command: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arguments: Option<Value>,
+}
+
+/// Debug Adapter Protocol response message.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Response)
+#[derive(Serialize)]
+struct ResponseMessage<'a> {
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    request_seq: u64,
+    success: bool,
+    command: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+/// Debug Adapter Protocol event message.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Event)
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Serialize)]
+struct EventMessage<'a> {
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    event: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+/// Any Debug Adapter Protocol message before it is deserialized into a concrete type.
+#[derive(Debug, Deserialize)]
+struct AnyMessage<'a> {
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    event: Option<String>,
+    #[serde(default)]
+    request_seq: Option<u64>,
+    #[serde(default)]
+    success: Option<bool>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(borrow, default)]
+    arguments: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    body: Option<&'a RawValue>,
+}
+
+#[derive(Debug)]
+struct Error {
+    message: String,
+}
+
+impl DebugAdapterClient {
+    /// Starts a debug adapter process.
+    pub fn new(
+        adapter_id: DebugAdapterId,
+        binary: DebugAdapterBinary,
+        working_dir: &Path,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        log::info!(
+            "starting debug adapter. binary path: {:?}, working directory: {:?}, args: {:?}",
+            binary.path,
+            working_dir,
+            &binary.arguments
+        );
+
+        let mut command = process::Command::new(&binary.path);
+        command
+            .current_dir(working_dir)
+            .args(&binary.arguments)
+            .envs(binary.env.unwrap_or_default())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        let mut adapter = command.spawn().with_context(|| {
+            format!(
+                "failed to spawn command. path: {:?}, working directory: {:?}, args: {:?}",
+                binary.path, working_dir, &binary.arguments
+            )
+        })?;
+
+        let stdin = adapter.stdin.take().unwrap();
+        let stdout = adapter.stdout.take().unwrap();
+        let stderr = adapter.stderr.take().unwrap();
+        let mut client =
+            Self::new_internal(adapter_id, stdin, stdout, Some(stderr), Some(adapter), cx);
+        if let Some(name) = binary.path.file_name() {
+            client.name = name.to_string_lossy().into();
+        }
+        Ok(client)
+    }
+
+    fn new_internal<Stdin, Stdout, Stderr>(
+        adapter_id: DebugAdapterId,
+        stdin: Stdin,
+        stdout: Stdout,
+        stderr: Option<Stderr>,
+        adapter: Option<Child>,
+        cx: AsyncAppContext,
+    ) -> Self
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+        Stdout: AsyncRead + Unpin + Send + 'static,
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (output_done_tx, output_done_rx) = barrier::channel();
+        let next_seq = Arc::new(AtomicU64::new(1));
+        let event_handlers = Arc::new(Mutex::new(HashMap::<_, EventHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let stdout_input_task = cx.spawn({
+            let event_handlers = event_handlers.clone();
+            let request_handlers = request_handlers.clone();
+            let response_handlers = response_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            let next_seq = next_seq.clone();
+            move |cx| {
+                Self::handle_input(
+                    adapter_id,
+                    stdout,
+                    event_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    next_seq,
+                    cx,
+                )
+                .log_err()
+            }
+        });
+        let stderr_input_task = stderr
+            .map(|stderr| cx.spawn(|_| Self::handle_stderr(adapter_id, stderr).log_err()))
+            .unwrap_or_else(|| Task::Ready(Some(None)));
+        let input_task = cx.spawn(|_| async move {
+            let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            stdout.or(stderr)
+        });
+        let output_task = cx.background_executor().spawn({
+            Self::handle_output(
+                stdin,
+                outbound_rx,
+                output_done_tx,
+                response_handlers.clone(),
+            )
+            .log_err()
+        });
+
+        Self {
+            adapter_id,
+            next_seq,
+            outbound_tx,
+            name: "".into(),
+            capabilities: Default::default(),
+            event_handlers,
+            request_handlers,
+            response_handlers,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            output_done_rx: Mutex::new(Some(output_done_rx)),
+            adapter: Arc::new(Mutex::new(adapter)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_input<Stdout>(
+        adapter_id: DebugAdapterId,
+        stdout: Stdout,
+        event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
+        next_seq: Arc<AtomicU64>,
+        cx: AsyncAppContext,
+    ) -> anyhow::Result<()>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stdout = BufReader::new(stdout);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+
+            read_headers(&mut stdout, &mut buffer).await?;
+
+            let headers = std::str::from_utf8(&buffer)?;
+
+            let message_len = headers
+                .split('\n')
+                .find(|line| line.starts_with(CONTENT_LEN_HEADER))
+                .and_then(|line| line.strip_prefix(CONTENT_LEN_HEADER))
+                .ok_or_else(|| anyhow!("invalid DAP message header {headers:?}"))?
+                .trim_end()
+                .parse()?;
+
+            buffer.resize(message_len, 0);
+            stdout.read_exact(&mut buffer).await?;
+            log::trace!("incoming message: {}", String::from_utf8_lossy(&buffer));
+
+            let message = match serde_json::from_slice::<AnyMessage>(&buffer) {
+                Ok(message) => message,
+                Err(error) => {
+                    log::warn!(
+                        "failed to deserialize DAP message: {error}\n{}",
+                        String::from_utf8_lossy(&buffer)
+                    );
+                    continue;
+                }
+            };
+            match (message.kind.as_str(), message.event, message.command) {
+                ("event", Some(event), _) => {
+                    let body = message.body.map(|body| body.get()).unwrap_or("null");
+                    if let Some(handler) = event_handlers.lock().get_mut(event.as_str()) {
+                        handler(body, cx.clone());
+                    } else {
+                        log::info!(
+                            "Debug adapter with id {adapter_id} sent unhandled event {event}: {body}"
+                        );
+                    }
+                }
+                ("request", _, Some(command)) => {
+                    let arguments = message.arguments.map(|args| args.get()).unwrap_or("null");
+                    if let Some(handler) = request_handlers.lock().get_mut(command.as_str()) {
+                        handler(message.seq, arguments, cx.clone());
+                    } else {
+                        log::info!(
+                            "Debug adapter with id {adapter_id} sent unhandled request {command}: {arguments}"
+                        );
+                        let response = ResponseMessage {
+                            seq: next_seq.fetch_add(1, SeqCst),
+                            kind: "response",
+                            request_seq: message.seq,
+                            success: false,
+                            command: &command,
+                            message: Some(format!("unsupported request {command}")),
+                            body: None,
+                        };
+                        if let Some(response) = serde_json::to_string(&response).log_err() {
+                            outbound_tx.try_send(response).ok();
+                        }
+                    }
+                }
+                ("response", _, _) => {
+                    let Some(request_seq) = message.request_seq else {
+                        log::warn!("DAP response without a request_seq");
+                        continue;
+                    };
+                    if let Some(handler) = response_handlers
+                        .lock()
+                        .as_mut()
+                        .and_then(|handlers| handlers.remove(&request_seq))
+                    {
+                        if message.success.unwrap_or(false) {
+                            handler(Ok(message.body.map_or_else(
+                                || "null".to_string(),
+                                |body| body.get().to_string(),
+                            )));
+                        } else {
+                            handler(Err(Error {
+                                message: message
+                                    .message
+                                    .unwrap_or_else(|| "request failed".to_string()),
+                            }));
+                        }
+                    }
+                }
+                (kind, _, _) => log::warn!("unexpected DAP message of type {kind:?}"),
+            }
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    async fn handle_stderr<Stderr>(adapter_id: DebugAdapterId, stderr: Stderr) -> anyhow::Result<()>
+    where
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stderr = BufReader::new(stderr);
+        let mut buffer = Vec::new();
+
+        loop {
+            buffer.clear();
+
+            let bytes_read = stderr.read_until(b'\n', &mut buffer).await?;
+            if bytes_read == 0 {
+                return Ok(());
+            }
+            log::debug!(
+                "debug adapter {adapter_id} stderr: {}",
+                String::from_utf8_lossy(&buffer).trim_end()
+            );
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    async fn handle_output<Stdin>(
+        stdin: Stdin,
+        outbound_rx: channel::Receiver<String>,
+        output_done_tx: barrier::Sender,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    ) -> anyhow::Result<()>
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut stdin = BufWriter::new(stdin);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut content_len_buffer = Vec::new();
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing message:{}", message);
+
+            content_len_buffer.clear();
+            write!(content_len_buffer, "{}", message.len()).unwrap();
+            stdin.write_all(CONTENT_LEN_HEADER.as_bytes()).await?;
+            stdin.write_all(&content_len_buffer).await?;
+            stdin.write_all(HEADER_DELIMITER).await?;
+            stdin.write_all(message.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        drop(output_done_tx);
+        Ok(())
+    }
+
+    /// Initializes the debug adapter by sending the `initialize` request,
+    /// remembering the capabilities it responds with.
+    pub fn initialize(
+        mut self,
+        arguments: InitializeRequestArguments,
+        cx: &AppContext,
+    ) -> Task<Result<Arc<Self>>> {
+        cx.spawn(|_| async move {
+            self.capabilities = self.request::<requests::Initialize>(arguments).await?;
+            Ok(Arc::new(self))
+        })
+    }
+
+    /// Disconnects from the debuggee, terminating it, and prepares the [`DebugAdapterClient`] to be dropped.
+    pub fn shutdown(&self) -> Option<impl 'static + Send + Future<Output = Option<()>>> {
+        let tasks = self.io_tasks.lock().take()?;
+        let response_handlers = self.response_handlers.clone();
+        let outbound_tx = self.outbound_tx.clone();
+        let mut output_done = self.output_done_rx.lock().take().unwrap();
+        let disconnect_request = Self::request_internal::<requests::Disconnect>(
+            &self.next_seq,
+            &response_handlers,
+            &outbound_tx,
+            &self.executor,
+            DisconnectArguments {
+                restart: Some(false),
+                terminate_debuggee: Some(true),
+            },
+        );
+        outbound_tx.close();
+
+        let adapter = self.adapter.clone();
+        let name = self.name.clone();
+        let mut timer = self.executor.timer(ADAPTER_SHUTDOWN_TIMEOUT).fuse();
+        Some(
+            async move {
+                log::debug!("debug adapter shutdown started");
+
+                select! {
+                    request_result = disconnect_request.fuse() => {
+                        request_result?;
+                    }
+
+                    _ = timer => {
+                        log::info!("timeout waiting for debug adapter {name} to disconnect");
+                    },
+                }
+
+                response_handlers.lock().take();
+                output_done.recv().await;
+                adapter.lock().take().map(|mut child| child.kill());
+                log::debug!("debug adapter shutdown finished");
+
+                drop(tasks);
+                anyhow::Ok(())
+            }
+            .log_err(),
+        )
+    }
+
+    /// Registers a handler for the events of the given type.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Event)
+    #[must_use]
+    pub fn on_event<T, F>(&self, mut f: F) -> Subscription
+    where
+        T: Event,
+        F: 'static + Send + FnMut(T::Body, AsyncAppContext),
+    {
+        let prev_handler = self.event_handlers.lock().insert(
+            T::EVENT,
+            Box::new(move |body, cx| {
+                if let Some(body) = deserialize_body(body).log_err() {
+                    f(body, cx);
+                }
+            }),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP event"
+        );
+        Subscription {
+            key: T::EVENT,
+            handlers: Some(SubscriptionHandlers::Event(self.event_handlers.clone())),
+        }
+    }
+
+    /// Registers a handler for the requests of the given type, sent by the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Reverse_Requests)
+    #[must_use]
+    pub fn on_request<T, F, Fut>(&self, mut f: F) -> Subscription
+    where
+        T: Request,
+        F: 'static + Send + FnMut(T::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Future<Output = Result<T::Response>>,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        let next_seq = self.next_seq.clone();
+        let prev_handler = self.request_handlers.lock().insert(
+            T::COMMAND,
+            Box::new(move |request_seq, arguments, cx| {
+                let response = serde_json::from_str::<T::Arguments>(arguments)
+                    .with_context(|| format!("deserializing {} request", T::COMMAND))
+                    .map(|arguments| f(arguments, cx.clone()));
+                let outbound_tx = outbound_tx.clone();
+                let next_seq = next_seq.clone();
+                cx.foreground_executor()
+                    .spawn(async move {
+                        let result = match response {
+                            Ok(response) => response.await,
+                            Err(error) => Err(error),
+                        };
+                        let (success, message, body) = match result {
+                            Ok(body) => (true, None, serde_json::to_value(body).log_err()),
+                            Err(error) => (false, Some(error.to_string()), None),
+                        };
+                        let response = ResponseMessage {
+                            seq: next_seq.fetch_add(1, SeqCst),
+                            kind: "response",
+                            request_seq,
+                            success,
+                            command: T::COMMAND,
+                            message,
+                            body: body.filter(|body| !body.is_null()),
+                        };
+                        if let Some(response) = serde_json::to_string(&response).log_err() {
+                            outbound_tx.try_send(response).ok();
+                        }
+                    })
+                    .detach();
+            }),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP request"
+        );
+        Subscription {
+            key: T::COMMAND,
+            handlers: Some(SubscriptionHandlers::Request(self.request_handlers.clone())),
+        }
+    }
+
+    /// Removes a request handler, registered via [`Self::on_request`].
+    pub fn remove_request_handler<T: Request>(&self) {
+        self.request_handlers.lock().remove(T::COMMAND);
+    }
+
+    /// Removes an event handler, registered via [`Self::on_event`].
+    pub fn remove_event_handler<T: Event>(&self) {
+        self.event_handlers.lock().remove(T::EVENT);
+    }
+
+    /// Get the name of the running debug adapter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the capabilities, the debug adapter has reported on initialization.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Get the id of the running debug adapter.
+    pub fn adapter_id(&self) -> DebugAdapterId {
+        self.adapter_id
+    }
+
+    /// Sends a request to the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Request)
+    pub fn request<T: Request>(
+        &self,
+        arguments: T::Arguments,
+    ) -> impl 'static + Future<Output = Result<T::Response>> {
+        Self::request_internal::<T>(
+            &self.next_seq,
+            &self.response_handlers,
+            &self.outbound_tx,
+            &self.executor,
+            arguments,
+        )
+    }
+
+    fn request_internal<T: Request>(
+        next_seq: &AtomicU64,
+        response_handlers: &Mutex<Option<HashMap<u64, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+        arguments: T::Arguments,
+    ) -> impl 'static + Future<Output = Result<T::Response>> {
+        let seq = next_seq.fetch_add(1, SeqCst);
+        let message = serde_json::to_value(arguments)
+            .and_then(|arguments| {
+                serde_json::to_string(&RequestMessage {
+                    seq,
+                    kind: "request",
+                    command: T::COMMAND,
+                    arguments: Some(arguments).filter(|arguments| !arguments.is_null()),
+                })
+            })
+            .context("serializing DAP request");
+
+        let (tx, rx) = oneshot::channel();
+        let handle_response = response_handlers
+            .lock()
+            .as_mut()
+            .ok_or_else(|| anyhow!("debug adapter shut down"))
+            .map(|handlers| {
+                handlers.insert(
+                    seq,
+                    Box::new(move |result| {
+                        let response = match result {
+                            Ok(body) => deserialize_body(&body).with_context(|| {
+                                format!("failed to deserialize {} response", T::COMMAND)
+                            }),
+                            Err(error) => Err(anyhow!("{}", error.message)),
+                        };
+                        _ = tx.send(response);
+                    }),
+                );
+            });
+
+        let send = message.and_then(|message| {
+            outbound_tx
+                .try_send(message)
+                .context("failed to write to debug adapter's stdin")
+        });
+
+        let mut timeout = executor.timer(DAP_REQUEST_TIMEOUT).fuse();
+        async move {
+            handle_response?;
+            send?;
+
+            let command = T::COMMAND;
+            select! {
+                response = rx.fuse() => response?,
+                _ = timeout => {
+                    log::error!("Cancelled DAP request {command:?} seq {seq} which took over {DAP_REQUEST_TIMEOUT:?}");
+                    anyhow::bail!("DAP request timeout");
+                }
+            }
+        }
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn send_event<T: Event>(&self, body: T::Body) -> Result<()> {
+        let body = serde_json::to_value(body)?;
+        let message = serde_json::to_string(&EventMessage {
+            seq: self.next_seq.fetch_add(1, SeqCst),
+            kind: "event",
+            event: T::EVENT,
+            body: Some(body).filter(|body| !body.is_null()),
+        })?;
+        self.outbound_tx.try_send(message)?;
+        Ok(())
+    }
+}
+
+/// Adapters may omit the bodies of the events and responses without data, or send empty objects as them.
+fn deserialize_body<T: DeserializeOwned>(body: &str) -> Result<T> {
+    serde_json::from_str(body)
+        .or_else(|error| serde_json::from_str("null").map_err(|_| error.into()))
+}
+
+impl Drop for DebugAdapterClient {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown() {
+            self.executor.spawn(shutdown).detach();
+        }
+    }
+}
+
+impl Subscription {
+    /// Detaching a subscription handle prevents it from unsubscribing on drop.
+    pub fn detach(&mut self) {
+        self.handlers = None;
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        match self.handlers.take() {
+            Some(SubscriptionHandlers::Event(handlers)) => {
+                handlers.lock().remove(self.key);
+            }
+            Some(SubscriptionHandlers::Request(handlers)) => {
+                handlers.lock().remove(self.key);
+            }
+            None => {}
+        }
+    }
+}
+
+impl fmt::Display for DebugAdapterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for DebugAdapterClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugAdapterClient")
+            .field("id", &self.adapter_id.0)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Mock debug adapter for use in tests, running in the same process as the client.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Clone)]
+pub struct FakeDebugAdapter {
+    pub adapter: Arc<DebugAdapterClient>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeDebugAdapter {
+    /// Construct a fake debug adapter and a client, connected to it.
+    pub fn new(
+        adapter_id: DebugAdapterId,
+        name: String,
+        capabilities: Capabilities,
+        cx: AsyncAppContext,
+    ) -> (DebugAdapterClient, FakeDebugAdapter) {
+        let (stdin_writer, stdin_reader) = async_pipe::pipe();
+        let (stdout_writer, stdout_reader) = async_pipe::pipe();
+
+        let mut client = DebugAdapterClient::new_internal(
+            adapter_id,
+            stdin_writer,
+            stdout_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx.clone(),
+        );
+        client.name = name.as_str().into();
+        let mut adapter = DebugAdapterClient::new_internal(
+            adapter_id,
+            stdout_writer,
+            stdin_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx,
+        );
+        adapter.name = name.as_str().into();
+        let fake = FakeDebugAdapter {
+            adapter: Arc::new(adapter),
+        };
+        fake.handle_request::<requests::Initialize, _, _>(move |_, _| {
+            let capabilities = capabilities.clone();
+            async move { Ok(capabilities) }
+        });
+        fake.handle_request::<requests::Disconnect, _, _>(|_, _| async move { Ok(()) });
+
+        (client, fake)
+    }
+
+    /// Sends an event to the client.
+    pub fn send_event<T: Event>(&self, body: T::Body) {
+        self.adapter.send_event::<T>(body).ok();
+    }
+
+    /// Sends a reverse request to the client. See [`DebugAdapterClient::request`].
+    pub async fn request<T: Request>(&self, arguments: T::Arguments) -> Result<T::Response> {
+        self.adapter.executor.start_waiting();
+        self.adapter.request::<T>(arguments).await
+    }
+
+    /// Registers a handler for a specific kind of request. Removes any existing handler for specified request type.
+    pub fn handle_request<T, F, Fut>(
+        &self,
+        mut handler: F,
+    ) -> futures::channel::mpsc::UnboundedReceiver<()>
+    where
+        T: 'static + Request,
+        F: 'static + Send + FnMut(T::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<T::Response>>,
+    {
+        let (responded_tx, responded_rx) = futures::channel::mpsc::unbounded();
+        self.adapter.remove_request_handler::<T>();
+        self.adapter
+            .on_request::<T, _, _>(move |arguments, cx| {
+                let result = handler(arguments, cx.clone());
+                let responded_tx = responded_tx.clone();
+                let executor = cx.background_executor().clone();
+                async move {
+                    executor.simulate_random_delay().await;
+                    let result = result.await;
+                    responded_tx.unbounded_send(()).ok();
+                    result
+                }
+            })
+            .detach();
+        responded_rx
+    }
+}
+
+async fn read_headers<Stdout>(reader: &mut BufReader<Stdout>, buffer: &mut Vec<u8>) -> Result<()>
+where
+    Stdout: AsyncRead + Unpin + Send + 'static,
+{
+    loop {
+        if buffer.len() >= HEADER_DELIMITER.len()
+            && buffer[(buffer.len() - HEADER_DELIMITER.len())..] == HEADER_DELIMITER[..]
+        {
+            return Ok(());
+        }
+
+        if reader.read_until(b'\n', buffer).await? == 0 {
+            return Err(anyhow!("cannot read DAP message headers"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+
+    #[ctor::ctor]
+    fn init_logger() {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::init();
+        }
+    }
+
+    #[gpui::test]
+    async fn test_fake(cx: &mut TestAppContext) {
+        let (client, fake) = FakeDebugAdapter::new(
+            DebugAdapterId(0),
+            "the-adapter".to_string(),
+            Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+
+        let (stopped_tx, stopped_rx) = channel::unbounded();
+        let (output_tx, output_rx) = channel::unbounded();
+        client
+            .on_event::<events::Stopped, _>(move |body, _| stopped_tx.try_send(body).unwrap())
+            .detach();
+        client
+            .on_event::<events::Output, _>(move |body, _| output_tx.try_send(body).unwrap())
+            .detach();
+
+        let client = cx
+            .update(|cx| client.initialize(InitializeRequestArguments::default(), cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            client.capabilities().supports_configuration_done_request,
+            Some(true)
+        );
+
+        let mut breakpoints_set =
+            fake.handle_request::<requests::SetBreakpoints, _, _>(|arguments, _| async move {
+                assert_eq!(arguments.source.path.as_deref(), Some("/root/src/main.rs"));
+                Ok(SetBreakpointsResponse {
+                    breakpoints: arguments
+                        .breakpoints
+                        .into_iter()
+                        .map(|breakpoint| Breakpoint {
+                            verified: breakpoint.line < 10,
+                            line: Some(breakpoint.line),
+                            ..Default::default()
+                        })
+                        .collect(),
+                })
+            });
+        let response = client
+            .request::<requests::SetBreakpoints>(SetBreakpointsArguments {
+                source: Source {
+                    path: Some("/root/src/main.rs".to_string()),
+                    ..Default::default()
+                },
+                breakpoints: vec![
+                    SourceBreakpoint {
+                        line: 3,
+                        ..Default::default()
+                    },
+                    SourceBreakpoint {
+                        line: 12,
+                        ..Default::default()
+                    },
+                ],
+                source_modified: None,
+            })
+            .await
+            .unwrap();
+        breakpoints_set.next().await.unwrap();
+        assert_eq!(
+            response
+                .breakpoints
+                .iter()
+                .map(|breakpoint| (breakpoint.line, breakpoint.verified))
+                .collect::<Vec<_>>(),
+            vec![(Some(3), true), (Some(12), false)]
+        );
+
+        fake.send_event::<events::Output>(OutputEventBody {
+            category: Some("stdout".to_string()),
+            output: "hello\n".to_string(),
+        });
+        fake.send_event::<events::Stopped>(StoppedEventBody {
+            reason: "breakpoint".to_string(),
+            thread_id: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(output_rx.recv().await.unwrap().output, "hello\n");
+        assert_eq!(stopped_rx.recv().await.unwrap().thread_id, Some(1));
+
+        let error = client
+            .request::<requests::Evaluate>(EvaluateArguments {
+                expression: "1 + 1".to_string(),
+                frame_id: None,
+                context: Some(EvaluateContext::Repl),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "unsupported request evaluate");
+
+        let mut disconnected =
+            fake.handle_request::<requests::Disconnect, _, _>(|arguments, _| async move {
+                assert_eq!(arguments.terminate_debuggee, Some(true));
+                Ok(())
+            });
+        drop(client);
+        disconnected.next().await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_read_headers() {
+        let mut buf = Vec::new();
+        let mut reader = smol::io::BufReader::new(b"Content-Length: 123\r\n\r\n" as &[u8]);
+        read_headers(&mut reader, &mut buf).await.unwrap();
+        assert_eq!(buf, b"Content-Length: 123\r\n\r\n");
+
+        let mut buf = Vec::new();
+        let mut reader =
+            smol::io::BufReader::new(b"Content-Length: 19\r\n\r\n{\"seq\":1,\"type\":1}" as &[u8]);
+        read_headers(&mut reader, &mut buf).await.unwrap();
+        assert_eq!(buf, b"Content-Length: 19\r\n\r\n");
+    }
+
+    #[test]
+    fn test_deserialize_empty_body() {
+        deserialize_body::<()>("{}").unwrap();
+        deserialize_body::<()>("null").unwrap();
+        assert_eq!(
+            deserialize_body::<Option<TerminatedEventBody>>("{}").unwrap(),
+            Some(TerminatedEventBody::default())
+        );
+        assert!(deserialize_body::<ThreadsResponse>("null").is_err());
+    }
+}
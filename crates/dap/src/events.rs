@@ -0,0 +1,45 @@
+//! Events of the Debug Adapter Protocol, used by Zed.
+//!
+//! [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Events)
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::types::*;
+
+/// An event, sent from the debug adapter to the client.
+pub trait Event {
+    type Body: DeserializeOwned + Serialize + Send + Sync + 'static;
+    const EVENT: &'static str;
+}
+
+macro_rules! event {
+    ($(#[$meta:meta])* $name:ident, $event:literal, $body:ty) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub enum $name {}
+
+        impl Event for $name {
+            type Body = $body;
+            const EVENT: &'static str = $event;
+        }
+    };
+}
+
+event!(
+    /// The adapter is ready to accept configuration requests, e.g. the breakpoints.
+    Initialized,
+    "initialized",
+    ()
+);
+event!(Stopped, "stopped", StoppedEventBody);
+event!(Continued, "continued", ContinuedEventBody);
+event!(Exited, "exited", ExitedEventBody);
+event!(
+    /// Debugging has ended, though the adapter may still be running.
+    Terminated,
+    "terminated",
+    Option<TerminatedEventBody>
+);
+event!(Thread, "thread", ThreadEventBody);
+event!(Output, "output", OutputEventBody);
+event!(Breakpoint, "breakpoint", BreakpointEventBody);
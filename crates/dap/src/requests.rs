@@ -0,0 +1,110 @@
+//! Requests of the Debug Adapter Protocol, used by Zed.
+//!
+//! [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Requests)
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::types::*;
+
+/// A request, sent from the client to the debug adapter or, as a reverse request, from the adapter to the client.
+pub trait Request {
+    type Arguments: DeserializeOwned + Serialize + Send + Sync + 'static;
+    type Response: DeserializeOwned + Serialize + Send + Sync + 'static;
+    const COMMAND: &'static str;
+}
+
+macro_rules! request {
+    ($(#[$meta:meta])* $name:ident, $command:literal, $arguments:ty, $response:ty) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub enum $name {}
+
+        impl Request for $name {
+            type Arguments = $arguments;
+            type Response = $response;
+            const COMMAND: &'static str = $command;
+        }
+    };
+}
+
+request!(
+    Initialize,
+    "initialize",
+    InitializeRequestArguments,
+    Capabilities
+);
+request!(
+    /// Starts the debuggee, with the adapter-specific arguments.
+    Launch,
+    "launch",
+    serde_json::Value,
+    ()
+);
+request!(
+    /// Attaches to a running debuggee, with the adapter-specific arguments.
+    Attach,
+    "attach",
+    serde_json::Value,
+    ()
+);
+request!(
+    /// Signals that the client has sent all of its configuration, e.g. the breakpoints.
+    ConfigurationDone,
+    "configurationDone",
+    (),
+    ()
+);
+request!(
+    /// Replaces all breakpoints in the source.
+    SetBreakpoints,
+    "setBreakpoints",
+    SetBreakpointsArguments,
+    SetBreakpointsResponse
+);
+request!(Threads, "threads", (), ThreadsResponse);
+request!(
+    StackTrace,
+    "stackTrace",
+    StackTraceArguments,
+    StackTraceResponse
+);
+request!(Scopes, "scopes", ScopesArguments, ScopesResponse);
+request!(
+    Variables,
+    "variables",
+    VariablesArguments,
+    VariablesResponse
+);
+request!(Evaluate, "evaluate", EvaluateArguments, EvaluateResponse);
+request!(Continue, "continue", ContinueArguments, ContinueResponse);
+request!(
+    /// Steps over the current line of the thread.
+    Next,
+    "next",
+    ThreadArguments,
+    ()
+);
+request!(StepIn, "stepIn", ThreadArguments, ());
+request!(StepOut, "stepOut", ThreadArguments, ());
+request!(Pause, "pause", ThreadArguments, ());
+request!(
+    /// Asks the adapter to terminate the debuggee gracefully.
+    Terminate,
+    "terminate",
+    TerminateArguments,
+    ()
+);
+request!(
+    /// Disconnects from the debuggee, terminating it if it was launched, and shuts the adapter down.
+    Disconnect,
+    "disconnect",
+    DisconnectArguments,
+    ()
+);
+request!(
+    /// Reverse request to run the debuggee in a terminal.
+    RunInTerminal,
+    "runInTerminal",
+    RunInTerminalRequestArguments,
+    RunInTerminalResponse
+);
@@ -0,0 +1,752 @@
+use std::{future::Future, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use dap::{
+    events, requests, ContinueArguments, DebugAdapterBinary, DebugAdapterClient, DebugAdapterId,
+    EvaluateArguments, EvaluateContext, InitializeRequestArguments, OutputEventBody, Scope,
+    ScopesArguments, SetBreakpointsArguments, Source, SourceBreakpoint, StackFrame,
+    StackTraceArguments, StoppedEventBody, Thread, ThreadArguments, Variable, VariablesArguments,
+};
+use futures::{future::join_all, FutureExt as _};
+use gpui::{AsyncAppContext, EventEmitter, Model, ModelContext, Subscription, Task, WeakModel};
+use language::BufferId;
+use project::Project;
+use task::{DebugRequestKind, SpawnInTerminal};
+use util::ResultExt;
+
+/// How many stack frames of the stopped thread to show.
+const MAX_STACK_FRAMES: u64 = 200;
+
+/// A program, debugged with a debug adapter, started by a debug task.
+pub struct DebugSession {
+    label: String,
+    project: Model<Project>,
+    client: Option<Arc<DebugAdapterClient>>,
+    status: DebugSessionStatus,
+    threads: Vec<Thread>,
+    stopped_thread_id: Option<i64>,
+    stack_frames: Vec<StackFrame>,
+    selected_frame_ix: Option<usize>,
+    scopes: Vec<Scope>,
+    /// Children of the scopes and structured variables, by their variables references.
+    variables: HashMap<i64, Vec<Variable>>,
+    expanded_variables: HashSet<i64>,
+    watches: Vec<Watch>,
+    console: Vec<ConsoleEntry>,
+    /// Whether the adapter got the initial breakpoints, so the changed ones are sent to it right away.
+    configured: bool,
+    _start_task: Task<()>,
+    stopped_state_task: Task<()>,
+    frame_task: Task<()>,
+    _adapter_subscriptions: Vec<dap::Subscription>,
+    _project_subscription: Subscription,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugSessionStatus {
+    Starting,
+    Running,
+    Stopped,
+    Exited,
+}
+
+pub enum DebugSessionEvent {
+    /// The debuggee has stopped, and a frame of its stopped thread got selected.
+    FrameSelected,
+    /// The debuggee has resumed running.
+    Resumed,
+    /// The debuggee and its adapter have exited.
+    Exited,
+}
+
+/// An expression, evaluated in the selected stack frame every time the debuggee stops.
+pub struct Watch {
+    pub expression: String,
+    pub result: Option<Result<String, String>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleEntryKind {
+    /// Output of the debuggee or the adapter.
+    Output,
+    /// An expression, evaluated in the console.
+    Input,
+    /// Result of an evaluated expression.
+    Result,
+    Error,
+}
+
+pub struct ConsoleEntry {
+    pub kind: ConsoleEntryKind,
+    pub text: String,
+}
+
+impl EventEmitter<DebugSessionEvent> for DebugSession {}
+
+impl DebugSession {
+    /// Spawns the debug task's command as a debug adapter and starts debugging with the task's configuration.
+    pub fn start(
+        adapter_id: DebugAdapterId,
+        spawn_in_terminal: SpawnInTerminal,
+        project: Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let project_subscription = cx.subscribe(&project, |session, _, event, cx| {
+            if let project::Event::BreakpointsChanged(buffer_id) = event {
+                session.send_buffer_breakpoints(*buffer_id, cx);
+            }
+        });
+        let label = spawn_in_terminal.label.clone();
+        let start_task = cx.spawn(|this, mut cx| async move {
+            let result =
+                Self::run_adapter(this.clone(), adapter_id, spawn_in_terminal, &mut cx).await;
+            if let Err(e) = result {
+                this.update(&mut cx, |session, cx| {
+                    session.push_console_entry(ConsoleEntryKind::Error, format!("{e:#}"));
+                    session.finish(cx);
+                })
+                .ok();
+            }
+        });
+        Self {
+            label,
+            project,
+            client: None,
+            status: DebugSessionStatus::Starting,
+            threads: Vec::new(),
+            stopped_thread_id: None,
+            stack_frames: Vec::new(),
+            selected_frame_ix: None,
+            scopes: Vec::new(),
+            variables: HashMap::default(),
+            expanded_variables: HashSet::default(),
+            watches: Vec::new(),
+            console: Vec::new(),
+            configured: false,
+            _start_task: start_task,
+            stopped_state_task: Task::ready(()),
+            frame_task: Task::ready(()),
+            _adapter_subscriptions: Vec::new(),
+            _project_subscription: project_subscription,
+        }
+    }
+
+    /// Initializes the adapter, launches or attaches to the debuggee and sends the breakpoints to the adapter,
+    /// following the [DAP initialization sequence](https://microsoft.github.io/debug-adapter-protocol/overview#initialization).
+    async fn run_adapter(
+        this: WeakModel<Self>,
+        adapter_id: DebugAdapterId,
+        spawn_in_terminal: SpawnInTerminal,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let debug = spawn_in_terminal
+            .debug
+            .context("task has no debug configuration")?;
+        let cwd = match spawn_in_terminal.cwd {
+            Some(cwd) => cwd,
+            None => std::env::current_dir().context("getting the current directory")?,
+        };
+        let binary = DebugAdapterBinary {
+            path: PathBuf::from(spawn_in_terminal.command),
+            arguments: spawn_in_terminal.args.into_iter().map(Into::into).collect(),
+            env: Some(spawn_in_terminal.env.into_iter().collect()),
+        };
+        let client = DebugAdapterClient::new(adapter_id, binary, &cwd, cx.clone())?;
+
+        let (initialized_tx, initialized_rx) = futures::channel::oneshot::channel();
+        let mut initialized_tx = Some(initialized_tx);
+        let subscriptions = vec![
+            client.on_event::<events::Initialized, _>(move |_, _| {
+                if let Some(initialized_tx) = initialized_tx.take() {
+                    initialized_tx.send(()).ok();
+                }
+            }),
+            client.on_event::<events::Stopped, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |session, cx| session.handle_stopped(body, cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Continued, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |session, cx| session.handle_resumed(cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Exited, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |session, cx| {
+                        session.push_console_entry(
+                            ConsoleEntryKind::Output,
+                            format!("Process exited with code {}", body.exit_code),
+                        );
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Terminated, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |session, cx| session.finish(cx)).ok();
+                }
+            }),
+            client.on_event::<events::Output, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |session, cx| session.handle_output(body, cx))
+                        .ok();
+                }
+            }),
+        ];
+
+        let client = cx
+            .update(|cx| {
+                client.initialize(
+                    InitializeRequestArguments {
+                        client_id: Some("zed".to_string()),
+                        client_name: Some("Zed".to_string()),
+                        adapter_id: debug.adapter.clone(),
+                        locale: None,
+                        lines_start_at1: Some(true),
+                        columns_start_at1: Some(true),
+                        path_format: Some("path".to_string()),
+                        supports_variable_type: Some(true),
+                        supports_run_in_terminal_request: Some(false),
+                    },
+                    cx,
+                )
+            })?
+            .await
+            .context("initializing the debug adapter")?;
+        this.update(cx, |session, _| {
+            session.client = Some(client.clone());
+            session._adapter_subscriptions = subscriptions;
+        })?;
+
+        let launch = match debug.request {
+            DebugRequestKind::Launch => client
+                .request::<requests::Launch>(debug.configuration)
+                .boxed_local(),
+            DebugRequestKind::Attach => client
+                .request::<requests::Attach>(debug.configuration)
+                .boxed_local(),
+        };
+        // Adapters may respond to the launch request only after the configuration is done,
+        // or fail to launch before sending the `initialized` event.
+        let mut launch = launch.fuse();
+        let mut initialized = initialized_rx.fuse();
+        let mut launched = false;
+        futures::select_biased! {
+            initialized_result = initialized => {
+                initialized_result.context("debug adapter has exited before initialization")?;
+            }
+            launch_result = launch => {
+                launch_result.context("starting the debuggee")?;
+                launched = true;
+                (&mut initialized)
+                    .await
+                    .context("debug adapter has exited before initialization")?;
+            }
+        }
+
+        let set_breakpoints = this.update(cx, |session, cx| {
+            let breakpoints = session.project.read(cx).all_breakpoints(cx);
+            join_all(
+                breakpoints
+                    .into_iter()
+                    .map(|(abs_path, rows)| set_breakpoints(&client, abs_path, rows)),
+            )
+        })?;
+        set_breakpoints.await;
+        if client.capabilities().supports_configuration_done_request == Some(true) {
+            client
+                .request::<requests::ConfigurationDone>(())
+                .await
+                .context("finishing the debug adapter configuration")?;
+        }
+        this.update(cx, |session, cx| {
+            session.configured = true;
+            if session.status == DebugSessionStatus::Starting {
+                session.status = DebugSessionStatus::Running;
+            }
+            cx.notify();
+        })?;
+        if !launched {
+            launch.await.context("starting the debuggee")?;
+        }
+        Ok(())
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn status(&self) -> DebugSessionStatus {
+        self.status
+    }
+
+    pub fn threads(&self) -> &[Thread] {
+        &self.threads
+    }
+
+    pub fn stopped_thread_id(&self) -> Option<i64> {
+        self.stopped_thread_id
+    }
+
+    pub fn stack_frames(&self) -> &[StackFrame] {
+        &self.stack_frames
+    }
+
+    pub fn selected_frame_ix(&self) -> Option<usize> {
+        self.selected_frame_ix
+    }
+
+    pub fn selected_frame(&self) -> Option<&StackFrame> {
+        self.stack_frames.get(self.selected_frame_ix?)
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// Fetched children of a scope or a structured variable.
+    pub fn variables(&self, variables_reference: i64) -> Option<&[Variable]> {
+        self.variables
+            .get(&variables_reference)
+            .map(|variables| variables.as_slice())
+    }
+
+    pub fn is_expanded(&self, variables_reference: i64) -> bool {
+        self.expanded_variables.contains(&variables_reference)
+    }
+
+    pub fn watches(&self) -> &[Watch] {
+        &self.watches
+    }
+
+    pub fn console(&self) -> &[ConsoleEntry] {
+        &self.console
+    }
+
+    pub fn continue_(&mut self, cx: &mut ModelContext<Self>) {
+        self.resume(
+            |client, thread_id| {
+                client
+                    .request::<requests::Continue>(ContinueArguments {
+                        thread_id,
+                        single_thread: None,
+                    })
+                    .map(|response| response.map(|_| ()))
+            },
+            cx,
+        );
+    }
+
+    pub fn step_over(&mut self, cx: &mut ModelContext<Self>) {
+        self.resume(
+            |client, thread_id| client.request::<requests::Next>(ThreadArguments { thread_id }),
+            cx,
+        );
+    }
+
+    pub fn step_into(&mut self, cx: &mut ModelContext<Self>) {
+        self.resume(
+            |client, thread_id| client.request::<requests::StepIn>(ThreadArguments { thread_id }),
+            cx,
+        );
+    }
+
+    pub fn step_out(&mut self, cx: &mut ModelContext<Self>) {
+        self.resume(
+            |client, thread_id| client.request::<requests::StepOut>(ThreadArguments { thread_id }),
+            cx,
+        );
+    }
+
+    pub fn pause(&mut self, cx: &mut ModelContext<Self>) {
+        if self.status != DebugSessionStatus::Running {
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let thread_id = self
+            .stopped_thread_id
+            .or_else(|| self.threads.first().map(|thread| thread.id))
+            .unwrap_or(1);
+        let request = client.request::<requests::Pause>(ThreadArguments { thread_id });
+        cx.spawn(|this, mut cx| async move {
+            if let Err(e) = request.await {
+                this.update(&mut cx, |session, cx| {
+                    session.push_console_entry(ConsoleEntryKind::Error, format!("{e:#}"));
+                    cx.notify();
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Terminates the debuggee and shuts its adapter down.
+    pub fn stop(&mut self, cx: &mut ModelContext<Self>) {
+        if let Some(shutdown) = self.client.as_ref().and_then(|client| client.shutdown()) {
+            cx.background_executor().spawn(shutdown).detach();
+        }
+        self.finish(cx);
+    }
+
+    /// Sends a resuming request for the stopped thread.
+    fn resume<F, Fut>(&mut self, request: F, cx: &mut ModelContext<Self>)
+    where
+        F: FnOnce(&DebugAdapterClient, i64) -> Fut,
+        Fut: 'static + Future<Output = Result<()>>,
+    {
+        if self.status != DebugSessionStatus::Stopped {
+            return;
+        }
+        let (Some(client), Some(thread_id)) = (self.client.clone(), self.stopped_thread_id) else {
+            return;
+        };
+        let request = request(&client, thread_id);
+        self.handle_resumed(cx);
+        cx.spawn(|this, mut cx| async move {
+            if let Err(e) = request.await {
+                this.update(&mut cx, |session, cx| {
+                    session.push_console_entry(ConsoleEntryKind::Error, format!("{e:#}"));
+                    cx.notify();
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn handle_stopped(&mut self, body: StoppedEventBody, cx: &mut ModelContext<Self>) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        self.status = DebugSessionStatus::Stopped;
+        if let Some(text) = body.text.or(body.description) {
+            self.push_console_entry(ConsoleEntryKind::Output, text);
+        }
+        let stopped_thread_id = body.thread_id.or(self.stopped_thread_id);
+        self.stopped_state_task = cx.spawn(|this, mut cx| async move {
+            let threads = client
+                .request::<requests::Threads>(())
+                .await
+                .log_err()
+                .map(|response| response.threads)
+                .unwrap_or_default();
+            let thread_id = stopped_thread_id.or_else(|| threads.first().map(|thread| thread.id));
+            let stack_frames = match thread_id {
+                Some(thread_id) => client
+                    .request::<requests::StackTrace>(StackTraceArguments {
+                        thread_id,
+                        start_frame: Some(0),
+                        levels: Some(MAX_STACK_FRAMES),
+                    })
+                    .await
+                    .log_err()
+                    .map(|response| response.stack_frames)
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            this.update(&mut cx, |session, cx| {
+                session.threads = threads;
+                session.stopped_thread_id = thread_id;
+                session.stack_frames = stack_frames;
+                session.select_frame(0, cx);
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn handle_resumed(&mut self, cx: &mut ModelContext<Self>) {
+        if self.status == DebugSessionStatus::Exited {
+            return;
+        }
+        self.status = DebugSessionStatus::Running;
+        self.stack_frames.clear();
+        self.selected_frame_ix = None;
+        self.clear_frame_state();
+        self.stopped_state_task = Task::ready(());
+        cx.emit(DebugSessionEvent::Resumed);
+        cx.notify();
+    }
+
+    fn handle_output(&mut self, body: OutputEventBody, cx: &mut ModelContext<Self>) {
+        if body.category.as_deref() == Some("telemetry") {
+            return;
+        }
+        let output = body.output.strip_suffix('\n').unwrap_or(&body.output);
+        self.push_console_entry(ConsoleEntryKind::Output, output.to_string());
+        cx.notify();
+    }
+
+    fn finish(&mut self, cx: &mut ModelContext<Self>) {
+        if self.status == DebugSessionStatus::Exited {
+            return;
+        }
+        self.status = DebugSessionStatus::Exited;
+        self.configured = false;
+        self.stack_frames.clear();
+        self.selected_frame_ix = None;
+        self.clear_frame_state();
+        self._adapter_subscriptions.clear();
+        // Dropping the last reference to the client shuts the adapter down.
+        self.client = None;
+        self.stopped_state_task = Task::ready(());
+        cx.emit(DebugSessionEvent::Exited);
+        cx.notify();
+    }
+
+    fn clear_frame_state(&mut self) {
+        self.scopes.clear();
+        self.variables.clear();
+        self.expanded_variables.clear();
+        for watch in &mut self.watches {
+            watch.result = None;
+        }
+        self.frame_task = Task::ready(());
+    }
+
+    /// Selects a stack frame of the stopped thread, fetching its scopes and evaluating the watches in it.
+    pub fn select_frame(&mut self, frame_ix: usize, cx: &mut ModelContext<Self>) {
+        self.clear_frame_state();
+        self.selected_frame_ix = (frame_ix < self.stack_frames.len()).then_some(frame_ix);
+        let (Some(client), Some(frame)) = (self.client.clone(), self.selected_frame()) else {
+            cx.notify();
+            return;
+        };
+        let frame_id = frame.id;
+        let expressions = self
+            .watches
+            .iter()
+            .map(|watch| watch.expression.clone())
+            .collect::<Vec<_>>();
+        self.frame_task = cx.spawn(|this, mut cx| async move {
+            let scopes = client
+                .request::<requests::Scopes>(ScopesArguments { frame_id })
+                .await
+                .log_err()
+                .map(|response| response.scopes)
+                .unwrap_or_default();
+            // Expensive scopes, like globals, are fetched only when expanded.
+            let variables = join_all(
+                scopes
+                    .iter()
+                    .filter(|scope| !scope.expensive)
+                    .map(|scope| fetch_variables(&client, scope.variables_reference)),
+            )
+            .await;
+            let watch_results = join_all(expressions.into_iter().map(|expression| {
+                evaluate(&client, expression, Some(frame_id), EvaluateContext::Watch)
+            }))
+            .await;
+            this.update(&mut cx, |session, cx| {
+                for scope in scopes.iter().filter(|scope| !scope.expensive) {
+                    session.expanded_variables.insert(scope.variables_reference);
+                }
+                session.variables.extend(variables.into_iter().flatten());
+                session.scopes = scopes;
+                for (watch, result) in session.watches.iter_mut().zip(watch_results) {
+                    watch.result = Some(result);
+                }
+                cx.emit(DebugSessionEvent::FrameSelected);
+                cx.notify();
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    /// Expands or collapses a scope or a structured variable, fetching its children when expanded first.
+    pub fn toggle_variable(&mut self, variables_reference: i64, cx: &mut ModelContext<Self>) {
+        if !self.expanded_variables.insert(variables_reference) {
+            self.expanded_variables.remove(&variables_reference);
+            cx.notify();
+            return;
+        }
+        cx.notify();
+        if self.variables.contains_key(&variables_reference) {
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        cx.spawn(|this, mut cx| async move {
+            if let Some((variables_reference, variables)) =
+                fetch_variables(&client, variables_reference).await
+            {
+                this.update(&mut cx, |session, cx| {
+                    session.variables.insert(variables_reference, variables);
+                    cx.notify();
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    pub fn add_watch(&mut self, expression: String, cx: &mut ModelContext<Self>) {
+        let expression = expression.trim().to_string();
+        if expression.is_empty() {
+            return;
+        }
+        let watch_ix = self.watches.len();
+        self.watches.push(Watch {
+            expression: expression.clone(),
+            result: None,
+        });
+        cx.notify();
+
+        let (Some(client), Some(frame)) = (self.client.clone(), self.selected_frame()) else {
+            return;
+        };
+        let frame_id = frame.id;
+        cx.spawn(|this, mut cx| async move {
+            let result = evaluate(
+                &client,
+                expression.clone(),
+                Some(frame_id),
+                EvaluateContext::Watch,
+            )
+            .await;
+            this.update(&mut cx, |session, cx| {
+                if let Some(watch) = session
+                    .watches
+                    .get_mut(watch_ix)
+                    .filter(|watch| watch.expression == expression)
+                {
+                    watch.result = Some(result);
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub fn remove_watch(&mut self, watch_ix: usize, cx: &mut ModelContext<Self>) {
+        if watch_ix < self.watches.len() {
+            self.watches.remove(watch_ix);
+            cx.notify();
+        }
+    }
+
+    /// Evaluates the console input in the selected stack frame, or globally, if the debuggee is running.
+    pub fn evaluate_in_console(&mut self, expression: String, cx: &mut ModelContext<Self>) {
+        let expression = expression.trim().to_string();
+        if expression.is_empty() {
+            return;
+        }
+        self.push_console_entry(ConsoleEntryKind::Input, expression.clone());
+        cx.notify();
+        let Some(client) = self.client.clone() else {
+            self.push_console_entry(
+                ConsoleEntryKind::Error,
+                "The debug session has ended".to_string(),
+            );
+            return;
+        };
+        let frame_id = self.selected_frame().map(|frame| frame.id);
+        cx.spawn(|this, mut cx| async move {
+            let result = evaluate(&client, expression, frame_id, EvaluateContext::Repl).await;
+            this.update(&mut cx, |session, cx| {
+                match result {
+                    Ok(result) => session.push_console_entry(ConsoleEntryKind::Result, result),
+                    Err(error) => session.push_console_entry(ConsoleEntryKind::Error, error),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn push_console_entry(&mut self, kind: ConsoleEntryKind, text: String) {
+        self.console.push(ConsoleEntry { kind, text });
+    }
+
+    /// Sends the changed breakpoints of the buffer to the adapter, replacing the file's previous breakpoints.
+    fn send_buffer_breakpoints(&mut self, buffer_id: BufferId, cx: &mut ModelContext<Self>) {
+        if !self.configured {
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some((abs_path, rows)) = self.project.read(cx).buffer_breakpoints(buffer_id, cx) else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(set_breakpoints(&client, abs_path, rows))
+            .detach();
+    }
+}
+
+fn set_breakpoints(
+    client: &DebugAdapterClient,
+    abs_path: PathBuf,
+    rows: Vec<u32>,
+) -> impl 'static + Future<Output = ()> {
+    let request = client.request::<requests::SetBreakpoints>(SetBreakpointsArguments {
+        source: Source {
+            name: abs_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            path: Some(abs_path.to_string_lossy().into_owned()),
+            source_reference: None,
+        },
+        breakpoints: rows
+            .into_iter()
+            .map(|row| SourceBreakpoint {
+                line: row as u64 + 1,
+                ..SourceBreakpoint::default()
+            })
+            .collect(),
+        source_modified: None,
+    });
+    async move {
+        request
+            .await
+            .with_context(|| format!("setting breakpoints in {abs_path:?}"))
+            .log_err();
+    }
+}
+
+async fn fetch_variables(
+    client: &DebugAdapterClient,
+    variables_reference: i64,
+) -> Option<(i64, Vec<Variable>)> {
+    let response = client
+        .request::<requests::Variables>(VariablesArguments {
+            variables_reference,
+        })
+        .await
+        .log_err()?;
+    Some((variables_reference, response.variables))
+}
+
+async fn evaluate(
+    client: &DebugAdapterClient,
+    expression: String,
+    frame_id: Option<i64>,
+    context: EvaluateContext,
+) -> Result<String, String> {
+    client
+        .request::<requests::Evaluate>(EvaluateArguments {
+            expression,
+            frame_id,
+            context: Some(context),
+        })
+        .await
+        .map(|response| response.result)
+        .map_err(|e| format!("{e:#}"))
+}
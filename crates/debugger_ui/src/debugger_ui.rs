@@ -0,0 +1,804 @@
+mod debug_session;
+mod debugger_panel_settings;
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use dap::{DebugAdapterId, Variable};
+use db::kvp::KEY_VALUE_STORE;
+use debug_session::{ConsoleEntryKind, DebugSession, DebugSessionEvent, DebugSessionStatus};
+use debugger_panel_settings::{DebuggerDockPosition, DebuggerPanelSettings};
+use editor::{scroll::Autoscroll, Editor, EditorElement, EditorStyle};
+use gpui::{
+    actions, Action, AnyElement, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, FontStyle, FontWeight, InteractiveElement, Model, ParentElement, Pixels, Render,
+    Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext as _, WeakView,
+    WhiteSpace, WindowContext,
+};
+use language::{Bias, Point};
+use menu::Confirm;
+use project::{Fs, Project};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use task::SpawnInTerminal;
+use theme::ThemeSettings;
+use ui::{prelude::*, IconButton, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const DEBUGGER_PANEL_KEY: &str = "DebuggerPanel";
+
+actions!(
+    debugger,
+    [
+        ToggleFocus,
+        Continue,
+        Pause,
+        StepOver,
+        StepInto,
+        StepOut,
+        Stop
+    ]
+);
+
+pub fn init_settings(cx: &mut AppContext) {
+    DebuggerPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<DebuggerPanel>(cx);
+            })
+            .register_action(|workspace, action: &Continue, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::continue_)
+            })
+            .register_action(|workspace, action: &Pause, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::pause)
+            })
+            .register_action(|workspace, action: &StepOver, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::step_over)
+            })
+            .register_action(|workspace, action: &StepInto, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::step_into)
+            })
+            .register_action(|workspace, action: &StepOut, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::step_out)
+            })
+            .register_action(|workspace, action: &Stop, cx| {
+                DebuggerPanel::update_session(workspace, action, cx, DebugSession::stop)
+            });
+    })
+    .detach();
+}
+
+/// Highlights the line the debuggee has stopped at.
+enum DebuggerStoppedLine {}
+
+/// A panel to control the debug session, started by a debug task, and to inspect the stopped debuggee.
+pub struct DebuggerPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    session: Option<Model<DebugSession>>,
+    next_adapter_id: usize,
+    console_input: View<Editor>,
+    watch_input: View<Editor>,
+    /// The editor, that has the stopped line highlighted.
+    stopped_line_editor: Option<WeakView<Editor>>,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _session_subscriptions: Vec<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDebuggerPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+impl DebuggerPanel {
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = cx.view().clone();
+        cx.new_view(|cx| {
+            let mut debugger_settings = *DebuggerPanelSettings::get_global(cx);
+            let subscriptions = vec![
+                cx.subscribe(&workspace_handle, |panel, _, event, cx| {
+                    if let workspace::Event::StartDebugSession(spawn_in_terminal) = event {
+                        panel.start_session(spawn_in_terminal.clone(), cx);
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(move |_, cx| {
+                    let new_settings = *DebuggerPanelSettings::get_global(cx);
+                    if debugger_settings != new_settings {
+                        debugger_settings = new_settings;
+                        cx.notify();
+                    }
+                }),
+            ];
+            let console_input = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Evaluate an expression…", cx);
+                editor
+            });
+            let watch_input = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Add a watch expression…", cx);
+                editor
+            });
+
+            Self {
+                workspace: workspace_handle.downgrade(),
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                session: None,
+                next_adapter_id: 0,
+                console_input,
+                watch_input,
+                stopped_line_editor: None,
+                width: None,
+                height: None,
+                pending_serialization: Task::ready(None),
+                _session_subscriptions: Vec::new(),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(DEBUGGER_PANEL_KEY) })
+            .await
+            .map_err(|e| anyhow!("Failed to load debugger panel: {}", e))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedDebuggerPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = DebuggerPanel::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.height = serialized_panel.height.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        DEBUGGER_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedDebuggerPanel { width, height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Dispatches a debugger action to the current debug session of the workspace, if any.
+    fn update_session<A: Action>(
+        workspace: &mut Workspace,
+        _: &A,
+        cx: &mut ViewContext<Workspace>,
+        update: fn(&mut DebugSession, &mut gpui::ModelContext<DebugSession>),
+    ) {
+        let Some(panel) = workspace.panel::<DebuggerPanel>(cx) else {
+            return;
+        };
+        if let Some(session) = panel.read(cx).session.clone() {
+            session.update(cx, update);
+        }
+    }
+
+    /// Starts debugging the debug task, stopping the previous debug session, if it is still running.
+    fn start_session(&mut self, spawn_in_terminal: SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        if let Some(session) = self.session.take() {
+            session.update(cx, |session, cx| session.stop(cx));
+        }
+        self.clear_stopped_line(cx);
+
+        let adapter_id = DebugAdapterId(util::post_inc(&mut self.next_adapter_id));
+        let project = self.project.clone();
+        let session =
+            cx.new_model(|cx| DebugSession::start(adapter_id, spawn_in_terminal, project, cx));
+        self._session_subscriptions = vec![
+            cx.subscribe(&session, |panel, _, event, cx| match event {
+                DebugSessionEvent::FrameSelected => panel.reveal_selected_frame(cx),
+                DebugSessionEvent::Resumed | DebugSessionEvent::Exited => {
+                    panel.clear_stopped_line(cx)
+                }
+            }),
+            cx.observe(&session, |_, _, cx| cx.notify()),
+        ];
+        self.session = Some(session);
+
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            workspace.update(&mut cx, |workspace, cx| workspace.open_panel::<Self>(cx))
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    /// Opens the file of the selected stack frame and highlights the frame's line.
+    fn reveal_selected_frame(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_stopped_line(cx);
+        let Some(session) = self.session.as_ref() else {
+            return;
+        };
+        let Some(frame) = session.read(cx).selected_frame() else {
+            return;
+        };
+        let Some(abs_path) = frame
+            .source
+            .as_ref()
+            .and_then(|source| source.path.as_ref())
+            .map(PathBuf::from)
+        else {
+            return;
+        };
+        let point = Point::new(
+            frame.line.saturating_sub(1) as u32,
+            frame.column.saturating_sub(1) as u32,
+        );
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(abs_path, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|panel, mut cx| async move {
+            let item = open_task.await?;
+            let Some(editor) = item.downcast::<Editor>() else {
+                return anyhow::Ok(());
+            };
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point(point, Bias::Left);
+                let anchor = snapshot.anchor_before(point);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point])
+                });
+                editor.highlight_rows::<DebuggerStoppedLine>(
+                    anchor..=anchor,
+                    Some(cx.theme().colors().editor_highlighted_line_background),
+                    false,
+                    cx,
+                );
+            })?;
+            panel.update(&mut cx, |panel, _| {
+                panel.stopped_line_editor = Some(editor.downgrade());
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn clear_stopped_line(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(editor) = self
+            .stopped_line_editor
+            .take()
+            .and_then(|editor| editor.upgrade())
+        {
+            editor.update(cx, |editor, cx| {
+                editor.clear_row_highlights::<DebuggerStoppedLine>();
+                cx.notify();
+            });
+        }
+    }
+
+    fn evaluate_console_input(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+        let expression = self.console_input.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(cx);
+            expression
+        });
+        session.update(cx, |session, cx| {
+            session.evaluate_in_console(expression, cx)
+        });
+    }
+
+    fn add_watch(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+        let expression = self.watch_input.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(cx);
+            expression
+        });
+        session.update(cx, |session, cx| session.add_watch(expression, cx));
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let session = self.session.as_ref().map(|session| session.read(cx));
+        let status = session.map(|session| session.status());
+        let is_stopped = status == Some(DebugSessionStatus::Stopped);
+        let is_running = status == Some(DebugSessionStatus::Running);
+        let is_active = matches!(
+            status,
+            Some(
+                DebugSessionStatus::Starting
+                    | DebugSessionStatus::Running
+                    | DebugSessionStatus::Stopped
+            )
+        );
+        let title = match session {
+            Some(session) => {
+                let status = match session.status() {
+                    DebugSessionStatus::Starting => "starting",
+                    DebugSessionStatus::Running => "running",
+                    DebugSessionStatus::Stopped => "paused",
+                    DebugSessionStatus::Exited => "exited",
+                };
+                format!("{} ({status})", session.label())
+            }
+            None => "No debug session".to_string(),
+        };
+
+        fn control_button(
+            id: &'static str,
+            icon: IconName,
+            tooltip: &'static str,
+            action: Box<dyn Action>,
+            enabled: bool,
+        ) -> IconButton {
+            let tooltip_action = action.boxed_clone();
+            IconButton::new(id, icon)
+                .icon_size(IconSize::Small)
+                .disabled(!enabled)
+                .tooltip(move |cx| Tooltip::for_action(tooltip, &*tooltip_action, cx))
+                .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+        }
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(if is_running {
+                        control_button(
+                            "debugger-pause",
+                            IconName::DebugPause,
+                            "Pause",
+                            Box::new(Pause),
+                            true,
+                        )
+                    } else {
+                        control_button(
+                            "debugger-continue",
+                            IconName::DebugContinue,
+                            "Continue",
+                            Box::new(Continue),
+                            is_stopped,
+                        )
+                    })
+                    .child(control_button(
+                        "debugger-step-over",
+                        IconName::DebugStepOver,
+                        "Step Over",
+                        Box::new(StepOver),
+                        is_stopped,
+                    ))
+                    .child(control_button(
+                        "debugger-step-into",
+                        IconName::DebugStepInto,
+                        "Step Into",
+                        Box::new(StepInto),
+                        is_stopped,
+                    ))
+                    .child(control_button(
+                        "debugger-step-out",
+                        IconName::DebugStepOut,
+                        "Step Out",
+                        Box::new(StepOut),
+                        is_stopped,
+                    ))
+                    .child(control_button(
+                        "debugger-stop",
+                        IconName::DebugStop,
+                        "Stop",
+                        Box::new(Stop),
+                        is_active,
+                    )),
+            )
+    }
+
+    fn render_section(
+        &self,
+        id: &'static str,
+        title: &'static str,
+        content: impl IntoElement,
+        cx: &mut ViewContext<Self>,
+    ) -> Div {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(title).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .id(id)
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(content),
+            )
+    }
+
+    fn render_call_stack(&self, session: &Model<DebugSession>, cx: &mut ViewContext<Self>) -> Div {
+        let session_state = session.read(cx);
+        let selected_frame_ix = session_state.selected_frame_ix();
+        let mut frames = v_flex();
+        if let Some(thread) = session_state.stopped_thread_id().and_then(|id| {
+            session_state
+                .threads()
+                .iter()
+                .find(|thread| thread.id == id)
+        }) {
+            frames = frames.child(
+                h_flex().px_2().child(
+                    Label::new(format!("Thread: {}", thread.name))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            );
+        }
+        for (frame_ix, frame) in session_state.stack_frames().iter().enumerate() {
+            let location = frame
+                .source
+                .as_ref()
+                .and_then(|source| source.name.clone().or_else(|| source.path.clone()))
+                .map(|name| format!("{name}:{}", frame.line));
+            let session = session.clone();
+            frames = frames.child(
+                ListItem::new(("stack-frame", frame_ix))
+                    .inset(true)
+                    .selected(selected_frame_ix == Some(frame_ix))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(frame.name.clone()).size(LabelSize::Small))
+                            .children(location.map(|location| {
+                                Label::new(location)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            })),
+                    )
+                    .on_click(move |_, cx| {
+                        session.update(cx, |session, cx| session.select_frame(frame_ix, cx));
+                    }),
+            );
+        }
+        frames
+    }
+
+    fn render_variables(&self, session: &Model<DebugSession>, cx: &mut ViewContext<Self>) -> Div {
+        let session_state = session.read(cx);
+        let mut entries = Vec::new();
+        for scope in session_state.scopes() {
+            let reference = scope.variables_reference;
+            let is_expanded = session_state.is_expanded(reference);
+            let session_handle = session.clone();
+            entries.push(
+                ListItem::new(("variables-scope", reference as usize))
+                    .inset(true)
+                    .toggle(is_expanded)
+                    .on_toggle(move |_, cx| {
+                        session_handle
+                            .update(cx, |session, cx| session.toggle_variable(reference, cx));
+                    })
+                    .child(Label::new(scope.name.clone()).size(LabelSize::Small))
+                    .into_any_element(),
+            );
+            if is_expanded {
+                render_variable_children(session, session_state, reference, 1, &mut entries);
+            }
+        }
+
+        let mut watches = v_flex();
+        for (watch_ix, watch) in session_state.watches().iter().enumerate() {
+            let (result, color) = match &watch.result {
+                Some(Ok(result)) => (result.clone(), Color::Default),
+                Some(Err(error)) => (error.clone(), Color::Error),
+                None => ("not available".to_string(), Color::Muted),
+            };
+            let session = session.clone();
+            watches = watches.child(
+                ListItem::new(("watch", watch_ix))
+                    .inset(true)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(watch.expression.clone()).size(LabelSize::Small))
+                            .child(Label::new(result).size(LabelSize::Small).color(color)),
+                    )
+                    .end_hover_slot(
+                        IconButton::new(("remove-watch", watch_ix), IconName::Close)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(|cx| Tooltip::text("Remove Watch", cx))
+                            .on_click(move |_, cx| {
+                                session
+                                    .update(cx, |session, cx| session.remove_watch(watch_ix, cx));
+                            }),
+                    ),
+            );
+        }
+
+        v_flex()
+            .children(entries)
+            .child(
+                h_flex().px_2().pt_2().child(
+                    Label::new("Watch")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .child(watches)
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .on_action(cx.listener(Self::add_watch))
+                    .child(self.render_text_input(&self.watch_input, cx)),
+            )
+    }
+
+    fn render_console(&self, session: &Model<DebugSession>, cx: &mut ViewContext<Self>) -> Div {
+        let entries = session.read(cx).console().iter().map(|entry| {
+            let (text, color) = match entry.kind {
+                ConsoleEntryKind::Output => (entry.text.clone(), Color::Default),
+                ConsoleEntryKind::Input => (format!("> {}", entry.text), Color::Muted),
+                ConsoleEntryKind::Result => (entry.text.clone(), Color::Accent),
+                ConsoleEntryKind::Error => (entry.text.clone(), Color::Error),
+            };
+            Label::new(text).size(LabelSize::Small).color(color)
+        });
+        v_flex().px_2().children(entries)
+    }
+
+    fn render_text_input(&self, editor: &View<Editor>, cx: &ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: FontWeight::NORMAL,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+        };
+
+        EditorElement::new(
+            editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+/// Renders the fetched children of the expanded variable, recursively.
+fn render_variable_children(
+    session: &Model<DebugSession>,
+    session_state: &DebugSession,
+    variables_reference: i64,
+    depth: usize,
+    entries: &mut Vec<AnyElement>,
+) {
+    let Some(variables) = session_state.variables(variables_reference) else {
+        return;
+    };
+    for (variable_ix, variable) in variables.iter().enumerate() {
+        let Variable {
+            name,
+            value,
+            variables_reference: child_reference,
+            ..
+        } = variable;
+        let child_reference = *child_reference;
+        let is_structured = child_reference > 0;
+        let is_expanded = is_structured && session_state.is_expanded(child_reference);
+        let session_handle = session.clone();
+        entries.push(
+            ListItem::new(SharedString::from(format!(
+                "variable-{variables_reference}-{variable_ix}"
+            )))
+            .inset(true)
+            .indent_level(depth)
+            .toggle(is_structured.then_some(is_expanded))
+            .on_toggle(move |_, cx| {
+                session_handle.update(cx, |session, cx| {
+                    session.toggle_variable(child_reference, cx)
+                });
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(name.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(value.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .into_any_element(),
+        );
+        if is_expanded {
+            render_variable_children(session, session_state, child_reference, depth + 1, entries);
+        }
+    }
+}
+
+impl Render for DebuggerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.session.clone() {
+            Some(session) => {
+                let call_stack = self.render_call_stack(&session, cx);
+                let variables = self.render_variables(&session, cx);
+                let console = self.render_console(&session, cx);
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .items_start()
+                    .child(self.render_section("debugger-call-stack", "Call Stack", call_stack, cx))
+                    .child(self.render_section("debugger-variables", "Variables", variables, cx))
+                    .child(
+                        self.render_section("debugger-console", "Console", console, cx)
+                            .child(
+                                div()
+                                    .px_2()
+                                    .py_1()
+                                    .border_t_1()
+                                    .border_color(cx.theme().colors().border)
+                                    .on_action(cx.listener(Self::evaluate_console_input))
+                                    .child(self.render_text_input(&self.console_input, cx)),
+                            ),
+                    )
+                    .into_any_element()
+            }
+            None => v_flex()
+                .p_4()
+                .child(
+                    Label::new("Run a task with a `debug` configuration to start debugging.")
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("debugger-panel")
+            .size_full()
+            .key_context("DebuggerPanel")
+            .track_focus(&self.focus_handle)
+            .child(self.render_toolbar(cx))
+            .child(content)
+    }
+}
+
+impl EventEmitter<PanelEvent> for DebuggerPanel {}
+
+impl FocusableView for DebuggerPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for DebuggerPanel {
+    fn persistent_name() -> &'static str {
+        "Debugger Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match DebuggerPanelSettings::get_global(cx).dock {
+            DebuggerDockPosition::Left => DockPosition::Left,
+            DebuggerDockPosition::Bottom => DockPosition::Bottom,
+            DebuggerDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<DebuggerPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| {
+                let dock = match position {
+                    DockPosition::Left => DebuggerDockPosition::Left,
+                    DockPosition::Bottom => DebuggerDockPosition::Bottom,
+                    DockPosition::Right => DebuggerDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        let settings = DebuggerPanelSettings::get_global(cx);
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or_else(|| settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or_else(|| settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        DebuggerPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Debug)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Debugger Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
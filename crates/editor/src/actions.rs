@@ -108,6 +108,12 @@ pub struct SelectDownByLines {
     pub(super) lines: u32,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct RunLanguageServerCommand {
+    /// The name of the command, as declared by the language server's adapter.
+    pub command: String,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct ExpandExcerpts {
     #[serde(default)]
@@ -127,6 +133,7 @@ impl_actions!(
         MoveToBeginningOfLine,
         MoveToEndOfLine,
         MoveUpByLines,
+        RunLanguageServerCommand,
         SelectDownByLines,
         SelectNext,
         SelectPrevious,
@@ -267,6 +274,7 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        ToggleBreakpoint,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleHunkDiff,
@@ -28,6 +28,7 @@ mod hover_links;
 mod hover_popover;
 mod inline_completion_provider;
 pub mod items;
+mod language_server_commands;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -88,7 +89,7 @@ use language::{
     Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{BufferRow, Runnable, RunnableRange};
-use task::{ResolvedTask, TaskTemplate, TaskVariables};
+use task::{ResolvedTask, TaskTemplate, TaskVariables, TestOutcome};
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
 use lsp::{DiagnosticSeverity, LanguageServerId};
@@ -104,7 +105,7 @@ use parking_lot::{Mutex, RwLock};
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
     CodeAction, Completion, FormatTrigger, Item, Location, Project, ProjectPath,
-    ProjectTransaction, TaskSourceKind, WorktreeId,
+    ProjectTransaction, TaskSourceKind, TestStatus, WorktreeId,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -407,6 +408,10 @@ struct RunnableTasks {
     // We need the column at which the task context evaluation should take place.
     column: u32,
     extra_variables: HashMap<String, String>,
+    /// Text of the runnable's `@run` capture, e.g. the test function name.
+    symbol: String,
+    /// The path of the runnable's test in its file, see [`project::test_path`].
+    test_path: String,
 }
 
 #[derive(Clone)]
@@ -479,6 +484,8 @@ pub struct Editor {
     remote_id: Option<ViewId>,
     hover_state: HoverState,
     gutter_hovered: bool,
+    /// The display row under the mouse, while it is over the gutter.
+    gutter_hovered_row: Option<DisplayRow>,
     hovered_link_state: Option<HoveredLinkState>,
     inline_completion_provider: Option<RegisteredInlineCompletionProvider>,
     active_inline_completion: Option<Inlay>,
@@ -1463,7 +1470,7 @@ struct ActiveDiagnosticGroup {
     is_valid: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ClipboardSelection {
     pub len: usize,
     pub is_entire_line: bool,
@@ -1584,11 +1591,18 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
-                project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
-                    };
-                }));
+                project_subscriptions.push(cx.subscribe(
+                    project,
+                    |editor, _, event, cx| match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::TestStatusesUpdated
+                        | project::Event::BreakpointsChanged(_) => cx.notify(),
+                        _ => {}
+                    },
+                ));
                 let task_inventory = project.read(cx).task_inventory().clone();
                 project_subscriptions.push(cx.observe(&task_inventory, |editor, _, cx| {
                     editor.tasks_update_task = Some(editor.refresh_runnables(cx));
@@ -1670,6 +1684,7 @@ impl Editor {
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             expanded_hunks: ExpandedHunks::default(),
             gutter_hovered: false,
+            gutter_hovered_row: None,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
             expect_bounds_change: None,
@@ -4603,18 +4618,47 @@ impl Editor {
         }
     }
 
+    /// The status of the runnable's last test run, if it was run as a test.
+    fn runnable_test_status(
+        &self,
+        buffer_id: BufferId,
+        runnable: &RunnableTasks,
+        cx: &AppContext,
+    ) -> Option<TestStatus> {
+        let project = self.project.as_ref()?.read(cx);
+        let buffer = project.buffer_for_id(buffer_id)?;
+        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+        project.test_status(&abs_path, &runnable.test_path).cloned()
+    }
+
     fn render_run_indicator(
         &self,
         _style: &EditorStyle,
         is_active: bool,
         row: DisplayRow,
+        test_status: Option<TestStatus>,
         cx: &mut ViewContext<Self>,
     ) -> IconButton {
+        let (icon_color, tooltip) = match test_status {
+            None => (Color::Muted, None),
+            Some(TestStatus::Running) => (Color::Accent, Some("Test is running".to_string())),
+            Some(TestStatus::Finished { outcome, message }) => match outcome {
+                TestOutcome::Passed => (Color::Success, Some("Test passed".to_string())),
+                TestOutcome::Failed => (
+                    Color::Error,
+                    Some(message.unwrap_or_else(|| "Test failed".to_string())),
+                ),
+                TestOutcome::Skipped => (Color::Warning, Some("Test skipped".to_string())),
+            },
+        };
         IconButton::new(("run_indicator", row.0 as usize), ui::IconName::Play)
             .icon_size(IconSize::XSmall)
             .size(ui::ButtonSize::None)
-            .icon_color(Color::Muted)
+            .icon_color(icon_color)
             .selected(is_active)
+            .when_some(tooltip, |button, tooltip| {
+                button.tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+            })
             .on_click(cx.listener(move |editor, _e, cx| {
                 editor.focus(cx);
                 editor.toggle_code_actions(
@@ -4626,6 +4670,100 @@ impl Editor {
             }))
     }
 
+    /// Display rows within the given ones, that have breakpoints set on them in the project.
+    fn breakpoint_display_rows(
+        &self,
+        snapshot: &EditorSnapshot,
+        rows: Range<DisplayRow>,
+        cx: &AppContext,
+    ) -> Vec<DisplayRow> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        let project = project.read(cx);
+        let multi_buffer = &snapshot.buffer_snapshot;
+        let mut display_rows = Vec::new();
+        for (excerpt_id, buffer, range) in multi_buffer.excerpts() {
+            let breakpoint_rows = project.breakpoint_rows(buffer.remote_id(), cx);
+            if breakpoint_rows.is_empty() {
+                continue;
+            }
+            let context = range.context.to_point(buffer);
+            for row in breakpoint_rows {
+                if row < context.start.row || row > context.end.row {
+                    continue;
+                }
+                let Some(anchor) = multi_buffer
+                    .anchor_in_excerpt(excerpt_id, buffer.anchor_before(Point::new(row, 0)))
+                else {
+                    continue;
+                };
+                let point = anchor.to_point(multi_buffer);
+                if snapshot.is_line_folded(MultiBufferRow(point.row)) {
+                    continue;
+                }
+                let display_row = point.to_display_point(snapshot).row();
+                if rows.contains(&display_row) {
+                    display_rows.push(display_row);
+                }
+            }
+        }
+        display_rows
+    }
+
+    fn render_breakpoint_indicator(
+        &self,
+        row: DisplayRow,
+        is_set: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(
+            ("breakpoint_indicator", row.0 as usize),
+            ui::IconName::DebugBreakpoint,
+        )
+        .icon_size(IconSize::XSmall)
+        .size(ui::ButtonSize::None)
+        .icon_color(if is_set { Color::Error } else { Color::Muted })
+        .tooltip(move |cx| {
+            let label = if is_set {
+                "Remove Breakpoint"
+            } else {
+                "Set Breakpoint"
+            };
+            Tooltip::for_action(label, &ToggleBreakpoint, cx)
+        })
+        .on_click(cx.listener(move |editor, _e, cx| {
+            editor.focus(cx);
+            editor.toggle_breakpoint_at_row(row, cx);
+        }))
+    }
+
+    /// Sets or removes a breakpoint on the row of the newest selection's head.
+    pub fn toggle_breakpoint(&mut self, _: &ToggleBreakpoint, cx: &mut ViewContext<Self>) {
+        let head = self.selections.newest_anchor().head();
+        self.toggle_breakpoint_at(head, cx);
+    }
+
+    fn toggle_breakpoint_at_row(&mut self, row: DisplayRow, cx: &mut ViewContext<Self>) {
+        let snapshot = self.snapshot(cx);
+        let point = DisplayPoint::new(row, 0).to_point(&snapshot.display_snapshot);
+        let position = snapshot.buffer_snapshot.anchor_before(point);
+        self.toggle_breakpoint_at(position, cx);
+    }
+
+    fn toggle_breakpoint_at(&mut self, position: Anchor, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        project.update(cx, |project, cx| {
+            project.toggle_breakpoint(&buffer, position, cx)
+        });
+    }
+
     pub fn render_fold_indicators(
         &mut self,
         fold_data: Vec<Option<(FoldStatus, MultiBufferRow, bool)>>,
@@ -6188,6 +6326,45 @@ impl Editor {
         }
     }
 
+    pub fn travel_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+
+        let previous = self
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).current_undo_state());
+        if !self
+            .buffer
+            .update(cx, |buffer, cx| buffer.travel_to_undo_state(target, cx))
+        {
+            return false;
+        }
+
+        let selections = match target.and_then(|tx_id| self.selection_history.transaction(tx_id)) {
+            Some((_, Some(selections_after))) => Some(selections_after.clone()),
+            _ => previous
+                .and_then(|tx_id| self.selection_history.transaction(tx_id))
+                .map(|(selections_before, _)| selections_before.clone()),
+        };
+        if let Some(selections) = selections {
+            self.change_selections(None, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(cx);
+        self.refresh_inline_completion(true, cx);
+        cx.emit(EditorEvent::Edited);
+        true
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut ViewContext<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -7896,6 +8073,16 @@ impl Editor {
                 }
 
                 let point = runnable.run_range.start.to_point(&snapshot.buffer_snapshot);
+                let symbol = snapshot
+                    .buffer_snapshot
+                    .text_for_range(runnable.run_range.clone())
+                    .collect::<String>();
+                let enclosing_items = snapshot
+                    .buffer_snapshot
+                    .symbols_containing(runnable.run_range.start, None)
+                    .map(|(_, items)| items)
+                    .unwrap_or_default();
+                let test_path = project::test_path(&symbol, &enclosing_items);
 
                 let row = snapshot
                     .buffer_snapshot
@@ -7912,6 +8099,8 @@ impl Editor {
                             templates: tasks,
                             column: point.column,
                             extra_variables: runnable.extra_captures,
+                            symbol,
+                            test_path,
                         },
                     ),
                 ))
@@ -9445,6 +9634,13 @@ impl Editor {
         }
     }
 
+    fn set_gutter_hovered_row(&mut self, row: Option<DisplayRow>, cx: &mut ViewContext<Self>) {
+        if row != self.gutter_hovered_row {
+            self.gutter_hovered_row = row;
+            cx.notify();
+        }
+    }
+
     pub fn insert_blocks(
         &mut self,
         blocks: impl IntoIterator<Item = BlockProperties<Anchor>>,
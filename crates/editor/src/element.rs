@@ -159,6 +159,11 @@ impl EditorElement {
         });
 
         crate::rust_analyzer_ext::apply_related_actions(view, cx);
+        register_action(
+            view,
+            cx,
+            crate::language_server_commands::run_language_server_command,
+        );
         register_action(view, cx, Editor::move_left);
         register_action(view, cx, Editor::move_right);
         register_action(view, cx, Editor::move_down);
@@ -317,6 +322,7 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_breakpoint);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
@@ -662,6 +668,12 @@ impl EditorElement {
         let modifiers = event.modifiers;
         let gutter_hovered = gutter_hitbox.is_hovered(cx);
         editor.set_gutter_hovered(gutter_hovered, cx);
+        let gutter_hovered_row = gutter_hovered.then(|| {
+            let y =
+                event.position.y - gutter_hitbox.origin.y + position_map.scroll_pixel_position.y;
+            DisplayRow((y / position_map.line_height) as u32)
+        });
+        editor.set_gutter_hovered_row(gutter_hovered_row, cx);
 
         // Don't trigger hover popover if mouse is hovering over context menu
         if text_hitbox.is_hovered(cx) {
@@ -1426,6 +1438,7 @@ impl EditorElement {
         gutter_dimensions: &GutterDimensions,
         gutter_hitbox: &Hitbox,
         snapshot: &EditorSnapshot,
+        breakpoint_rows: &[DisplayRow],
         cx: &mut WindowContext,
     ) -> Vec<AnyElement> {
         self.editor.update(cx, |editor, cx| {
@@ -1447,17 +1460,22 @@ impl EditorElement {
             editor
                 .tasks
                 .iter()
-                .filter_map(|(_, (multibuffer_offset, _))| {
+                .filter_map(|((buffer_id, _), (multibuffer_offset, runnable))| {
                     let multibuffer_point = multibuffer_offset.to_point(&snapshot.buffer_snapshot);
                     let multibuffer_row = MultiBufferRow(multibuffer_point.row);
                     if snapshot.is_line_folded(multibuffer_row) {
                         return None;
                     }
                     let display_row = multibuffer_point.to_display_point(snapshot).row();
+                    if breakpoint_rows.contains(&display_row) {
+                        return None;
+                    }
+                    let test_status = editor.runnable_test_status(*buffer_id, runnable, cx);
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
                         display_row,
+                        test_status,
                         cx,
                     );
 
@@ -1476,6 +1494,57 @@ impl EditorElement {
         })
     }
 
+    /// Lays out the indicators of the breakpoints on the rows, and the one to set a new breakpoint
+    /// on the hovered gutter row, if it has no other indicators.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_breakpoint_indicators(
+        &self,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        snapshot: &EditorSnapshot,
+        visible_rows: Range<DisplayRow>,
+        breakpoint_rows: Vec<DisplayRow>,
+        code_actions_indicator_row: Option<DisplayRow>,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            let hovered_row = editor
+                .gutter_hovered_row
+                .filter(|_| editor.project.is_some())
+                .filter(|row| {
+                    visible_rows.contains(row)
+                        && !breakpoint_rows.contains(row)
+                        && code_actions_indicator_row != Some(*row)
+                        && !editor.tasks.values().any(|(multibuffer_offset, _)| {
+                            multibuffer_offset
+                                .to_point(&snapshot.buffer_snapshot)
+                                .to_display_point(snapshot)
+                                .row()
+                                == *row
+                        })
+                });
+            breakpoint_rows
+                .into_iter()
+                .map(|row| (row, true))
+                .chain(hovered_row.map(|row| (row, false)))
+                .map(|(row, is_set)| {
+                    let button = editor.render_breakpoint_indicator(row, is_set, cx);
+                    prepaint_gutter_button(
+                        button,
+                        row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
     fn layout_code_actions_indicator(
         &self,
         line_height: Pixels,
@@ -2475,6 +2544,10 @@ impl EditorElement {
                 test_indicators.paint(cx);
             }
 
+            for breakpoint_indicator in layout.breakpoint_indicators.iter_mut() {
+                breakpoint_indicator.paint(cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -4156,8 +4229,15 @@ impl Element for EditorElement {
 
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
+                    let breakpoint_rows = self.editor.read(cx).breakpoint_display_rows(
+                        &snapshot,
+                        start_row..end_row,
+                        cx,
+                    );
+
                     let mut context_menu_visible = false;
                     let mut code_actions_indicator = None;
+                    let mut code_actions_indicator_row = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         if (start_row..end_row).contains(&newest_selection_head.row()) {
                             context_menu_visible = self.layout_context_menu(
@@ -4184,7 +4264,12 @@ impl Element for EditorElement {
                                     let has_test_indicator =
                                         self.editor.read(cx).tasks.contains_key(&(buffer_id, row));
 
-                                    if !has_test_indicator {
+                                    let has_breakpoint_indicator =
+                                        breakpoint_rows.contains(&newest_selection_head.row());
+
+                                    if !has_test_indicator && !has_breakpoint_indicator {
+                                        code_actions_indicator_row =
+                                            Some(newest_selection_head.row());
                                         code_actions_indicator = self
                                             .layout_code_actions_indicator(
                                                 line_height,
@@ -4206,6 +4291,18 @@ impl Element for EditorElement {
                         &gutter_dimensions,
                         &gutter_hitbox,
                         &snapshot,
+                        &breakpoint_rows,
+                        cx,
+                    );
+                    let breakpoint_indicators = self.layout_breakpoint_indicators(
+                        line_height,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &snapshot,
+                        start_row..end_row,
+                        breakpoint_rows,
+                        code_actions_indicator_row,
                         cx,
                     );
 
@@ -4309,6 +4406,7 @@ impl Element for EditorElement {
                         selections,
                         mouse_context_menu,
                         test_indicators,
+                        breakpoint_indicators,
                         code_actions_indicator,
                         fold_indicators,
                         tab_invisible,
@@ -4430,6 +4528,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    breakpoint_indicators: Vec<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
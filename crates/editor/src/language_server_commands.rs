@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Context as _};
+use gpui::{Context, ViewContext, VisualContext};
+use language::{point_from_lsp, LanguageServerCommandOutput};
+use multi_buffer::MultiBuffer;
+use text::{Bias, ToPointUtf16};
+use util::ResultExt;
+use workspace::{notifications::NotificationId, Toast};
+
+use crate::{actions::RunLanguageServerCommand, scroll::Autoscroll, Editor};
+
+pub fn run_language_server_command(
+    editor: &mut Editor,
+    action: &RunLanguageServerCommand,
+    cx: &mut ViewContext<'_, Editor>,
+) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+
+    let selection = editor.selections.newest_anchor().clone();
+    let head = selection.head();
+    let Some(buffer) = head
+        .buffer_id
+        .and_then(|buffer_id| editor.buffer().read(cx).buffer(buffer_id))
+    else {
+        return;
+    };
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let position = head.text_anchor.to_point_utf16(&buffer_snapshot);
+    let selection = if selection.start.buffer_id == selection.end.buffer_id {
+        selection.start.text_anchor.to_point_utf16(&buffer_snapshot)
+            ..selection.end.text_anchor.to_point_utf16(&buffer_snapshot)
+    } else {
+        position..position
+    };
+
+    let command = action.command.clone();
+    let command_task = project.update(cx, |project, cx| {
+        project.run_language_server_command(
+            &buffer,
+            position,
+            selection,
+            command.as_str().into(),
+            cx,
+        )
+    });
+    cx.spawn(|_editor, mut cx| async move {
+        let output = command_task
+            .await
+            .with_context(|| format!("run language server command `{command}`"))?;
+        match output {
+            LanguageServerCommandOutput::None => {}
+            LanguageServerCommandOutput::Text {
+                title,
+                text,
+                language,
+            } => {
+                let language = match language {
+                    Some(language) => project
+                        .update(&mut cx, |project, _| {
+                            project.languages().language_for_name(&language)
+                        })?
+                        .await
+                        .log_err(),
+                    None => None,
+                };
+                let buffer = project
+                    .update(&mut cx, |project, cx| project.create_buffer(cx))?
+                    .await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.edit([(0..0, text)], None, cx);
+                        buffer.set_language(language, cx)
+                    });
+                    let multibuffer =
+                        cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                    workspace.add_item_to_active_pane(
+                        Box::new(cx.new_view(|cx| {
+                            Editor::for_multibuffer(multibuffer, Some(project), cx)
+                        })),
+                        None,
+                        cx,
+                    );
+                })?;
+            }
+            LanguageServerCommandOutput::Location { uri, position } => {
+                let abs_path = uri
+                    .to_file_path()
+                    .map_err(|()| anyhow!("invalid file URI {uri}"))?;
+                let item = workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_abs_path(abs_path, true, cx)
+                    })?
+                    .await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update(&mut cx, |editor, cx| {
+                        let point = editor
+                            .buffer()
+                            .read(cx)
+                            .snapshot(cx)
+                            .clip_point_utf16(point_from_lsp(position), Bias::Left);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([point..point])
+                        });
+                    })?;
+                }
+            }
+            LanguageServerCommandOutput::Message(message) => {
+                workspace.update(&mut cx, |workspace, cx| {
+                    struct LanguageServerCommandMessage;
+
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<LanguageServerCommandMessage>(),
+                            message,
+                        ),
+                        cx,
+                    )
+                })?;
+            }
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
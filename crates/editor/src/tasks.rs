@@ -10,7 +10,8 @@ use task::{TaskContext, TaskVariables, VariableName};
 use util::ResultExt;
 use workspace::Workspace;
 
-pub(crate) fn task_context_for_location(
+/// Builds the context to resolve tasks with, for the given location in a buffer.
+pub fn task_context_for_location(
     workspace: &Workspace,
     location: Location,
     cx: &mut WindowContext<'_>,
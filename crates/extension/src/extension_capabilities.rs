@@ -0,0 +1,236 @@
+use collections::BTreeMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A permission to go beyond the extension's sandbox, declared in the extension manifest.
+///
+/// Extensions may only use the capabilities they declare, once the user has granted them.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum ExtensionCapability {
+    /// Running a command on the user's machine.
+    #[serde(rename = "process:exec")]
+    ProcessExec {
+        /// The command to run, or `*` for any command.
+        ///
+        /// Commands are either looked up in Zed's `PATH` or given as absolute paths.
+        command: String,
+        /// The arguments the command may be run with.
+        ///
+        /// `*` matches any single argument, and a trailing `**` matches any remaining arguments.
+        #[serde(default)]
+        args: Vec<String>,
+        /// The environment variables the command may be run with, and their exact values.
+        ///
+        /// No other variables can be set, as many of them make the command run other programs,
+        /// like `NODE_OPTIONS`, `RUSTC_WRAPPER` or `BASH_ENV`. `PATH` and the variables of the
+        /// dynamic linker can't be set at all.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+    },
+    /// Listing the directories of the user's worktrees.
+    #[serde(rename = "worktree:read-dir")]
+    WorktreeReadDir,
+}
+
+/// Returns whether an extension is never allowed to set the environment variable for a command,
+/// because it changes which program runs.
+pub fn is_forbidden_env_var(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    name == "PATH" || name.starts_with("LD_") || name.starts_with("DYLD_")
+}
+
+impl ExtensionCapability {
+    /// Returns whether the capability allows running the command with the arguments and
+    /// environment variables given.
+    pub fn allows_process_exec(
+        &self,
+        command: &str,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> bool {
+        let ExtensionCapability::ProcessExec {
+            command: allowed_command,
+            args: allowed_args,
+            env: allowed_env,
+        } = self
+        else {
+            return false;
+        };
+        if allowed_command != "*" && allowed_command != command {
+            return false;
+        }
+        if !env.iter().all(|(name, value)| {
+            !is_forbidden_env_var(name) && allowed_env.get(name) == Some(value)
+        }) {
+            return false;
+        }
+
+        for (ix, allowed_arg) in allowed_args.iter().enumerate() {
+            if allowed_arg == "**" && ix == allowed_args.len() - 1 {
+                return true;
+            }
+            match args.get(ix) {
+                Some(arg) if allowed_arg == "*" || allowed_arg == arg => {}
+                _ => return false,
+            }
+        }
+        args.len() == allowed_args.len()
+    }
+
+    pub fn allows_worktree_read_dir(&self) -> bool {
+        matches!(self, ExtensionCapability::WorktreeReadDir)
+    }
+}
+
+impl fmt::Display for ExtensionCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtensionCapability::ProcessExec { command, args, env } => {
+                write!(f, "Run `{command}")?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                write!(f, "`")?;
+                if !env.is_empty() {
+                    let env = env
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}"))
+                        .collect::<Vec<_>>();
+                    write!(f, " with `{}` set", env.join("`, `"))?;
+                }
+                Ok(())
+            }
+            ExtensionCapability::WorktreeReadDir => write!(f, "List the project's directories"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_exec(command: &str, args: &[&str]) -> ExtensionCapability {
+        ExtensionCapability::ProcessExec {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: BTreeMap::default(),
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_allows_process_exec() {
+        let capability = process_exec("go", &["env", "*"]);
+        assert!(capability.allows_process_exec("go", &args(&["env", "GOROOT"]), &[]));
+        assert!(!capability.allows_process_exec("go", &args(&["env"]), &[]));
+        assert!(!capability.allows_process_exec("go", &args(&["env", "GOROOT", "GOPATH"]), &[]));
+        assert!(!capability.allows_process_exec("gofmt", &args(&["env", "GOROOT"]), &[]));
+
+        let capability = process_exec("rustup", &["which", "**"]);
+        assert!(capability.allows_process_exec("rustup", &args(&["which"]), &[]));
+        assert!(capability.allows_process_exec("rustup", &args(&["which", "rustc", "-v"]), &[]));
+        assert!(!capability.allows_process_exec("rustup", &args(&["update"]), &[]));
+
+        let capability = process_exec("*", &["--version"]);
+        assert!(capability.allows_process_exec("node", &args(&["--version"]), &[]));
+        assert!(!capability.allows_process_exec("node", &args(&[]), &[]));
+
+        assert!(!ExtensionCapability::WorktreeReadDir.allows_process_exec("go", &args(&[]), &[]));
+    }
+
+    #[test]
+    fn test_allows_process_exec_env() {
+        let env = |name: &str| vec![(name.to_string(), "value".to_string())];
+
+        let capability = process_exec("go", &["env", "*"]);
+        assert!(!capability.allows_process_exec("go", &args(&["env", "GOROOT"]), &env("GOOS")));
+
+        let capability = ExtensionCapability::ProcessExec {
+            command: "go".into(),
+            args: args(&["env", "*"]),
+            env: [
+                "GOOS",
+                "PATH",
+                "Path",
+                "LD_PRELOAD",
+                "LD_LIBRARY_PATH",
+                "DYLD_INSERT_LIBRARIES",
+            ]
+            .into_iter()
+            .map(|name| (name.to_string(), "value".to_string()))
+            .collect(),
+        };
+        assert!(capability.allows_process_exec("go", &args(&["env", "GOROOT"]), &env("GOOS")));
+        assert!(!capability.allows_process_exec("go", &args(&["env", "GOROOT"]), &env("GOARCH")));
+        // Only the approved values can be set.
+        assert!(!capability.allows_process_exec(
+            "go",
+            &args(&["env", "GOROOT"]),
+            &[("GOOS".to_string(), "windows".to_string())]
+        ));
+
+        // Overriding the variables that pick the program to run is denied, even when declared.
+        for name in [
+            "PATH",
+            "Path",
+            "LD_PRELOAD",
+            "LD_LIBRARY_PATH",
+            "DYLD_INSERT_LIBRARIES",
+        ] {
+            assert!(
+                !capability.allows_process_exec("go", &args(&["env", "GOROOT"]), &env(name)),
+                "{name} should be denied"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_capabilities() {
+        assert_eq!(process_exec("go", &[]).to_string(), "Run `go`");
+        assert_eq!(
+            ExtensionCapability::ProcessExec {
+                command: "go".into(),
+                args: args(&["env", "*"]),
+                env: BTreeMap::from_iter([
+                    ("GOOS".to_string(), "linux".to_string()),
+                    ("GOARCH".to_string(), "arm64".to_string()),
+                ]),
+            }
+            .to_string(),
+            "Run `go env *` with `GOARCH=arm64`, `GOOS=linux` set"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_capabilities() {
+        #[derive(Deserialize)]
+        struct Manifest {
+            capabilities: Vec<ExtensionCapability>,
+        }
+
+        let manifest: Manifest = toml::from_str(
+            r#"
+                [[capabilities]]
+                kind = "process:exec"
+                command = "go"
+                args = ["env", "**"]
+
+                [[capabilities]]
+                kind = "worktree:read-dir"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.capabilities,
+            vec![
+                process_exec("go", &["env", "**"]),
+                ExtensionCapability::WorktreeReadDir
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Dependencies of extensions, on other extensions and on versions of Zed.
+
+use crate::{ExtensionIndexEntry, ExtensionManifest};
+use anyhow::{anyhow, bail, Result};
+use collections::{BTreeMap, HashSet};
+use semantic_version::SemanticVersion;
+use std::sync::Arc;
+
+/// Returns an error if the extension doesn't support the given version of Zed.
+pub(crate) fn check_zed_version(
+    manifest: &ExtensionManifest,
+    zed_version: SemanticVersion,
+) -> Result<()> {
+    let Some(requirement) = &manifest.zed_version else {
+        return Ok(());
+    };
+    let version = semver::Version::new(
+        zed_version.major() as u64,
+        zed_version.minor() as u64,
+        zed_version.patch() as u64,
+    );
+    if !requirement.matches(&version) {
+        bail!(
+            "{} {} requires Zed {requirement}, but this is Zed {zed_version}",
+            manifest.name,
+            manifest.version
+        );
+    }
+    Ok(())
+}
+
+/// Returns the dependencies of the extension that aren't installed.
+///
+/// Returns an error if an installed dependency has a version that the extension doesn't support.
+pub(crate) fn missing_dependencies(
+    manifest: &ExtensionManifest,
+    installed: &BTreeMap<Arc<str>, ExtensionIndexEntry>,
+) -> Result<Vec<Arc<str>>> {
+    let mut missing = Vec::new();
+    for (dependency_id, requirement) in &manifest.dependencies {
+        let Some(dependency) = installed.get(dependency_id) else {
+            missing.push(dependency_id.clone());
+            continue;
+        };
+        let dependency_version = semver::Version::parse(&dependency.manifest.version)
+            .map_err(|_| {
+                anyhow!(
+                    "{} {} requires {dependency_id} {requirement}, but the installed version {} is invalid",
+                    manifest.name,
+                    manifest.version,
+                    dependency.manifest.version
+                )
+            })?;
+        if !requirement.matches(&dependency_version) {
+            bail!(
+                "{} {} requires {dependency_id} {requirement}, but {dependency_id} {dependency_version} is installed",
+                manifest.name,
+                manifest.version,
+            );
+        }
+    }
+    Ok(missing)
+}
+
+/// Sorts the extensions so that each comes after the dependencies it's sorted with.
+///
+/// The extensions in a dependency cycle are sorted in an arbitrary order.
+pub(crate) fn sort_by_dependencies(
+    extension_ids: &mut Vec<Arc<str>>,
+    extensions: &BTreeMap<Arc<str>, ExtensionIndexEntry>,
+) {
+    fn visit(
+        extension_id: &Arc<str>,
+        to_sort: &HashSet<Arc<str>>,
+        extensions: &BTreeMap<Arc<str>, ExtensionIndexEntry>,
+        visited: &mut HashSet<Arc<str>>,
+        sorted: &mut Vec<Arc<str>>,
+    ) {
+        if !visited.insert(extension_id.clone()) {
+            return;
+        }
+        if let Some(extension) = extensions.get(extension_id) {
+            for dependency_id in extension.manifest.dependencies.keys() {
+                if to_sort.contains(dependency_id) {
+                    visit(dependency_id, to_sort, extensions, visited, sorted);
+                }
+            }
+        }
+        sorted.push(extension_id.clone());
+    }
+
+    let to_sort = extension_ids.iter().cloned().collect::<HashSet<_>>();
+    let mut visited = HashSet::default();
+    let mut sorted = Vec::with_capacity(extension_ids.len());
+    for extension_id in extension_ids.iter() {
+        visit(
+            extension_id,
+            &to_sort,
+            extensions,
+            &mut visited,
+            &mut sorted,
+        );
+    }
+    *extension_ids = sorted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(id: &str, version: &str, dependencies: &[(&str, &str)]) -> ExtensionManifest {
+        let mut manifest = toml::from_str::<ExtensionManifest>(&format!(
+            r#"
+            id = "{id}"
+            name = "{id}"
+            version = "{version}"
+            schema_version = 1
+            "#
+        ))
+        .unwrap();
+        manifest.dependencies = dependencies
+            .iter()
+            .map(|(id, requirement)| ((*id).into(), requirement.parse().unwrap()))
+            .collect();
+        manifest
+    }
+
+    fn index(manifests: Vec<ExtensionManifest>) -> BTreeMap<Arc<str>, ExtensionIndexEntry> {
+        manifests
+            .into_iter()
+            .map(|manifest| {
+                (
+                    manifest.id.clone(),
+                    ExtensionIndexEntry {
+                        manifest: Arc::new(manifest),
+                        dev: false,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_zed_version() {
+        let mut extension = manifest("framework", "1.0.0", &[]);
+        assert!(check_zed_version(&extension, SemanticVersion::new(0, 130, 0)).is_ok());
+
+        extension.zed_version = Some(">=0.137.0".parse().unwrap());
+        assert!(check_zed_version(&extension, SemanticVersion::new(0, 137, 2)).is_ok());
+        assert_eq!(
+            check_zed_version(&extension, SemanticVersion::new(0, 136, 0))
+                .unwrap_err()
+                .to_string(),
+            "framework 1.0.0 requires Zed >=0.137.0, but this is Zed 0.136.0"
+        );
+    }
+
+    #[test]
+    fn test_missing_dependencies() {
+        let extension = manifest("framework", "1.0.0", &[("base", "^2.1"), ("theme", "*")]);
+
+        let installed = index(vec![manifest("base", "2.3.0", &[])]);
+        assert_eq!(
+            missing_dependencies(&extension, &installed).unwrap(),
+            vec![Arc::<str>::from("theme")]
+        );
+
+        let installed = index(vec![manifest("base", "2.0.0", &[])]);
+        assert_eq!(
+            missing_dependencies(&extension, &installed)
+                .unwrap_err()
+                .to_string(),
+            "framework 1.0.0 requires base ^2.1, but base 2.0.0 is installed"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_dependencies() {
+        let extensions = index(vec![
+            manifest("a", "1.0.0", &[("c", "*"), ("unrelated", "*")]),
+            manifest("b", "1.0.0", &[]),
+            manifest("c", "1.0.0", &[("b", "*")]),
+            manifest("d", "1.0.0", &[("e", "*")]),
+            manifest("e", "1.0.0", &[("d", "*")]),
+        ]);
+
+        let mut extension_ids = ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(Arc::<str>::from)
+            .collect::<Vec<_>>();
+        sort_by_dependencies(&mut extension_ids, &extensions);
+        assert_eq!(
+            extension_ids
+                .iter()
+                .map(|id| id.as_ref())
+                .collect::<Vec<_>>(),
+            ["b", "c", "a", "e", "d"]
+        );
+    }
+}
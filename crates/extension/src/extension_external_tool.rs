@@ -0,0 +1,67 @@
+use crate::wasm_host::{WasmExtension, WasmHost};
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use gpui::{AppContext, Task};
+use language::LspAdapterDelegate;
+use project::{ExternalTool, ExternalToolCommand};
+use std::sync::Arc;
+use wasmtime_wasi::WasiView;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ExternalToolKind {
+    Formatter,
+    Linter,
+}
+
+/// A formatter or linter, declared in the extension manifest and installed by the extension.
+pub struct ExtensionExternalTool {
+    pub(crate) extension: WasmExtension,
+    pub(crate) host: Arc<WasmHost>,
+    pub(crate) id: Arc<str>,
+    pub(crate) kind: ExternalToolKind,
+}
+
+impl ExternalTool for ExtensionExternalTool {
+    fn command(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<ExternalToolCommand>> {
+        let extension = self.extension.clone();
+        let host = self.host.clone();
+        let id = self.id.clone();
+        let kind = self.kind;
+        cx.background_executor().spawn(async move {
+            let command = extension
+                .call({
+                    let id = id.clone();
+                    move |extension, store| {
+                        async move {
+                            let resource = store.data_mut().table().push(worktree)?;
+                            let command = match kind {
+                                ExternalToolKind::Formatter => {
+                                    extension
+                                        .call_formatter_command(store, &id, resource)
+                                        .await?
+                                }
+                                ExternalToolKind::Linter => {
+                                    extension.call_linter_command(store, &id, resource).await?
+                                }
+                            }
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                            anyhow::Ok(command)
+                        }
+                        .boxed()
+                    }
+                })
+                .await?;
+
+            Ok(ExternalToolCommand {
+                path: host.path_from_extension(&extension.manifest.id, command.command.as_ref()),
+                arguments: command.args,
+                env: command.env.into_iter().collect(),
+            })
+        })
+    }
+}
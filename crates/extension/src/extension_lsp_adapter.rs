@@ -6,9 +6,11 @@ use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::HashMap;
 use futures::{Future, FutureExt};
-use gpui::AsyncAppContext;
+use gpui::{AsyncAppContext, PromptLevel};
 use language::{
-    CodeLabel, HighlightId, Language, LanguageServerName, LspAdapter, LspAdapterDelegate,
+    CodeLabel, CustomNotificationEffect, HighlightId, Language, LanguageServerCommand,
+    LanguageServerCommandContext, LanguageServerCommandOutput, LanguageServerName, LspAdapter,
+    LspAdapterDelegate,
 };
 use lsp::{CodeActionKind, LanguageServerBinary};
 use serde::Serialize;
@@ -302,6 +304,171 @@ impl LspAdapter for ExtensionLspAdapter {
 
         Ok(labels_from_wit(labels, language))
     }
+
+    fn language_server_commands(&self) -> Vec<LanguageServerCommand> {
+        self.extension
+            .manifest
+            .language_servers
+            .get(&self.language_server_id)
+            .map(|server| {
+                server
+                    .commands
+                    .iter()
+                    .map(|(name, command)| LanguageServerCommand {
+                        name: name.clone(),
+                        label: command.label.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    async fn language_server_command_request(
+        self: Arc<Self>,
+        command: &str,
+        context: LanguageServerCommandContext,
+    ) -> Result<(String, Value)> {
+        let command = command.to_string();
+        let context = wit::CommandContext {
+            uri: context.uri.to_string(),
+            position: context.position.into(),
+            selection_start: context.selection.start.into(),
+            selection_end: context.selection.end.into(),
+        };
+        let request = self
+            .extension
+            .call({
+                let this = self.clone();
+                |extension, store| {
+                    async move {
+                        extension
+                            .call_language_server_command_request(
+                                store,
+                                &this.language_server_id,
+                                &command,
+                                &context,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                }
+            })
+            .await?;
+        let params = serde_json::from_str(&request.params).with_context(|| {
+            format!(
+                "failed to parse the params of request `{}` from extension",
+                request.method
+            )
+        })?;
+        Ok((request.method, params))
+    }
+
+    async fn language_server_command_output(
+        self: Arc<Self>,
+        command: &str,
+        response: Value,
+    ) -> Result<LanguageServerCommandOutput> {
+        let command = command.to_string();
+        let response = response.to_string();
+        let output = self
+            .extension
+            .call({
+                let this = self.clone();
+                |extension, store| {
+                    async move {
+                        extension
+                            .call_language_server_command_output(
+                                store,
+                                &this.language_server_id,
+                                &command,
+                                &response,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                }
+            })
+            .await?;
+
+        Ok(match output {
+            wit::CommandOutput::None => LanguageServerCommandOutput::None,
+            wit::CommandOutput::Text(output) => LanguageServerCommandOutput::Text {
+                title: output.title,
+                text: output.text,
+                language: output.language.map(Into::into),
+            },
+            wit::CommandOutput::Location(output) => LanguageServerCommandOutput::Location {
+                uri: lsp::Url::parse(&output.uri)
+                    .with_context(|| format!("invalid URI from extension: {}", output.uri))?,
+                position: output.position.into(),
+            },
+            wit::CommandOutput::Message(message) => LanguageServerCommandOutput::Message(message),
+        })
+    }
+
+    fn custom_notifications(&self) -> Vec<String> {
+        self.extension
+            .manifest
+            .language_servers
+            .get(&self.language_server_id)
+            .map(|server| server.notifications.clone())
+            .unwrap_or_default()
+    }
+
+    async fn handle_custom_notification(
+        self: Arc<Self>,
+        method: &str,
+        params: Value,
+    ) -> Result<Vec<CustomNotificationEffect>> {
+        let method = method.to_string();
+        let params = params.to_string();
+        let effects = self
+            .extension
+            .call({
+                let this = self.clone();
+                |extension, store| {
+                    async move {
+                        extension
+                            .call_handle_language_server_notification(
+                                store,
+                                &this.language_server_id,
+                                &method,
+                                &params,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                }
+            })
+            .await?;
+
+        Ok(effects
+            .into_iter()
+            .map(|effect| match effect {
+                wit::NotificationEffect::Progress(progress) => CustomNotificationEffect::Progress {
+                    token: progress.token,
+                    message: progress.message,
+                    percentage: progress.percentage.map(|percentage| percentage as usize),
+                },
+                wit::NotificationEffect::ProgressEnd(token) => {
+                    CustomNotificationEffect::ProgressEnd { token }
+                }
+                wit::NotificationEffect::Message((level, text)) => {
+                    CustomNotificationEffect::Message {
+                        level: match level {
+                            wit::MessageLevel::Info => PromptLevel::Info,
+                            wit::MessageLevel::Warning => PromptLevel::Warning,
+                            wit::MessageLevel::Error => PromptLevel::Critical,
+                        },
+                        text,
+                    }
+                }
+            })
+            .collect())
+    }
 }
 
 fn labels_from_wit(
@@ -383,6 +550,24 @@ fn build_code_label(
     })
 }
 
+impl From<lsp::Position> for wit::Position {
+    fn from(value: lsp::Position) -> Self {
+        Self {
+            line: value.line,
+            character: value.character,
+        }
+    }
+}
+
+impl From<wit::Position> for lsp::Position {
+    fn from(value: wit::Position) -> Self {
+        Self {
+            line: value.line,
+            character: value.character,
+        }
+    }
+}
+
 impl From<wit::Range> for Range<usize> {
     fn from(range: wit::Range) -> Self {
         let start = range.start as usize;
@@ -1,3 +1,4 @@
+use crate::ExtensionCapability;
 use anyhow::{anyhow, Context, Result};
 use collections::{BTreeMap, HashMap};
 use fs::Fs;
@@ -10,6 +11,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use task::ProblemMatcher;
 
 /// This is the old version of the extension manifest, from when it was `extension.json`.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -65,15 +67,46 @@ pub struct ExtensionManifest {
     pub authors: Vec<String>,
     #[serde(default)]
     pub lib: LibManifestEntry,
+    /// The versions of Zed the extension works with.
+    #[serde(default)]
+    pub zed_version: Option<semver::VersionReq>,
+    /// The extensions this extension needs, with the versions it works with.
+    #[serde(default)]
+    pub dependencies: BTreeMap<Arc<str>, semver::VersionReq>,
 
     #[serde(default)]
     pub themes: Vec<PathBuf>,
     #[serde(default)]
     pub languages: Vec<PathBuf>,
+    /// The snippet files, named after the language of their snippets, or `snippets.json`
+    /// for the snippets of every language.
+    #[serde(default)]
+    pub snippets: Vec<PathBuf>,
+    /// The file icon themes, in the format of Zed's `file_types.json`.
+    #[serde(default)]
+    pub icon_themes: Vec<PathBuf>,
+    /// The keymap files, with bindings that the user's keymap takes precedence over.
+    #[serde(default)]
+    pub keymaps: Vec<PathBuf>,
     #[serde(default)]
     pub grammars: BTreeMap<Arc<str>, GrammarManifestEntry>,
     #[serde(default)]
     pub language_servers: BTreeMap<LanguageServerName, LanguageServerManifestEntry>,
+    #[serde(default)]
+    pub slash_commands: BTreeMap<Arc<str>, SlashCommandManifestEntry>,
+    /// Whether the extension lists tasks and computes task variables for the worktrees.
+    #[serde(default)]
+    pub provides_tasks: bool,
+    #[serde(default)]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default)]
+    pub linters: BTreeMap<Arc<str>, LinterManifestEntry>,
+    /// The panels whose views the extension builds.
+    #[serde(default)]
+    pub panels: BTreeMap<Arc<str>, PanelManifestEntry>,
+    /// The permissions beyond the sandbox, that the extension asks the user for.
+    #[serde(default)]
+    pub capabilities: Vec<ExtensionCapability>,
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -108,6 +141,18 @@ pub struct LanguageServerManifestEntry {
     pub language_ids: HashMap<String, String>,
     #[serde(default)]
     pub code_action_kinds: Option<Vec<lsp::CodeActionKind>>,
+    /// The commands, implemented by the extension with custom requests to the language server.
+    #[serde(default)]
+    pub commands: BTreeMap<Arc<str>, LanguageServerCommandManifestEntry>,
+    /// The methods of the custom notifications from the language server, handled by the extension.
+    #[serde(default)]
+    pub notifications: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageServerCommandManifestEntry {
+    /// The label of the command, shown to the user.
+    pub label: String,
 }
 
 impl LanguageServerManifestEntry {
@@ -128,6 +173,30 @@ impl LanguageServerManifestEntry {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct SlashCommandManifestEntry {
+    pub description: String,
+    #[serde(default)]
+    pub tooltip_text: Option<String>,
+    #[serde(default)]
+    pub requires_argument: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {}
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LinterManifestEntry {
+    /// The matchers to find the problems in the linter's output with.
+    pub problem_matcher: Vec<ProblemMatcher>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct PanelManifestEntry {
+    /// The name of the panel, shown to the user.
+    pub label: String,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -172,6 +241,8 @@ fn manifest_from_old_manifest(
         authors: manifest_json.authors,
         schema_version: SchemaVersion::ZERO,
         lib: Default::default(),
+        zed_version: None,
+        dependencies: BTreeMap::default(),
         themes: {
             let mut themes = manifest_json.themes.into_values().collect::<Vec<_>>();
             themes.sort();
@@ -184,11 +255,20 @@ fn manifest_from_old_manifest(
             languages.dedup();
             languages
         },
+        snippets: Vec::new(),
+        icon_themes: Vec::new(),
+        keymaps: Vec::new(),
         grammars: manifest_json
             .grammars
             .into_keys()
             .map(|grammar_name| (grammar_name, Default::default()))
             .collect(),
         language_servers: Default::default(),
+        slash_commands: BTreeMap::default(),
+        provides_tasks: false,
+        formatters: BTreeMap::default(),
+        linters: BTreeMap::default(),
+        panels: BTreeMap::default(),
+        capabilities: Vec::new(),
     }
 }
@@ -0,0 +1,277 @@
+//! Extension registries other than zed.dev, served as static files.
+//!
+//! A registry has an `extensions.json` index, in the format of the zed.dev API's responses,
+//! that lists every version of its extensions. The archive of a version, as packaged by the
+//! extension CLI, is at `<id>/<version>/archive.tar.gz`, with its SHA-256 checksum next to it
+//! in `archive.tar.gz.sha256`, and its Ed25519 signature, if it was signed, in `archive.tar.gz.sig`.
+//!
+//! The checksum only detects corrupted downloads, since it's served by the same registry as
+//! the archive. Archives are authenticated by their signatures, which are checked against the
+//! public key in the `extension_public_key` setting when one is configured.
+
+use crate::is_version_compatible;
+use anyhow::{anyhow, bail, Context as _, Result};
+use client::ExtensionMetadata;
+use collections::BTreeMap;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use semantic_version::SemanticVersion;
+use sha2::{Digest, Sha256};
+use std::{
+    cmp::Ordering,
+    path::{Component, Path},
+    str::FromStr,
+    sync::Arc,
+};
+use url::Url;
+
+/// Returns the URL of the registry's index.
+pub(crate) fn index_url(registry_url: &Url) -> Result<Url> {
+    base_url(registry_url)
+        .join("extensions.json")
+        .context("invalid extension registry URL")
+}
+
+/// The URLs of the archive of an extension version in a registry, and of the files next to it.
+pub(crate) struct ArchiveUrls {
+    pub archive: Url,
+    pub checksum: Url,
+    pub signature: Url,
+}
+
+/// How an archive downloaded from a registry is checked before it's extracted.
+pub(crate) struct ArchiveVerification {
+    pub checksum_url: Url,
+    /// The URL of the archive's signature, and the public key it must be signed with, when
+    /// the user has configured one.
+    pub signature: Option<(Url, String)>,
+}
+
+/// Returns the URLs of the archive of an extension version in the registry, and of its
+/// checksum and signature.
+pub(crate) fn archive_urls(
+    registry_url: &Url,
+    extension_id: &str,
+    version: &str,
+) -> Result<ArchiveUrls> {
+    let archive = base_url(registry_url)
+        .join(&format!("{extension_id}/{version}/archive.tar.gz"))
+        .context("invalid extension registry URL")?;
+    let mut checksum = archive.clone();
+    checksum.set_path(&format!("{}.sha256", archive.path()));
+    let mut signature = archive.clone();
+    signature.set_path(&format!("{}.sig", archive.path()));
+    Ok(ArchiveUrls {
+        archive,
+        checksum,
+        signature,
+    })
+}
+
+fn base_url(registry_url: &Url) -> Url {
+    let mut base_url = registry_url.clone();
+    if !base_url.path().ends_with('/') {
+        base_url.set_path(&format!("{}/", registry_url.path()));
+    }
+    base_url
+}
+
+/// Returns an error unless the extension id, which comes from a registry or an archive, can
+/// safely be used as the name of the extension's directory.
+pub(crate) fn validate_extension_id(extension_id: &str) -> Result<()> {
+    let mut components = Path::new(extension_id).components();
+    let is_single_component = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    let has_valid_chars = extension_id
+        .chars()
+        .all(|c| matches!(c, 'a'..='z' | '0'..='9' | '-' | '_'));
+    if !is_single_component || !has_valid_chars {
+        bail!("invalid extension id {extension_id:?}");
+    }
+    Ok(())
+}
+
+/// Returns whether the extension matches the search query of the extensions view.
+pub(crate) fn matches_search(extension: &ExtensionMetadata, search: &str) -> bool {
+    let search = search.to_lowercase();
+    extension.id.to_lowercase().contains(&search)
+        || extension.manifest.name.to_lowercase().contains(&search)
+        || extension
+            .manifest
+            .description
+            .as_ref()
+            .map_or(false, |description| {
+                description.to_lowercase().contains(&search)
+            })
+}
+
+/// Returns the latest version of each extension that is compatible with this version of Zed.
+pub(crate) fn latest_compatible_versions(
+    extensions: Vec<ExtensionMetadata>,
+) -> Vec<ExtensionMetadata> {
+    let mut latest_versions = BTreeMap::<Arc<str>, ExtensionMetadata>::default();
+    for extension in extensions {
+        if !is_version_compatible(&extension) {
+            continue;
+        }
+        let is_latest = latest_versions.get(&extension.id).map_or(true, |latest| {
+            compare_versions(&latest.manifest.version, &extension.manifest.version)
+                == Ordering::Less
+        });
+        if is_latest {
+            latest_versions.insert(extension.id.clone(), extension);
+        }
+    }
+    latest_versions.into_values().collect()
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (SemanticVersion::from_str(a), SemanticVersion::from_str(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Returns an error if the SHA-256 checksum of the archive doesn't match the checksum file,
+/// which contains the hex digest, optionally followed by the file name, like `sha256sum`'s output.
+///
+/// This only detects corrupted archives: whoever can replace an archive can replace its checksum.
+pub(crate) fn verify_checksum(tar_gz_bytes: &[u8], checksum_file: &str) -> Result<()> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| anyhow!("empty checksum file"))?;
+    let actual = hex::encode(Sha256::digest(tar_gz_bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "extension archive checksum {actual} does not match the expected checksum {expected}"
+        );
+    }
+    Ok(())
+}
+
+/// Returns an error unless the signature file contains an Ed25519 signature of the archive by
+/// the given public key. Both the signature and the key are base64-encoded.
+pub(crate) fn verify_signature(
+    tar_gz_bytes: &[u8],
+    signature_file: &str,
+    public_key: &str,
+) -> Result<()> {
+    let public_key = base64::decode(public_key.trim()).context("invalid extension public key")?;
+    let signature =
+        base64::decode(signature_file.trim()).context("invalid extension archive signature")?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(tar_gz_bytes, &signature)
+        .map_err(|_| anyhow!("extension archive is not signed by the configured public key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::ExtensionApiManifest;
+    use ring::signature::{Ed25519KeyPair, KeyPair as _};
+
+    fn extension(id: &str, version: &str, schema_version: i32) -> ExtensionMetadata {
+        ExtensionMetadata {
+            id: id.into(),
+            manifest: ExtensionApiManifest {
+                name: id.to_uppercase(),
+                version: version.into(),
+                description: Some(format!("The {id} extension")),
+                authors: Vec::new(),
+                repository: String::new(),
+                schema_version: Some(schema_version),
+                wasm_api_version: None,
+            },
+            published_at: Default::default(),
+            download_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_registry_urls() {
+        for registry_url in ["https://example.com/zed", "https://example.com/zed/"] {
+            let registry_url = Url::parse(registry_url).unwrap();
+            assert_eq!(
+                index_url(&registry_url).unwrap().as_str(),
+                "https://example.com/zed/extensions.json"
+            );
+            let urls = archive_urls(&registry_url, "gleam", "0.1.0").unwrap();
+            assert_eq!(
+                urls.archive.as_str(),
+                "https://example.com/zed/gleam/0.1.0/archive.tar.gz"
+            );
+            assert_eq!(
+                urls.checksum.as_str(),
+                "https://example.com/zed/gleam/0.1.0/archive.tar.gz.sha256"
+            );
+            assert_eq!(
+                urls.signature.as_str(),
+                "https://example.com/zed/gleam/0.1.0/archive.tar.gz.sig"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_extension_id() {
+        for id in ["gleam", "zed-ruby", "tree_sitter_2"] {
+            assert!(validate_extension_id(id).is_ok(), "{id}");
+        }
+        for id in [
+            "", ".", "..", "../..", "a/b", "a\\b", "/etc", "Gleam", "v1.0",
+        ] {
+            assert!(validate_extension_id(id).is_err(), "{id}");
+        }
+    }
+
+    #[test]
+    fn test_latest_compatible_versions() {
+        let extensions = latest_compatible_versions(vec![
+            extension("gleam", "0.10.0", 1),
+            extension("gleam", "0.9.0", 1),
+            extension("gleam", "0.11.0", 2),
+            extension("ruby", "1.0.0", 0),
+        ]);
+        assert_eq!(
+            extensions
+                .iter()
+                .map(|extension| (extension.id.as_ref(), extension.manifest.version.as_ref()))
+                .collect::<Vec<_>>(),
+            [("gleam", "0.10.0"), ("ruby", "1.0.0")]
+        );
+
+        assert!(matches_search(&extensions[0], "GLE"));
+        assert!(matches_search(&extensions[1], "the ruby"));
+        assert!(!matches_search(&extensions[1], "gleam"));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let checksum = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert!(verify_checksum(b"test", checksum).is_ok());
+        assert!(verify_checksum(b"test", &format!("{checksum}  archive.tar.gz\n")).is_ok());
+        assert!(verify_checksum(b"tset", checksum).is_err());
+        assert!(verify_checksum(b"test", "").is_err());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&[1; 32]).unwrap();
+        let other_key_pair = Ed25519KeyPair::from_seed_unchecked(&[2; 32]).unwrap();
+        let public_key = base64::encode(key_pair.public_key());
+        let signature = base64::encode(key_pair.sign(b"test"));
+
+        assert!(verify_signature(b"test", &signature, &public_key).is_ok());
+        assert!(verify_signature(b"test", &format!("{signature}\n"), &public_key).is_ok());
+        assert!(verify_signature(b"tset", &signature, &public_key).is_err());
+        assert!(verify_signature(
+            b"test",
+            &base64::encode(other_key_pair.sign(b"test")),
+            &public_key
+        )
+        .is_err());
+        assert!(verify_signature(b"test", "", &public_key).is_err());
+        assert!(verify_signature(b"test", &signature, "not a key").is_err());
+    }
+}
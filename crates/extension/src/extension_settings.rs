@@ -1,10 +1,13 @@
-use anyhow::Result;
+use crate::ExtensionCapability;
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 use std::sync::Arc;
+use url::Url;
+use util::ResultExt;
 
 #[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
 pub struct ExtensionSettings {
@@ -16,6 +19,22 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// The capabilities, declared in their manifests, that the user has granted to the extensions.
+    #[serde(default)]
+    pub granted_capabilities: HashMap<Arc<str>, Vec<ExtensionCapability>>,
+    /// The URL of a registry to install extensions from, instead of zed.dev.
+    ///
+    /// The registry is served as static files. Its archives are checked against their SHA-256
+    /// checksums before they're extracted, which only detects corrupted downloads: set
+    /// `extension_public_key` to also check who signed them.
+    #[serde(default)]
+    pub extension_registry_url: Option<String>,
+    /// The base64-encoded Ed25519 public key that archives from the extension registry, and
+    /// archives installed from disk, must be signed with.
+    ///
+    /// Archives without a valid signature in the `.sig` file next to them are rejected.
+    #[serde(default)]
+    pub extension_public_key: Option<String>,
 }
 
 impl ExtensionSettings {
@@ -33,6 +52,21 @@ impl ExtensionSettings {
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns the capabilities the user has granted to the given extension.
+    pub fn granted_capabilities(&self, extension_id: &str) -> &[ExtensionCapability] {
+        self.granted_capabilities
+            .get(extension_id)
+            .map_or(&[], |capabilities| capabilities.as_slice())
+    }
+
+    /// Returns the URL of the extension registry to use instead of zed.dev, if one is configured.
+    pub fn registry_url(&self) -> Option<Url> {
+        let registry_url = self.extension_registry_url.as_deref()?;
+        Url::parse(registry_url)
+            .with_context(|| format!("invalid extension registry URL {registry_url:?}"))
+            .log_err()
+    }
 }
 
 impl Settings for ExtensionSettings {
@@ -0,0 +1,111 @@
+use crate::wasm_host::{wit::SlashCommand as WitSlashCommand, WasmExtension};
+use anyhow::{anyhow, Result};
+use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
+use futures::FutureExt;
+use gpui::{AppContext, Task, WeakView, WindowContext};
+use language::LspAdapterDelegate;
+use std::sync::{atomic::AtomicBool, Arc};
+use wasmtime_wasi::WasiView;
+use workspace::Workspace;
+
+pub struct ExtensionSlashCommand {
+    pub(crate) extension: WasmExtension,
+    pub(crate) command: WitSlashCommand,
+}
+
+impl SlashCommand for ExtensionSlashCommand {
+    fn name(&self) -> String {
+        self.command.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.command.description.clone()
+    }
+
+    fn tooltip_text(&self) -> String {
+        self.command.tooltip_text.clone()
+    }
+
+    fn requires_argument(&self) -> bool {
+        self.command.requires_argument
+    }
+
+    fn complete_argument(
+        &self,
+        query: String,
+        _cancel: Arc<AtomicBool>,
+        _workspace: WeakView<Workspace>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<String>>> {
+        let command = self.command.clone();
+        let extension = self.extension.clone();
+        cx.background_executor().spawn(async move {
+            extension
+                .call(move |extension, store| {
+                    async move {
+                        let completions = extension
+                            .call_complete_slash_command_argument(store, &command, &query)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(completions)
+                    }
+                    .boxed()
+                })
+                .await
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        _workspace: WeakView<Workspace>,
+        delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let argument = argument.map(|arg| arg.to_string());
+        cx.background_executor().spawn(async move {
+            let output = self
+                .extension
+                .call({
+                    let this = self.clone();
+                    move |extension, store| {
+                        async move {
+                            let resource = store.data_mut().table().push(delegate)?;
+                            let output = extension
+                                .call_run_slash_command(
+                                    store,
+                                    &this.command,
+                                    argument.as_deref(),
+                                    resource,
+                                )
+                                .await?
+                                .map_err(|e| anyhow!("{}", e))?;
+
+                            anyhow::Ok(output)
+                        }
+                        .boxed()
+                    }
+                })
+                .await?;
+
+            let text = output.text;
+            let sections = output
+                .sections
+                .into_iter()
+                .map(|section| SlashCommandOutputSection {
+                    range: section.range.start as usize..section.range.end as usize,
+                    label: section.label.into(),
+                })
+                // Discard any sections that don't fall within the output text.
+                .filter(|section| {
+                    section.range.start <= section.range.end
+                        && text.is_char_boundary(section.range.start)
+                        && text.is_char_boundary(section.range.end)
+                })
+                .collect();
+
+            Ok(SlashCommandOutput { text, sections })
+        })
+    }
+}
@@ -1,20 +1,37 @@
 pub mod extension_builder;
+mod extension_capabilities;
+mod extension_dependencies;
+mod extension_external_tool;
 mod extension_lsp_adapter;
 mod extension_manifest;
+mod extension_registry;
 mod extension_settings;
+mod extension_slash_command;
+mod extension_task_provider;
+pub mod extension_test_runner;
+mod extension_view;
 mod wasm_host;
 
 #[cfg(test)]
 mod extension_store_test;
 
 use crate::extension_manifest::SchemaVersion;
-use crate::{extension_lsp_adapter::ExtensionLspAdapter, wasm_host::wit};
+use crate::{
+    extension_external_tool::{ExtensionExternalTool, ExternalToolKind},
+    extension_lsp_adapter::ExtensionLspAdapter,
+    extension_registry::ArchiveVerification,
+    extension_slash_command::ExtensionSlashCommand,
+    extension_task_provider::ExtensionTaskProvider,
+    wasm_host::wit,
+};
 use anyhow::{anyhow, bail, Context as _, Result};
+use assistant_slash_command::SlashCommandRegistry;
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
 use collections::{btree_map, BTreeMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+use file_icons::{FileIconTheme, FileIcons};
 use fs::{Fs, RemoveOptions};
 use futures::{
     channel::{
@@ -31,16 +48,20 @@ use gpui::{
 use http::{AsyncBody, HttpClient, HttpClientWithUrl};
 use language::{
     ContextProviderWithTasks, LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry,
-    QUERY_FILENAME_PREFIXES,
+    Snippet, QUERY_FILENAME_PREFIXES,
 };
 use node_runtime::NodeRuntime;
+use project::{ExternalLinter, ExternalToolRegistry, TaskProviderRegistry};
+use release_channel::AppVersion;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{KeymapFile, Settings};
+use std::ffi::OsStr;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
     cmp::Ordering,
+    io::Read as _,
     path::{self, Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
@@ -53,14 +74,21 @@ use wasm_host::{
     WasmExtension, WasmHost,
 };
 
+pub use extension_capabilities::ExtensionCapability;
 pub use extension_manifest::{
     ExtensionLibraryKind, ExtensionManifest, GrammarManifestEntry, OldExtensionManifest,
+    PanelManifestEntry, SlashCommandManifestEntry,
 };
 pub use extension_settings::ExtensionSettings;
+pub use extension_view::{ExtensionViewEvent, ExtensionViewNode};
 
 const RELOAD_DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
 const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
 
+/// The directories of dev extensions whose changes don't reload the extension,
+/// like the build output of their Rust code.
+const DEV_EXTENSION_IGNORED_DIRS: &[&str] = &[".git", "target", "grammars"];
+
 /// The current extension [`SchemaVersion`] supported by Zed.
 const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(1);
 
@@ -103,9 +131,14 @@ pub struct ExtensionStore {
     index_path: PathBuf,
     language_registry: Arc<LanguageRegistry>,
     theme_registry: Arc<ThemeRegistry>,
+    slash_command_registry: Arc<SlashCommandRegistry>,
+    task_provider_registry: Arc<TaskProviderRegistry>,
+    external_tool_registry: Arc<ExternalToolRegistry>,
     modified_extensions: HashSet<Arc<str>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
+    extension_keymaps: BTreeMap<Arc<str>, Vec<KeymapFile>>,
+    dev_extension_watchers: BTreeMap<Arc<str>, Task<()>>,
     tasks: Vec<Task<()>>,
 }
 
@@ -122,6 +155,7 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    KeymapsUpdated,
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -135,6 +169,8 @@ pub struct ExtensionIndex {
     pub extensions: BTreeMap<Arc<str>, ExtensionIndexEntry>,
     pub themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
     pub languages: BTreeMap<Arc<str>, ExtensionIndexLanguageEntry>,
+    #[serde(default)]
+    pub icon_themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -165,6 +201,9 @@ pub fn init(
     node_runtime: Arc<dyn NodeRuntime>,
     language_registry: Arc<LanguageRegistry>,
     theme_registry: Arc<ThemeRegistry>,
+    slash_command_registry: Arc<SlashCommandRegistry>,
+    task_provider_registry: Arc<TaskProviderRegistry>,
+    external_tool_registry: Arc<ExternalToolRegistry>,
     cx: &mut AppContext,
 ) {
     ExtensionSettings::register(cx);
@@ -179,6 +218,9 @@ pub fn init(
             node_runtime,
             language_registry,
             theme_registry,
+            slash_command_registry,
+            task_provider_registry,
+            external_tool_registry,
             cx,
         )
     });
@@ -211,6 +253,9 @@ impl ExtensionStore {
         node_runtime: Arc<dyn NodeRuntime>,
         language_registry: Arc<LanguageRegistry>,
         theme_registry: Arc<ThemeRegistry>,
+        slash_command_registry: Arc<SlashCommandRegistry>,
+        task_provider_registry: Arc<TaskProviderRegistry>,
+        external_tool_registry: Arc<ExternalToolRegistry>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
         let work_dir = extensions_dir.join("work");
@@ -236,11 +281,16 @@ impl ExtensionStore {
                 cx,
             ),
             wasm_extensions: Vec::new(),
+            extension_keymaps: BTreeMap::default(),
+            dev_extension_watchers: BTreeMap::default(),
             fs,
             http_client,
             telemetry,
             language_registry,
             theme_registry,
+            slash_command_registry,
+            task_provider_registry,
+            external_tool_registry,
             reload_tx,
             tasks: Vec::new(),
         };
@@ -379,6 +429,110 @@ impl ExtensionStore {
         }
     }
 
+    /// Watches the source directory of a dev extension, that the installed extension links to,
+    /// so that the extension is reloaded when it changes.
+    fn watch_dev_extension(&self, extension_id: Arc<str>, cx: &mut ModelContext<Self>) -> Task<()> {
+        let fs = self.fs.clone();
+        let reload_tx = self.reload_tx.clone();
+        let extension_dir = self.installed_dir.join(extension_id.as_ref());
+        cx.background_executor().spawn(async move {
+            let Some(source_dir) = fs.canonicalize(&extension_dir).await.log_err() else {
+                return;
+            };
+            let mut paths = fs.watch(&source_dir, FS_WATCH_LATENCY).await;
+            while let Some(paths) = paths.next().await {
+                let is_source_changed = paths.iter().any(|path| {
+                    let Ok(relative_path) = path.strip_prefix(&source_dir) else {
+                        return false;
+                    };
+                    match relative_path.components().next() {
+                        Some(path::Component::Normal(dir_name)) => !DEV_EXTENSION_IGNORED_DIRS
+                            .iter()
+                            .any(|ignored_dir| dir_name == *ignored_dir),
+                        _ => false,
+                    }
+                });
+                if is_source_changed {
+                    reload_tx.unbounded_send(Some(extension_id.clone())).ok();
+                }
+            }
+        })
+    }
+
+    /// Returns the keymaps of the loaded extensions, which the user's keymap takes precedence over.
+    pub fn keymaps(&self) -> impl Iterator<Item = &KeymapFile> {
+        self.extension_keymaps.values().flatten()
+    }
+
+    /// Returns the panels of the loaded extensions, with the IDs of their extensions and panels.
+    pub fn panels(&self) -> impl Iterator<Item = (&Arc<str>, &Arc<str>, &PanelManifestEntry)> {
+        self.wasm_extensions.iter().flat_map(|(manifest, _)| {
+            manifest
+                .panels
+                .iter()
+                .map(|(panel_id, panel)| (&manifest.id, panel_id, panel))
+        })
+    }
+
+    /// Returns the view of an extension's panel.
+    pub fn render_panel(
+        &self,
+        extension_id: &str,
+        panel_id: Arc<str>,
+        cx: &AppContext,
+    ) -> Task<Result<ExtensionViewNode>> {
+        self.call_panel(extension_id, panel_id, None, cx)
+    }
+
+    /// Sends an interaction of the user with the view of an extension's panel to the extension,
+    /// and returns the panel's new view.
+    pub fn handle_panel_event(
+        &self,
+        extension_id: &str,
+        panel_id: Arc<str>,
+        event: ExtensionViewEvent,
+        cx: &AppContext,
+    ) -> Task<Result<ExtensionViewNode>> {
+        self.call_panel(extension_id, panel_id, Some(event), cx)
+    }
+
+    fn call_panel(
+        &self,
+        extension_id: &str,
+        panel_id: Arc<str>,
+        event: Option<ExtensionViewEvent>,
+        cx: &AppContext,
+    ) -> Task<Result<ExtensionViewNode>> {
+        let Some(extension) = self
+            .wasm_extensions
+            .iter()
+            .find(|(manifest, _)| manifest.id.as_ref() == extension_id)
+            .map(|(_, extension)| extension.clone())
+        else {
+            return Task::ready(Err(anyhow!("extension {extension_id} is not loaded")));
+        };
+
+        cx.background_executor().spawn(async move {
+            extension
+                .call(move |extension, store| {
+                    async move {
+                        let view = match event {
+                            Some(event) => {
+                                extension
+                                    .call_handle_panel_event(store, &panel_id, &event.into())
+                                    .await?
+                            }
+                            None => extension.call_render_panel(store, &panel_id).await?,
+                        }
+                        .map_err(|e| anyhow!("{}", e))?;
+                        ExtensionViewNode::from_wit(view)
+                    }
+                    .boxed()
+                })
+                .await
+        })
+    }
+
     fn extensions_dir(&self) -> PathBuf {
         self.installed_dir.clone()
     }
@@ -414,6 +568,19 @@ impl ExtensionStore {
         search: Option<&str>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
+        if let Some(registry_url) = ExtensionSettings::get_global(cx).registry_url() {
+            let search = search.map(str::to_string);
+            let task = self.fetch_extensions_from_registry(&registry_url, cx);
+            return cx.background_executor().spawn(async move {
+                let mut extensions = extension_registry::latest_compatible_versions(task.await?);
+                if let Some(search) = search {
+                    extensions
+                        .retain(|extension| extension_registry::matches_search(extension, &search));
+                }
+                Ok(extensions)
+            });
+        }
+
         let version = CURRENT_SCHEMA_VERSION.to_string();
         let mut query = vec![("max_schema_version", version.as_str())];
         if let Some(search) = search {
@@ -436,22 +603,35 @@ impl ExtensionStore {
             .keys()
             .map(|id| id.as_ref())
             .filter(|id| extension_settings.should_auto_update(id))
-            .collect::<Vec<_>>()
-            .join(",");
-        let task = self.fetch_extensions_from_api(
-            "/extensions/updates",
-            &[
-                ("min_schema_version", &schema_versions.start().to_string()),
-                ("max_schema_version", &schema_versions.end().to_string()),
-                (
-                    "min_wasm_api_version",
-                    &wasm_api_versions.start().to_string(),
-                ),
-                ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
-                ("ids", &extension_ids),
-            ],
-            cx,
-        );
+            .collect::<Vec<_>>();
+        let registry_url = extension_settings.registry_url();
+        let task = if let Some(registry_url) = registry_url {
+            let extension_ids = extension_ids
+                .into_iter()
+                .map(Arc::<str>::from)
+                .collect::<HashSet<_>>();
+            let task = self.fetch_extensions_from_registry(&registry_url, cx);
+            cx.background_executor().spawn(async move {
+                let mut extensions = extension_registry::latest_compatible_versions(task.await?);
+                extensions.retain(|extension| extension_ids.contains(&extension.id));
+                Ok(extensions)
+            })
+        } else {
+            self.fetch_extensions_from_api(
+                "/extensions/updates",
+                &[
+                    ("min_schema_version", &schema_versions.start().to_string()),
+                    ("max_schema_version", &schema_versions.end().to_string()),
+                    (
+                        "min_wasm_api_version",
+                        &wasm_api_versions.start().to_string(),
+                    ),
+                    ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
+                    ("ids", &extension_ids.join(",")),
+                ],
+                cx,
+            )
+        };
         cx.spawn(move |this, mut cx| async move {
             let extensions = task.await?;
             this.update(&mut cx, |this, _cx| {
@@ -475,6 +655,16 @@ impl ExtensionStore {
         extension_id: &str,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
+        if let Some(registry_url) = ExtensionSettings::get_global(cx).registry_url() {
+            let extension_id = extension_id.to_string();
+            let task = self.fetch_extensions_from_registry(&registry_url, cx);
+            return cx.background_executor().spawn(async move {
+                let mut extensions = task.await?;
+                extensions.retain(|extension| extension.id.as_ref() == extension_id);
+                Ok(extensions)
+            });
+        }
+
         self.fetch_extensions_from_api(&format!("/extensions/{extension_id}"), &[], cx)
     }
 
@@ -555,6 +745,24 @@ impl ExtensionStore {
         cx: &mut ModelContext<'_, ExtensionStore>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
         let url = self.http_client.build_zed_api_url(path, &query);
+        self.fetch_extensions_at_url(url, cx)
+    }
+
+    /// Fetches every version of the extensions in the registry.
+    fn fetch_extensions_from_registry(
+        &self,
+        registry_url: &Url,
+        cx: &mut ModelContext<'_, ExtensionStore>,
+    ) -> Task<Result<Vec<ExtensionMetadata>>> {
+        let url = extension_registry::index_url(registry_url);
+        self.fetch_extensions_at_url(url, cx)
+    }
+
+    fn fetch_extensions_at_url(
+        &self,
+        url: Result<Url>,
+        cx: &mut ModelContext<'_, ExtensionStore>,
+    ) -> Task<Result<Vec<ExtensionMetadata>>> {
         let http_client = self.http_client.clone();
         cx.spawn(move |_, _| async move {
             let mut response = http_client
@@ -595,9 +803,13 @@ impl ExtensionStore {
         &mut self,
         extension_id: Arc<str>,
         url: Url,
+        verification: Option<ArchiveVerification>,
         operation: ExtensionOperation,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
+        if let Err(error) = extension_registry::validate_extension_id(&extension_id) {
+            return Task::ready(Err(error));
+        }
         let extension_dir = self.installed_dir.join(extension_id.as_ref());
         let http_client = self.http_client.clone();
         let fs = self.fs.clone();
@@ -627,15 +839,6 @@ impl ExtensionStore {
                 .await
                 .map_err(|err| anyhow!("error downloading extension: {}", err))?;
 
-            fs.remove_dir(
-                &extension_dir,
-                RemoveOptions {
-                    recursive: true,
-                    ignore_if_not_exists: true,
-                },
-            )
-            .await?;
-
             let content_length = response
                 .headers()
                 .get(isahc::http::header::CONTENT_LENGTH)
@@ -651,13 +854,19 @@ impl ExtensionStore {
                     bail!("downloaded extension size {actual_len} does not match content length {content_length}");
                 }
             }
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(extension_dir).await?;
-            this.update(&mut cx, |this, cx| {
-                this.reload(Some(extension_id.clone()), cx)
-            })?
-            .await;
+
+            if let Some(verification) = verification {
+                let checksum =
+                    download_text(&http_client, &verification.checksum_url, "checksum").await?;
+                extension_registry::verify_checksum(&tar_gz_bytes, &checksum)?;
+                if let Some((signature_url, public_key)) = verification.signature {
+                    let signature = download_text(&http_client, &signature_url, "signature").await?;
+                    extension_registry::verify_signature(&tar_gz_bytes, &signature, &public_key)?;
+                }
+            }
+
+            Self::install_archive(&this, &extension_id, &tar_gz_bytes, &extension_dir, &fs, &mut cx)
+                .await?;
 
             match operation {
                 ExtensionOperation::Install => {
@@ -673,13 +882,242 @@ impl ExtensionStore {
         })
     }
 
+    /// Installs a `.tar.gz` archive of an extension, as packaged by the extension CLI.
+    ///
+    /// The archive is checked against the SHA-256 checksum in the `.sha256` file next to it, and,
+    /// when the `extension_public_key` setting is configured, against the signature in the `.sig`
+    /// file next to it.
+    pub fn install_extension_from_archive(
+        &mut self,
+        archive_path: PathBuf,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let installed_dir = self.installed_dir.clone();
+        let fs = self.fs.clone();
+        let public_key = ExtensionSettings::get_global(cx)
+            .extension_public_key
+            .clone();
+
+        cx.spawn(move |this, mut cx| async move {
+            let mut tar_gz_bytes = Vec::new();
+            fs.open_sync(&archive_path)
+                .await?
+                .read_to_end(&mut tar_gz_bytes)
+                .with_context(|| format!("failed to read extension archive {archive_path:?}"))?;
+            let mut checksum_path = archive_path.clone().into_os_string();
+            checksum_path.push(".sha256");
+            let checksum_path = PathBuf::from(checksum_path);
+            let checksum = fs
+                .load(&checksum_path)
+                .await
+                .with_context(|| format!("failed to load extension checksum {checksum_path:?}"))?;
+            extension_registry::verify_checksum(&tar_gz_bytes, &checksum)?;
+            if let Some(public_key) = public_key {
+                let mut signature_path = archive_path.clone().into_os_string();
+                signature_path.push(".sig");
+                let signature_path = PathBuf::from(signature_path);
+                let signature = fs.load(&signature_path).await.with_context(|| {
+                    format!("failed to load extension signature {signature_path:?}")
+                })?;
+                extension_registry::verify_signature(&tar_gz_bytes, &signature, &public_key)?;
+            }
+
+            let manifest = manifest_from_archive(&tar_gz_bytes)
+                .await?
+                .ok_or_else(|| anyhow!("no extension.toml in {archive_path:?}"))?;
+            let extension_id = manifest.id.clone();
+            extension_registry::validate_extension_id(&extension_id)?;
+            let extension_dir = installed_dir.join(extension_id.as_ref());
+
+            let Some(operation) = this.update(&mut cx, |this, cx| {
+                let operation = if this.extension_index.extensions.contains_key(&extension_id) {
+                    ExtensionOperation::Upgrade
+                } else {
+                    ExtensionOperation::Install
+                };
+                match this.outstanding_operations.entry(extension_id.clone()) {
+                    btree_map::Entry::Occupied(_) => return None,
+                    btree_map::Entry::Vacant(e) => e.insert(operation),
+                };
+                cx.notify();
+                Some(operation)
+            })?
+            else {
+                return Ok(());
+            };
+
+            let _finish = util::defer({
+                let this = this.clone();
+                let mut cx = cx.clone();
+                let extension_id = extension_id.clone();
+                move || {
+                    this.update(&mut cx, |this, cx| {
+                        this.outstanding_operations.remove(extension_id.as_ref());
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            });
+
+            log::info!("installing extension {extension_id} from {archive_path:?}");
+            Self::install_archive(
+                &this,
+                &extension_id,
+                &tar_gz_bytes,
+                &extension_dir,
+                &fs,
+                &mut cx,
+            )
+            .await?;
+
+            if let ExtensionOperation::Install = operation {
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::ExtensionInstalled(extension_id));
+                })
+                .ok();
+            }
+
+            anyhow::Ok(())
+        })
+    }
+
+    /// Replaces the extension's directory with the contents of its archive, once its
+    /// dependencies are installed, and loads it.
+    async fn install_archive(
+        this: &WeakModel<Self>,
+        extension_id: &Arc<str>,
+        tar_gz_bytes: &[u8],
+        extension_dir: &Path,
+        fs: &Arc<dyn Fs>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if let Some(manifest) = manifest_from_archive(tar_gz_bytes).await? {
+            if manifest.id != *extension_id {
+                bail!(
+                    "archive of extension {extension_id} contains extension {}",
+                    manifest.id
+                );
+            }
+            this.update(cx, |this, cx| this.install_dependencies(manifest, cx))?
+                .await?;
+        }
+
+        fs.remove_dir(
+            extension_dir,
+            RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: true,
+            },
+        )
+        .await?;
+
+        let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes));
+        let archive = Archive::new(decompressed_bytes);
+        archive.unpack(extension_dir).await?;
+        this.update(cx, |this, cx| this.reload(Some(extension_id.clone()), cx))?
+            .await;
+        Ok(())
+    }
+
+    /// Checks that the extension works with this version of Zed and with the installed versions
+    /// of its dependencies, and installs the latest versions of the dependencies that are missing.
+    fn install_dependencies(
+        &mut self,
+        manifest: ExtensionManifest,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let Some(zed_version) = AppVersion::try_global(cx) {
+            if let Err(error) = extension_dependencies::check_zed_version(&manifest, zed_version) {
+                return Task::ready(Err(error));
+            }
+        }
+        let missing_dependencies = match extension_dependencies::missing_dependencies(
+            &manifest,
+            &self.extension_index.extensions,
+        ) {
+            Ok(missing_dependencies) => missing_dependencies,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        if missing_dependencies.is_empty() {
+            return Task::ready(Ok(()));
+        }
+
+        let installs = missing_dependencies
+            .into_iter()
+            .map(|dependency_id| {
+                log::info!(
+                    "installing extension {dependency_id}, a dependency of {}",
+                    manifest.id
+                );
+                self.install_latest_extension_task(dependency_id, cx)
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |this, mut cx| async move {
+            futures::future::try_join_all(installs)
+                .await
+                .with_context(|| {
+                    format!("failed to install the dependencies of {}", manifest.name)
+                })?;
+
+            // A dependency can still be missing if it's being installed by another operation,
+            // like when it depends on this extension in turn.
+            this.update(&mut cx, |this, _| {
+                let missing_dependencies = extension_dependencies::missing_dependencies(
+                    &manifest,
+                    &this.extension_index.extensions,
+                )?;
+                if let Some(dependency_id) = missing_dependencies
+                    .iter()
+                    .find(|dependency_id| !this.outstanding_operations.contains_key(*dependency_id))
+                {
+                    bail!(
+                        "{} requires {dependency_id}, which could not be installed",
+                        manifest.name
+                    );
+                }
+                Ok(())
+            })?
+        })
+    }
+
     pub fn install_latest_extension(
         &mut self,
         extension_id: Arc<str>,
         cx: &mut ModelContext<Self>,
     ) {
+        self.install_latest_extension_task(extension_id, cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn install_latest_extension_task(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} latest version");
 
+        if let Some(registry_url) = ExtensionSettings::get_global(cx).registry_url() {
+            let task = self.fetch_extensions_from_registry(&registry_url, cx);
+            return cx.spawn(move |this, mut cx| async move {
+                let mut extensions = task.await?;
+                extensions.retain(|extension| extension.id == extension_id);
+                let latest_version = extension_registry::latest_compatible_versions(extensions)
+                    .pop()
+                    .ok_or_else(|| {
+                        anyhow!("no compatible version of extension {extension_id} in the registry")
+                    })?;
+                this.update(&mut cx, |this, cx| {
+                    this.install_or_upgrade_extension(
+                        extension_id,
+                        latest_version.manifest.version,
+                        ExtensionOperation::Install,
+                        cx,
+                    )
+                })?
+                .await
+            });
+        }
+
         let schema_versions = schema_version_range();
         let wasm_api_versions = wasm_api_version_range();
 
@@ -699,16 +1137,16 @@ impl ExtensionStore {
             )
             .log_err()
         else {
-            return;
+            return Task::ready(Ok(()));
         };
 
         self.install_or_upgrade_extension_at_endpoint(
             extension_id,
             url,
+            None,
             ExtensionOperation::Install,
             cx,
         )
-        .detach_and_log_err(cx);
     }
 
     pub fn upgrade_extension(
@@ -728,6 +1166,29 @@ impl ExtensionStore {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} {version}");
+        let settings = ExtensionSettings::get_global(cx);
+        if let Some(registry_url) = settings.registry_url() {
+            let urls =
+                match extension_registry::archive_urls(&registry_url, &extension_id, &version) {
+                    Ok(urls) => urls,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+            let verification = ArchiveVerification {
+                checksum_url: urls.checksum,
+                signature: settings
+                    .extension_public_key
+                    .clone()
+                    .map(|public_key| (urls.signature, public_key)),
+            };
+            return self.install_or_upgrade_extension_at_endpoint(
+                extension_id,
+                urls.archive,
+                Some(verification),
+                operation,
+                cx,
+            );
+        }
+
         let Some(url) = self
             .http_client
             .build_zed_api_url(
@@ -739,7 +1200,7 @@ impl ExtensionStore {
             return Task::ready(Ok(()));
         };
 
-        self.install_or_upgrade_extension_at_endpoint(extension_id, url, operation, cx)
+        self.install_or_upgrade_extension_at_endpoint(extension_id, url, None, operation, cx)
     }
 
     pub fn uninstall_extension(&mut self, extension_id: Arc<str>, cx: &mut ModelContext<Self>) {
@@ -795,6 +1256,11 @@ impl ExtensionStore {
                 ExtensionManifest::load(fs.clone(), &extension_source_path).await?;
             let extension_id = extension_manifest.id.clone();
 
+            this.update(&mut cx, |this, cx| {
+                this.install_dependencies(extension_manifest.clone(), cx)
+            })?
+            .await?;
+
             if !this.update(&mut cx, |this, cx| {
                 match this.outstanding_operations.entry(extension_id.clone()) {
                     btree_map::Entry::Occupied(_) => return false,
@@ -951,6 +1417,23 @@ impl ExtensionStore {
             self.modified_extensions.clear();
         }
 
+        // Extensions that were installed for another version of Zed stay installed, but aren't
+        // loaded, and the extensions are loaded after their dependencies.
+        if let Some(zed_version) = AppVersion::try_global(cx) {
+            extensions_to_load.retain(|extension_id| {
+                let Some(extension) = new_index.extensions.get(extension_id) else {
+                    return true;
+                };
+                extension_dependencies::check_zed_version(&extension.manifest, zed_version)
+                    .log_err()
+                    .is_some()
+            });
+        }
+        extension_dependencies::sort_by_dependencies(
+            &mut extensions_to_load,
+            &new_index.extensions,
+        );
+
         if extensions_to_load.is_empty() && extensions_to_unload.is_empty() {
             return Task::ready(());
         }
@@ -989,6 +1472,17 @@ impl ExtensionStore {
                 }
             })
             .collect::<Vec<_>>();
+        let icon_themes_to_remove = old_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, entry)| {
+                if extensions_to_unload.contains(&entry.extension) {
+                    Some(name.clone())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
         let languages_to_remove = old_index
             .languages
             .iter()
@@ -1001,7 +1495,12 @@ impl ExtensionStore {
             })
             .collect::<Vec<_>>();
         let mut grammars_to_remove = Vec::new();
+        let mut keymaps_removed = false;
         for extension_id in &extensions_to_unload {
+            self.language_registry.remove_snippets(extension_id);
+            keymaps_removed |= self.extension_keymaps.remove(extension_id).is_some();
+            self.dev_extension_watchers.remove(extension_id);
+
             let Some(extension) = old_index.extensions.get(extension_id) else {
                 continue;
             };
@@ -1012,11 +1511,33 @@ impl ExtensionStore {
                         .remove_lsp_adapter(&language, language_server_name);
                 }
             }
+            for slash_command_name in extension.manifest.slash_commands.keys() {
+                self.slash_command_registry
+                    .unregister_command(slash_command_name);
+            }
+            if extension.manifest.provides_tasks {
+                self.task_provider_registry
+                    .unregister_provider(extension_id);
+            }
+            for formatter_name in extension.manifest.formatters.keys() {
+                self.external_tool_registry
+                    .unregister_formatter(formatter_name);
+            }
+            for linter_name in extension.manifest.linters.keys() {
+                self.external_tool_registry.unregister_linter(linter_name);
+            }
         }
 
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.theme_registry.remove_user_themes(&themes_to_remove);
+        if !icon_themes_to_remove.is_empty() && cx.has_global::<FileIcons>() {
+            cx.update_global::<FileIcons, _>(|file_icons, _| {
+                for icon_theme_name in &icon_themes_to_remove {
+                    file_icons.unregister_icon_theme(icon_theme_name);
+                }
+            });
+        }
         self.language_registry
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -1044,7 +1565,23 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+
+            if extension.dev && !self.dev_extension_watchers.contains_key(extension_id) {
+                let watcher = self.watch_dev_extension(extension_id.clone(), cx);
+                self.dev_extension_watchers
+                    .insert(extension_id.clone(), watcher);
+            }
         }
+        let icon_themes_to_add = new_index
+            .icon_themes
+            .values()
+            .filter(|entry| extensions_to_load.contains(&entry.extension))
+            .map(|entry| {
+                let extension_dir = self.installed_dir.join(entry.extension.as_ref());
+                let path = extension_dir.join(&entry.path);
+                (extension_dir, path)
+            })
+            .collect::<Vec<_>>();
 
         self.language_registry
             .register_wasm_grammars(grammars_to_add);
@@ -1075,6 +1612,7 @@ impl ExtensionStore {
             );
         }
 
+        let icon_themes_removed = !icon_themes_to_remove.is_empty();
         let fs = self.fs.clone();
         let wasm_host = self.wasm_host.clone();
         let root_dir = self.installed_dir.clone();
@@ -1089,9 +1627,12 @@ impl ExtensionStore {
         cx.emit(Event::ExtensionsUpdated);
 
         cx.spawn(|this, mut cx| async move {
-            cx.background_executor()
+            let (snippets, keymaps, icon_themes) = cx
+                .background_executor()
                 .spawn({
                     let fs = fs.clone();
+                    let root_dir = root_dir.clone();
+                    let extension_entries = extension_entries.clone();
                     async move {
                         for theme_path in &themes_to_add {
                             theme_registry
@@ -1099,6 +1640,53 @@ impl ExtensionStore {
                                 .await
                                 .log_err();
                         }
+
+                        let mut snippets = Vec::new();
+                        let mut keymaps = Vec::new();
+                        for extension in &extension_entries {
+                            let extension_id = &extension.manifest.id;
+                            let extension_dir = root_dir.join(extension_id.as_ref());
+
+                            let mut extension_snippets = Vec::new();
+                            for snippets_path in &extension.manifest.snippets {
+                                if let Some(file_snippets) =
+                                    load_snippets(&fs, &extension_dir.join(snippets_path))
+                                        .await
+                                        .log_err()
+                                {
+                                    extension_snippets.extend(file_snippets);
+                                }
+                            }
+                            if !extension_snippets.is_empty() {
+                                snippets.push((extension_id.clone(), extension_snippets));
+                            }
+
+                            let mut extension_keymaps = Vec::new();
+                            for keymap_path in &extension.manifest.keymaps {
+                                let keymap_path = extension_dir.join(keymap_path);
+                                let keymap = maybe!(async {
+                                    let content = fs.load(&keymap_path).await?;
+                                    KeymapFile::parse(&content)
+                                        .with_context(|| format!("invalid keymap {keymap_path:?}"))
+                                })
+                                .await;
+                                extension_keymaps.extend(keymap.log_err());
+                            }
+                            if !extension_keymaps.is_empty() {
+                                keymaps.push((extension_id.clone(), extension_keymaps));
+                            }
+                        }
+
+                        let mut icon_themes = Vec::new();
+                        for (extension_dir, icon_theme_path) in &icon_themes_to_add {
+                            if let Some(icon_theme) =
+                                load_icon_theme(&fs, icon_theme_path).await.log_err()
+                            {
+                                icon_themes.push(icon_theme.with_icons_in(extension_dir));
+                            }
+                        }
+
+                        (snippets, keymaps, icon_themes)
                     }
                 })
                 .await;
@@ -1165,8 +1753,84 @@ impl ExtensionStore {
                             );
                         }
                     }
+
+                    for (slash_command_name, slash_command) in &manifest.slash_commands {
+                        let description = slash_command.description.clone();
+                        let tooltip_text = slash_command
+                            .tooltip_text
+                            .clone()
+                            .unwrap_or_else(|| description.clone());
+                        this.slash_command_registry
+                            .register_command(ExtensionSlashCommand {
+                                command: wit::SlashCommand {
+                                    name: slash_command_name.to_string(),
+                                    description,
+                                    tooltip_text,
+                                    requires_argument: slash_command.requires_argument,
+                                },
+                                extension: wasm_extension.clone(),
+                            });
+                    }
+
+                    if manifest.provides_tasks {
+                        this.task_provider_registry.register_provider(
+                            manifest.id.clone(),
+                            ExtensionTaskProvider {
+                                extension: wasm_extension.clone(),
+                            },
+                        );
+                    }
+
+                    for formatter_name in manifest.formatters.keys() {
+                        this.external_tool_registry.register_formatter(
+                            formatter_name.clone(),
+                            ExtensionExternalTool {
+                                extension: wasm_extension.clone(),
+                                host: this.wasm_host.clone(),
+                                id: formatter_name.clone(),
+                                kind: ExternalToolKind::Formatter,
+                            },
+                        );
+                    }
+
+                    for (linter_name, linter) in &manifest.linters {
+                        this.external_tool_registry.register_linter(
+                            linter_name.clone(),
+                            ExternalLinter {
+                                tool: Arc::new(ExtensionExternalTool {
+                                    extension: wasm_extension.clone(),
+                                    host: this.wasm_host.clone(),
+                                    id: linter_name.clone(),
+                                    kind: ExternalToolKind::Linter,
+                                }),
+                                problem_matcher: linter.problem_matcher.clone(),
+                            },
+                        );
+                    }
                 }
                 this.wasm_extensions.extend(wasm_extensions);
+                cx.notify();
+
+                for (extension_id, snippets) in snippets {
+                    this.language_registry
+                        .register_snippets(extension_id, snippets);
+                }
+
+                if keymaps_removed || !keymaps.is_empty() {
+                    this.extension_keymaps.extend(keymaps);
+                    cx.emit(Event::KeymapsUpdated);
+                }
+
+                if (icon_themes_removed || !icon_themes.is_empty()) && cx.has_global::<FileIcons>()
+                {
+                    cx.update_global::<FileIcons, _>(|file_icons, _| {
+                        for icon_theme in icon_themes {
+                            file_icons.register_icon_theme(icon_theme);
+                        }
+                    });
+                    cx.refresh();
+                }
+
                 ThemeSettings::reload_current_theme(cx)
             })
             .ok();
@@ -1296,6 +1960,55 @@ impl ExtensionStore {
             }
         }
 
+        if let Ok(mut icon_theme_paths) = fs.read_dir(&extension_dir.join("icon_themes")).await {
+            while let Some(icon_theme_path) = icon_theme_paths.next().await {
+                let icon_theme_path = icon_theme_path?;
+                let Ok(relative_path) = icon_theme_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+
+                let Some(icon_theme) = load_icon_theme(&fs, &icon_theme_path).await.log_err()
+                else {
+                    continue;
+                };
+
+                let relative_path = relative_path.to_path_buf();
+                if !extension_manifest.icon_themes.contains(&relative_path) {
+                    extension_manifest.icon_themes.push(relative_path.clone());
+                }
+
+                index.icon_themes.insert(
+                    icon_theme.name.into(),
+                    ExtensionIndexThemeEntry {
+                        extension: extension_id.clone(),
+                        path: relative_path,
+                    },
+                );
+            }
+        }
+
+        for (dir_name, paths) in [
+            ("snippets", &mut extension_manifest.snippets),
+            ("keymaps", &mut extension_manifest.keymaps),
+        ] {
+            let Ok(mut file_paths) = fs.read_dir(&extension_dir.join(dir_name)).await else {
+                continue;
+            };
+            while let Some(file_path) = file_paths.next().await {
+                let file_path = file_path?;
+                if file_path.extension() != Some(OsStr::new("json")) {
+                    continue;
+                }
+                let Ok(relative_path) = file_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+                let relative_path = relative_path.to_path_buf();
+                if !paths.contains(&relative_path) {
+                    paths.push(relative_path);
+                }
+            }
+        }
+
         let extension_wasm_path = extension_dir.join("extension.wasm");
         if fs.is_file(&extension_wasm_path).await {
             extension_manifest
@@ -1316,6 +2029,75 @@ impl ExtensionStore {
     }
 }
 
+/// Loads the snippets of a snippets file, which is named after the language of its snippets,
+/// or is `snippets.json` for the snippets of every language.
+async fn load_snippets(fs: &Arc<dyn Fs>, path: &Path) -> Result<Vec<(Option<Arc<str>>, Snippet)>> {
+    let content = fs.load(path).await?;
+    let snippets = language::parse_snippets(&content)
+        .with_context(|| format!("invalid snippets file {path:?}"))?;
+    let language = path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .filter(|stem| *stem != "snippets")
+        .map(Arc::<str>::from);
+    Ok(snippets
+        .into_iter()
+        .map(|snippet| (language.clone(), snippet))
+        .collect())
+}
+
+async fn load_icon_theme(fs: &Arc<dyn Fs>, path: &Path) -> Result<FileIconTheme> {
+    let content = fs.load(path).await?;
+    serde_json_lenient::from_str(&content)
+        .with_context(|| format!("invalid file icon theme {path:?}"))
+}
+
+/// Reads the `extension.toml` manifest from a downloaded extension archive.
+///
+/// Returns `None` for extensions with an old `extension.json` manifest.
+/// Downloads a small text file that's served next to an extension archive.
+async fn download_text(
+    http_client: &HttpClientWithUrl,
+    url: &Url,
+    description: &str,
+) -> Result<String> {
+    let mut response = http_client
+        .get(url.as_ref(), Default::default(), true)
+        .await
+        .map_err(|err| anyhow!("error downloading extension {description}: {err}"))?;
+    if !response.status().is_success() {
+        bail!(
+            "error downloading extension {description}: status {}",
+            response.status().as_u16()
+        );
+    }
+    let mut text = String::new();
+    response.body_mut().read_to_string(&mut text).await?;
+    Ok(text)
+}
+
+async fn manifest_from_archive(tar_gz_bytes: &[u8]) -> Result<Option<ExtensionManifest>> {
+    let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes));
+    let archive = Archive::new(decompressed_bytes);
+    let mut entries = archive.entries()?;
+    while let Some(entry) = entries.next().await {
+        let mut entry = entry?;
+        let is_manifest = {
+            let path = entry.path()?;
+            path.strip_prefix(".").unwrap_or(path.as_ref()) == Path::new("extension.toml")
+        };
+        if !is_manifest {
+            continue;
+        }
+        let mut manifest_content = String::new();
+        entry.read_to_string(&mut manifest_content).await?;
+        let manifest = toml::from_str(&manifest_content)
+            .context("invalid extension.toml in extension archive")?;
+        return Ok(Some(manifest));
+    }
+    Ok(None)
+}
+
 fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     let mut result = LanguageQueries::default();
     if let Some(entries) = std::fs::read_dir(root_path).log_err() {
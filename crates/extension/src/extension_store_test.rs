@@ -5,8 +5,10 @@ use crate::{
     ExtensionIndexThemeEntry, ExtensionManifest, ExtensionStore, GrammarManifestEntry,
     RELOAD_DEBOUNCE_DURATION,
 };
+use assistant_slash_command::SlashCommandRegistry;
 use async_compression::futures::bufread::GzipEncoder;
 use collections::BTreeMap;
+use file_icons::FileIcons;
 use fs::{FakeFs, Fs, RealFs};
 use futures::{io::BufReader, AsyncReadExt, StreamExt};
 use gpui::{Context, TestAppContext};
@@ -14,7 +16,7 @@ use http::{FakeHttpClient, Response};
 use language::{LanguageMatcher, LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName};
 use node_runtime::FakeNodeRuntime;
 use parking_lot::Mutex;
-use project::Project;
+use project::{ExternalToolRegistry, Project, TaskProviderRegistry};
 use serde_json::json;
 use settings::{Settings as _, SettingsStore};
 use std::{
@@ -148,7 +150,12 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         repository: None,
                         themes: Default::default(),
                         lib: Default::default(),
+                        zed_version: None,
+                        dependencies: BTreeMap::default(),
                         languages: vec!["languages/erb".into(), "languages/ruby".into()],
+                        snippets: Vec::new(),
+                        icon_themes: Vec::new(),
+                        keymaps: Vec::new(),
                         grammars: [
                             ("embedded_template".into(), GrammarManifestEntry::default()),
                             ("ruby".into(), GrammarManifestEntry::default()),
@@ -156,6 +163,12 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         .into_iter()
                         .collect(),
                         language_servers: BTreeMap::default(),
+                        slash_commands: BTreeMap::default(),
+                        provides_tasks: false,
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        panels: BTreeMap::default(),
+                        capabilities: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -176,9 +189,20 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                             "themes/monokai.json".into(),
                         ],
                         lib: Default::default(),
+                        zed_version: None,
+                        dependencies: BTreeMap::default(),
                         languages: Default::default(),
+                        snippets: Vec::new(),
+                        icon_themes: Vec::new(),
+                        keymaps: Vec::new(),
                         grammars: BTreeMap::default(),
                         language_servers: BTreeMap::default(),
+                        slash_commands: BTreeMap::default(),
+                        provides_tasks: false,
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        panels: BTreeMap::default(),
+                        capabilities: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -246,10 +270,14 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ]
         .into_iter()
         .collect(),
+        icon_themes: BTreeMap::default(),
     };
 
     let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
     let theme_registry = Arc::new(ThemeRegistry::new(Box::new(())));
+    let slash_command_registry = SlashCommandRegistry::new();
+    let task_provider_registry = TaskProviderRegistry::new();
+    let external_tool_registry = ExternalToolRegistry::new();
     let node_runtime = FakeNodeRuntime::new();
 
     let store = cx.new_model(|cx| {
@@ -262,6 +290,9 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             node_runtime.clone(),
             language_registry.clone(),
             theme_registry.clone(),
+            slash_command_registry.clone(),
+            task_provider_registry.clone(),
+            external_tool_registry.clone(),
             cx,
         )
     });
@@ -312,7 +343,29 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         }
                     ]
                 }"#,
-            }
+            },
+            "icon_themes": {
+                "gruvbox-icons.json": r#"{
+                    "name": "Gruvbox Icons",
+                    "suffixes": { "rb": "ruby" },
+                    "types": { "ruby": { "icon": "icons/ruby.svg" } }
+                }"#,
+            },
+            "snippets": {
+                "ruby.json": r#"{
+                    "Method": {
+                        "prefix": "def",
+                        "body": ["def ${1:name}", "  $0", "end"]
+                    }
+                }"#,
+            },
+            "keymaps": {
+                "gruvbox.json": r#"[
+                    {
+                        "bindings": { "ctrl-g": "editor::Cancel" }
+                    }
+                ]"#,
+            },
         }),
     )
     .await;
@@ -330,9 +383,20 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 repository: None,
                 themes: vec!["themes/gruvbox.json".into()],
                 lib: Default::default(),
+                zed_version: None,
+                dependencies: BTreeMap::default(),
                 languages: Default::default(),
+                snippets: vec!["snippets/ruby.json".into()],
+                icon_themes: vec!["icon_themes/gruvbox-icons.json".into()],
+                keymaps: vec!["keymaps/gruvbox.json".into()],
                 grammars: BTreeMap::default(),
                 language_servers: BTreeMap::default(),
+                slash_commands: BTreeMap::default(),
+                provides_tasks: false,
+                formatters: BTreeMap::default(),
+                linters: BTreeMap::default(),
+                panels: BTreeMap::default(),
+                capabilities: Vec::new(),
             }),
             dev: false,
         },
@@ -344,15 +408,38 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             path: "themes/gruvbox.json".into(),
         },
     );
+    expected_index.icon_themes.insert(
+        "Gruvbox Icons".into(),
+        ExtensionIndexThemeEntry {
+            extension: "zed-gruvbox".into(),
+            path: "icon_themes/gruvbox-icons.json".into(),
+        },
+    );
 
     let _ = store.update(cx, |store, cx| store.reload(None, cx));
 
     cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
-    store.read_with(cx, |store, _| {
+    store.read_with(cx, |store, cx| {
         let index = &store.extension_index;
         assert_eq!(index.extensions, expected_index.extensions);
         assert_eq!(index.languages, expected_index.languages);
         assert_eq!(index.themes, expected_index.themes);
+        assert_eq!(index.icon_themes, expected_index.icon_themes);
+
+        assert_eq!(
+            language_registry
+                .snippets_for_language("Ruby")
+                .iter()
+                .map(|snippet| (snippet.name.as_ref(), snippet.body.as_str()))
+                .collect::<Vec<_>>(),
+            [("Method", "def ${1:name}\n  $0\nend")]
+        );
+        assert!(language_registry.snippets_for_language("ERB").is_empty());
+        assert_eq!(store.keymaps().count(), 1);
+        assert_eq!(
+            FileIcons::get(cx).icon_theme_names().collect::<Vec<_>>(),
+            [&Arc::<str>::from("Gruvbox Icons")]
+        );
 
         assert_eq!(
             theme_registry.list_names(false),
@@ -382,6 +469,9 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             node_runtime.clone(),
             language_registry.clone(),
             theme_registry.clone(),
+            slash_command_registry.clone(),
+            task_provider_registry.clone(),
+            external_tool_registry.clone(),
             cx,
         )
     });
@@ -460,6 +550,9 @@ async fn test_extension_store_with_gleam_extension(cx: &mut TestAppContext) {
 
     let language_registry = project.read_with(cx, |project, _cx| project.languages().clone());
     let theme_registry = Arc::new(ThemeRegistry::new(Box::new(())));
+    let slash_command_registry = SlashCommandRegistry::new();
+    let task_provider_registry = TaskProviderRegistry::new();
+    let external_tool_registry = ExternalToolRegistry::new();
     let node_runtime = FakeNodeRuntime::new();
 
     let mut status_updates = language_registry.language_server_binary_statuses();
@@ -541,6 +634,9 @@ async fn test_extension_store_with_gleam_extension(cx: &mut TestAppContext) {
             node_runtime,
             language_registry.clone(),
             theme_registry.clone(),
+            slash_command_registry.clone(),
+            task_provider_registry.clone(),
+            external_tool_registry.clone(),
             cx,
         )
     });
@@ -718,5 +814,6 @@ fn init_test(cx: &mut TestAppContext) {
         Project::init_settings(cx);
         ExtensionSettings::register(cx);
         language::init(cx);
+        file_icons::init((), cx);
     });
 }
@@ -0,0 +1,101 @@
+use crate::wasm_host::{
+    wit::{TaskContext as WitTaskContext, TaskTemplate as WitTaskTemplate},
+    WasmExtension,
+};
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use gpui::{AppContext, Task};
+use language::LspAdapterDelegate;
+use project::TaskProvider;
+use std::sync::Arc;
+use task::{TaskContext, TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use wasmtime_wasi::WasiView;
+
+pub struct ExtensionTaskProvider {
+    pub(crate) extension: WasmExtension,
+}
+
+impl TaskProvider for ExtensionTaskProvider {
+    fn tasks_for_worktree(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskTemplates>> {
+        let extension = self.extension.clone();
+        cx.background_executor().spawn(async move {
+            let tasks = extension
+                .call(move |extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let tasks = extension
+                            .call_tasks_for_worktree(store, resource)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(tasks)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(TaskTemplates(
+                tasks.into_iter().map(task_template_from_wit).collect(),
+            ))
+        })
+    }
+
+    fn task_variables(
+        &self,
+        task_context: TaskContext,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskVariables>> {
+        let extension = self.extension.clone();
+        let context = WitTaskContext {
+            cwd: task_context
+                .cwd
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+            task_variables: task_context
+                .task_variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        };
+        cx.background_executor().spawn(async move {
+            let variables = extension
+                .call(move |extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let variables = extension
+                            .call_task_variables(store, &context, resource)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(variables)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(variables
+                .into_iter()
+                .map(|(name, value)| (VariableName::Custom(name.into()), value))
+                .collect())
+        })
+    }
+}
+
+fn task_template_from_wit(task: WitTaskTemplate) -> TaskTemplate {
+    TaskTemplate {
+        label: task.label,
+        command: task.command,
+        args: task.args,
+        env: task.env.into_iter().collect(),
+        cwd: task.cwd,
+        use_new_terminal: task.use_new_terminal,
+        allow_concurrent_runs: task.allow_concurrent_runs,
+        tags: task.tags,
+        depends_on: task.depends_on,
+        ..TaskTemplate::default()
+    }
+}
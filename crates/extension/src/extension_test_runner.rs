@@ -0,0 +1,634 @@
+//! Runs the tests of an extension: calls its exports with the inputs in the test fixtures, against a fake worktree,
+//! HTTP client and Node runtime, and compares their outputs with the expected ones.
+
+use crate::{
+    wasm_host::{
+        wit::{self, LanguageServerConfig},
+        WasmExtension, WasmHost,
+    },
+    ExtensionManifest, ExtensionSettings,
+};
+use anyhow::{anyhow, bail, Context as _, Result};
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use fs::Fs;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, BorrowAppContext, Task};
+use http::{AsyncBody, HttpClient, Request, Response, StatusCode};
+use language::{
+    LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName, LspAdapterDelegate,
+};
+use node_runtime::NodeRuntime;
+use project::Project;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use settings::SettingsStore;
+use std::{
+    ffi::OsStr,
+    io::Read as _,
+    path::{Path, PathBuf},
+    process::Output,
+    sync::Arc,
+};
+use wasmtime_wasi::WasiView as _;
+
+/// The tests of an extension, with the fakes to run them against, read from a JSON file in its `tests` directory.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExtensionTestFixture {
+    /// The user settings to run the tests with, e.g. to grant capabilities to the extension.
+    #[serde(default)]
+    pub settings: Option<Value>,
+    #[serde(default)]
+    pub worktree: WorktreeFixture,
+    /// The responses of the fake HTTP client, by URL. Requests to other URLs get a 404 response.
+    #[serde(default)]
+    pub http: HashMap<String, HttpResponseFixture>,
+    /// The latest versions of the npm packages, known to the fake Node runtime.
+    #[serde(default)]
+    pub npm_packages: HashMap<String, String>,
+    pub tests: Vec<ExtensionTest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorktreeFixture {
+    #[serde(default = "default_worktree_root_path")]
+    pub root_path: PathBuf,
+    /// The contents of the files in the worktree, by their path relative to its root.
+    #[serde(default)]
+    pub files: BTreeMap<PathBuf, String>,
+    #[serde(default)]
+    pub shell_env: HashMap<String, String>,
+    /// The paths of the binaries found on the `PATH`, by name.
+    #[serde(default)]
+    pub binaries: HashMap<String, PathBuf>,
+}
+
+impl Default for WorktreeFixture {
+    fn default() -> Self {
+        Self {
+            root_path: default_worktree_root_path(),
+            files: BTreeMap::default(),
+            shell_env: HashMap::default(),
+            binaries: HashMap::default(),
+        }
+    }
+}
+
+fn default_worktree_root_path() -> PathBuf {
+    PathBuf::from("/worktree")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HttpResponseFixture {
+    #[serde(default = "default_http_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub body: String,
+    /// The file to respond with instead of the body, relative to the extension directory, e.g. an archive to download.
+    #[serde(default)]
+    pub body_path: Option<PathBuf>,
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+/// A call of one of the extension's exports, with the output expected from it.
+#[derive(Debug, Deserialize)]
+pub struct ExtensionTest {
+    pub name: String,
+    #[serde(flatten)]
+    pub call: ExtensionTestCall,
+    /// The output of the call, as JSON.
+    #[serde(default)]
+    pub expected: Option<Value>,
+    /// A part of the error message, when the call is expected to fail.
+    #[serde(default)]
+    pub expected_error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum ExtensionTestCall {
+    LanguageServerCommand {
+        language_server_id: LanguageServerName,
+    },
+    LanguageServerInitializationOptions {
+        language_server_id: LanguageServerName,
+    },
+    LanguageServerWorkspaceConfiguration {
+        language_server_id: LanguageServerName,
+    },
+    LabelsForCompletions {
+        language_server_id: LanguageServerName,
+        completions: Vec<lsp::CompletionItem>,
+    },
+    LabelsForSymbols {
+        language_server_id: LanguageServerName,
+        symbols: Vec<SymbolFixture>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SymbolFixture {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+}
+
+#[derive(Debug)]
+pub struct ExtensionTestOutcome {
+    pub name: String,
+    /// Why the test failed, if it did.
+    pub failure: Option<String>,
+}
+
+/// Registers the settings read by the extensions, in an app running the tests without the rest of Zed.
+pub fn init(cx: &mut AppContext) {
+    settings::init(cx);
+    Project::init_settings(cx);
+    ExtensionSettings::register(cx);
+    language::init(cx);
+}
+
+/// Loads the compiled extension, with the fakes of the fixture, and runs the fixture's tests in order.
+pub fn run_extension_tests(
+    extension_dir: PathBuf,
+    manifest: Arc<ExtensionManifest>,
+    fixture: ExtensionTestFixture,
+    fs: Arc<dyn Fs>,
+    work_dir: PathBuf,
+    cx: &mut AppContext,
+) -> Task<Result<Vec<ExtensionTestOutcome>>> {
+    let settings = fixture
+        .settings
+        .as_ref()
+        .map_or_else(|| "{}".to_string(), |settings| settings.to_string());
+    if let Err(error) =
+        cx.update_global::<SettingsStore, _>(|store, cx| store.set_user_settings(&settings, cx))
+    {
+        return Task::ready(Err(error.context("invalid settings in test fixture")));
+    }
+
+    let http_client: Arc<dyn HttpClient> = Arc::new(FixtureHttpClient {
+        responses: fixture.http,
+        extension_dir: extension_dir.clone(),
+    });
+    let node_runtime = Arc::new(FixtureNodeRuntime {
+        latest_versions: fixture.npm_packages,
+    });
+    let language_registry = Arc::new(LanguageRegistry::new(
+        Task::ready(()),
+        cx.background_executor().clone(),
+    ));
+    let host = WasmHost::new(
+        fs.clone(),
+        http_client.clone(),
+        node_runtime,
+        language_registry,
+        work_dir,
+        cx,
+    );
+    let worktree: Arc<dyn LspAdapterDelegate> = Arc::new(FixtureWorktree {
+        fixture: fixture.worktree,
+        http_client,
+    });
+    let tests = fixture.tests;
+    let executor = cx.background_executor().clone();
+    cx.spawn(|_| async move {
+        let wasm_path = extension_dir.join("extension.wasm");
+        let mut wasm_bytes = Vec::new();
+        fs.open_sync(&wasm_path)
+            .await
+            .context("failed to open wasm file")?
+            .read_to_end(&mut wasm_bytes)
+            .context("failed to read wasm")?;
+        let extension = host
+            .load_extension(wasm_bytes, manifest.clone(), executor)
+            .await
+            .with_context(|| format!("failed to load wasm extension {}", manifest.id))?;
+
+        let mut outcomes = Vec::new();
+        for test in tests {
+            let output = call_extension(
+                &extension,
+                manifest.clone(),
+                test.call.clone(),
+                worktree.clone(),
+            )
+            .await;
+            outcomes.push(ExtensionTestOutcome {
+                failure: check_output(output, &test),
+                name: test.name,
+            });
+        }
+        Ok(outcomes)
+    })
+}
+
+async fn call_extension(
+    extension: &WasmExtension,
+    manifest: Arc<ExtensionManifest>,
+    call: ExtensionTestCall,
+    worktree: Arc<dyn LspAdapterDelegate>,
+) -> Result<Value> {
+    extension
+        .call(move |extension, store| {
+            async move {
+                let output = match call {
+                    ExtensionTestCall::LanguageServerCommand { language_server_id } => {
+                        let config = language_server_config(&manifest, &language_server_id)?;
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let command = extension
+                            .call_language_server_command(
+                                store,
+                                &language_server_id,
+                                &config,
+                                resource,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+                        json!({
+                            "command": command.command,
+                            "args": command.args,
+                            "env": command.env.into_iter().collect::<BTreeMap<_, _>>(),
+                        })
+                    }
+                    ExtensionTestCall::LanguageServerInitializationOptions {
+                        language_server_id,
+                    } => {
+                        let config = language_server_config(&manifest, &language_server_id)?;
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let options = extension
+                            .call_language_server_initialization_options(
+                                store,
+                                &language_server_id,
+                                &config,
+                                resource,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+                        parse_json_output(options)?
+                    }
+                    ExtensionTestCall::LanguageServerWorkspaceConfiguration {
+                        language_server_id,
+                    } => {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let configuration = extension
+                            .call_language_server_workspace_configuration(
+                                store,
+                                &language_server_id,
+                                resource,
+                            )
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+                        parse_json_output(configuration)?
+                    }
+                    ExtensionTestCall::LabelsForCompletions {
+                        language_server_id,
+                        completions,
+                    } => {
+                        let completions = completions.into_iter().map(Into::into).collect();
+                        let labels = extension
+                            .call_labels_for_completions(store, &language_server_id, completions)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+                        labels_to_json(labels)
+                    }
+                    ExtensionTestCall::LabelsForSymbols {
+                        language_server_id,
+                        symbols,
+                    } => {
+                        let symbols = symbols
+                            .into_iter()
+                            .map(|symbol| wit::Symbol {
+                                name: symbol.name,
+                                kind: symbol.kind.into(),
+                            })
+                            .collect();
+                        let labels = extension
+                            .call_labels_for_symbols(store, &language_server_id, symbols)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+                        labels_to_json(labels)
+                    }
+                };
+                anyhow::Ok(output)
+            }
+            .boxed()
+        })
+        .await
+}
+
+fn language_server_config(
+    manifest: &ExtensionManifest,
+    language_server_id: &LanguageServerName,
+) -> Result<LanguageServerConfig> {
+    let language_server = manifest
+        .language_servers
+        .get(language_server_id)
+        .ok_or_else(|| {
+            anyhow!(
+                "language server {} is not in the extension manifest",
+                language_server_id.0
+            )
+        })?;
+    let language = language_server
+        .languages()
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("language server {} has no languages", language_server_id.0))?;
+    Ok(LanguageServerConfig {
+        name: language_server_id.0.to_string(),
+        language_name: language.to_string(),
+    })
+}
+
+fn parse_json_output(output: Option<String>) -> Result<Value> {
+    match output {
+        Some(output) => serde_json::from_str(&output)
+            .with_context(|| format!("failed to parse JSON from extension: {output}")),
+        None => Ok(Value::Null),
+    }
+}
+
+fn labels_to_json(labels: Vec<Option<wit::CodeLabel>>) -> Value {
+    labels
+        .into_iter()
+        .map(|label| {
+            let Some(label) = label else {
+                return Value::Null;
+            };
+            let spans = label
+                .spans
+                .into_iter()
+                .map(|span| match span {
+                    wit::CodeLabelSpan::CodeRange(range) => {
+                        json!({ "code_range": [range.start, range.end] })
+                    }
+                    wit::CodeLabelSpan::Literal(literal) => json!({
+                        "literal": {
+                            "text": literal.text,
+                            "highlight_name": literal.highlight_name,
+                        }
+                    }),
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "code": label.code,
+                "spans": spans,
+                "filter_range": [label.filter_range.start, label.filter_range.end],
+            })
+        })
+        .collect()
+}
+
+/// Returns why the test failed, if it did.
+fn check_output(output: Result<Value>, test: &ExtensionTest) -> Option<String> {
+    match (output, &test.expected_error) {
+        (Ok(output), None) => match &test.expected {
+            Some(expected) if *expected != output => Some(format!(
+                "expected:\n{}\nactual:\n{}",
+                to_pretty_json(expected),
+                to_pretty_json(&output)
+            )),
+            _ => None,
+        },
+        (Ok(output), Some(expected_error)) => Some(format!(
+            "expected an error containing {expected_error:?}, got:\n{}",
+            to_pretty_json(&output)
+        )),
+        (Err(error), Some(expected_error))
+            if format!("{error:#}").contains(expected_error.as_str()) =>
+        {
+            None
+        }
+        (Err(error), _) => Some(format!("{error:#}")),
+    }
+}
+
+fn to_pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+struct FixtureWorktree {
+    fixture: WorktreeFixture,
+    http_client: Arc<dyn HttpClient>,
+}
+
+#[async_trait]
+impl LspAdapterDelegate for FixtureWorktree {
+    fn show_notification(&self, message: &str, _: &mut AppContext) {
+        log::info!("notification: {message}");
+    }
+
+    fn http_client(&self) -> Arc<dyn HttpClient> {
+        self.http_client.clone()
+    }
+
+    fn worktree_id(&self) -> u64 {
+        0
+    }
+
+    fn worktree_root_path(&self) -> &Path {
+        &self.fixture.root_path
+    }
+
+    fn update_status(&self, _: LanguageServerName, _: LanguageServerBinaryStatus) {}
+
+    async fn which(&self, command: &OsStr) -> Option<PathBuf> {
+        self.fixture.binaries.get(command.to_str()?).cloned()
+    }
+
+    async fn shell_env(&self) -> HashMap<String, String> {
+        self.fixture.shell_env.clone()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.fixture
+            .files
+            .get(&path)
+            .cloned()
+            .ok_or_else(|| anyhow!("no such path {path:?}"))
+    }
+
+    async fn read_dir(&self, path: PathBuf) -> Result<Vec<(String, bool)>> {
+        // The directories are the ancestors of the files in the fixture.
+        let mut entries = BTreeMap::default();
+        for file_path in self.fixture.files.keys() {
+            let Ok(relative_path) = file_path.strip_prefix(&path) else {
+                continue;
+            };
+            let mut components = relative_path.components();
+            let Some(name) = components.next() else {
+                continue;
+            };
+            let is_dir = components.next().is_some();
+            *entries
+                .entry(name.as_os_str().to_string_lossy().into_owned())
+                .or_insert(is_dir) |= is_dir;
+        }
+        if entries.is_empty() && path != Path::new("") {
+            bail!("no such directory {path:?}");
+        }
+        Ok(entries.into_iter().collect())
+    }
+}
+
+struct FixtureHttpClient {
+    responses: HashMap<String, HttpResponseFixture>,
+    extension_dir: PathBuf,
+}
+
+impl HttpClient for FixtureHttpClient {
+    fn send(
+        &self,
+        request: Request<AsyncBody>,
+    ) -> BoxFuture<'static, Result<Response<AsyncBody>, http::Error>> {
+        let uri = request.uri().to_string();
+        let (status, body) = match self.responses.get(&uri) {
+            Some(response) => {
+                let status = StatusCode::from_u16(response.status).unwrap_or_else(|_| {
+                    log::error!("invalid status {} for {uri} in fixture", response.status);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                });
+                match &response.body_path {
+                    Some(body_path) => match std::fs::read(self.extension_dir.join(body_path)) {
+                        Ok(body) => (status, body),
+                        Err(error) => {
+                            log::error!("failed to read {body_path:?} for {uri}: {error}");
+                            (StatusCode::INTERNAL_SERVER_ERROR, Vec::new())
+                        }
+                    },
+                    None => (status, response.body.clone().into_bytes()),
+                }
+            }
+            None => {
+                log::warn!("no response for {uri} in fixture");
+                (StatusCode::NOT_FOUND, Vec::new())
+            }
+        };
+        let response = Response::builder()
+            .status(status)
+            .body(AsyncBody::from(body))
+            .unwrap();
+        async move { Ok(response) }.boxed()
+    }
+
+    fn proxy(&self) -> Option<&str> {
+        None
+    }
+}
+
+struct FixtureNodeRuntime {
+    latest_versions: HashMap<String, String>,
+}
+
+#[async_trait]
+impl NodeRuntime for FixtureNodeRuntime {
+    async fn binary_path(&self) -> Result<PathBuf> {
+        Ok(PathBuf::from("node"))
+    }
+
+    async fn run_npm_subcommand(
+        &self,
+        _: Option<&Path>,
+        subcommand: &str,
+        _: &[&str],
+    ) -> Result<Output> {
+        bail!("npm subcommand '{subcommand}' can't be run in tests")
+    }
+
+    async fn npm_package_latest_version(&self, name: &str) -> Result<String> {
+        self.latest_versions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("no version of npm package '{name}' in fixture"))
+    }
+
+    async fn npm_install_packages(&self, _: &Path, packages: &[(&str, &str)]) -> Result<()> {
+        log::info!("installing npm packages {packages:?}");
+        Ok(())
+    }
+
+    async fn npm_package_installed_version(
+        &self,
+        _local_package_directory: &PathBuf,
+        _name: &str,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_fixture() {
+        let fixture: ExtensionTestFixture = serde_json::from_str(
+            r#"{
+                "worktree": { "binaries": { "gopls": "/usr/bin/gopls" } },
+                "tests": [
+                    {
+                        "name": "finds gopls on the PATH",
+                        "call": "language_server_command",
+                        "language_server_id": "gopls",
+                        "expected": { "command": "/usr/bin/gopls", "args": [], "env": {} }
+                    },
+                    {
+                        "name": "labels a function",
+                        "call": "labels_for_symbols",
+                        "language_server_id": "gopls",
+                        "symbols": [{ "name": "main", "kind": 12 }],
+                        "expected_error": "not implemented"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(fixture.worktree.root_path, Path::new("/worktree"));
+        assert_eq!(fixture.tests.len(), 2);
+        assert!(matches!(
+            &fixture.tests[0].call,
+            ExtensionTestCall::LanguageServerCommand { language_server_id }
+                if language_server_id.0.as_ref() == "gopls"
+        ));
+        assert!(matches!(
+            &fixture.tests[1].call,
+            ExtensionTestCall::LabelsForSymbols { symbols, .. }
+                if symbols[0].kind == lsp::SymbolKind::FUNCTION
+        ));
+    }
+
+    #[gpui::test]
+    async fn test_fixture_worktree_read_dir() {
+        let worktree = FixtureWorktree {
+            fixture: WorktreeFixture {
+                files: BTreeMap::from_iter([
+                    (PathBuf::from("go.mod"), String::new()),
+                    (PathBuf::from("cmd/server/main.go"), String::new()),
+                    (PathBuf::from("cmd/client.go"), String::new()),
+                ]),
+                ..WorktreeFixture::default()
+            },
+            http_client: Arc::new(FixtureHttpClient {
+                responses: HashMap::default(),
+                extension_dir: PathBuf::new(),
+            }),
+        };
+
+        assert_eq!(
+            worktree.read_dir(PathBuf::new()).await.unwrap(),
+            vec![("cmd".to_string(), true), ("go.mod".to_string(), false)]
+        );
+        assert_eq!(
+            worktree.read_dir(PathBuf::from("cmd")).await.unwrap(),
+            vec![
+                ("client.go".to_string(), false),
+                ("server".to_string(), true)
+            ]
+        );
+        assert!(worktree.read_dir(PathBuf::from("pkg")).await.is_err());
+    }
+}
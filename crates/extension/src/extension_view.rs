@@ -0,0 +1,207 @@
+use crate::wasm_host::wit;
+use anyhow::{anyhow, bail, Result};
+
+/// Views with more nodes are rejected.
+const MAX_VIEW_NODES: usize = 10_000;
+/// Views nested deeper are rejected, as the nodes are built, rendered and dropped recursively.
+const MAX_VIEW_DEPTH: usize = 64;
+
+/// A node of the view of a panel provided by an extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExtensionViewNode {
+    Vertical(Vec<ExtensionViewNode>),
+    Horizontal(Vec<ExtensionViewNode>),
+    ListItem {
+        id: String,
+        children: Vec<ExtensionViewNode>,
+        selected: bool,
+    },
+    TreeItem {
+        id: String,
+        label: String,
+        expanded: bool,
+        children: Vec<ExtensionViewNode>,
+    },
+    Text(String),
+    Button {
+        id: String,
+        label: String,
+    },
+    Input {
+        id: String,
+        placeholder: String,
+        value: String,
+    },
+}
+
+/// An interaction of the user with the view of an extension's panel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExtensionViewEvent {
+    ButtonClicked(String),
+    ListItemClicked(String),
+    TreeItemToggled { id: String, expanded: bool },
+    InputSubmitted { id: String, value: String },
+}
+
+impl ExtensionViewNode {
+    /// Builds the tree of nodes from a view returned by an extension, where the nodes refer to
+    /// their children by their index in the view's list of nodes.
+    pub(crate) fn from_wit(view: wit::View) -> Result<Self> {
+        let mut nodes = view.nodes.into_iter().map(Some).collect::<Vec<_>>();
+        if nodes.is_empty() {
+            bail!("the view has no nodes");
+        }
+        if nodes.len() > MAX_VIEW_NODES {
+            bail!("the view has more than {MAX_VIEW_NODES} nodes");
+        }
+        Self::take_node(&mut nodes, 0, 0)
+    }
+
+    fn take_node(nodes: &mut [Option<wit::ViewNode>], ix: usize, depth: usize) -> Result<Self> {
+        if depth > MAX_VIEW_DEPTH {
+            bail!("the view is nested more than {MAX_VIEW_DEPTH} levels deep");
+        }
+        let node = nodes[ix]
+            .take()
+            .ok_or_else(|| anyhow!("node {ix} of the view has more than one parent"))?;
+        let mut children = |children: Vec<u32>| {
+            children
+                .into_iter()
+                .map(|child_ix| {
+                    let child_ix = child_ix as usize;
+                    // Children come after their parents, which rules out cycles.
+                    if child_ix <= ix || child_ix >= nodes.len() {
+                        bail!("node {ix} of the view has an invalid child index {child_ix}");
+                    }
+                    Self::take_node(nodes, child_ix, depth + 1)
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(match node {
+            wit::ViewNode::Vertical(ixs) => Self::Vertical(children(ixs)?),
+            wit::ViewNode::Horizontal(ixs) => Self::Horizontal(children(ixs)?),
+            wit::ViewNode::ListItem(item) => Self::ListItem {
+                id: item.id,
+                children: children(item.children)?,
+                selected: item.selected,
+            },
+            wit::ViewNode::TreeItem(item) => Self::TreeItem {
+                id: item.id,
+                label: item.label,
+                expanded: item.expanded,
+                children: children(item.children)?,
+            },
+            wit::ViewNode::Text(text) => Self::Text(text),
+            wit::ViewNode::Button(button) => Self::Button {
+                id: button.id,
+                label: button.label,
+            },
+            wit::ViewNode::Input(input) => Self::Input {
+                id: input.id,
+                placeholder: input.placeholder,
+                value: input.value,
+            },
+        })
+    }
+}
+
+impl From<ExtensionViewEvent> for wit::ViewEvent {
+    fn from(event: ExtensionViewEvent) -> Self {
+        match event {
+            ExtensionViewEvent::ButtonClicked(id) => Self::ButtonClicked(id),
+            ExtensionViewEvent::ListItemClicked(id) => Self::ListItemClicked(id),
+            ExtensionViewEvent::TreeItemToggled { id, expanded } => {
+                Self::TreeItemToggled(wit::TreeItemToggled { id, expanded })
+            }
+            ExtensionViewEvent::InputSubmitted { id, value } => {
+                Self::InputSubmitted(wit::InputSubmitted { id, value })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(nodes: Vec<wit::ViewNode>) -> wit::View {
+        wit::View { nodes }
+    }
+
+    #[test]
+    fn test_view_from_wit() {
+        let node = ExtensionViewNode::from_wit(view(vec![
+            wit::ViewNode::Vertical(vec![1, 3]),
+            wit::ViewNode::TreeItem(wit::TreeItem {
+                id: "services".into(),
+                label: "Services".into(),
+                expanded: true,
+                children: vec![2],
+            }),
+            wit::ViewNode::Text("api".into()),
+            wit::ViewNode::Button(wit::Button {
+                id: "refresh".into(),
+                label: "Refresh".into(),
+            }),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            node,
+            ExtensionViewNode::Vertical(vec![
+                ExtensionViewNode::TreeItem {
+                    id: "services".into(),
+                    label: "Services".into(),
+                    expanded: true,
+                    children: vec![ExtensionViewNode::Text("api".into())],
+                },
+                ExtensionViewNode::Button {
+                    id: "refresh".into(),
+                    label: "Refresh".into(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_view_from_wit() {
+        assert!(ExtensionViewNode::from_wit(view(Vec::new())).is_err());
+        // A child out of bounds.
+        assert!(ExtensionViewNode::from_wit(view(vec![wit::ViewNode::Vertical(vec![1])])).is_err());
+        // A cycle.
+        assert!(ExtensionViewNode::from_wit(view(vec![
+            wit::ViewNode::Vertical(vec![1]),
+            wit::ViewNode::Horizontal(vec![0]),
+        ]))
+        .is_err());
+        // A node with two parents.
+        assert!(ExtensionViewNode::from_wit(view(vec![
+            wit::ViewNode::Vertical(vec![1, 1]),
+            wit::ViewNode::Text("twice".into()),
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_view_size_limits() {
+        let nested = |depth: usize| {
+            let mut nodes = (1..=depth as u32)
+                .map(|child_ix| wit::ViewNode::Vertical(vec![child_ix]))
+                .collect::<Vec<_>>();
+            nodes.push(wit::ViewNode::Text("leaf".into()));
+            view(nodes)
+        };
+        assert!(ExtensionViewNode::from_wit(nested(MAX_VIEW_DEPTH)).is_ok());
+        assert!(ExtensionViewNode::from_wit(nested(MAX_VIEW_DEPTH + 1)).is_err());
+        assert!(ExtensionViewNode::from_wit(nested(MAX_VIEW_NODES - 1)).is_err());
+
+        let wide = |len: usize| {
+            let mut nodes = vec![wit::ViewNode::Vertical((1..len as u32).collect())];
+            nodes.extend((1..len).map(|_| wit::ViewNode::Text("item".into())));
+            view(nodes)
+        };
+        assert!(ExtensionViewNode::from_wit(wide(MAX_VIEW_NODES)).is_ok());
+        assert!(ExtensionViewNode::from_wit(wide(MAX_VIEW_NODES + 1)).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 pub(crate) mod wit;
 
-use crate::ExtensionManifest;
+use crate::{ExtensionCapability, ExtensionManifest, ExtensionSettings};
 use anyhow::{anyhow, bail, Context as _, Result};
 use fs::{normalize_path, Fs};
 use futures::future::LocalBoxFuture;
@@ -17,6 +17,7 @@ use http::HttpClient;
 use language::LanguageRegistry;
 use node_runtime::NodeRuntime;
 use semantic_version::SemanticVersion;
+use settings::Settings;
 use std::{
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
@@ -284,6 +285,53 @@ impl WasmState {
     fn work_dir(&self) -> PathBuf {
         self.host.work_dir.join(self.manifest.id.as_ref())
     }
+
+    /// Fails unless the extension declares a capability allowing the action in its manifest,
+    /// and the user has granted it.
+    fn ensure_capability(
+        &self,
+        action: String,
+        allows: impl Fn(&ExtensionCapability) -> bool,
+    ) -> impl 'static + Future<Output = Result<()>> {
+        let extension_id = self.manifest.id.clone();
+        let declared_capabilities = self
+            .manifest
+            .capabilities
+            .iter()
+            .filter(|capability| allows(capability))
+            .cloned()
+            .collect::<Vec<_>>();
+        let granted_capabilities = self.on_main_thread({
+            let extension_id = extension_id.clone();
+            move |cx| {
+                async move {
+                    cx.update(|cx| {
+                        ExtensionSettings::get_global(cx)
+                            .granted_capabilities(&extension_id)
+                            .to_vec()
+                    })
+                }
+                .boxed_local()
+            }
+        });
+
+        async move {
+            if declared_capabilities.is_empty() {
+                log::warn!("extension {extension_id} denied to {action}: capability not declared");
+                bail!("the extension does not declare a capability to {action}");
+            }
+            let granted_capabilities = granted_capabilities.await?;
+            if !declared_capabilities
+                .iter()
+                .any(|capability| granted_capabilities.contains(capability))
+            {
+                log::warn!("extension {extension_id} denied to {action}: capability not granted");
+                bail!("the user has not granted the extension the capability to {action}");
+            }
+            log::info!("extension {extension_id} allowed to {action}");
+            Ok(())
+        }
+    }
 }
 
 impl wasi::WasiView for WasmState {
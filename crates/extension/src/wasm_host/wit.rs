@@ -1,10 +1,11 @@
 mod since_v0_0_1;
 mod since_v0_0_4;
 mod since_v0_0_6;
-use since_v0_0_6 as latest;
+mod since_v0_0_7;
+use since_v0_0_7 as latest;
 
 use super::{wasm_engine, WasmState};
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use language::{LanguageServerName, LspAdapterDelegate};
 use semantic_version::SemanticVersion;
 use std::{ops::RangeInclusive, sync::Arc};
@@ -16,7 +17,15 @@ use wasmtime::{
 #[cfg(test)]
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
-    zed::extension::lsp::{Completion, CompletionKind, InsertTextFormat, Symbol, SymbolKind},
+    zed::extension::lsp::{
+        CommandContext, CommandOutput, Completion, CompletionKind, InsertTextFormat, MessageLevel,
+        NotificationEffect, Position, Request, Symbol, SymbolKind,
+    },
+    zed::extension::slash_command::{SlashCommand, SlashCommandOutput},
+    zed::extension::task::{TaskContext, TaskTemplate},
+    zed::extension::ui::{
+        Button, InputSubmitted, TreeItem, TreeItemToggled, View, ViewEvent, ViewNode,
+    },
     CodeLabel, CodeLabelSpan, Command, Range,
 };
 pub use since_v0_0_4::LanguageServerConfig;
@@ -46,6 +55,7 @@ pub fn wasm_api_version_range() -> RangeInclusive<SemanticVersion> {
 }
 
 pub enum Extension {
+    V007(since_v0_0_7::Extension),
     V006(since_v0_0_6::Extension),
     V004(since_v0_0_4::Extension),
     V001(since_v0_0_1::Extension),
@@ -62,6 +72,15 @@ impl Extension {
                 latest::Extension::instantiate_async(store, &component, latest::linker())
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok((Self::V007(extension), instance))
+        } else if version >= since_v0_0_6::MIN_VERSION {
+            let (extension, instance) = since_v0_0_6::Extension::instantiate_async(
+                store,
+                &component,
+                since_v0_0_6::linker(),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok((Self::V006(extension), instance))
         } else if version >= since_v0_0_4::MIN_VERSION {
             let (extension, instance) = since_v0_0_4::Extension::instantiate_async(
@@ -86,6 +105,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V007(ext) => ext.call_init_extension(store).await,
             Extension::V006(ext) => ext.call_init_extension(store).await,
             Extension::V004(ext) => ext.call_init_extension(store).await,
             Extension::V001(ext) => ext.call_init_extension(store).await,
@@ -100,10 +120,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_language_server_command(store, &language_server_id.0, resource)
+                .await?
+                .map(|command| command.into())),
             Extension::V004(ext) => Ok(ext
                 .call_language_server_command(store, config, resource)
                 .await?
@@ -123,6 +147,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V006(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -153,6 +185,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V006(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -173,10 +213,19 @@ impl Extension {
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
             Extension::V001(_) | Extension::V004(_) => Ok(Ok(Vec::new())),
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
         }
     }
 
@@ -188,10 +237,200 @@ impl Extension {
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
             Extension::V001(_) | Extension::V004(_) => Ok(Ok(Vec::new())),
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
+        }
+    }
+
+    pub async fn call_complete_slash_command_argument(
+        &self,
+        store: &mut Store<WasmState>,
+        command: &SlashCommand,
+        query: &str,
+    ) -> Result<Result<Vec<String>, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_complete_slash_command_argument(store, command, query)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_run_slash_command(
+        &self,
+        store: &mut Store<WasmState>,
+        command: &SlashCommand,
+        argument: Option<&str>,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<SlashCommandOutput, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_run_slash_command(store, command, argument, resource)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => {
+                Err(anyhow!("`run_slash_command` not available prior to v0.0.7"))
+            }
+        }
+    }
+
+    pub async fn call_tasks_for_worktree(
+        &self,
+        store: &mut Store<WasmState>,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Vec<TaskTemplate>, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_tasks_for_worktree(store, resource).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_task_variables(
+        &self,
+        store: &mut Store<WasmState>,
+        context: &TaskContext,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Vec<(String, String)>, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_task_variables(store, context, resource).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_formatter_command(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_id: &str,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Command, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_formatter_command(store, formatter_id, resource)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => {
+                Err(anyhow!("`formatter_command` not available prior to v0.0.7"))
+            }
+        }
+    }
+
+    pub async fn call_linter_command(
+        &self,
+        store: &mut Store<WasmState>,
+        linter_id: &str,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Command, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_linter_command(store, linter_id, resource).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => {
+                Err(anyhow!("`linter_command` not available prior to v0.0.7"))
+            }
+        }
+    }
+
+    pub async fn call_language_server_command_request(
+        &self,
+        store: &mut Store<WasmState>,
+        language_server_id: &LanguageServerName,
+        command: &str,
+        context: &CommandContext,
+    ) -> Result<Result<Request, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_command_request(
+                    store,
+                    &language_server_id.0,
+                    command,
+                    context,
+                )
+                .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Err(anyhow!(
+                "`language_server_command_request` not available prior to v0.0.7"
+            )),
+        }
+    }
+
+    pub async fn call_language_server_command_output(
+        &self,
+        store: &mut Store<WasmState>,
+        language_server_id: &LanguageServerName,
+        command: &str,
+        response: &str,
+    ) -> Result<Result<CommandOutput, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_command_output(
+                    store,
+                    &language_server_id.0,
+                    command,
+                    response,
+                )
+                .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Err(anyhow!(
+                "`language_server_command_output` not available prior to v0.0.7"
+            )),
+        }
+    }
+
+    pub async fn call_handle_language_server_notification(
+        &self,
+        store: &mut Store<WasmState>,
+        language_server_id: &LanguageServerName,
+        method: &str,
+        params: &str,
+    ) -> Result<Result<Vec<NotificationEffect>, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_handle_language_server_notification(
+                    store,
+                    &language_server_id.0,
+                    method,
+                    params,
+                )
+                .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_render_panel(
+        &self,
+        store: &mut Store<WasmState>,
+        panel_id: &str,
+    ) -> Result<Result<View, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_render_panel(store, panel_id).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => {
+                Err(anyhow!("`render_panel` not available prior to v0.0.7"))
+            }
+        }
+    }
+
+    pub async fn call_handle_panel_event(
+        &self,
+        store: &mut Store<WasmState>,
+        panel_id: &str,
+        event: &ViewEvent,
+    ) -> Result<Result<View, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_handle_panel_event(store, panel_id, event).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Err(anyhow!(
+                "`handle_panel_event` not available prior to v0.0.7"
+            )),
         }
     }
 }
@@ -23,8 +23,10 @@ pub use wit::{
         npm_package_latest_version,
     },
     zed::extension::platform::{current_platform, Architecture, Os},
-    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
-    LanguageServerInstallationStatus, Range, Worktree,
+    zed::extension::slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection},
+    zed::extension::task::{TaskContext, TaskTemplate},
+    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DirectoryEntry, DownloadedFileType,
+    EnvVars, LanguageServerInstallationStatus, Range, Worktree,
 };
 
 // Undocumented WIT re-exports.
@@ -38,10 +40,128 @@ pub use wit::Guest;
 /// Language Server Protocol (LSP).
 pub mod lsp {
     pub use crate::wit::zed::extension::lsp::{
-        Completion, CompletionKind, InsertTextFormat, Symbol, SymbolKind,
+        CommandContext, CommandOutput, Completion, CompletionKind, InsertTextFormat,
+        LocationOutput, MessageLevel, NotificationEffect, Position, Progress, Request, Symbol,
+        SymbolKind, TextOutput,
     };
 }
 
+/// The views of the panels provided by the extension.
+pub mod ui {
+    use crate::wit::zed::extension::ui::{self as wit, View, ViewNode};
+    pub use crate::wit::zed::extension::ui::{InputSubmitted, TreeItemToggled, ViewEvent};
+
+    /// A node of the view of a panel.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Node {
+        /// A vertical list of nodes.
+        Vertical(Vec<Node>),
+        /// A horizontal row of nodes.
+        Horizontal(Vec<Node>),
+        /// An item of a list, that sends [`ViewEvent::ListItemClicked`] with its ID when it is clicked.
+        ListItem {
+            id: String,
+            children: Vec<Node>,
+            selected: bool,
+        },
+        /// An item of a tree, that sends [`ViewEvent::TreeItemToggled`] with its ID when it is expanded or collapsed.
+        TreeItem {
+            id: String,
+            label: String,
+            expanded: bool,
+            children: Vec<Node>,
+        },
+        /// A text label.
+        Text(String),
+        /// A button, that sends [`ViewEvent::ButtonClicked`] with its ID when it is clicked.
+        Button { id: String, label: String },
+        /// A single-line text input, that sends [`ViewEvent::InputSubmitted`] with its ID when the user presses enter.
+        Input {
+            id: String,
+            placeholder: String,
+            value: String,
+        },
+    }
+
+    impl Node {
+        /// Returns a [`Node::Text`].
+        pub fn text(text: impl Into<String>) -> Self {
+            Self::Text(text.into())
+        }
+
+        /// Returns a [`Node::Button`].
+        pub fn button(id: impl Into<String>, label: impl Into<String>) -> Self {
+            Self::Button {
+                id: id.into(),
+                label: label.into(),
+            }
+        }
+
+        pub(crate) fn into_view(self) -> View {
+            let mut nodes = Vec::new();
+            self.flatten(&mut nodes);
+            View { nodes }
+        }
+
+        /// Adds the node and its descendants to the nodes of a view, and returns the node's index.
+        fn flatten(self, nodes: &mut Vec<ViewNode>) -> u32 {
+            let ix = nodes.len();
+            // The node is added before its children, which refer to it by a greater index.
+            nodes.push(ViewNode::Text(String::new()));
+            let node = match self {
+                Node::Vertical(children) => ViewNode::Vertical(flatten_all(children, nodes)),
+                Node::Horizontal(children) => ViewNode::Horizontal(flatten_all(children, nodes)),
+                Node::ListItem {
+                    id,
+                    children,
+                    selected,
+                } => ViewNode::ListItem(wit::ListItem {
+                    id,
+                    children: flatten_all(children, nodes),
+                    selected,
+                }),
+                Node::TreeItem {
+                    id,
+                    label,
+                    expanded,
+                    children,
+                } => ViewNode::TreeItem(wit::TreeItem {
+                    id,
+                    label,
+                    expanded,
+                    children: flatten_all(children, nodes),
+                }),
+                Node::Text(text) => ViewNode::Text(text),
+                Node::Button { id, label } => ViewNode::Button(wit::Button { id, label }),
+                Node::Input {
+                    id,
+                    placeholder,
+                    value,
+                } => ViewNode::Input(wit::Input {
+                    id,
+                    placeholder,
+                    value,
+                }),
+            };
+            nodes[ix] = node;
+            ix as u32
+        }
+    }
+
+    fn flatten_all(children: Vec<Node>, nodes: &mut Vec<ViewNode>) -> Vec<u32> {
+        children
+            .into_iter()
+            .map(|child| child.flatten(nodes))
+            .collect()
+    }
+}
+
+/// Running commands on the user's machine, as allowed by the `process:exec`
+/// capabilities the extension declares and the user grants.
+pub mod process {
+    pub use crate::wit::zed::extension::process::{run, Output};
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -103,6 +223,96 @@ pub trait Extension: Send + Sync {
     ) -> Option<CodeLabel> {
         None
     }
+
+    /// Returns the completions that should be shown when completing the provided slash command with the given query.
+    fn complete_slash_command_argument(
+        &self,
+        _command: SlashCommand,
+        _query: String,
+    ) -> Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the output from running the provided slash command.
+    fn run_slash_command(
+        &self,
+        _command: SlashCommand,
+        _argument: Option<String>,
+        _worktree: &Worktree,
+    ) -> Result<SlashCommandOutput, String> {
+        Err("`run_slash_command` not implemented".to_string())
+    }
+
+    /// Returns the tasks available in the given worktree, e.g. the targets defined in its build files.
+    fn tasks_for_worktree(&self, _worktree: &Worktree) -> Result<Vec<TaskTemplate>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the values of the custom task variables for the tasks spawned in the given worktree with the provided context.
+    ///
+    /// A variable named `foo` is available to the tasks as `ZED_CUSTOM_foo`.
+    fn task_variables(
+        &self,
+        _context: TaskContext,
+        _worktree: &Worktree,
+    ) -> Result<Vec<(String, String)>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the command used to run the formatter, installing it first if needed.
+    ///
+    /// The text to format is written to the command's standard input and the formatted text is read from its standard output.
+    /// `{buffer_path}` in the arguments is replaced with the path of the file being formatted.
+    fn formatter_command(&mut self, _formatter_id: &str, _worktree: &Worktree) -> Result<Command> {
+        Err("`formatter_command` not implemented".to_string())
+    }
+
+    /// Returns the command used to run the linter on a saved file, installing it first if needed.
+    ///
+    /// `{buffer_path}` in the arguments is replaced with the path of the file to lint.
+    fn linter_command(&mut self, _linter_id: &str, _worktree: &Worktree) -> Result<Command> {
+        Err("`linter_command` not implemented".to_string())
+    }
+
+    /// Returns the request to send to the language server for the given command, declared in the extension manifest.
+    fn language_server_command_request(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        _command: &str,
+        _context: lsp::CommandContext,
+    ) -> Result<lsp::Request> {
+        Err("`language_server_command_request` not implemented".to_string())
+    }
+
+    /// Returns what to show for the language server's response to the given command.
+    fn language_server_command_output(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        _command: &str,
+        _response: serde_json::Value,
+    ) -> Result<lsp::CommandOutput> {
+        Ok(lsp::CommandOutput::None)
+    }
+
+    /// Handles a custom notification from the language server, declared in the extension manifest.
+    fn handle_language_server_notification(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        _method: &str,
+        _params: serde_json::Value,
+    ) -> Result<Vec<lsp::NotificationEffect>> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the view of the given panel, declared in the extension manifest.
+    fn render_panel(&mut self, _panel_id: &str) -> Result<ui::Node> {
+        Err("`render_panel` not implemented".to_string())
+    }
+
+    /// Handles an interaction of the user with the view of the given panel, and returns the panel's new view.
+    fn handle_panel_event(&mut self, panel_id: &str, _event: ui::ViewEvent) -> Result<ui::Node> {
+        self.render_panel(panel_id)
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -140,7 +350,7 @@ pub static ZED_API_VERSION: [u8; 6] = *include_bytes!(concat!(env!("OUT_DIR"), "
 mod wit {
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.0.6",
+        path: "./wit/since_v0.0.7",
     });
 }
 
@@ -208,6 +418,82 @@ impl wit::Guest for Component {
         }
         Ok(labels)
     }
+
+    fn complete_slash_command_argument(
+        command: SlashCommand,
+        query: String,
+    ) -> Result<Vec<String>, String> {
+        extension().complete_slash_command_argument(command, query)
+    }
+
+    fn run_slash_command(
+        command: SlashCommand,
+        argument: Option<String>,
+        worktree: &Worktree,
+    ) -> Result<SlashCommandOutput, String> {
+        extension().run_slash_command(command, argument, worktree)
+    }
+
+    fn tasks_for_worktree(worktree: &Worktree) -> Result<Vec<TaskTemplate>, String> {
+        extension().tasks_for_worktree(worktree)
+    }
+
+    fn task_variables(
+        context: TaskContext,
+        worktree: &Worktree,
+    ) -> Result<Vec<(String, String)>, String> {
+        extension().task_variables(context, worktree)
+    }
+
+    fn formatter_command(formatter_id: String, worktree: &Worktree) -> Result<Command> {
+        extension().formatter_command(&formatter_id, worktree)
+    }
+
+    fn linter_command(linter_id: String, worktree: &Worktree) -> Result<Command> {
+        extension().linter_command(&linter_id, worktree)
+    }
+
+    fn language_server_command_request(
+        language_server_id: String,
+        command: String,
+        context: lsp::CommandContext,
+    ) -> Result<lsp::Request> {
+        let language_server_id = LanguageServerId(language_server_id);
+        extension().language_server_command_request(&language_server_id, &command, context)
+    }
+
+    fn language_server_command_output(
+        language_server_id: String,
+        command: String,
+        response: String,
+    ) -> Result<lsp::CommandOutput> {
+        let language_server_id = LanguageServerId(language_server_id);
+        let response = serde_json::from_str(&response).map_err(|error| error.to_string())?;
+        extension().language_server_command_output(&language_server_id, &command, response)
+    }
+
+    fn handle_language_server_notification(
+        language_server_id: String,
+        method: String,
+        params: String,
+    ) -> Result<Vec<lsp::NotificationEffect>> {
+        let language_server_id = LanguageServerId(language_server_id);
+        let params = serde_json::from_str(&params).map_err(|error| error.to_string())?;
+        extension().handle_language_server_notification(&language_server_id, &method, params)
+    }
+
+    fn render_panel(panel_id: String) -> Result<wit::zed::extension::ui::View> {
+        Ok(extension().render_panel(&panel_id)?.into_view())
+    }
+
+    fn handle_panel_event(
+        panel_id: String,
+        event: ui::ViewEvent,
+    ) -> Result<wit::zed::extension::ui::View> {
+        Ok(extension()
+            .handle_panel_event(&panel_id, event)?
+            .into_view())
+    }
 }
 
 /// The ID of a language server.
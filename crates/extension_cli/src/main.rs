@@ -8,27 +8,58 @@ use std::{
 
 use ::fs::{copy_recursive, CopyOptions, Fs, RealFs};
 use anyhow::{anyhow, bail, Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use extension::{
     extension_builder::{CompileExtensionOptions, ExtensionBuilder},
+    extension_test_runner::{self, run_extension_tests, ExtensionTestFixture},
     ExtensionManifest,
 };
 use language::LanguageConfig;
+use ring::{
+    rand::SystemRandom,
+    signature::{Ed25519KeyPair, KeyPair as _},
+};
+use sha2::{Digest, Sha256};
 use theme::ThemeRegistry;
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
-#[command(name = "zed-extension")]
+#[command(name = "zed-extension", subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// The path to the extension directory
-    #[arg(long)]
-    source_dir: PathBuf,
+    #[arg(long, required = true)]
+    source_dir: Option<PathBuf>,
     /// The output directory to place the packaged extension.
-    #[arg(long)]
-    output_dir: PathBuf,
+    #[arg(long, required = true)]
+    output_dir: Option<PathBuf>,
     /// The path to a directory where build dependencies are downloaded
+    #[arg(long, required = true)]
+    scratch_dir: Option<PathBuf>,
+    /// The path to a private key created with `generate-signing-key`, to sign the archive with
     #[arg(long)]
-    scratch_dir: PathBuf,
+    signing_key: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Runs the tests of the extension, described by the JSON fixtures in its `tests` directory.
+    Test {
+        /// The path to the extension directory
+        #[arg(long)]
+        source_dir: PathBuf,
+        /// The path to a directory where build dependencies are downloaded
+        #[arg(long)]
+        scratch_dir: PathBuf,
+    },
+    /// Generates a key pair to sign extension archives with, writing the private key to a file
+    /// and printing the public key to set as `extension_public_key` in Zed's settings.
+    GenerateSigningKey {
+        /// The path of the file to write the private key to
+        #[arg(long)]
+        output: PathBuf,
+    },
 }
 
 #[tokio::main]
@@ -36,22 +67,55 @@ async fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    match (
+        args.command,
+        args.source_dir,
+        args.output_dir,
+        args.scratch_dir,
+    ) {
+        (
+            Some(Command::Test {
+                source_dir,
+                scratch_dir,
+            }),
+            _,
+            _,
+            _,
+        ) => test_extension(&source_dir, &scratch_dir).await,
+        (Some(Command::GenerateSigningKey { output }), _, _, _) => generate_signing_key(&output),
+        (None, Some(source_dir), Some(output_dir), Some(scratch_dir)) => {
+            package_extension(
+                &source_dir,
+                &output_dir,
+                &scratch_dir,
+                args.signing_key.as_deref(),
+            )
+            .await
+        }
+        _ => unreachable!("the packaging arguments are required without a subcommand"),
+    }
+}
+
+async fn package_extension(
+    source_dir: &Path,
+    output_dir: &Path,
+    scratch_dir: &Path,
+    signing_key: Option<&Path>,
+) -> Result<()> {
     let fs = Arc::new(RealFs::default());
     let engine = wasmtime::Engine::default();
     let mut wasm_store = WasmStore::new(engine)?;
 
-    let extension_path = args
-        .source_dir
+    let extension_path = source_dir
         .canonicalize()
         .context("failed to canonicalize source_dir")?;
-    let scratch_dir = args
-        .scratch_dir
+    let scratch_dir = scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(output_dir)
     } else {
-        args.output_dir
+        output_dir.to_path_buf()
     };
 
     log::info!("loading extension manifest");
@@ -90,6 +154,20 @@ async fn main() -> Result<()> {
         );
     }
 
+    let archive = fs::read(output_dir.join("archive.tar.gz"))?;
+    let archive_checksum = hex::encode(Sha256::digest(&archive));
+    fs::write(
+        output_dir.join("archive.tar.gz.sha256"),
+        format!("{archive_checksum}  archive.tar.gz\n"),
+    )?;
+    if let Some(signing_key) = signing_key {
+        let signature = load_signing_key(signing_key)?.sign(&archive);
+        fs::write(
+            output_dir.join("archive.tar.gz.sig"),
+            format!("{}\n", base64::encode(signature)),
+        )?;
+    }
+
     let manifest_json = serde_json::to_string(&rpc::ExtensionApiManifest {
         name: manifest.name,
         version: manifest.version,
@@ -107,6 +185,137 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn generate_signing_key(output: &Path) -> Result<()> {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+        .map_err(|_| anyhow!("failed to generate signing key"))?;
+    fs::write(output, format!("{}\n", base64::encode(pkcs8.as_ref())))
+        .with_context(|| format!("failed to write signing key to {output:?}"))?;
+    println!("{}", base64::encode(load_signing_key(output)?.public_key()));
+    Ok(())
+}
+
+/// Loads a private key written by `generate_signing_key`.
+fn load_signing_key(path: &Path) -> Result<Ed25519KeyPair> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read signing key {path:?}"))?;
+    let pkcs8 = base64::decode(contents.trim()).context("invalid signing key")?;
+    Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|error| anyhow!("invalid signing key: {error}"))
+}
+
+async fn test_extension(source_dir: &Path, scratch_dir: &Path) -> Result<()> {
+    let fs = Arc::new(RealFs::default());
+    let extension_path = source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let scratch_dir = scratch_dir
+        .canonicalize()
+        .context("failed to canonicalize scratch_dir")?;
+
+    log::info!("loading extension manifest");
+    let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
+    if manifest.lib.kind.is_none() {
+        bail!("extension has no library to test");
+    }
+
+    log::info!("compiling extension");
+    ExtensionBuilder::new(scratch_dir.clone())
+        .compile_extension(
+            &extension_path,
+            &mut manifest,
+            CompileExtensionOptions { release: false },
+        )
+        .await
+        .context("failed to compile extension")?;
+
+    let fixtures = load_test_fixtures(&extension_path.join("tests"))?;
+    let work_dir = scratch_dir.join("test-work");
+    fs::create_dir_all(&work_dir).context("failed to create test work dir")?;
+
+    let manifest = Arc::new(manifest);
+    gpui::App::new().run(move |cx| {
+        extension_test_runner::init(cx);
+        cx.spawn(|cx| async move {
+            let result = async {
+                let mut failures = Vec::new();
+                let mut passed_count = 0;
+                for (fixture_name, fixture) in fixtures {
+                    let outcomes = cx
+                        .update(|cx| {
+                            run_extension_tests(
+                                extension_path.clone(),
+                                manifest.clone(),
+                                fixture,
+                                fs.clone(),
+                                work_dir.clone(),
+                                cx,
+                            )
+                        })?
+                        .await
+                        .with_context(|| format!("failed to run tests in {fixture_name}"))?;
+                    for outcome in outcomes {
+                        let name = format!("{fixture_name}::{}", outcome.name);
+                        if let Some(failure) = outcome.failure {
+                            println!("test {name} ... FAILED");
+                            failures.push((name, failure));
+                        } else {
+                            println!("test {name} ... ok");
+                            passed_count += 1;
+                        }
+                    }
+                }
+
+                for (name, failure) in &failures {
+                    println!("\n---- {name} ----\n{failure}");
+                }
+                println!(
+                    "\ntest result: {}. {passed_count} passed; {} failed",
+                    if failures.is_empty() { "ok" } else { "FAILED" },
+                    failures.len()
+                );
+                anyhow::Ok(failures.is_empty())
+            }
+            .await;
+            // The tests run until the process exits, as the app's event loop doesn't return on every platform.
+            let status = match result {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(error) => {
+                    eprintln!("error: {error:#}");
+                    1
+                }
+            };
+            std::process::exit(status);
+        })
+        .detach();
+    });
+
+    Ok(())
+}
+
+/// Reads the test fixtures in the directory, with their file names, in sorted order.
+fn load_test_fixtures(tests_dir: &Path) -> Result<Vec<(String, ExtensionTestFixture)>> {
+    let mut fixture_paths = fs::read_dir(tests_dir)
+        .with_context(|| format!("failed to read tests dir {}", tests_dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    fixture_paths.retain(|path| path.extension() == Some("json".as_ref()));
+    fixture_paths.sort();
+
+    fixture_paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)?;
+            let fixture = serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse test fixture {}", path.display()))?;
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Ok((name, fixture))
+        })
+        .collect()
+}
+
 async fn copy_extension_resources(
     manifest: &ExtensionManifest,
     extension_path: &Path,
@@ -158,6 +367,44 @@ async fn copy_extension_resources(
         }
     }
 
+    for (dir_name, paths) in [
+        ("snippets", &manifest.snippets),
+        ("icon_themes", &manifest.icon_themes),
+        ("keymaps", &manifest.keymaps),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        let output_files_dir = output_dir.join(dir_name);
+        fs::create_dir_all(&output_files_dir)?;
+        for path in paths {
+            fs::copy(
+                extension_path.join(path),
+                output_files_dir.join(
+                    path.file_name()
+                        .ok_or_else(|| anyhow!("invalid path in {dir_name}"))?,
+                ),
+            )
+            .with_context(|| format!("failed to copy '{}'", path.display()))?;
+        }
+    }
+
+    // The icons of the icon themes are resolved relative to the extension directory.
+    let icons_dir = extension_path.join("icons");
+    if !manifest.icon_themes.is_empty() && icons_dir.is_dir() {
+        copy_recursive(
+            fs.as_ref(),
+            &icons_dir,
+            &output_dir.join("icons"),
+            CopyOptions {
+                overwrite: true,
+                ignore_if_exists: false,
+            },
+        )
+        .await
+        .context("failed to copy icons dir")?;
+    }
+
     if !manifest.languages.is_empty() {
         let output_languages_dir = output_dir.join("languages");
         fs::create_dir_all(&output_languages_dir)?;
@@ -0,0 +1,513 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use extension::{ExtensionStore, ExtensionViewEvent, ExtensionViewNode};
+use fs::Fs;
+use gpui::{
+    actions, Action, AnyElement, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, ParentElement, Pixels, Render, Styled, Subscription, Task,
+    View, ViewContext, VisualContext as _, WeakView, WindowContext,
+};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{prelude::*, IconButton, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::extension_panel_settings::{ExtensionPanelDockPosition, ExtensionPanelSettings};
+
+const EXTENSION_PANEL_KEY: &str = "ExtensionPanel";
+
+actions!(extension_panel, [ToggleFocus, RefreshExtensionPanel]);
+
+pub fn init_settings(cx: &mut AppContext) {
+    ExtensionPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<ExtensionPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// A panel showing the views of the panels provided by the installed extensions,
+/// one at a time.
+pub struct ExtensionPanel {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    panels: Vec<PanelEntry>,
+    active_panel: Option<usize>,
+    view: Option<ExtensionViewNode>,
+    error: Option<SharedString>,
+    /// The editors of the view's inputs by their IDs, along with the value the extension
+    /// gave them, so that they're only reset when the extension changes it.
+    inputs: HashMap<String, (String, View<Editor>)>,
+    pending_view: Task<()>,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedExtensionPanel {
+    width: Option<Pixels>,
+}
+
+#[derive(Clone, PartialEq)]
+struct PanelEntry {
+    extension_id: Arc<str>,
+    panel_id: Arc<str>,
+    label: SharedString,
+}
+
+impl ExtensionPanel {
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let mut extension_panel_settings = *ExtensionPanelSettings::get_global(cx);
+            let mut subscriptions = vec![cx.observe_global::<SettingsStore>(move |_, cx| {
+                let new_settings = *ExtensionPanelSettings::get_global(cx);
+                if extension_panel_settings != new_settings {
+                    extension_panel_settings = new_settings;
+                    cx.notify();
+                }
+            })];
+            if let Some(store) = ExtensionStore::try_global(cx) {
+                subscriptions.push(cx.observe(&store, |panel, _, cx| panel.update_panels(cx)));
+            }
+
+            let mut panel = Self {
+                fs,
+                focus_handle: cx.focus_handle(),
+                panels: Vec::new(),
+                active_panel: None,
+                view: None,
+                error: None,
+                inputs: HashMap::default(),
+                pending_view: Task::ready(()),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            panel.update_panels(cx);
+            panel
+        })
+    }
+
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(EXTENSION_PANEL_KEY) })
+            .await
+            .map_err(|e| anyhow!("Failed to load extension panel: {}", e))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedExtensionPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = ExtensionPanel::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        EXTENSION_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedExtensionPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Updates the list of panels after extensions are loaded or unloaded, keeping the active
+    /// panel if it's still provided.
+    fn update_panels(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(store) = ExtensionStore::try_global(cx) else {
+            return;
+        };
+        let panels = store
+            .read(cx)
+            .panels()
+            .map(|(extension_id, panel_id, panel)| PanelEntry {
+                extension_id: extension_id.clone(),
+                panel_id: panel_id.clone(),
+                label: panel.label.clone().into(),
+            })
+            .collect::<Vec<_>>();
+        if panels == self.panels {
+            return;
+        }
+
+        let active_panel = self
+            .active_panel
+            .and_then(|ix| self.panels.get(ix))
+            .and_then(|active_panel| panels.iter().position(|panel| panel == active_panel))
+            .or_else(|| (!panels.is_empty()).then_some(0));
+        self.panels = panels;
+        self.activate_panel(active_panel, cx);
+    }
+
+    fn activate_panel(&mut self, ix: Option<usize>, cx: &mut ViewContext<Self>) {
+        if ix != self.active_panel {
+            self.view = None;
+            self.inputs.clear();
+        }
+        self.active_panel = ix;
+        self.error = None;
+        self.refresh(&RefreshExtensionPanel, cx);
+    }
+
+    fn refresh(&mut self, _: &RefreshExtensionPanel, cx: &mut ViewContext<Self>) {
+        self.request_view(None, cx);
+    }
+
+    fn send_event(&mut self, event: ExtensionViewEvent, cx: &mut ViewContext<Self>) {
+        self.request_view(Some(event), cx);
+    }
+
+    /// Asks the extension of the active panel for its view, after the given event if any.
+    fn request_view(&mut self, event: Option<ExtensionViewEvent>, cx: &mut ViewContext<Self>) {
+        let Some(panel) = self.active_panel.and_then(|ix| self.panels.get(ix)) else {
+            self.pending_view = Task::ready(());
+            cx.notify();
+            return;
+        };
+        let Some(store) = ExtensionStore::try_global(cx) else {
+            return;
+        };
+        let store = store.read(cx);
+        let view = match event {
+            Some(event) => {
+                store.handle_panel_event(&panel.extension_id, panel.panel_id.clone(), event, cx)
+            }
+            None => store.render_panel(&panel.extension_id, panel.panel_id.clone(), cx),
+        };
+        self.pending_view = cx.spawn(|panel, mut cx| async move {
+            let view = view.await;
+            panel
+                .update(&mut cx, |panel, cx| {
+                    match view {
+                        Ok(view) => {
+                            panel.update_inputs(&view, cx);
+                            panel.view = Some(view);
+                            panel.error = None;
+                        }
+                        Err(error) => panel.error = Some(format!("{error:#}").into()),
+                    }
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    /// Creates the editors of the inputs in the view, and drops the ones of the inputs
+    /// that are gone.
+    fn update_inputs(&mut self, view: &ExtensionViewNode, cx: &mut ViewContext<Self>) {
+        let mut inputs = HashMap::default();
+        let mut nodes = vec![view];
+        while let Some(node) = nodes.pop() {
+            match node {
+                ExtensionViewNode::Vertical(children)
+                | ExtensionViewNode::Horizontal(children)
+                | ExtensionViewNode::ListItem { children, .. }
+                | ExtensionViewNode::TreeItem { children, .. } => nodes.extend(children),
+                ExtensionViewNode::Input {
+                    id,
+                    placeholder,
+                    value,
+                } => {
+                    let input = match self.inputs.remove(id) {
+                        Some((last_value, editor)) if &last_value == value => editor,
+                        Some((_, editor)) => {
+                            editor.update(cx, |editor, cx| editor.set_text(value.clone(), cx));
+                            editor
+                        }
+                        None => cx.new_view(|cx| {
+                            let mut editor = Editor::single_line(cx);
+                            editor.set_text(value.clone(), cx);
+                            editor
+                        }),
+                    };
+                    input.update(cx, |editor, cx| {
+                        editor.set_placeholder_text(placeholder.clone(), cx)
+                    });
+                    inputs.insert(id.clone(), (value.clone(), input));
+                }
+                ExtensionViewNode::Text(_) | ExtensionViewNode::Button { .. } => {}
+            }
+        }
+        self.inputs = inputs;
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .flex_wrap()
+                    .children(self.panels.iter().enumerate().map(|(ix, panel)| {
+                        Button::new(("extension-panel", ix), panel.label.clone())
+                            .selected(self.active_panel == Some(ix))
+                            .on_click(
+                                cx.listener(move |panel, _, cx| panel.activate_panel(Some(ix), cx)),
+                            )
+                    })),
+            )
+            .child(
+                IconButton::new("refresh-extension-panel", IconName::ArrowCircle)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.active_panel.is_none())
+                    .tooltip(|cx| Tooltip::for_action("Refresh", &RefreshExtensionPanel, cx))
+                    .on_click(|_, cx| cx.dispatch_action(RefreshExtensionPanel.boxed_clone())),
+            )
+    }
+
+    fn render_node(
+        &self,
+        node: &ExtensionViewNode,
+        indent_level: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        match node {
+            ExtensionViewNode::Vertical(children) => v_flex()
+                .gap_1()
+                .children(
+                    children
+                        .iter()
+                        .map(|child| self.render_node(child, indent_level, cx)),
+                )
+                .into_any_element(),
+            ExtensionViewNode::Horizontal(children) => h_flex()
+                .gap_1()
+                .children(
+                    children
+                        .iter()
+                        .map(|child| self.render_node(child, indent_level, cx)),
+                )
+                .into_any_element(),
+            ExtensionViewNode::ListItem {
+                id,
+                children,
+                selected,
+            } => {
+                let event = ExtensionViewEvent::ListItemClicked(id.clone());
+                ListItem::new(SharedString::from(format!("list-item-{id}")))
+                    .indent_level(indent_level)
+                    .selected(*selected)
+                    .children(
+                        children
+                            .iter()
+                            .map(|child| self.render_node(child, indent_level, cx)),
+                    )
+                    .on_click(cx.listener(move |panel, _, cx| panel.send_event(event.clone(), cx)))
+                    .into_any_element()
+            }
+            ExtensionViewNode::TreeItem {
+                id,
+                label,
+                expanded,
+                children,
+            } => {
+                let event = ExtensionViewEvent::TreeItemToggled {
+                    id: id.clone(),
+                    expanded: !expanded,
+                };
+                let click_event = event.clone();
+                v_flex()
+                    .child(
+                        ListItem::new(SharedString::from(format!("tree-item-{id}")))
+                            .indent_level(indent_level)
+                            .toggle((!children.is_empty()).then_some(*expanded))
+                            .on_toggle(
+                                cx.listener(move |panel, _, cx| {
+                                    panel.send_event(event.clone(), cx)
+                                }),
+                            )
+                            .child(Label::new(label.clone()))
+                            .on_click(cx.listener(move |panel, _, cx| {
+                                panel.send_event(click_event.clone(), cx)
+                            })),
+                    )
+                    .when(*expanded, |this| {
+                        this.children(
+                            children
+                                .iter()
+                                .map(|child| self.render_node(child, indent_level + 1, cx)),
+                        )
+                    })
+                    .into_any_element()
+            }
+            ExtensionViewNode::Text(text) => Label::new(text.clone()).into_any_element(),
+            ExtensionViewNode::Button { id, label } => {
+                let event = ExtensionViewEvent::ButtonClicked(id.clone());
+                Button::new(SharedString::from(format!("button-{id}")), label.clone())
+                    .on_click(cx.listener(move |panel, _, cx| panel.send_event(event.clone(), cx)))
+                    .into_any_element()
+            }
+            ExtensionViewNode::Input { id, .. } => {
+                let Some((_, editor)) = self.inputs.get(id) else {
+                    return div().into_any_element();
+                };
+                let id = id.clone();
+                let editor = editor.clone();
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .on_action(cx.listener({
+                        let editor = editor.clone();
+                        move |panel, _: &menu::Confirm, cx| {
+                            let value = editor.read(cx).text(cx);
+                            panel.send_event(
+                                ExtensionViewEvent::InputSubmitted {
+                                    id: id.clone(),
+                                    value,
+                                },
+                                cx,
+                            );
+                        }
+                    }))
+                    .child(editor)
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+impl Render for ExtensionPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if let Some(error) = self.error.clone() {
+            v_flex()
+                .p_4()
+                .child(Label::new(error).color(Color::Error))
+                .into_any_element()
+        } else if let Some(view) = &self.view {
+            v_flex()
+                .id("extension-panel-view")
+                .p_1()
+                .size_full()
+                .overflow_y_scroll()
+                .child(self.render_node(view, 0, cx))
+                .into_any_element()
+        } else {
+            let message = if self.panels.is_empty() {
+                "No installed extension provides a panel."
+            } else {
+                "Loading…"
+            };
+            v_flex()
+                .p_4()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .key_context("ExtensionPanel")
+            .on_action(cx.listener(Self::refresh))
+            .track_focus(&self.focus_handle)
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+impl EventEmitter<PanelEvent> for ExtensionPanel {}
+
+impl FocusableView for ExtensionPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for ExtensionPanel {
+    fn persistent_name() -> &'static str {
+        "Extension Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match ExtensionPanelSettings::get_global(cx).dock {
+            ExtensionPanelDockPosition::Left => DockPosition::Left,
+            ExtensionPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<ExtensionPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => ExtensionPanelDockPosition::Left,
+                    DockPosition::Right => ExtensionPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ExtensionPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        (ExtensionPanelSettings::get_global(cx).button && !self.panels.is_empty())
+            .then(|| IconName::Library)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Extension Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
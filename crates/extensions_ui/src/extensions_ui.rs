@@ -1,4 +1,6 @@
 mod components;
+mod extension_panel;
+mod extension_panel_settings;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -9,14 +11,16 @@ use crate::extension_version_selector::{
 use client::telemetry::Telemetry;
 use client::ExtensionMetadata;
 use editor::{Editor, EditorElement, EditorStyle};
-use extension::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension::{
+    ExtensionCapability, ExtensionManifest, ExtensionOperation, ExtensionSettings, ExtensionStore,
+};
 use fuzzy::{match_strings, StringMatchCandidate};
 use gpui::{
     actions, canvas, uniform_list, AnyElement, AppContext, EventEmitter, FocusableView, FontStyle,
     FontWeight, InteractiveElement, KeyContext, ParentElement, Render, Styled, Task, TextStyle,
     UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
-use settings::Settings;
+use settings::{update_settings_file, Settings, SettingsStore};
 use std::ops::DerefMut;
 use std::time::Duration;
 use std::{ops::Range, sync::Arc};
@@ -29,9 +33,13 @@ use workspace::{
     Workspace, WorkspaceId,
 };
 
-actions!(zed, [Extensions, InstallDevExtension]);
+pub use extension_panel::{init_settings as init_panel_settings, ExtensionPanel};
+
+actions!(zed, [Extensions, InstallDevExtension, InstallExtensionFromArchive]);
 
 pub fn init(cx: &mut AppContext) {
+    extension_panel::init(cx);
+
     cx.observe_new_views(move |workspace: &mut Workspace, cx| {
         workspace
             .register_action(move |workspace, _: &Extensions, cx| {
@@ -69,6 +77,28 @@ pub fn init(cx: &mut AppContext) {
                         Some(())
                     })
                     .detach();
+            })
+            .register_action(move |_, _: &InstallExtensionFromArchive, cx| {
+                let store = ExtensionStore::global(cx);
+                let prompt = cx.prompt_for_paths(gpui::PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                });
+
+                cx.deref_mut()
+                    .spawn(|mut cx| async move {
+                        let archive_path = prompt.await.log_err()??.pop()?;
+                        store
+                            .update(&mut cx, |store, cx| {
+                                store
+                                    .install_extension_from_archive(archive_path, cx)
+                                    .detach_and_log_err(cx)
+                            })
+                            .ok()?;
+                        Some(())
+                    })
+                    .detach();
             });
 
         cx.subscribe(workspace.project(), |_, _, event, cx| match event {
@@ -118,7 +148,7 @@ pub struct ExtensionsPage {
     filtered_remote_extension_indices: Vec<usize>,
     query_editor: View<Editor>,
     query_contains_error: bool,
-    _subscriptions: [gpui::Subscription; 2],
+    _subscriptions: [gpui::Subscription; 3],
     extension_fetch_task: Option<Task<()>>,
 }
 
@@ -136,6 +166,7 @@ impl ExtensionsPage {
                     }
                     _ => {}
                 }),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
             ];
 
             let query_editor = cx.new_view(|cx| {
@@ -417,6 +448,7 @@ impl ExtensionsPage {
                         .tooltip(move |cx| Tooltip::text(repository_url.clone(), cx))
                     })),
             )
+            .children(self.render_capabilities(extension, cx))
     }
 
     fn render_remote_extension(
@@ -438,6 +470,13 @@ impl ExtensionsPage {
             ExtensionStatus::Installed(installed_version) => Some(installed_version),
             _ => None,
         };
+        let installed_manifest = installed_version.as_ref().and_then(|_| {
+            ExtensionStore::global(cx)
+                .read(cx)
+                .installed_extensions()
+                .get(&extension.id)
+                .map(|entry| entry.manifest.clone())
+        });
 
         ExtensionCard::new()
             .overridden_by_dev_extension(has_dev_extension)
@@ -541,6 +580,85 @@ impl ExtensionsPage {
                             ),
                     ),
             )
+            .children(
+                installed_manifest.and_then(|manifest| self.render_capabilities(&manifest, cx)),
+            )
+    }
+
+    /// Renders the capabilities the installed extension asks for, with a button to grant or revoke them.
+    fn render_capabilities(
+        &self,
+        manifest: &ExtensionManifest,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Div> {
+        if manifest.capabilities.is_empty() {
+            return None;
+        }
+
+        let granted_capabilities =
+            ExtensionSettings::get_global(cx).granted_capabilities(&manifest.id);
+        let granted = manifest
+            .capabilities
+            .iter()
+            .all(|capability| granted_capabilities.contains(capability));
+        let description = manifest
+            .capabilities
+            .iter()
+            .map(|capability| capability.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Some(
+            h_flex()
+                .gap_2()
+                .justify_between()
+                .child(
+                    Label::new(format!("Permissions: {description}"))
+                        .size(LabelSize::Small)
+                        .color(if granted {
+                            Color::Default
+                        } else {
+                            Color::Warning
+                        }),
+                )
+                .child(
+                    Button::new(
+                        SharedString::from(format!("capabilities-{}", manifest.id)),
+                        if granted { "Revoke" } else { "Grant" },
+                    )
+                    .color(Color::Accent)
+                    .on_click(cx.listener({
+                        let extension_id = manifest.id.clone();
+                        let capabilities = manifest.capabilities.clone();
+                        move |this, _, cx| {
+                            let capabilities = (!granted).then(|| capabilities.clone());
+                            this.set_granted_capabilities(extension_id.clone(), capabilities, cx)
+                        }
+                    })),
+                ),
+        )
+    }
+
+    fn set_granted_capabilities(
+        &mut self,
+        extension_id: Arc<str>,
+        capabilities: Option<Vec<ExtensionCapability>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let fs = workspace.read(cx).project().read(cx).fs().clone();
+        update_settings_file::<ExtensionSettings>(fs, cx, move |settings| match capabilities {
+            Some(capabilities) => {
+                settings
+                    .granted_capabilities
+                    .insert(extension_id, capabilities);
+            }
+            None => {
+                settings.granted_capabilities.remove(&extension_id);
+            }
+        });
     }
 
     fn render_remote_extension_context_menu(
@@ -699,6 +817,25 @@ impl ExtensionsPage {
         }
     }
 
+    fn render_install_buttons(&self) -> Div {
+        h_flex()
+            .gap_2()
+            .child(
+                Button::new("install-from-archive", "Install from Archive")
+                    .style(ButtonStyle::Filled)
+                    .size(ButtonSize::Large)
+                    .on_click(|_event, cx| {
+                        cx.dispatch_action(Box::new(InstallExtensionFromArchive))
+                    }),
+            )
+            .child(
+                Button::new("install-dev-extension", "Install Dev Extension")
+                    .style(ButtonStyle::Filled)
+                    .size(ButtonSize::Large)
+                    .on_click(|_event, cx| cx.dispatch_action(Box::new(InstallDevExtension))),
+            )
+    }
+
     fn render_search(&self, cx: &mut ViewContext<Self>) -> Div {
         let mut key_context = KeyContext::new_with_defaults();
         key_context.add("BufferSearchBar");
@@ -861,14 +998,7 @@ impl Render for ExtensionsPage {
                             .gap_2()
                             .justify_between()
                             .child(Headline::new("Extensions").size(HeadlineSize::XLarge))
-                            .child(
-                                Button::new("install-dev-extension", "Install Dev Extension")
-                                    .style(ButtonStyle::Filled)
-                                    .size(ButtonSize::Large)
-                                    .on_click(|_event, cx| {
-                                        cx.dispatch_action(Box::new(InstallDevExtension))
-                                    }),
-                            ),
+                            .child(self.render_install_buttons()),
                     )
                     .child(
                         h_flex()
@@ -1,25 +1,89 @@
 use std::{path::Path, str, sync::Arc};
 
+use anyhow::Result;
 use collections::HashMap;
 
 use gpui::{AppContext, AssetSource, Global};
-use serde_derive::Deserialize;
-use util::{maybe, paths::PathExt};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources, SettingsStore};
+use util::paths::PathExt;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct TypeConfig {
     icon: Arc<str>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct FileIcons {
+/// A mapping of file types to icons, in the format of `file_types.json`.
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct FileIconTheme {
+    /// The name of the theme, that selects it in the `file_icon_theme` setting.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
     stems: HashMap<String, String>,
+    #[serde(default)]
     suffixes: HashMap<String, String>,
+    #[serde(default)]
     types: HashMap<String, TypeConfig>,
 }
 
+impl FileIconTheme {
+    /// Resolves the paths of the theme's icons against the given directory.
+    pub fn with_icons_in(mut self, dir: &Path) -> Self {
+        for type_config in self.types.values_mut() {
+            type_config.icon = dir.join(type_config.icon.as_ref()).to_string_lossy().into();
+        }
+        self
+    }
+
+    fn icon_for_path(&self, path: &Path) -> Option<Arc<str>> {
+        let suffix = path.icon_stem_or_suffix()?;
+
+        if let Some(type_str) = self.stems.get(suffix) {
+            return self.icon_for_type(type_str);
+        }
+
+        self.suffixes
+            .get(suffix)
+            .and_then(|type_str| self.icon_for_type(type_str))
+    }
+
+    fn icon_for_type(&self, typ: &str) -> Option<Arc<str>> {
+        self.types
+            .get(typ)
+            .map(|type_config| type_config.icon.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileIcons {
+    default_theme: FileIconTheme,
+    icon_themes: HashMap<Arc<str>, FileIconTheme>,
+    active_icon_theme: Option<Arc<str>>,
+}
+
 impl Global for FileIcons {}
 
+#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+pub struct FileIconSettings {
+    /// The name of the file icon theme to use, as provided by an extension.
+    ///
+    /// Zed's own icons are used when it's not set, and for the files the theme has no icons for.
+    #[serde(default)]
+    pub file_icon_theme: Option<String>,
+}
+
+impl Settings for FileIconSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _cx: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
 const COLLAPSED_DIRECTORY_TYPE: &str = "collapsed_folder";
 const EXPANDED_DIRECTORY_TYPE: &str = "expanded_folder";
 const COLLAPSED_CHEVRON_TYPE: &str = "collapsed_chevron";
@@ -27,7 +91,22 @@ const EXPANDED_CHEVRON_TYPE: &str = "expanded_chevron";
 pub const FILE_TYPES_ASSET: &str = "icons/file_icons/file_types.json";
 
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
-    cx.set_global(FileIcons::new(assets))
+    FileIconSettings::register(cx);
+    cx.set_global(FileIcons::new(assets));
+    update_active_icon_theme(cx);
+    cx.observe_global::<SettingsStore>(update_active_icon_theme)
+        .detach();
+}
+
+fn update_active_icon_theme(cx: &mut AppContext) {
+    let active_icon_theme = FileIconSettings::get_global(cx)
+        .file_icon_theme
+        .as_deref()
+        .map(Arc::from);
+    if FileIcons::get(cx).active_icon_theme != active_icon_theme {
+        cx.global_mut::<FileIcons>().active_icon_theme = active_icon_theme;
+        cx.refresh();
+    }
 }
 
 impl FileIcons {
@@ -36,17 +115,48 @@ impl FileIcons {
     }
 
     pub fn new(assets: impl AssetSource) -> Self {
+        Self {
+            default_theme: Self::load_default_theme(assets),
+            icon_themes: HashMap::default(),
+            active_icon_theme: None,
+        }
+    }
+
+    fn load_default_theme(assets: impl AssetSource) -> FileIconTheme {
         assets
-            .load("icons/file_icons/file_types.json")
+            .load(FILE_TYPES_ASSET)
             .and_then(|file| {
-                serde_json::from_str::<FileIcons>(str::from_utf8(&file).unwrap())
+                serde_json::from_str::<FileIconTheme>(str::from_utf8(&file).unwrap())
                     .map_err(Into::into)
             })
-            .unwrap_or_else(|_| FileIcons {
-                stems: HashMap::default(),
-                suffixes: HashMap::default(),
-                types: HashMap::default(),
-            })
+            .unwrap_or_default()
+    }
+
+    /// Reloads Zed's own icons, keeping the registered icon themes.
+    pub fn reload_default_theme(&mut self, assets: impl AssetSource) {
+        self.default_theme = Self::load_default_theme(assets);
+    }
+
+    /// Adds an icon theme, e.g. from an extension, that can be selected in the settings.
+    pub fn register_icon_theme(&mut self, theme: FileIconTheme) {
+        self.icon_themes.insert(theme.name.as_str().into(), theme);
+    }
+
+    pub fn unregister_icon_theme(&mut self, name: &str) {
+        self.icon_themes.remove(name);
+    }
+
+    pub fn icon_theme_names(&self) -> impl Iterator<Item = &Arc<str>> {
+        self.icon_themes.keys()
+    }
+
+    /// Returns the active icon theme, followed by Zed's own icons.
+    fn themes(&self) -> impl Iterator<Item = &FileIconTheme> {
+        self.active_icon_theme
+            .as_ref()
+            .and_then(|name| self.icon_themes.get(name))
+            .into_iter()
+            .chain([&self.default_theme])
     }
 
     pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
@@ -54,24 +164,13 @@ impl FileIcons {
 
         // FIXME: Associate a type with the languages and have the file's language
         //        override these associations
-        maybe!({
-            let suffix = path.icon_stem_or_suffix()?;
-
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
-            }
-
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
-        })
-        .or_else(|| this.get_type_icon("default"))
+        this.themes()
+            .find_map(|theme| theme.icon_for_path(path))
+            .or_else(|| this.get_type_icon("default"))
     }
 
     pub fn get_type_icon(&self, typ: &str) -> Option<Arc<str>> {
-        self.types
-            .get(typ)
-            .map(|type_config| type_config.icon.clone())
+        self.themes().find_map(|theme| theme.icon_for_type(typ))
     }
 
     pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
@@ -14,12 +14,12 @@ use crate::{
         SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, RunnableTag,
+    LanguageScope, Outline, RunnableTag, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
 use futures::channel::oneshot;
-use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
+use gpui::{AppContext, Context as _, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
@@ -34,6 +34,7 @@ use std::{
     future::Future,
     iter::{self, Iterator, Peekable},
     mem,
+    num::NonZeroU64,
     ops::{Deref, Range},
     path::{Path, PathBuf},
     str,
@@ -48,7 +49,7 @@ pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
     OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection, SelectionGoal,
     Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint, ToPointUtf16,
-    Transaction, TransactionId, Unclipped,
+    Transaction, TransactionId, Unclipped, UndoTreeNode,
 };
 use theme::SyntaxTheme;
 #[cfg(any(test, feature = "test-support"))]
@@ -701,6 +702,38 @@ impl Buffer {
         }
     }
 
+    /// Builds a snapshot of a buffer with the given text, without creating the buffer itself.
+    /// The text is parsed with the language in the returned future, to query the syntax of files that are not open.
+    pub fn build_snapshot(
+        text: String,
+        language: Option<Arc<Language>>,
+        language_registry: Option<Arc<LanguageRegistry>>,
+    ) -> impl Future<Output = BufferSnapshot> {
+        // The snapshot has no buffer, so it gets an ID that no buffer's model can have.
+        let buffer_id = BufferId::from(NonZeroU64::MAX);
+        async move {
+            let text = TextBuffer::new(0, buffer_id, text).snapshot();
+            let mut syntax = SyntaxMap::new().snapshot();
+            if let Some(language) = language.clone() {
+                syntax.reparse(&text, language_registry, language);
+            }
+            BufferSnapshot {
+                text,
+                syntax,
+                git_diff: git::diff::BufferDiff::new(),
+                file: None,
+                remote_selections: Default::default(),
+                diagnostics: Default::default(),
+                diagnostics_update_count: 0,
+                file_update_count: 0,
+                git_diff_update_count: 0,
+                language,
+                parse_count: 0,
+                selections_update_count: 0,
+            }
+        }
+    }
+
     /// Retrieve a snapshot of the buffer's current state. This is computationally
     /// cheap, and allows reading from the buffer on a background thread.
     pub fn snapshot(&self) -> BufferSnapshot {
@@ -2081,6 +2114,27 @@ impl Buffer {
         redone
     }
 
+    /// Moves to the state of the undo tree produced by the given transaction, undoing
+    /// and redoing along whichever branches are needed to get there.
+    pub fn travel_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.travel_to_undo_state(target);
+        let traveled = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), cx);
+        }
+        if traveled {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        traveled
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(&mut self, triggers: Vec<String>, cx: &mut ModelContext<Self>) {
         self.completion_triggers.clone_from(&triggers);
@@ -2989,6 +3043,61 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of the text objects (functions, classes, arguments, comments)
+    /// that intersect the given range, as defined by the language's `textobjects` query.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut captures = Vec::<(Range<usize>, TextObject)>::new();
+        iter::from_fn(move || loop {
+            if let Some(capture) = captures.pop() {
+                return Some(capture);
+            }
+
+            let mat = syntax_matches.peek()?;
+            if let Some(config) = configs[mat.grammar_index] {
+                // A single text object may be made up of several captures within
+                // one match (e.g. the statements of a function body), so those
+                // are merged into a single range spanning all of them.
+                for capture in mat.captures {
+                    let Some((_, text_object)) = config
+                        .text_objects_by_capture_ix
+                        .iter()
+                        .find(|(ix, _)| *ix == capture.index)
+                    else {
+                        continue;
+                    };
+                    let range = capture.node.byte_range();
+                    if let Some((existing, _)) = captures
+                        .iter_mut()
+                        .find(|(_, object)| object == text_object)
+                    {
+                        existing.start = existing.start.min(range.start);
+                        existing.end = existing.end.max(range.end);
+                    } else {
+                        captures.push((range, *text_object));
+                    }
+                }
+            }
+            syntax_matches.advance();
+        })
+    }
+
     pub fn runnable_ranges(
         &self,
         range: Range<Anchor>,
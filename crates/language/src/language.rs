@@ -13,6 +13,7 @@ mod language_registry;
 pub mod language_settings;
 mod outline;
 pub mod proto;
+mod snippets;
 mod syntax_map;
 mod task_context;
 
@@ -25,7 +26,7 @@ use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::{HashMap, HashSet};
 use futures::Future;
-use gpui::{AppContext, AsyncAppContext, Model, Task};
+use gpui::{AppContext, AsyncAppContext, Model, PromptLevel, Task};
 pub use highlight_map::HighlightMap;
 use http::HttpClient;
 use lazy_static::lazy_static;
@@ -73,6 +74,7 @@ pub use language_registry::{
 };
 pub use lsp::LanguageServerId;
 pub use outline::{Outline, OutlineItem};
+pub use snippets::{parse_snippets, Snippet};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
 pub use text::LineEnding;
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -276,6 +278,8 @@ pub trait LspAdapterDelegate: Send + Sync {
     async fn which(&self, command: &OsStr) -> Option<PathBuf>;
     async fn shell_env(&self) -> HashMap<String, String>;
     async fn read_text_file(&self, path: PathBuf) -> Result<String>;
+    /// Lists the entries of the directory at the given worktree path, as their file names and whether they are directories.
+    async fn read_dir(&self, path: PathBuf) -> Result<Vec<(String, bool)>>;
 }
 
 #[async_trait(?Send)]
@@ -504,6 +508,42 @@ pub trait LspAdapter: 'static + Send + Sync {
         Default::default()
     }
 
+    /// Returns the commands of the language server, implemented with custom requests to it.
+    fn language_server_commands(&self) -> Vec<LanguageServerCommand> {
+        Vec::new()
+    }
+
+    /// Returns the method and the parameters of the request to send to the language server for the command.
+    async fn language_server_command_request(
+        self: Arc<Self>,
+        command: &str,
+        _: LanguageServerCommandContext,
+    ) -> Result<(String, Value)> {
+        Err(anyhow!("unknown language server command `{command}`"))
+    }
+
+    /// Returns what to show for the language server's response to the command.
+    async fn language_server_command_output(
+        self: Arc<Self>,
+        _command: &str,
+        _response: Value,
+    ) -> Result<LanguageServerCommandOutput> {
+        Ok(LanguageServerCommandOutput::None)
+    }
+
+    /// Returns the methods of the custom notifications from the language server, handled by [`Self::handle_custom_notification`].
+    fn custom_notifications(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    async fn handle_custom_notification(
+        self: Arc<Self>,
+        _method: &str,
+        _params: Value,
+    ) -> Result<Vec<CustomNotificationEffect>> {
+        Ok(Vec::new())
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     fn as_fake(&self) -> Option<&FakeLspAdapter> {
         None
@@ -547,6 +587,62 @@ pub struct CodeLabel {
     pub filter_range: Range<usize>,
 }
 
+/// A command of a language server, implemented with a custom request to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageServerCommand {
+    /// Identifies the command to the [`LspAdapter`].
+    pub name: Arc<str>,
+    /// The label of the command, shown to the user.
+    pub label: String,
+}
+
+/// Where a [`LanguageServerCommand`] is run from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageServerCommandContext {
+    pub uri: lsp::Url,
+    /// The position of the cursor.
+    pub position: lsp::Position,
+    pub selection: lsp::Range,
+}
+
+/// What to show for the language server's response to a [`LanguageServerCommand`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageServerCommandOutput {
+    None,
+    /// A document to open in a new editor.
+    Text {
+        title: String,
+        text: String,
+        /// The name of the language to highlight the text with.
+        language: Option<Arc<str>>,
+    },
+    /// A location to open.
+    Location {
+        uri: lsp::Url,
+        position: lsp::Position,
+    },
+    Message(String),
+}
+
+/// What to show in response to a custom notification from the language server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CustomNotificationEffect {
+    /// Starts the work with the given token, or updates its progress.
+    Progress {
+        token: String,
+        message: Option<String>,
+        percentage: Option<usize>,
+    },
+    /// Ends the work with the given token.
+    ProgressEnd {
+        token: String,
+    },
+    Message {
+        level: PromptLevel,
+        text: String,
+    },
+}
+
 #[derive(Clone, Deserialize, JsonSchema)]
 pub struct LanguageConfig {
     /// Human-readable name of the language.
@@ -838,6 +934,7 @@ pub struct Grammar {
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
+    pub(crate) text_object_config: Option<TextObjectConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -892,6 +989,51 @@ struct RunnableConfig {
     pub run_capture_ix: u32,
 }
 
+/// A syntactic region that can be selected as a whole, e.g. by vim's `af` or `ic`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextObject {
+    InsideFunction,
+    AroundFunction,
+    InsideClass,
+    AroundClass,
+    InsideArgument,
+    AroundArgument,
+    InsideComment,
+    AroundComment,
+}
+
+impl TextObject {
+    pub fn from_capture_name(name: &str) -> Option<TextObject> {
+        match name {
+            "function.inside" => Some(TextObject::InsideFunction),
+            "function.around" => Some(TextObject::AroundFunction),
+            "class.inside" => Some(TextObject::InsideClass),
+            "class.around" => Some(TextObject::AroundClass),
+            "argument.inside" => Some(TextObject::InsideArgument),
+            "argument.around" => Some(TextObject::AroundArgument),
+            "comment.inside" => Some(TextObject::InsideComment),
+            "comment.around" => Some(TextObject::AroundComment),
+            _ => None,
+        }
+    }
+
+    /// Returns the corresponding "around" text object for an "inside" one.
+    pub fn around(&self) -> Option<Self> {
+        match self {
+            TextObject::InsideFunction => Some(TextObject::AroundFunction),
+            TextObject::InsideClass => Some(TextObject::AroundClass),
+            TextObject::InsideArgument => Some(TextObject::AroundArgument),
+            TextObject::InsideComment => Some(TextObject::AroundComment),
+            _ => None,
+        }
+    }
+}
+
+struct TextObjectConfig {
+    pub query: Query,
+    pub text_objects_by_capture_ix: Vec<(u32, TextObject)>,
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -934,6 +1076,7 @@ impl Language {
                     override_config: None,
                     redactions_config: None,
                     runnable_config: None,
+                    text_object_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -994,6 +1137,11 @@ impl Language {
                 .with_runnable_query(query.as_ref())
                 .context("Error loading tests query")?;
         }
+        if let Some(query) = queries.text_objects {
+            self = self
+                .with_text_object_query(query.as_ref())
+                .context("Error loading textobject query")?;
+        }
         Ok(self)
     }
 
@@ -1032,6 +1180,26 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_text_object_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut text_objects_by_capture_ix = Vec::new();
+        for (ix, name) in query.capture_names().iter().enumerate() {
+            if let Some(text_object) = TextObject::from_capture_name(name) {
+                text_objects_by_capture_ix.push((ix as u32, text_object));
+            }
+        }
+
+        grammar.text_object_config = Some(TextObjectConfig {
+            query,
+            text_objects_by_capture_ix,
+        });
+        Ok(self)
+    }
+
     pub fn with_outline_query(mut self, source: &str) -> Result<Self> {
         let grammar = self
             .grammar_mut()
@@ -1311,6 +1479,13 @@ impl Language {
         self.context_provider.clone()
     }
 
+    /// Whether the language has a `runnables.scm` query, to find the runnables (e.g. tests) with.
+    pub fn has_runnables(&self) -> bool {
+        self.grammar
+            .as_ref()
+            .map_or(false, |grammar| grammar.runnable_config.is_some())
+    }
+
     pub fn highlight_text<'a>(
         self: &'a Arc<Self>,
         text: &'a Rope,
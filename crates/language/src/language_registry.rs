@@ -4,7 +4,7 @@ use crate::{
     },
     task_context::ContextProvider,
     with_parser, CachedLspAdapter, File, Language, LanguageConfig, LanguageId, LanguageMatcher,
-    LanguageServerName, LspAdapter, LspAdapterDelegate, PLAIN_TEXT,
+    LanguageServerName, LspAdapter, LspAdapterDelegate, Snippet, PLAIN_TEXT,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{hash_map, HashMap};
@@ -49,6 +49,9 @@ struct LanguageRegistryState {
     available_lsp_adapters:
         HashMap<LanguageServerName, Arc<dyn Fn() -> Arc<CachedLspAdapter> + 'static + Send + Sync>>,
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
+    /// The snippets of each source, e.g. an extension, with the names of the languages
+    /// they are for. Snippets without languages are offered in every language.
+    snippets: HashMap<Arc<str>, Vec<(Option<Arc<str>>, Snippet)>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
     version: usize,
@@ -125,6 +128,7 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
+    ("textobjects", |q| &mut q.text_objects),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -139,6 +143,7 @@ pub struct LanguageQueries {
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
+    pub text_objects: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -156,6 +161,7 @@ impl LanguageRegistry {
                 grammars: Default::default(),
                 language_settings: Default::default(),
                 loading_languages: Default::default(),
+                snippets: Default::default(),
                 lsp_adapters: Default::default(),
                 available_lsp_adapters: HashMap::default(),
                 subscription: watch::channel(),
@@ -208,6 +214,39 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    /// Replaces the snippets from the given source, e.g. an extension.
+    pub fn register_snippets(
+        &self,
+        source: Arc<str>,
+        snippets: impl IntoIterator<Item = (Option<Arc<str>>, Snippet)>,
+    ) {
+        self.state
+            .write()
+            .snippets
+            .insert(source, snippets.into_iter().collect());
+    }
+
+    pub fn remove_snippets(&self, source: &str) {
+        self.state.write().snippets.remove(source);
+    }
+
+    /// Returns the snippets that can be inserted in a buffer of the given language.
+    pub fn snippets_for_language(&self, language_name: &str) -> Vec<Snippet> {
+        let language_name = UniCase::new(language_name);
+        self.state
+            .read()
+            .snippets
+            .values()
+            .flatten()
+            .filter(|(language, _)| {
+                language.as_ref().map_or(true, |language| {
+                    UniCase::new(language.as_ref()) == language_name
+                })
+            })
+            .map(|(_, snippet)| snippet.clone())
+            .collect()
+    }
+
     #[cfg(any(feature = "test-support", test))]
     pub fn register_test_language(&self, config: LanguageConfig) {
         self.register_language(
@@ -99,6 +99,8 @@ pub struct LanguageSettings {
     /// - `"!<language_server_id>"` - A language server ID prefixed with a `!` will be disabled.
     /// - `"..."` - A placeholder to refer to the **rest** of the registered language servers for this language.
     pub language_servers: Vec<Arc<str>>,
+    /// The linters, provided by extensions, to run on the files of this language when they are saved.
+    pub linters: Vec<Arc<str>>,
     /// Controls whether inline completions are shown immediately (true)
     /// or manually by triggering `editor::ShowInlineCompletion` (false).
     pub show_inline_completions: bool,
@@ -286,6 +288,12 @@ pub struct LanguageSettingsContent {
     /// Default: ["..."]
     #[serde(default)]
     pub language_servers: Option<Vec<Arc<str>>>,
+    /// The linters, provided by extensions, to run on the files of this language when they are saved.
+    /// Their problems are reported as diagnostics.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub linters: Option<Vec<Arc<str>>>,
     /// Controls whether inline completions are shown immediately (true)
     /// or manually by triggering `editor::ShowInlineCompletion` (false).
     ///
@@ -374,6 +382,8 @@ pub enum FormatOnSave {
     },
     /// Files should be formatted using code actions executed by language servers.
     CodeActions(HashMap<String, bool>),
+    /// Format code using the formatter with the given name, provided by an extension.
+    Extension(Arc<str>),
 }
 
 /// Controls how whitespace should be displayedin the editor.
@@ -737,6 +747,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.enable_language_server,
     );
     merge(&mut settings.language_servers, src.language_servers.clone());
+    merge(&mut settings.linters, src.linters.clone());
     merge(
         &mut settings.show_inline_completions,
         src.show_inline_completions,
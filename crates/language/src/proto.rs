@@ -489,6 +489,80 @@ pub fn deserialize_transaction(transaction: proto::Transaction) -> Result<Transa
     })
 }
 
+/// Serializes a buffer's undo tree, along with the base text and operations it was
+/// recorded on, so that it can be stored.
+pub fn serialize_undo_history<'a>(
+    base_text: &Rope,
+    operations: impl IntoIterator<Item = &'a text::Operation>,
+    history: &SavedHistory,
+) -> proto::UndoHistory {
+    let serialize_entries = |entries: &[SavedHistoryEntry]| {
+        entries
+            .iter()
+            .map(|entry| proto::UndoHistoryEntry {
+                transaction: Some(serialize_transaction(&entry.transaction)),
+                parent: entry.parent.map(serialize_timestamp),
+                first_edit_at: Some(entry.first_edit_at.into()),
+                last_edit_at: Some(entry.last_edit_at.into()),
+            })
+            .collect()
+    };
+    proto::UndoHistory {
+        base_text: base_text.to_string(),
+        operations: operations
+            .into_iter()
+            .map(|operation| serialize_operation(&crate::Operation::Buffer(operation.clone())))
+            .collect(),
+        undo_stack: serialize_entries(&history.undo_stack),
+        redo_stack: serialize_entries(&history.redo_stack),
+        abandoned: serialize_entries(&history.abandoned),
+    }
+}
+
+/// Deserializes a buffer's undo tree, returning the base text and operations it was
+/// recorded on along with it.
+pub fn deserialize_undo_history(
+    message: proto::UndoHistory,
+) -> Result<(String, Vec<text::Operation>, SavedHistory)> {
+    let operations = message
+        .operations
+        .into_iter()
+        .map(|operation| match deserialize_operation(operation)? {
+            crate::Operation::Buffer(operation) => Ok(operation),
+            _ => Err(anyhow!("unexpected operation in undo history")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let deserialize_entries = |entries: Vec<proto::UndoHistoryEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| {
+                Ok(SavedHistoryEntry {
+                    transaction: deserialize_transaction(
+                        entry
+                            .transaction
+                            .ok_or_else(|| anyhow!("missing transaction"))?,
+                    )?,
+                    parent: entry.parent.map(deserialize_timestamp),
+                    first_edit_at: entry
+                        .first_edit_at
+                        .ok_or_else(|| anyhow!("missing first edit time"))?
+                        .into(),
+                    last_edit_at: entry
+                        .last_edit_at
+                        .ok_or_else(|| anyhow!("missing last edit time"))?
+                        .into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    let history = SavedHistory {
+        undo_stack: deserialize_entries(message.undo_stack)?,
+        redo_stack: deserialize_entries(message.redo_stack)?,
+        abandoned: deserialize_entries(message.abandoned)?,
+    };
+    Ok((message.base_text, operations, history))
+}
+
 /// Serializes a [`clock::Lamport`] timestamp to be sent over RPC.
 pub fn serialize_timestamp(timestamp: clock::Lamport) -> proto::LamportTimestamp {
     proto::LamportTimestamp {
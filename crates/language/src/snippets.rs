@@ -0,0 +1,105 @@
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// A snippet that can be inserted with a completion, e.g. one contributed by an extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// The name of the snippet, shown in the completions menu when there is no description.
+    pub name: Arc<str>,
+    /// The words that the completion is offered for.
+    pub prefixes: Vec<String>,
+    /// The text of the snippet, in the LSP snippet syntax.
+    pub body: String,
+    pub description: Option<String>,
+}
+
+/// A snippet in a VS Code snippets file, where the prefix and the body are
+/// either a string or a list of strings.
+#[derive(Deserialize)]
+struct SnippetDefinition {
+    prefix: StringOrList,
+    body: StringOrList,
+    #[serde(default)]
+    description: Option<StringOrList>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::String(string) => vec![string],
+            Self::List(list) => list,
+        }
+    }
+
+    fn into_lines(self) -> String {
+        self.into_vec().join("\n")
+    }
+}
+
+/// Parses a snippets file in the format of VS Code, mapping the names of the snippets to
+/// their prefixes, bodies and descriptions.
+pub fn parse_snippets(content: &str) -> Result<Vec<Snippet>> {
+    let definitions = serde_json::from_str::<BTreeMap<String, SnippetDefinition>>(content)
+        .context("invalid snippets file")?;
+    Ok(definitions
+        .into_iter()
+        .map(|(name, definition)| Snippet {
+            name: name.into(),
+            prefixes: definition.prefix.into_vec(),
+            body: definition.body.into_lines(),
+            description: definition.description.map(StringOrList::into_lines),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snippets() {
+        let snippets = parse_snippets(
+            r#"{
+                "Print to console": {
+                    "prefix": ["log", "print"],
+                    "body": ["console.log('$1');", "$0"],
+                    "description": "Log output to console"
+                },
+                "For loop": {
+                    "prefix": "for",
+                    "body": "for (let ${1:i} = 0; $1 < ${2:n}; $1++) {\n\t$0\n}"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            snippets,
+            [
+                Snippet {
+                    name: "For loop".into(),
+                    prefixes: vec!["for".into()],
+                    body: "for (let ${1:i} = 0; $1 < ${2:n}; $1++) {\n\t$0\n}".into(),
+                    description: None,
+                },
+                Snippet {
+                    name: "Print to console".into(),
+                    prefixes: vec!["log".into(), "print".into()],
+                    body: "console.log('$1');\n$0".into(),
+                    description: Some("Log output to console".into()),
+                },
+            ]
+        );
+
+        assert!(parse_snippets(r#"{ "Empty": { "body": "" } }"#).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+(function_definition) @function.around
+
+(function_definition
+    body: (compound_statement
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(comment)+ @comment.around
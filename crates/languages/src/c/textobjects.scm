@@ -0,0 +1,46 @@
+(function_definition) @function.around
+
+(function_definition
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(struct_specifier
+    body: (_)) @class.around
+
+(struct_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(union_specifier
+    body: (_)) @class.around
+
+(union_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(enum_specifier
+    body: (_)) @class.around
+
+(enum_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(parameter_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(argument_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(comment)+ @comment.around
@@ -0,0 +1,78 @@
+(function_definition) @function.around
+
+(function_definition
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(lambda_expression) @function.around
+
+(lambda_expression
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(class_specifier
+    body: (_)) @class.around
+
+(class_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(struct_specifier
+    body: (_)) @class.around
+
+(struct_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(union_specifier
+    body: (_)) @class.around
+
+(union_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(enum_specifier
+    body: (_)) @class.around
+
+(enum_specifier
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(namespace_definition
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}")) @class.around
+
+(parameter_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(template_parameter_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(argument_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(template_argument_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(comment)+ @comment.around
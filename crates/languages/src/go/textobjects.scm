@@ -0,0 +1,55 @@
+(function_declaration) @function.around
+
+(function_declaration
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(method_declaration) @function.around
+
+(method_declaration
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(func_literal) @function.around
+
+(func_literal
+    body: (_
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(type_declaration
+    (type_spec
+        type: [(struct_type) (interface_type)])) @class.around
+
+(type_declaration
+    (type_spec
+        type: (struct_type
+            (field_declaration_list
+                "{"
+                . (_) @class.inside
+                (_)? @class.inside .
+                "}"))))
+
+(type_declaration
+    (type_spec
+        type: (interface_type
+            "{"
+            . (_) @class.inside
+            (_)? @class.inside .
+            "}")))
+
+(parameter_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(argument_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(comment)+ @comment.around
@@ -0,0 +1,73 @@
+(comment)+ @comment.around
+
+[
+    (function_declaration)
+    (generator_function_declaration)
+    (function)
+    (generator_function)
+    (method_definition)
+    (arrow_function)
+] @function.around
+
+(function_declaration
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(generator_function_declaration
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(generator_function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(method_definition
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(arrow_function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(arrow_function
+    body: (expression) @function.inside)
+
+[
+    (class_declaration)
+    (class)
+] @class.around
+
+(_
+    body: (class_body
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(formal_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(arguments
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
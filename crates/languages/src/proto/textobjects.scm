@@ -0,0 +1,27 @@
+(rpc) @function.around
+
+(message) @class.around
+
+(message
+    (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(enum) @class.around
+
+(enum
+    (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(service
+    "{"
+    . (_) @class.inside
+    (_)? @class.inside .
+    "}") @class.around
+
+(comment)+ @comment.around
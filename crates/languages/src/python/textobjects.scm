@@ -0,0 +1,16 @@
+(function_definition
+    body: (_) @function.inside) @function.around
+
+(class_definition
+    body: (_) @class.inside) @class.around
+
+(parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(lambda_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(argument_list
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(comment)+ @comment.around
@@ -18,7 +18,9 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use task::{
+    ProblemMatcher, TaskTemplate, TaskTemplates, TaskVariables, TestResultsFormat, VariableName,
+};
 use util::{fs::remove_matching, maybe, ResultExt};
 
 pub struct RustLspAdapter;
@@ -363,12 +365,14 @@ impl ContextProvider for RustContextProvider {
                     "-p".into(),
                     RUST_PACKAGE_TASK_VARIABLE.template_value(),
                 ],
+                problem_matcher: vec![ProblemMatcher::Preset("$rustc".into())],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
                 label: "cargo check --workspace --all-targets".into(),
                 command: "cargo".into(),
                 args: vec!["check".into(), "--workspace".into(), "--all-targets".into()],
+                problem_matcher: vec![ProblemMatcher::Preset("$rustc".into())],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
@@ -389,6 +393,23 @@ impl ContextProvider for RustContextProvider {
                 tags: vec!["rust-test".to_owned()],
                 ..TaskTemplate::default()
             },
+            TaskTemplate {
+                label: format!(
+                    "cargo test -p {} {}",
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    VariableName::Symbol.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "test".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    VariableName::Symbol.template_value(),
+                ],
+                tags: vec!["rust-test".to_owned()],
+                test_results: Some(TestResultsFormat::Libtest),
+                ..TaskTemplate::default()
+            },
             TaskTemplate {
                 label: format!(
                     "cargo test -p {}",
@@ -400,6 +421,8 @@ impl ContextProvider for RustContextProvider {
                     "-p".into(),
                     RUST_PACKAGE_TASK_VARIABLE.template_value(),
                 ],
+                tags: vec!["rust-test-all".to_owned()],
+                test_results: Some(TestResultsFormat::Libtest),
                 ..TaskTemplate::default()
             },
             TaskTemplate {
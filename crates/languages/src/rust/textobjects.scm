@@ -0,0 +1,72 @@
+; functions
+(function_signature_item) @function.around
+
+(function_item
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}" )) @function.around
+
+(closure_expression
+    body: (_) @function.inside) @function.around
+
+; classes
+(struct_item
+    body: (field_declaration_list
+        "{"
+        [(_) ","]* @class.inside
+        "}" )) @class.around
+
+(struct_item
+    body: (ordered_field_declaration_list
+        "("
+        [(_) ","]* @class.inside
+        ")" )) @class.around
+
+(enum_item
+    body: (_
+        "{"
+        [(_) ","]* @class.inside
+        "}" )) @class.around
+
+(union_item
+    body: (_
+        "{"
+        [(_) ","]* @class.inside
+        "}" )) @class.around
+
+(trait_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(impl_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(mod_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+; arguments
+(parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(type_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(closure_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(arguments
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+; comments
+(line_comment)+ @comment.around
+
+(block_comment) @comment.around
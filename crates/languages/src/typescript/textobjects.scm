@@ -0,0 +1,91 @@
+(comment)+ @comment.around
+
+[
+    (function_declaration)
+    (function_signature)
+    (generator_function_declaration)
+    (function)
+    (generator_function)
+    (method_definition)
+    (arrow_function)
+] @function.around
+
+(function_declaration
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(generator_function_declaration
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(generator_function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(method_definition
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(arrow_function
+    body: (statement_block
+        "{"
+        . (_) @function.inside
+        (_)? @function.inside .
+        "}"))
+
+(arrow_function
+    body: (expression) @function.inside)
+
+[
+    (class_declaration)
+    (abstract_class_declaration)
+    (class)
+] @class.around
+
+(_
+    body: (class_body
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(interface_declaration
+    body: (_)) @class.around
+
+(interface_declaration
+    body: (_
+        "{"
+        . (_) @class.inside
+        (_)? @class.inside .
+        "}"))
+
+(type_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(type_arguments
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(formal_parameters
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
+
+(arguments
+    ((_) @argument.inside . ","? @argument.around) @argument.around)
@@ -5,7 +5,7 @@ pub use lsp_types::*;
 use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, Future, FutureExt};
-use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Task};
+use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, SharedString, Task};
 use parking_lot::Mutex;
 use postage::{barrier, prelude::Stream};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -71,7 +71,7 @@ pub struct LanguageServer {
     name: Arc<str>,
     capabilities: ServerCapabilities,
     code_action_kinds: Option<Vec<CodeActionKind>>,
-    notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    notification_handlers: Arc<Mutex<HashMap<SharedString, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     io_handlers: Arc<Mutex<HashMap<i32, IoHandler>>>,
     executor: BackgroundExecutor,
@@ -90,8 +90,8 @@ pub struct LanguageServerId(pub usize);
 /// Handle to a language server RPC activity subscription.
 pub enum Subscription {
     Notification {
-        method: &'static str,
-        notification_handlers: Option<Arc<Mutex<HashMap<&'static str, NotificationHandler>>>>,
+        method: SharedString,
+        notification_handlers: Option<Arc<Mutex<HashMap<SharedString, NotificationHandler>>>>,
     },
     Io {
         id: i32,
@@ -405,7 +405,7 @@ impl LanguageServer {
     async fn handle_input<Stdout, F>(
         stdout: Stdout,
         mut on_unhandled_notification: F,
-        notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        notification_handlers: Arc<Mutex<HashMap<SharedString, NotificationHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
         io_handlers: Arc<Mutex<HashMap<i32, IoHandler>>>,
         cx: AsyncAppContext,
@@ -832,14 +832,26 @@ impl LanguageServer {
         self.notification_handlers.lock().contains_key(T::METHOD)
     }
 
+    /// Checks if a handler has been registered for the notifications or requests with the given method.
+    pub fn has_custom_notification_handler(&self, method: &str) -> bool {
+        self.notification_handlers.lock().contains_key(method)
+    }
+
+    /// Register a handler to handle incoming LSP notifications with the given method,
+    /// e.g. the protocol extensions of a particular language server.
     #[must_use]
-    fn on_custom_notification<Params, F>(&self, method: &'static str, mut f: F) -> Subscription
+    pub fn on_custom_notification<Params, F>(
+        &self,
+        method: impl Into<SharedString>,
+        mut f: F,
+    ) -> Subscription
     where
         F: 'static + FnMut(Params, AsyncAppContext) + Send,
         Params: DeserializeOwned,
     {
+        let method = method.into();
         let prev_handler = self.notification_handlers.lock().insert(
-            method,
+            method.clone(),
             Box::new(move |_, params, cx| {
                 if let Some(params) = serde_json::from_str(params).log_err() {
                     f(params, cx);
@@ -866,7 +878,7 @@ impl LanguageServer {
     {
         let outbound_tx = self.outbound_tx.clone();
         let prev_handler = self.notification_handlers.lock().insert(
-            method,
+            method.into(),
             Box::new(move |id, params, cx| {
                 if let Some(id) = id {
                     match serde_json::from_str(params) {
@@ -928,7 +940,7 @@ impl LanguageServer {
             "registered multiple handlers for the same LSP method"
         );
         Subscription::Notification {
-            method,
+            method: method.into(),
             notification_handlers: Some(self.notification_handlers.clone()),
         }
     }
@@ -972,6 +984,23 @@ impl LanguageServer {
         )
     }
 
+    /// Sends a RPC request with the given method to the language server,
+    /// e.g. one of the protocol extensions of a particular language server.
+    pub fn request_custom(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> impl LspRequestFuture<Result<Value>> {
+        Self::request_with_method(
+            &self.next_id,
+            &self.response_handlers,
+            &self.outbound_tx,
+            &self.executor,
+            method,
+            params,
+        )
+    }
+
     fn request_internal<T: request::Request>(
         next_id: &AtomicI32,
         response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
@@ -981,12 +1010,34 @@ impl LanguageServer {
     ) -> impl LspRequestFuture<Result<T::Result>>
     where
         T::Result: 'static + Send,
+    {
+        Self::request_with_method(
+            next_id,
+            response_handlers,
+            outbound_tx,
+            executor,
+            T::METHOD,
+            params,
+        )
+    }
+
+    fn request_with_method<Params, Res>(
+        next_id: &AtomicI32,
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+        method: &str,
+        params: Params,
+    ) -> impl LspRequestFuture<Result<Res>>
+    where
+        Params: Serialize,
+        Res: 'static + Send + DeserializeOwned,
     {
         let id = next_id.fetch_add(1, SeqCst);
         let message = serde_json::to_string(&Request {
             jsonrpc: JSON_RPC_VERSION,
             id: RequestId::Int(id),
-            method: T::METHOD,
+            method,
             params,
         })
         .unwrap();
@@ -1024,6 +1075,7 @@ impl LanguageServer {
             .try_send(message)
             .context("failed to write to language server's stdin");
 
+        let method = method.to_string();
         let outbound_tx = outbound_tx.downgrade();
         let mut timeout = executor.timer(LSP_REQUEST_TIMEOUT).fuse();
         let started = Instant::now();
@@ -1043,7 +1095,6 @@ impl LanguageServer {
                 }
             });
 
-            let method = T::METHOD;
             select! {
                 response = rx.fuse() => {
                     let elapsed = started.elapsed();
@@ -936,6 +936,19 @@ impl MultiBuffer {
         None
     }
 
+    /// Travels to a state of the undo tree. Only singleton buffers keep an undo tree.
+    pub fn travel_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        if let Some(buffer) = self.as_singleton() {
+            buffer.update(cx, |buffer, cx| buffer.travel_to_undo_state(target, cx))
+        } else {
+            false
+        }
+    }
+
     pub fn undo_transaction(&mut self, transaction_id: TransactionId, cx: &mut ModelContext<Self>) {
         if let Some(buffer) = self.as_singleton() {
             buffer.update(cx, |buffer, cx| buffer.undo_transaction(transaction_id, cx));
@@ -0,0 +1,86 @@
+use crate::{Event, Project};
+use collections::HashMap;
+use gpui::{AppContext, Model, ModelContext};
+use language::{Buffer, Point, ToPoint};
+use std::path::PathBuf;
+use text::{Anchor, BufferId};
+
+/// Breakpoints, set in the project's buffers, for the debugged programs to stop at.
+#[derive(Default)]
+pub(crate) struct Breakpoints {
+    /// Positions of the breakpoints by their buffers, that are kept open while they have any breakpoints.
+    by_buffer: HashMap<BufferId, (Model<Buffer>, Vec<Anchor>)>,
+}
+
+impl Project {
+    /// Sets a breakpoint at the start of the position's row in the buffer,
+    /// or removes the breakpoint, if there is one on the row already.
+    pub fn toggle_breakpoint(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let row = position.to_point(&snapshot).row;
+        let (_, positions) = self
+            .breakpoints
+            .by_buffer
+            .entry(buffer_id)
+            .or_insert_with(|| (buffer.clone(), Vec::new()));
+        match positions
+            .iter()
+            .position(|position| position.to_point(&snapshot).row == row)
+        {
+            Some(ix) => {
+                positions.remove(ix);
+            }
+            None => positions.push(snapshot.anchor_before(Point::new(row, 0))),
+        }
+        if positions.is_empty() {
+            self.breakpoints.by_buffer.remove(&buffer_id);
+        }
+        cx.emit(Event::BreakpointsChanged(buffer_id));
+    }
+
+    /// Sorted rows of the breakpoints in the buffer.
+    pub fn breakpoint_rows(&self, buffer_id: BufferId, cx: &AppContext) -> Vec<u32> {
+        let Some((buffer, positions)) = self.breakpoints.by_buffer.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let mut rows = positions
+            .iter()
+            .map(|position| position.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        // Edits may move several breakpoints onto the same row.
+        rows.dedup();
+        rows
+    }
+
+    /// Absolute path of the buffer's file and the rows of the breakpoints in it, if the file is local.
+    /// The rows are empty for an open buffer, whose last breakpoint was removed.
+    pub fn buffer_breakpoints(
+        &self,
+        buffer_id: BufferId,
+        cx: &AppContext,
+    ) -> Option<(PathBuf, Vec<u32>)> {
+        let buffer = match self.breakpoints.by_buffer.get(&buffer_id) {
+            Some((buffer, _)) => buffer.clone(),
+            None => self.buffer_for_id(buffer_id)?,
+        };
+        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+        Some((abs_path, self.breakpoint_rows(buffer_id, cx)))
+    }
+
+    /// Breakpoints in all local files of the project, by the files' absolute paths.
+    pub fn all_breakpoints(&self, cx: &AppContext) -> Vec<(PathBuf, Vec<u32>)> {
+        self.breakpoints
+            .by_buffer
+            .keys()
+            .filter_map(|buffer_id| self.buffer_breakpoints(*buffer_id, cx))
+            .collect()
+    }
+}
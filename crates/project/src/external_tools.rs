@@ -0,0 +1,343 @@
+//! Formatters and linters, run as external commands and contributed by sources other than the settings, e.g. extensions.
+
+use crate::{File, Project, ProjectLspAdapterDelegate};
+use anyhow::{anyhow, Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use futures::StreamExt as _;
+use gpui::{AppContext, Global, Model, ModelContext, Task, WeakModel};
+use language::{language_settings::language_settings, Buffer, LspAdapterDelegate};
+use lsp::LanguageServerId;
+use parking_lot::RwLock;
+use postage::watch;
+use std::{path::PathBuf, sync::Arc};
+use task::{Problem, ProblemMatcher, ProblemScanner};
+use util::{post_inc, ResultExt as _, TryFutureExt as _};
+
+/// The command to run an external formatter or linter with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExternalToolCommand {
+    pub path: PathBuf,
+    /// Arguments to pass to the command, `{buffer_path}` is replaced with the absolute path of the file.
+    pub arguments: Vec<String>,
+    pub env: HashMap<String, String>,
+}
+
+/// A formatter or linter, run as an external command.
+pub trait ExternalTool: 'static + Send + Sync {
+    /// Returns the command to run the tool with in the worktree, installing the tool first if needed.
+    fn command(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<ExternalToolCommand>>;
+}
+
+/// A linter, whose output is scanned for problems to report as diagnostics.
+pub struct ExternalLinter {
+    pub tool: Arc<dyn ExternalTool>,
+    pub problem_matcher: Vec<ProblemMatcher>,
+}
+
+#[derive(Default)]
+struct GlobalExternalToolRegistry(Arc<ExternalToolRegistry>);
+
+impl Global for GlobalExternalToolRegistry {}
+
+struct ExternalToolRegistryState {
+    formatters: BTreeMap<Arc<str>, Arc<dyn ExternalTool>>,
+    linters: BTreeMap<Arc<str>, Arc<ExternalLinter>>,
+    linters_changed_tx: watch::Sender<()>,
+}
+
+/// The registry of the formatters and linters, that the language settings refer to by name.
+pub struct ExternalToolRegistry {
+    state: RwLock<ExternalToolRegistryState>,
+    linters_changed_rx: watch::Receiver<()>,
+}
+
+impl Default for ExternalToolRegistry {
+    fn default() -> Self {
+        let (linters_changed_tx, linters_changed_rx) = watch::channel();
+        Self {
+            state: RwLock::new(ExternalToolRegistryState {
+                formatters: BTreeMap::default(),
+                linters: BTreeMap::default(),
+                linters_changed_tx,
+            }),
+            linters_changed_rx,
+        }
+    }
+}
+
+impl ExternalToolRegistry {
+    /// Returns the global [`ExternalToolRegistry`].
+    ///
+    /// Inserts a default [`ExternalToolRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalExternalToolRegistry>().0.clone()
+    }
+
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registers the formatter under the name given, replacing any existing formatter with the same name.
+    pub fn register_formatter(&self, name: Arc<str>, formatter: impl ExternalTool) {
+        self.state
+            .write()
+            .formatters
+            .insert(name, Arc::new(formatter));
+    }
+
+    pub fn unregister_formatter(&self, name: &str) {
+        self.state.write().formatters.remove(name);
+    }
+
+    pub fn formatter(&self, name: &str) -> Option<Arc<dyn ExternalTool>> {
+        self.state.read().formatters.get(name).cloned()
+    }
+
+    /// Registers the linter under the name given, replacing any existing linter with the same name.
+    pub fn register_linter(&self, name: Arc<str>, linter: ExternalLinter) {
+        let mut state = self.state.write();
+        state.linters.insert(name, Arc::new(linter));
+        *state.linters_changed_tx.borrow_mut() = ();
+    }
+
+    pub fn unregister_linter(&self, name: &str) {
+        let mut state = self.state.write();
+        if state.linters.remove(name).is_some() {
+            *state.linters_changed_tx.borrow_mut() = ();
+        }
+    }
+
+    pub fn linter(&self, name: &str) -> Option<Arc<ExternalLinter>> {
+        self.state.read().linters.get(name).cloned()
+    }
+
+    /// Returns a receiver, notified every time a linter is registered or unregistered.
+    pub fn subscribe_to_linters(&self) -> watch::Receiver<()> {
+        self.linters_changed_rx.clone()
+    }
+}
+
+/// Diagnostics, reported by a linter for the files it was run on.
+pub(crate) struct LinterDiagnostics {
+    /// Keeps the linter's diagnostics apart from the language servers' and other linters' ones.
+    server_id: LanguageServerId,
+    /// The linted buffers, whose language settings tell whether the linter still applies to them.
+    buffers: HashMap<PathBuf, WeakModel<Buffer>>,
+}
+
+impl Project {
+    /// Returns the command to run the registered formatter with, for the buffer given.
+    pub(crate) fn external_formatter_command(
+        &self,
+        name: &str,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ExternalToolCommand>> {
+        let Some(formatter) = ExternalToolRegistry::default_global(cx).formatter(name) else {
+            return Task::ready(Err(anyhow!("no formatter named `{name}` is installed")));
+        };
+        self.external_tool_command(formatter.as_ref(), buffer, cx)
+    }
+
+    fn external_tool_command(
+        &self,
+        tool: &dyn ExternalTool,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ExternalToolCommand>> {
+        let Some(worktree) = File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.clone())
+            .filter(|worktree| worktree.read(cx).is_local())
+        else {
+            return Task::ready(Err(anyhow!("buffer is not in a local worktree")));
+        };
+        let delegate = ProjectLspAdapterDelegate::new(self, &worktree, cx);
+        tool.command(delegate, cx)
+    }
+
+    /// Runs the linters, configured for the buffer's language, on the saved buffer file
+    /// and replaces their previous diagnostics for it.
+    pub(crate) fn lint_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let (buffer_id, abs_path, working_dir, linters) = {
+            let buffer = buffer.read(cx);
+            let Some(file) = File::from_dyn(buffer.file()) else {
+                return;
+            };
+            let Some(worktree) = file.worktree.read(cx).as_local() else {
+                return;
+            };
+            let linters = language_settings(buffer.language(), buffer.file(), cx)
+                .linters
+                .clone();
+            if linters.is_empty() {
+                return;
+            }
+            let mut working_dir = worktree.abs_path().to_path_buf();
+            if worktree.root_entry().map_or(false, |entry| entry.is_file()) {
+                working_dir.pop();
+            }
+            (buffer.remote_id(), file.abs_path(cx), working_dir, linters)
+        };
+        let opened_buffers = &self.opened_buffers;
+        self.lint_tasks
+            .retain(|(buffer_id, _), _| opened_buffers.contains_key(buffer_id));
+
+        let registry = ExternalToolRegistry::default_global(cx);
+        for name in linters {
+            let Some(linter) = registry.linter(&name) else {
+                log::warn!("no linter named `{name}` is installed");
+                continue;
+            };
+            let command = self.external_tool_command(linter.tool.as_ref(), buffer, cx);
+            let abs_path = abs_path.clone();
+            let working_dir = working_dir.clone();
+            let weak_buffer = buffer.downgrade();
+            // Replacing the linter's previous run on the buffer cancels it, so that its problems
+            // can't overwrite the ones of the file saved last.
+            let task_name = name.clone();
+            let lint_task = cx.spawn(|project, mut cx| {
+                async move {
+                    let command = command
+                        .await
+                        .with_context(|| format!("getting the command of linter `{name}`"))?;
+                    let problems = cx
+                        .background_executor()
+                        .spawn(run_linter(
+                            name.clone(),
+                            linter,
+                            command,
+                            abs_path.clone(),
+                            working_dir,
+                        ))
+                        .await
+                        .with_context(|| format!("running linter `{name}`"))?;
+                    project.update(&mut cx, |project, cx| {
+                        project.publish_linter_problems(&name, abs_path, weak_buffer, &problems, cx)
+                    })?
+                }
+                .log_err()
+            });
+            self.lint_tasks.insert((buffer_id, task_name), lint_task);
+        }
+    }
+
+    fn publish_linter_problems(
+        &mut self,
+        name: &Arc<str>,
+        abs_path: PathBuf,
+        buffer: WeakModel<Buffer>,
+        problems: &[Problem],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let linter_diagnostics = self
+            .linter_diagnostics
+            .entry(name.clone())
+            .or_insert_with(|| LinterDiagnostics {
+                server_id: self.languages.next_language_server_id(),
+                buffers: HashMap::default(),
+            });
+        linter_diagnostics.buffers.insert(abs_path.clone(), buffer);
+        let server_id = linter_diagnostics.server_id;
+        let diagnostics = problems
+            .iter()
+            .map(|problem| {
+                let group_id = post_inc(&mut self.next_diagnostic_group_id);
+                crate::terminals::problem_diagnostic(problem, group_id)
+            })
+            .collect();
+        self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)
+    }
+}
+
+impl Project {
+    /// Clears the diagnostics of the linters that were unregistered, or removed from the settings of the linted files.
+    pub(crate) fn maintain_linter_diagnostics(cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let mut linters_changed_rx =
+            ExternalToolRegistry::default_global(cx).subscribe_to_linters();
+        let _ = postage::stream::Stream::try_recv(&mut linters_changed_rx);
+
+        cx.spawn(move |this, mut cx| async move {
+            while let Some(()) = linters_changed_rx.next().await {
+                this.update(&mut cx, |this, cx| {
+                    this.clear_inactive_linter_diagnostics(cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+    }
+
+    pub(crate) fn clear_inactive_linter_diagnostics(&mut self, cx: &mut ModelContext<Self>) {
+        let registry = ExternalToolRegistry::default_global(cx);
+        let mut cleared = Vec::new();
+        for (name, linter_diagnostics) in &mut self.linter_diagnostics {
+            let registered = registry.linter(name).is_some();
+            linter_diagnostics.buffers.retain(|abs_path, buffer| {
+                // Without the buffer, the settings of the file can't be told, so its diagnostics stay until the next lint.
+                let active = registered
+                    && buffer.upgrade().map_or(true, |buffer| {
+                        let buffer = buffer.read(cx);
+                        language_settings(buffer.language(), buffer.file(), cx)
+                            .linters
+                            .contains(name)
+                    });
+                if !active {
+                    cleared.push((linter_diagnostics.server_id, abs_path.clone()));
+                }
+                active
+            });
+        }
+        self.lint_tasks
+            .retain(|(_, name), _| registry.linter(name).is_some());
+
+        for (server_id, abs_path) in cleared {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+    }
+}
+
+/// Runs the linter on the file, returning the problems it reports for that file.
+async fn run_linter(
+    name: Arc<str>,
+    linter: Arc<ExternalLinter>,
+    command: ExternalToolCommand,
+    abs_path: PathBuf,
+    working_dir: PathBuf,
+) -> Result<Vec<Problem>> {
+    let mut scanner = ProblemScanner::new(&linter.problem_matcher, &name)?;
+    let output = smol::process::Command::new(&command.path)
+        .args(
+            command
+                .arguments
+                .iter()
+                .map(|arg| arg.replace("{buffer_path}", &abs_path.to_string_lossy())),
+        )
+        .envs(command.env)
+        .current_dir(&working_dir)
+        .stdin(smol::process::Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("spawning {:?}", command.path))?;
+
+    // Linters usually exit with a failure status when they find problems, so the status is not checked.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let problems = stdout
+        .lines()
+        .chain(stderr.lines())
+        .flat_map(|line| scanner.scan_line(line))
+        .filter(|problem| {
+            let path = if problem.path.is_absolute() {
+                problem.path.clone()
+            } else {
+                working_dir.join(&problem.path)
+            };
+            path == abs_path
+        })
+        .collect();
+    Ok(problems)
+}
@@ -0,0 +1,188 @@
+//! Commands and notifications of language servers beyond the protocol, implemented by their adapters, e.g. in extensions.
+
+use crate::{
+    BufferOrderedMessage, Event, File, LanguageServerProgress, LanguageServerPromptRequest, Project,
+};
+use anyhow::{anyhow, Result};
+use client::proto;
+use gpui::{AppContext, Model, ModelContext, Task, WeakModel};
+use language::{
+    point_to_lsp, range_to_lsp, Buffer, CustomNotificationEffect, LanguageServerCommand,
+    LanguageServerCommandContext, LanguageServerCommandOutput, LanguageServerId, LspAdapter,
+    PointUtf16,
+};
+use lsp::LanguageServer;
+use std::{ops::Range, sync::Arc, time::Instant};
+use util::{maybe, ResultExt};
+
+impl Project {
+    /// Returns the commands of the language servers running for the buffer.
+    pub fn language_server_commands_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<LanguageServerCommand> {
+        self.language_servers_for_buffer(buffer.read(cx), cx)
+            .flat_map(|(adapter, _)| adapter.adapter.language_server_commands())
+            .collect()
+    }
+
+    /// Runs the command of the language server for the buffer, with the cursor at the position given.
+    pub fn run_language_server_command(
+        &self,
+        buffer: &Model<Buffer>,
+        position: PointUtf16,
+        selection: Range<PointUtf16>,
+        command: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<LanguageServerCommandOutput>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "language server commands are not supported in remote projects"
+            )));
+        }
+
+        let buffer = buffer.read(cx);
+        let Some(abs_path) = File::from_dyn(buffer.file()).map(|file| file.abs_path(cx)) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+        let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+            return Task::ready(Err(anyhow!("invalid file path {abs_path:?}")));
+        };
+        let server = self
+            .language_servers_for_buffer(buffer, cx)
+            .find(|(adapter, _)| {
+                adapter
+                    .adapter
+                    .language_server_commands()
+                    .iter()
+                    .any(|server_command| server_command.name == command)
+            })
+            .map(|(adapter, server)| (adapter.adapter.clone(), server.clone()));
+        let Some((adapter, server)) = server else {
+            return Task::ready(Err(anyhow!(
+                "no language server provides the command `{command}`"
+            )));
+        };
+
+        let context = LanguageServerCommandContext {
+            uri,
+            position: point_to_lsp(position),
+            selection: range_to_lsp(selection),
+        };
+        cx.spawn(|_, _| async move {
+            let (method, params) = adapter
+                .clone()
+                .language_server_command_request(&command, context)
+                .await?;
+            let response = server.request_custom(&method, params).await?;
+            adapter
+                .language_server_command_output(&command, response)
+                .await
+        })
+    }
+
+    /// Handles the custom notifications, declared by the adapter, from the language server.
+    pub(crate) fn register_custom_notification_handlers(
+        this: WeakModel<Self>,
+        language_server: &LanguageServer,
+        adapter: Arc<dyn LspAdapter>,
+        server_id: LanguageServerId,
+    ) {
+        let name = language_server.name().to_string();
+        for method in adapter.custom_notifications() {
+            if language_server.has_custom_notification_handler(&method) {
+                log::warn!("notification `{method}` of language server {name} is already handled");
+                continue;
+            }
+
+            language_server
+                .on_custom_notification(method.clone(), {
+                    let this = this.clone();
+                    let adapter = adapter.clone();
+                    let name = name.clone();
+                    move |params: serde_json::Value, cx| {
+                        let this = this.clone();
+                        let adapter = adapter.clone();
+                        let name = name.clone();
+                        let method = method.clone();
+                        cx.spawn(|mut cx| async move {
+                            maybe!(async move {
+                                let effects =
+                                    adapter.handle_custom_notification(&method, params).await?;
+                                this.update(&mut cx, |this, cx| {
+                                    for effect in effects {
+                                        this.on_custom_notification_effect(
+                                            server_id, &name, effect, cx,
+                                        );
+                                    }
+                                })
+                            })
+                            .await
+                            .log_err();
+                        })
+                        .detach();
+                    }
+                })
+                .detach();
+        }
+    }
+
+    fn on_custom_notification_effect(
+        &mut self,
+        server_id: LanguageServerId,
+        name: &str,
+        effect: CustomNotificationEffect,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match effect {
+            CustomNotificationEffect::Progress {
+                token,
+                message,
+                percentage,
+            } => {
+                let progress = LanguageServerProgress {
+                    message,
+                    percentage,
+                    last_update_at: Instant::now(),
+                };
+                let is_started = self
+                    .language_server_statuses
+                    .get(&server_id)
+                    .map_or(false, |status| status.pending_work.contains_key(&token));
+                if !is_started {
+                    self.on_lsp_work_start(server_id, token, progress, cx);
+                    return;
+                }
+
+                self.on_lsp_work_progress(server_id, token.clone(), progress.clone(), cx);
+                self.enqueue_language_server_progress(
+                    BufferOrderedMessage::LanguageServerUpdate {
+                        language_server_id: server_id,
+                        message: proto::update_language_server::Variant::WorkProgress(
+                            proto::LspWorkProgress {
+                                token,
+                                message: progress.message,
+                                percentage: progress.percentage.map(|p| p as u32),
+                            },
+                        ),
+                    },
+                    cx,
+                );
+            }
+            CustomNotificationEffect::ProgressEnd { token } => {
+                self.on_lsp_work_end(server_id, token, cx);
+            }
+            CustomNotificationEffect::Message { level, text } => {
+                let (tx, _rx) = smol::channel::bounded(1);
+                cx.emit(Event::LanguageServerPrompt(LanguageServerPromptRequest {
+                    level,
+                    message: text,
+                    actions: Vec::new(),
+                    response_channel: tx,
+                    lsp_name: name.to_string(),
+                }));
+            }
+        }
+    }
+}
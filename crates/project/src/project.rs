@@ -1,12 +1,17 @@
+mod breakpoints;
 pub mod connection_manager;
 pub mod debounced_delay;
+mod external_tools;
+mod language_server_commands;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
 pub mod search;
 mod task_inventory;
+mod task_provider;
 pub mod terminals;
+mod test_statuses;
 
 #[cfg(test)]
 mod project_tests;
@@ -14,6 +19,7 @@ pub mod search_history;
 
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
+use breakpoints::Breakpoints;
 use client::{
     proto, Client, Collaborator, DevServerProjectId, PendingEntitySubscription, ProjectId,
     TypedEnvelope, UserStore,
@@ -49,7 +55,7 @@ use language::{
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
     Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation, Event as BufferEvent,
     File as _, Language, LanguageRegistry, LanguageServerName, LocalFile, LspAdapterDelegate,
-    Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
+    Operation, Patch, PendingLanguageServer, PointUtf16, Snippet, TextBufferSnapshot, ToOffset,
     ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
@@ -96,23 +102,31 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use task::static_source::{StaticSource, TrackedFile};
+use task::{
+    static_source::{StaticSource, TrackedFile},
+    TaskContext, TaskId, TestResult,
+};
 use terminals::Terminals;
+use test_statuses::TestStatuses;
 use text::{Anchor, BufferId, LineEnding};
 use util::{
     debug_panic, defer, maybe, merge_json_value_into, parse_env_output,
     paths::{
-        LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH, LOCAL_VSCODE_TASKS_RELATIVE_PATH,
+        LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH, LOCAL_VSCODE_LAUNCH_RELATIVE_PATH,
+        LOCAL_VSCODE_TASKS_RELATIVE_PATH,
     },
     post_inc, ResultExt, TryFutureExt as _,
 };
 use worktree::{Snapshot, Traversal};
 
+pub use external_tools::{ExternalLinter, ExternalTool, ExternalToolCommand, ExternalToolRegistry};
 pub use fs::*;
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{Inventory, TaskSourceKind};
+pub use task_provider::{TaskProvider, TaskProviderRegistry};
+pub use test_statuses::{test_path, TestStatus};
 pub use worktree::{
     DiagnosticSummary, Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId,
     RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
@@ -170,6 +184,9 @@ pub struct Project {
     next_entry_id: Arc<AtomicUsize>,
     join_project_response_message_id: u32,
     next_diagnostic_group_id: usize,
+    linter_diagnostics: HashMap<Arc<str>, external_tools::LinterDiagnostics>,
+    /// The last run of each linter on each buffer.
+    lint_tasks: HashMap<(BufferId, Arc<str>), Task<Option<()>>>,
     user_store: Model<UserStore>,
     fs: Arc<dyn Fs>,
     client_state: ProjectClientState,
@@ -197,7 +214,11 @@ pub struct Project {
     nonce: u128,
     _maintain_buffer_languages: Task<()>,
     _maintain_workspace_config: Task<Result<()>>,
+    _maintain_provided_tasks: Task<Result<()>>,
+    _maintain_linter_diagnostics: Task<Result<()>>,
     terminals: Terminals,
+    test_statuses: TestStatuses,
+    breakpoints: Breakpoints,
     current_lsp_settings: HashMap<Arc<str>, LspSettings>,
     node: Option<Arc<dyn NodeRuntime>>,
     default_prettier: DefaultPrettier,
@@ -332,6 +353,19 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    /// Test results, reported by a running task.
+    TestResultsFound {
+        task_id: TaskId,
+        results: Vec<TestResult>,
+    },
+    /// A local task has finished, after all of its test results got reported.
+    TaskFinished {
+        task_id: TaskId,
+        success: bool,
+    },
+    TestStatusesUpdated,
+    /// Breakpoints were set or removed in the buffer.
+    BreakpointsChanged(BufferId),
 }
 
 pub enum LanguageServerState {
@@ -709,6 +743,8 @@ impl Project {
                 ],
                 _maintain_buffer_languages: Self::maintain_buffer_languages(languages.clone(), cx),
                 _maintain_workspace_config: Self::maintain_workspace_config(cx),
+                _maintain_provided_tasks: Self::maintain_provided_tasks(cx),
+                _maintain_linter_diagnostics: Self::maintain_linter_diagnostics(cx),
                 active_entry: None,
                 languages,
                 client,
@@ -716,6 +752,8 @@ impl Project {
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                linter_diagnostics: HashMap::default(),
+                lint_tasks: HashMap::default(),
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
@@ -729,7 +767,10 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
+                test_statuses: TestStatuses::default(),
+                breakpoints: Breakpoints::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: Some(node),
                 default_prettier: DefaultPrettier::default(),
@@ -833,11 +874,15 @@ impl Project {
                 join_project_response_message_id: response.message_id,
                 _maintain_buffer_languages: Self::maintain_buffer_languages(languages.clone(), cx),
                 _maintain_workspace_config: Self::maintain_workspace_config(cx),
+                _maintain_provided_tasks: Self::maintain_provided_tasks(cx),
+                _maintain_linter_diagnostics: Self::maintain_linter_diagnostics(cx),
                 languages,
                 user_store: user_store.clone(),
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                linter_diagnostics: HashMap::default(),
+                lint_tasks: HashMap::default(),
                 client_subscriptions: Default::default(),
                 _subscriptions: vec![
                     cx.on_release(Self::release),
@@ -881,7 +926,10 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
+                test_statuses: TestStatuses::default(),
+                breakpoints: Breakpoints::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: None,
                 default_prettier: DefaultPrettier::default(),
@@ -1174,6 +1222,8 @@ impl Project {
             self.restart_language_servers(worktree, language, cx);
         }
 
+        self.clear_inactive_linter_diagnostics(cx);
+
         cx.notify();
     }
 
@@ -2737,6 +2787,8 @@ impl Project {
                 for language_server_id in self.language_server_ids_for_buffer(buffer.read(cx), cx) {
                     self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
                 }
+
+                self.lint_buffer(&buffer, cx);
             }
             BufferEvent::FileHandleChanged => {
                 let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
@@ -3023,6 +3075,98 @@ impl Project {
         })
     }
 
+    fn maintain_provided_tasks(cx: &mut ModelContext<Project>) -> Task<Result<()>> {
+        let mut providers_changed_rx = TaskProviderRegistry::default_global(cx).subscribe();
+        let _ = postage::stream::Stream::try_recv(&mut providers_changed_rx);
+
+        cx.spawn(move |this, mut cx| async move {
+            while let Some(()) = providers_changed_rx.next().await {
+                this.update(&mut cx, |this, cx| {
+                    for worktree in this.visible_worktrees(cx).collect::<Vec<_>>() {
+                        this.refresh_provided_tasks(&worktree, cx);
+                    }
+                })?;
+            }
+            anyhow::Ok(())
+        })
+    }
+
+    /// Asks the registered [`TaskProvider`]s for the tasks of the worktree, replacing the ones listed for it before.
+    fn refresh_provided_tasks(&mut self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
+        if !worktree.read(cx).is_local() {
+            return;
+        }
+
+        let worktree_id = worktree.read(cx).id();
+        let task_inventory = self.task_inventory().clone();
+        task_inventory.update(cx, |inventory, cx| {
+            inventory.remove_provided_tasks(worktree_id, cx);
+        });
+        for (name, provider) in TaskProviderRegistry::default_global(cx).providers() {
+            let delegate = ProjectLspAdapterDelegate::new(self, worktree, cx);
+            let tasks = provider.tasks_for_worktree(delegate, cx);
+            let task_inventory = task_inventory.clone();
+            cx.spawn(|_, mut cx| async move {
+                let tasks = tasks
+                    .await
+                    .with_context(|| format!("listing tasks of `{name}`"))?;
+                task_inventory.update(&mut cx, |inventory, cx| {
+                    inventory.set_provided_tasks(
+                        TaskSourceKind::Provider {
+                            name,
+                            worktree: worktree_id,
+                        },
+                        tasks,
+                        cx,
+                    );
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    /// Extends the task context with the custom variables,
+    /// computed by the registered [`TaskProvider`]s for the tasks spawned in the worktree given.
+    ///
+    /// Providers that fail to compute their variables are skipped.
+    pub fn task_context_with_provided_variables(
+        &self,
+        worktree_id: Option<WorktreeId>,
+        mut task_context: TaskContext,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<TaskContext> {
+        let Some(worktree) = worktree_id
+            .and_then(|worktree_id| self.worktree_for_id(worktree_id, cx))
+            .filter(|worktree| worktree.read(cx).is_local())
+        else {
+            return Task::ready(task_context);
+        };
+        let providers = TaskProviderRegistry::default_global(cx).providers();
+        if providers.is_empty() {
+            return Task::ready(task_context);
+        }
+
+        let variables = providers
+            .into_iter()
+            .map(|(name, provider)| {
+                let delegate = ProjectLspAdapterDelegate::new(self, &worktree, cx);
+                let variables = provider.task_variables(task_context.clone(), delegate, cx);
+                async move {
+                    variables
+                        .await
+                        .with_context(|| format!("computing task variables of `{name}`"))
+                        .log_err()
+                }
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            for variables in join_all(variables).await.into_iter().flatten() {
+                task_context.task_variables.extend(variables);
+            }
+            task_context
+        })
+    }
+
     fn detect_language_for_buffer(
         &mut self,
         buffer_handle: &Model<Buffer>,
@@ -3556,6 +3700,13 @@ impl Project {
             })
             .detach();
 
+        Self::register_custom_notification_handlers(
+            this.clone(),
+            &language_server,
+            adapter.adapter.clone(),
+            server_id,
+        );
+
         let disk_based_diagnostics_progress_token =
             adapter.disk_based_diagnostics_progress_token.clone();
 
@@ -4843,6 +4994,7 @@ impl Project {
                             buffer_abs_path,
                             command,
                             arguments,
+                            &HashMap::default(),
                             &mut cx,
                         )
                         .await
@@ -4853,6 +5005,27 @@ impl Project {
                         .map(FormatOperation::External);
                     }
                 }
+                (Formatter::Extension(name), FormatOnSave::On | FormatOnSave::Off) => {
+                    if let Some(buffer_abs_path) = buffer_abs_path {
+                        let command = project
+                            .update(&mut cx, |project, cx| {
+                                project.external_formatter_command(name, buffer, cx)
+                            })?
+                            .await
+                            .with_context(|| format!("failed to get formatter {name:?}"))?;
+                        format_operation = Self::format_via_external_command(
+                            buffer,
+                            buffer_abs_path,
+                            &command.path.to_string_lossy(),
+                            &command.arguments,
+                            &command.env,
+                            &mut cx,
+                        )
+                        .await
+                        .with_context(|| format!("failed to format via formatter {name:?}"))?
+                        .map(FormatOperation::External);
+                    }
+                }
                 (Formatter::Auto, FormatOnSave::On | FormatOnSave::Off) => {
                     let prettier = if prettier_settings.allowed {
                         prettier_support::format_with_prettier(&project, buffer, &mut cx)
@@ -4993,6 +5166,7 @@ impl Project {
         buffer_abs_path: &Path,
         command: &str,
         arguments: &[String],
+        env: &HashMap<String, String>,
         cx: &mut AsyncAppContext,
     ) -> Result<Option<Diff>> {
         let working_dir_path = buffer.update(cx, |buffer, cx| {
@@ -5011,6 +5185,7 @@ impl Project {
                     .args(arguments.iter().map(|arg| {
                         arg.replace("{buffer_path}", &buffer_abs_path.to_string_lossy())
                     }))
+                    .envs(env)
                     .current_dir(&working_dir_path)
                     .stdin(smol::process::Stdio::piped())
                     .stdout(smol::process::Stdio::piped())
@@ -5505,6 +5680,16 @@ impl Project {
                 })
                 .map(|(_, server)| server.server_id())
                 .collect();
+            let snippet_completions = language
+                .as_ref()
+                .map(|language| {
+                    snippet_completions(
+                        &language_registry.snippets_for_language(&language.name()),
+                        &snapshot,
+                        offset,
+                    )
+                })
+                .unwrap_or_default();
 
             let buffer = buffer.clone();
             cx.spawn(move |this, mut cx| async move {
@@ -5537,6 +5722,7 @@ impl Project {
                         .await;
                     }
                 }
+                completions.extend(snippet_completions);
 
                 Ok(completions)
             })
@@ -7293,6 +7479,10 @@ impl Project {
         })
         .detach();
 
+        if worktree.read(cx).is_visible() {
+            self.refresh_provided_tasks(worktree, cx);
+        }
+
         cx.emit(Event::WorktreeAdded);
         self.metadata_changed(cx);
     }
@@ -7678,31 +7868,23 @@ impl Project {
                     }
                 })
             } else if abs_path.ends_with(&*LOCAL_VSCODE_TASKS_RELATIVE_PATH) {
-                self.task_inventory().update(cx, |task_inventory, cx| {
-                    if removed {
-                        task_inventory.remove_local_static_source(&abs_path);
-                    } else {
-                        let fs = self.fs.clone();
-                        let task_abs_path = abs_path.clone();
-                        let tasks_file_rx =
-                            watch_config_file(&cx.background_executor(), fs, task_abs_path);
-                        task_inventory.add_source(
-                            TaskSourceKind::Worktree {
-                                id: remote_worktree_id,
-                                abs_path,
-                                id_base: "local_vscode_tasks_for_worktree",
-                            },
-                            |tx, cx| {
-                                StaticSource::new(TrackedFile::new_convertible::<
-                                    task::VsCodeTaskFile,
-                                >(
-                                    tasks_file_rx, tx, cx
-                                ))
-                            },
-                            cx,
-                        );
-                    }
-                })
+                self.update_vscode_task_source(
+                    remote_worktree_id,
+                    abs_path,
+                    removed,
+                    "local_vscode_tasks_for_worktree",
+                    task::VsCodeTaskFile::into_zed_templates,
+                    cx,
+                );
+            } else if abs_path.ends_with(&*LOCAL_VSCODE_LAUNCH_RELATIVE_PATH) {
+                self.update_vscode_task_source(
+                    remote_worktree_id,
+                    abs_path,
+                    removed,
+                    "local_vscode_launch_for_worktree",
+                    task::VsCodeLaunchFile::into_zed_templates,
+                    cx,
+                );
             }
         }
 
@@ -7744,6 +7926,73 @@ impl Project {
         .detach();
     }
 
+    /// Tracks the tasks converted from a VS Code configuration file of the worktree,
+    /// showing the warnings about what could not be converted as is in a notification.
+    fn update_vscode_task_source<F: 'static + for<'a> serde::Deserialize<'a>>(
+        &mut self,
+        worktree_id: WorktreeId,
+        abs_path: PathBuf,
+        removed: bool,
+        id_base: &'static str,
+        convert: fn(F) -> (task::TaskTemplates, Vec<String>),
+        cx: &mut ModelContext<Self>,
+    ) {
+        if removed {
+            self.task_inventory().update(cx, |task_inventory, _| {
+                task_inventory.remove_local_static_source(&abs_path);
+            });
+            return;
+        }
+
+        let (warnings_tx, mut warnings_rx) = mpsc::unbounded();
+        let tasks_file_rx =
+            watch_config_file(&cx.background_executor(), self.fs.clone(), abs_path.clone());
+        self.task_inventory().update(cx, |task_inventory, cx| {
+            task_inventory.add_source(
+                TaskSourceKind::Worktree {
+                    id: worktree_id,
+                    abs_path: abs_path.clone(),
+                    id_base,
+                },
+                |tx, cx| {
+                    StaticSource::new(TrackedFile::new_convertible(
+                        tasks_file_rx,
+                        tx,
+                        move |file: F| {
+                            let (templates, warnings) = convert(file);
+                            warnings_tx.unbounded_send(warnings).ok();
+                            Ok(templates)
+                        },
+                        cx,
+                    ))
+                },
+                cx,
+            );
+        });
+        cx.spawn(|this, mut cx| async move {
+            let mut last_warnings = Vec::new();
+            while let Some(warnings) = warnings_rx.next().await {
+                if warnings.is_empty() || warnings == last_warnings {
+                    last_warnings = warnings;
+                    continue;
+                }
+                let message = format!(
+                    "Some of the tasks in {} could not be imported as is:\n{}",
+                    abs_path.display(),
+                    warnings.join("\n")
+                );
+                last_warnings = warnings;
+                if this
+                    .update(&mut cx, |_, cx| cx.emit(Event::Notification(message)))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
     pub fn set_active_path(&mut self, entry: Option<ProjectPath>, cx: &mut ModelContext<Self>) {
         let new_active_entry = entry.and_then(|project_path| {
             let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
@@ -10313,6 +10562,56 @@ async fn populate_labels_for_completions(
     }
 }
 
+/// The server id of the completions for snippets, which don't come from a language server.
+const SNIPPET_COMPLETION_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+/// Returns the completions for the snippets with a prefix that starts with the word before the cursor.
+fn snippet_completions(
+    snippets: &[Snippet],
+    snapshot: &BufferSnapshot,
+    offset: usize,
+) -> Vec<Completion> {
+    let (word_range, _) = snapshot.surrounding_word(offset);
+    if word_range.start >= offset {
+        return Vec::new();
+    }
+    let word = snapshot
+        .text_for_range(word_range.start..offset)
+        .collect::<String>()
+        .to_lowercase();
+    let old_range = snapshot.anchor_before(word_range.start)..snapshot.anchor_after(offset);
+
+    let mut completions = Vec::new();
+    for snippet in snippets {
+        for prefix in &snippet.prefixes {
+            if !prefix.to_lowercase().starts_with(&word) {
+                continue;
+            }
+            let description = snippet
+                .description
+                .clone()
+                .unwrap_or_else(|| snippet.name.to_string());
+            completions.push(Completion {
+                old_range: old_range.clone(),
+                new_text: snippet.body.clone(),
+                label: CodeLabel::plain(prefix.clone(), None),
+                server_id: SNIPPET_COMPLETION_SERVER_ID,
+                // Snippets are fully resolved, so they're never sent to a language server.
+                documentation: Some(Documentation::SingleLine(description.clone())),
+                lsp_completion: lsp::CompletionItem {
+                    label: prefix.clone(),
+                    kind: Some(lsp::CompletionItemKind::SNIPPET),
+                    detail: Some(description),
+                    insert_text: Some(snippet.body.clone()),
+                    insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                    ..Default::default()
+                },
+            });
+        }
+    }
+    completions
+}
+
 fn deserialize_code_actions(code_actions: &HashMap<String, bool>) -> Vec<lsp::CodeActionKind> {
     code_actions
         .iter()
@@ -10601,7 +10900,7 @@ impl<P: AsRef<Path>> From<(WorktreeId, P)> for ProjectPath {
     }
 }
 
-struct ProjectLspAdapterDelegate {
+pub struct ProjectLspAdapterDelegate {
     project: WeakModel<Project>,
     worktree: worktree::Snapshot,
     fs: Arc<dyn Fs>,
@@ -10611,7 +10910,11 @@ struct ProjectLspAdapterDelegate {
 }
 
 impl ProjectLspAdapterDelegate {
-    fn new(project: &Project, worktree: &Model<Worktree>, cx: &ModelContext<Project>) -> Arc<Self> {
+    pub fn new(
+        project: &Project,
+        worktree: &Model<Worktree>,
+        cx: &ModelContext<Project>,
+    ) -> Arc<Self> {
         Arc::new(Self {
             project: cx.weak_model(),
             worktree: worktree.read(cx).snapshot(),
@@ -10697,6 +11000,23 @@ impl LspAdapterDelegate for ProjectLspAdapterDelegate {
         let content = self.fs.load(&path).await?;
         Ok(content)
     }
+
+    async fn read_dir(&self, path: PathBuf) -> Result<Vec<(String, bool)>> {
+        let Some(entry) = self.worktree.entry_for_path(&path) else {
+            return Err(anyhow!("no such path {path:?}"));
+        };
+        if !entry.is_dir() {
+            return Err(anyhow!("{path:?} is not a directory"));
+        }
+        Ok(self
+            .worktree
+            .child_entries(&path)
+            .filter_map(|entry| {
+                let name = entry.path.file_name()?.to_string_lossy().into_owned();
+                Some((name, entry.is_dir()))
+            })
+            .collect())
+    }
 }
 
 fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
@@ -1,11 +1,12 @@
 use crate::{Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
-use gpui::{AppContext, UpdateGlobal};
+use gpui::{AppContext, EntityId, UpdateGlobal};
 use language::{
-    language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
-    LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
+    language_settings::{AllLanguageSettings, Formatter, LanguageSettingsContent},
+    tree_sitter_rust, tree_sitter_typescript, CustomNotificationEffect, Diagnostic, FakeLspAdapter,
+    LanguageConfig, LanguageMatcher, LanguageServerCommand, LanguageServerCommandContext,
+    LanguageServerCommandOutput, LineEnding, LspAdapter, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::Url;
 use parking_lot::Mutex;
@@ -14,7 +15,10 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 use std::task::Poll;
-use task::{TaskContext, TaskTemplate, TaskTemplates};
+use task::{
+    CustomProblemMatcher, Problem, ProblemMatcher, ProblemPattern, ProblemSeverity, TaskContext,
+    TaskId, TaskTemplate, TaskTemplates,
+};
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree};
 use worktree::WorktreeModelHandle as _;
@@ -316,6 +320,92 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_vscode_tasks_import(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".vscode": {
+                "tasks.json": r#"{ "tasks": [{ "label": "build", "type": "shell", "command": "make" }] }"#,
+                "launch.json": r#"{ "configurations": [{
+                    "name": "debug app",
+                    "type": "lldb",
+                    "request": "launch",
+                    "program": "${workspaceFolder}/app",
+                    "preLaunchTask": "build"
+                }] }"#,
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let notifications = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let notifications = notifications.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::Notification(message) = event {
+                notifications.lock().push(message.clone());
+            }
+        })
+        .detach();
+    });
+    cx.executor().run_until_parked();
+
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let tasks = project.update(cx, |project, cx| {
+        project.task_inventory().update(cx, |inventory, _| {
+            let (_, current) = inventory.used_and_current_resolved_tasks(
+                None,
+                Some(worktree_id),
+                &TaskContext::default(),
+            );
+            let mut tasks = current
+                .into_iter()
+                .map(|(_, task)| {
+                    let resolved = task.resolved.unwrap();
+                    (
+                        task.resolved_label,
+                        resolved.command,
+                        resolved.debug.is_some(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            tasks.sort();
+            tasks
+        })
+    });
+    assert_eq!(
+        tasks,
+        vec![
+            ("build".to_string(), "make".to_string(), false),
+            ("debug app".to_string(), "lldb-dap".to_string(), true),
+        ]
+    );
+    assert_eq!(
+        *notifications.lock(),
+        Vec::<String>::new(),
+        "Tasks imported as is should not be reported"
+    );
+
+    fs.insert_file(
+        "/the-root/.vscode/tasks.json",
+        r#"{ "tasks": [{ "label": "build", "type": "shell", "command": "make", "runOptions": {} }] }"#
+            .as_bytes()
+            .to_vec(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *notifications.lock(),
+        ["Some of the tasks in /the-root/.vscode/tasks.json could not be imported as is:\nTask `build`: `runOptions` is not supported"]
+    );
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1959,6 +2049,544 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_problems_as_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ "src": { "main.rs": "fn main() {\n    let x = 5;\n}\n" } }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let task_id = TaskId("cargo check".to_string());
+    let problem = |path: &str, line: u32, column: Option<u32>, severity: ProblemSeverity| Problem {
+        source: "rustc".to_string(),
+        path: PathBuf::from(path),
+        line,
+        column,
+        end_line: None,
+        end_column: None,
+        severity,
+        code: None,
+        message: format!("problem at {line}"),
+    };
+
+    let first_terminal = EntityId::from(1);
+    project.update(cx, |project, cx| {
+        project.clear_task_diagnostics(&task_id, Some(PathBuf::from("/dir")), first_terminal, cx);
+        project.publish_task_problems(
+            &task_id,
+            &[
+                problem("src/main.rs", 2, Some(9), ProblemSeverity::Warning),
+                problem("/dir/src/main.rs", 3, None, ProblemSeverity::Error),
+                problem("/outside/lib.rs", 1, Some(1), ProblemSeverity::Error),
+            ],
+            cx,
+        );
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 1,
+            }
+        );
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| {
+                (
+                    entry.range,
+                    entry.diagnostic.severity,
+                    entry.diagnostic.source,
+                    entry.diagnostic.message,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            &[
+                (
+                    Point::new(1, 8)..Point::new(1, 9),
+                    DiagnosticSeverity::WARNING,
+                    Some("rustc".to_string()),
+                    "problem at 2".to_string()
+                ),
+                (
+                    Point::new(2, 0)..Point::new(2, 1),
+                    DiagnosticSeverity::ERROR,
+                    Some("rustc".to_string()),
+                    "problem at 3".to_string()
+                ),
+            ]
+        );
+    });
+
+    // The next run of the task clears the problems of the previous one.
+    let second_terminal = EntityId::from(2);
+    project.update(cx, |project, cx| {
+        project.clear_task_diagnostics(&task_id, Some(PathBuf::from("/dir")), second_terminal, cx);
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary::default()
+        );
+    });
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .count(),
+            0
+        );
+    });
+
+    // Closing the previous run's terminal keeps the problems of the current one,
+    // which are removed when its own terminal is closed.
+    project.update(cx, |project, cx| {
+        project.publish_task_problems(
+            &task_id,
+            &[problem("src/main.rs", 2, None, ProblemSeverity::Error)],
+            cx,
+        );
+        project.remove_task_diagnostics(&task_id, first_terminal, cx);
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 0,
+            }
+        );
+        project.remove_task_diagnostics(&task_id, second_terminal, cx);
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary::default()
+        );
+    });
+}
+
+struct FakeExternalTool(ExternalToolCommand);
+
+impl ExternalTool for FakeExternalTool {
+    fn command(
+        &self,
+        _: Arc<dyn LspAdapterDelegate>,
+        _: &mut AppContext,
+    ) -> Task<Result<ExternalToolCommand>> {
+        Task::ready(Ok(self.0.clone()))
+    }
+}
+
+fn shell_tool(script: &str) -> FakeExternalTool {
+    FakeExternalTool(ExternalToolCommand {
+        path: PathBuf::from("sh"),
+        arguments: vec![
+            "-c".to_string(),
+            script.to_string(),
+            "{buffer_path}".to_string(),
+        ],
+        env: HashMap::default(),
+    })
+}
+
+#[gpui::test]
+async fn test_extension_formatter(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let dir = temp_tree(json!({ "a.rs": "fn a() {}\n" }));
+    let project = Project::test(Arc::new(RealFs::default()), [dir.path()], cx).await;
+    cx.update(|cx| {
+        ExternalToolRegistry::default_global(cx)
+            .register_formatter("upcase".into(), shell_tool("tr a-z A-Z"));
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.formatter = Some(Formatter::Extension("upcase".into()));
+            });
+        });
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(dir.path().join("a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.format(
+                HashSet::from_iter([buffer.clone()]),
+                true,
+                FormatTrigger::Manual,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "FN A() {}\n"));
+}
+
+#[gpui::test]
+async fn test_extension_linters(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let dir = temp_tree(json!({ "a.rs": "a.rs:1: first\nb.rs:1: elsewhere\n" }));
+    let project = Project::test(Arc::new(RealFs::default()), [dir.path()], cx).await;
+    let linter = |script: &str| ExternalLinter {
+        tool: Arc::new(shell_tool(script)),
+        problem_matcher: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+            owner: None,
+            severity: ProblemSeverity::Warning,
+            pattern: vec![ProblemPattern {
+                regexp: r"^(.*):(\d+): (.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                message: Some(3),
+                ..ProblemPattern::default()
+            }],
+        })],
+    };
+    let set_linters = |linters: &[&str], cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.linters =
+                        Some(linters.iter().map(|name| Arc::from(*name)).collect());
+                });
+            });
+        })
+    };
+    // The `cat` linter reports the problems written in the linted file itself.
+    cx.update(|cx| {
+        let registry = ExternalToolRegistry::default_global(cx);
+        registry.register_linter("cat".into(), linter(r#"cat "$0""#));
+        registry.register_linter("other".into(), linter("echo a.rs:2: other"));
+    });
+    set_linters(&["cat", "other"], cx);
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(dir.path().join("a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let save_and_lint = |cx: &mut gpui::TestAppContext| {
+        let save = project.update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+        let project = project.clone();
+        async move {
+            save.await.unwrap();
+            cx.run_until_parked();
+            let lint_tasks = project.update(cx, |project, _| {
+                project
+                    .lint_tasks
+                    .drain()
+                    .map(|(_, task)| task)
+                    .collect::<Vec<_>>()
+            });
+            future::join_all(lint_tasks).await;
+        }
+    };
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            let mut diagnostics = buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| {
+                    (
+                        entry.range.start.row,
+                        entry.diagnostic.source.unwrap(),
+                        entry.diagnostic.message,
+                    )
+                })
+                .collect::<Vec<_>>();
+            diagnostics.sort();
+            diagnostics
+        })
+    };
+    let diagnostic =
+        |row: u32, source: &str, message: &str| (row, source.to_string(), message.to_string());
+
+    // Only the problems in the linted file are reported.
+    save_and_lint(cx).await;
+    assert_eq!(
+        diagnostics(cx),
+        [
+            diagnostic(0, "cat", "first"),
+            diagnostic(1, "other", "other")
+        ]
+    );
+
+    // Each linter replaces its own problems only.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_text("a.rs:2: second\nsecond line\n", cx)
+    });
+    save_and_lint(cx).await;
+    assert_eq!(
+        diagnostics(cx),
+        [
+            diagnostic(1, "cat", "second"),
+            diagnostic(1, "other", "other")
+        ]
+    );
+
+    // The problems of the linters, removed from the settings or unregistered, are cleared.
+    set_linters(&["other"], cx);
+    cx.run_until_parked();
+    assert_eq!(diagnostics(cx), [diagnostic(1, "other", "other")]);
+    cx.update(|cx| ExternalToolRegistry::default_global(cx).unregister_linter("other"));
+    cx.run_until_parked();
+    assert_eq!(diagnostics(cx), Vec::<(u32, String, String)>::new());
+}
+
+/// Runs a fake language server with a command and a notification beyond the protocol.
+struct CustomMethodsLspAdapter(FakeLspAdapter);
+
+#[async_trait(?Send)]
+impl LspAdapter for CustomMethodsLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        self.0.name()
+    }
+
+    fn get_language_server_command<'a>(
+        self: Arc<Self>,
+        _: Arc<Language>,
+        _: Arc<Path>,
+        _: Arc<dyn LspAdapterDelegate>,
+        _: futures::lock::MutexGuard<'a, Option<LanguageServerBinary>>,
+        _: &'a mut AsyncAppContext,
+    ) -> std::pin::Pin<Box<dyn 'a + Future<Output = Result<LanguageServerBinary>>>> {
+        async move { Ok(self.0.language_server_binary.clone()) }.boxed_local()
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + std::any::Any>> {
+        unreachable!();
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + std::any::Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        unreachable!();
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        unreachable!();
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        unreachable!();
+    }
+
+    fn language_server_commands(&self) -> Vec<LanguageServerCommand> {
+        vec![LanguageServerCommand {
+            name: "switch-source-header".into(),
+            label: "Switch Source/Header".to_string(),
+        }]
+    }
+
+    async fn language_server_command_request(
+        self: Arc<Self>,
+        _: &str,
+        context: LanguageServerCommandContext,
+    ) -> Result<(String, serde_json::Value)> {
+        Ok((
+            "test/switchSourceHeader".to_string(),
+            json!({ "uri": context.uri, "position": context.position }),
+        ))
+    }
+
+    async fn language_server_command_output(
+        self: Arc<Self>,
+        _: &str,
+        response: serde_json::Value,
+    ) -> Result<LanguageServerCommandOutput> {
+        Ok(LanguageServerCommandOutput::Location {
+            uri: serde_json::from_value(response)?,
+            position: lsp::Position::new(0, 0),
+        })
+    }
+
+    fn custom_notifications(&self) -> Vec<String> {
+        vec!["test/indexingStatus".to_string()]
+    }
+
+    async fn handle_custom_notification(
+        self: Arc<Self>,
+        _: &str,
+        params: serde_json::Value,
+    ) -> Result<Vec<CustomNotificationEffect>> {
+        let token = "indexing".to_string();
+        Ok(match params["percentage"].as_u64() {
+            Some(percentage) => vec![CustomNotificationEffect::Progress {
+                token,
+                message: Some("Indexing".to_string()),
+                percentage: Some(percentage as usize),
+            }],
+            None => vec![
+                CustomNotificationEffect::ProgressEnd { token },
+                CustomNotificationEffect::Message {
+                    level: PromptLevel::Info,
+                    text: "Indexing finished".to_string(),
+                },
+            ],
+        })
+    }
+}
+
+#[gpui::test]
+async fn test_language_server_custom_methods(cx: &mut gpui::TestAppContext) {
+    enum SwitchSourceHeader {}
+
+    impl lsp::request::Request for SwitchSourceHeader {
+        type Params = serde_json::Value;
+        type Result = serde_json::Value;
+        const METHOD: &'static str = "test/switchSourceHeader";
+    }
+
+    enum IndexingStatus {}
+
+    impl lsp::notification::Notification for IndexingStatus {
+        type Params = serde_json::Value;
+        const METHOD: &'static str = "test/indexingStatus";
+    }
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() {}", "a.h": "" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    language_registry.register_lsp_adapter(
+        "Rust".into(),
+        Arc::new(CustomMethodsLspAdapter(FakeLspAdapter::default())),
+    );
+    let mut fake_servers = language_registry.fake_language_servers("Rust");
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let prompts = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let prompts = prompts.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::LanguageServerPrompt(prompt) = event {
+                prompts.lock().push((prompt.level, prompt.message.clone()));
+            }
+        })
+        .detach();
+    });
+
+    // The command sends the request built by the adapter, and the adapter reads its response.
+    fake_server.handle_request::<SwitchSourceHeader, _, _>(|params, _| async move {
+        assert_eq!(
+            params,
+            json!({
+                "uri": Url::from_file_path("/dir/a.rs").unwrap(),
+                "position": lsp::Position::new(0, 3),
+            })
+        );
+        Ok(json!(Url::from_file_path("/dir/a.h").unwrap()))
+    });
+    let commands = project.update(cx, |project, cx| {
+        project.language_server_commands_for_buffer(&buffer, cx)
+    });
+    assert_eq!(
+        commands
+            .iter()
+            .map(|command| command.name.as_ref())
+            .collect::<Vec<_>>(),
+        ["switch-source-header"]
+    );
+    let output = project
+        .update(cx, |project, cx| {
+            let position = PointUtf16::new(0, 3);
+            project.run_language_server_command(
+                &buffer,
+                position,
+                position..position,
+                "switch-source-header".into(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        output,
+        LanguageServerCommandOutput::Location {
+            uri: Url::from_file_path("/dir/a.h").unwrap(),
+            position: lsp::Position::new(0, 0),
+        }
+    );
+    let unknown_command = project.update(cx, |project, cx| {
+        let position = PointUtf16::new(0, 0);
+        project.run_language_server_command(
+            &buffer,
+            position,
+            position..position,
+            "unknown".into(),
+            cx,
+        )
+    });
+    assert!(unknown_command.await.is_err());
+
+    // The notifications start, update and end the work of the server, and show its messages.
+    let pending_work = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, _| {
+            project
+                .language_server_statuses()
+                .flat_map(|status| {
+                    status
+                        .pending_work
+                        .iter()
+                        .map(|(token, progress)| (token.clone(), progress.percentage))
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    fake_server.notify::<IndexingStatus>(json!({ "percentage": 10 }));
+    cx.run_until_parked();
+    assert_eq!(pending_work(cx), [("indexing".to_string(), Some(10))]);
+
+    fake_server.notify::<IndexingStatus>(json!({ "percentage": 60 }));
+    cx.run_until_parked();
+    assert_eq!(pending_work(cx), [("indexing".to_string(), Some(60))]);
+    assert!(prompts.lock().is_empty());
+
+    fake_server.notify::<IndexingStatus>(json!({}));
+    cx.run_until_parked();
+    assert_eq!(pending_work(cx), Vec::<(String, Option<usize>)>::new());
+    assert_eq!(
+        prompts.lock().as_slice(),
+        [(PromptLevel::Info, "Indexing finished".to_string())]
+    );
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4944,6 +5572,68 @@ async fn search(
         .collect())
 }
 
+#[gpui::test]
+async fn test_breakpoints(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_id = buffer.update(cx, |buffer, _| buffer.remote_id());
+    let events = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::BreakpointsChanged(_) = event {
+                events.lock().push(event.clone());
+            }
+        })
+        .detach();
+    });
+
+    project.update(cx, |project, cx| {
+        for (row, column) in [(1, 8), (2, 0), (1, 0)] {
+            let position = buffer.read(cx).anchor_before(Point::new(row, column));
+            project.toggle_breakpoint(&buffer, position, cx);
+        }
+        assert_eq!(
+            project.breakpoint_rows(buffer_id, cx),
+            vec![2],
+            "toggling a breakpoint on the same row twice should remove it"
+        );
+        let position = buffer.read(cx).anchor_before(Point::new(1, 4));
+        project.toggle_breakpoint(&buffer, position, cx);
+    });
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// comment\n")], None, cx)
+    });
+    project.update(cx, |project, cx| {
+        assert_eq!(project.breakpoint_rows(buffer_id, cx), vec![2, 3]);
+        assert_eq!(
+            project.all_breakpoints(cx),
+            vec![(PathBuf::from("/dir/main.rs"), vec![2, 3])]
+        );
+    });
+    assert_eq!(
+        events.lock().as_slice(),
+        vec![Event::BreakpointsChanged(buffer_id); 4].as_slice()
+    );
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
         env_logger::try_init().ok();
@@ -15,7 +15,8 @@ use gpui::{AppContext, Context, Model, ModelContext, Task};
 use itertools::Itertools;
 use language::Language;
 use task::{
-    static_source::StaticSource, ResolvedTask, TaskContext, TaskId, TaskTemplate, VariableName,
+    static_source::StaticSource, ResolvedTask, TaskContext, TaskId, TaskPlan, TaskTemplate,
+    TaskTemplates, VariableName,
 };
 use util::{post_inc, NumericPrefixWithSuffix};
 use worktree::WorktreeId;
@@ -23,6 +24,7 @@ use worktree::WorktreeId;
 /// Inventory tracks available tasks for a given project.
 pub struct Inventory {
     sources: Vec<SourceInInventory>,
+    provided_tasks: BTreeMap<TaskSourceKind, TaskTemplates>,
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
     update_sender: UnboundedSender<()>,
     _update_pooler: Task<anyhow::Result<()>>,
@@ -51,19 +53,25 @@ pub enum TaskSourceKind {
     },
     /// Languages-specific tasks coming from extensions.
     Language { name: Arc<str> },
+    /// Tasks listed for the worktree by the [`crate::TaskProvider`] with the given name.
+    Provider {
+        name: Arc<str>,
+        worktree: WorktreeId,
+    },
 }
 
 impl TaskSourceKind {
     pub fn abs_path(&self) -> Option<&Path> {
         match self {
             Self::AbsPath { abs_path, .. } | Self::Worktree { abs_path, .. } => Some(abs_path),
-            Self::UserInput | Self::Language { .. } => None,
+            Self::UserInput | Self::Language { .. } | Self::Provider { .. } => None,
         }
     }
 
     pub fn worktree(&self) -> Option<WorktreeId> {
         match self {
             Self::Worktree { id, .. } => Some(*id),
+            Self::Provider { worktree, .. } => Some(*worktree),
             _ => None,
         }
     }
@@ -82,6 +90,7 @@ impl TaskSourceKind {
                 format!("{id_base}_{id}_{}", abs_path.display())
             }
             TaskSourceKind::Language { name } => format!("language_{name}"),
+            TaskSourceKind::Provider { name, worktree } => format!("provider_{name}_{worktree}"),
         }
     }
 }
@@ -100,6 +109,7 @@ impl Inventory {
             });
             Self {
                 sources: Vec::new(),
+                provided_tasks: BTreeMap::default(),
                 last_scheduled_tasks: VecDeque::new(),
                 update_sender,
                 _update_pooler,
@@ -143,6 +153,61 @@ impl Inventory {
     /// Now, entry for this path can be re-added again.
     pub fn remove_worktree_sources(&mut self, worktree: WorktreeId) {
         self.sources.retain(|s| s.kind.worktree() != Some(worktree));
+        self.provided_tasks
+            .retain(|kind, _| kind.worktree() != Some(worktree));
+    }
+
+    /// Sets the tasks, listed by a [`crate::TaskProvider`], replacing the ones it listed before.
+    pub fn set_provided_tasks(
+        &mut self,
+        kind: TaskSourceKind,
+        tasks: TaskTemplates,
+        cx: &mut ModelContext<Self>,
+    ) {
+        debug_assert!(
+            matches!(kind, TaskSourceKind::Provider { .. }),
+            "Unexpected provided tasks kind: {kind:?}"
+        );
+        self.provided_tasks.insert(kind, tasks);
+        cx.notify();
+    }
+
+    /// Removes the tasks, listed by all [`crate::TaskProvider`]s for the given worktree.
+    pub fn remove_provided_tasks(&mut self, worktree: WorktreeId, cx: &mut ModelContext<Self>) {
+        let tasks_count = self.provided_tasks.len();
+        self.provided_tasks
+            .retain(|kind, _| kind.worktree() != Some(worktree));
+        if self.provided_tasks.len() != tasks_count {
+            cx.notify();
+        }
+    }
+
+    /// Lists the tasks of the static sources and the tasks provided, that are relevant to the worktree given.
+    fn worktree_tasks(
+        &self,
+        worktree: Option<WorktreeId>,
+    ) -> impl Iterator<Item = (&TaskSourceKind, TaskTemplate)> {
+        let is_relevant = move |kind: &TaskSourceKind| {
+            let source_worktree = kind.worktree();
+            worktree.is_none() || source_worktree.is_none() || source_worktree == worktree
+        };
+        self.sources
+            .iter()
+            .filter(move |source| is_relevant(&source.kind))
+            .flat_map(|source| {
+                source
+                    .source
+                    .tasks_to_schedule()
+                    .0
+                    .into_iter()
+                    .map(|task| (&source.kind, task))
+            })
+            .chain(
+                self.provided_tasks
+                    .iter()
+                    .filter(move |(kind, _)| is_relevant(kind))
+                    .flat_map(|(kind, tasks)| tasks.0.iter().map(move |task| (kind, task.clone()))),
+            )
     }
 
     /// Pulls its task sources relevant to the worktree and the language given,
@@ -161,20 +226,7 @@ impl Inventory {
             .flat_map(|tasks| tasks.0.into_iter())
             .flat_map(|task| Some((task_source_kind.as_ref()?, task)));
 
-        self.sources
-            .iter()
-            .filter(|source| {
-                let source_worktree = source.kind.worktree();
-                worktree.is_none() || source_worktree.is_none() || source_worktree == worktree
-            })
-            .flat_map(|source| {
-                source
-                    .source
-                    .tasks_to_schedule()
-                    .0
-                    .into_iter()
-                    .map(|task| (&source.kind, task))
-            })
+        self.worktree_tasks(worktree)
             .chain(language_tasks)
             .map(|(task_source_kind, task)| (task_source_kind.clone(), task))
             .collect()
@@ -225,20 +277,7 @@ impl Inventory {
             );
         let not_used_score = post_inc(&mut lru_score);
         let currently_resolved_tasks = self
-            .sources
-            .iter()
-            .filter(|source| {
-                let source_worktree = source.kind.worktree();
-                worktree.is_none() || source_worktree.is_none() || source_worktree == worktree
-            })
-            .flat_map(|source| {
-                source
-                    .source
-                    .tasks_to_schedule()
-                    .0
-                    .into_iter()
-                    .map(|task| (&source.kind, task))
-            })
+            .worktree_tasks(worktree)
             .chain(language_tasks)
             .filter_map(|(kind, task)| {
                 let id_base = kind.to_id_base();
@@ -316,6 +355,25 @@ impl Inventory {
         )
     }
 
+    /// Arranges the task and the tasks it depends on into a [`TaskPlan`].
+    /// Dependencies are looked up by label among the task templates of the task's worktree,
+    /// preferring the ones from the task's own source, and resolved with the same [`TaskContext`] as the task.
+    pub fn task_plan(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        task: &ResolvedTask,
+    ) -> anyhow::Result<TaskPlan> {
+        let templates = self.list_tasks(None, task_source_kind.worktree());
+        let task_context = task.task_context();
+        TaskPlan::new(task, |label| {
+            let (kind, template) = templates
+                .iter()
+                .filter(|(_, template)| template.label == label)
+                .min_by_key(|(kind, _)| kind != task_source_kind)?;
+            template.resolve_task(&kind.to_id_base(), task_context)
+        })
+    }
+
     /// Returns the last scheduled task, if any of the sources contains one with the matching id.
     pub fn last_scheduled_task(&self) -> Option<(TaskSourceKind, ResolvedTask)> {
         self.last_scheduled_tasks.back().cloned()
@@ -369,7 +427,8 @@ fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
         TaskSourceKind::Language { .. } => 1,
         TaskSourceKind::UserInput => 2,
         TaskSourceKind::Worktree { .. } => 3,
-        TaskSourceKind::AbsPath { .. } => 4,
+        TaskSourceKind::Provider { .. } => 4,
+        TaskSourceKind::AbsPath { .. } => 5,
     }
 }
 
@@ -406,7 +465,7 @@ mod test_inventory {
         updates: UnboundedSender<()>,
         cx: &mut AppContext,
     ) -> StaticSource {
-        let tasks = TaskTemplates(
+        static_template_source(
             task_names
                 .into_iter()
                 .map(|name| TaskTemplate {
@@ -415,7 +474,17 @@ mod test_inventory {
                     ..TaskTemplate::default()
                 })
                 .collect(),
-        );
+            updates,
+            cx,
+        )
+    }
+
+    pub(super) fn static_template_source(
+        tasks: Vec<TaskTemplate>,
+        updates: UnboundedSender<()>,
+        cx: &mut AppContext,
+    ) -> StaticSource {
+        let tasks = TaskTemplates(tasks);
         let (tx, rx) = futures::channel::mpsc::unbounded();
         let file = TrackedFile::new(rx, updates, cx);
         tx.unbounded_send(serde_json::to_string(&tasks).unwrap())
@@ -830,4 +899,159 @@ mod tests {
                 .collect::<Vec<_>>(),
         );
     }
+
+    #[gpui::test]
+    fn test_task_plan_prefers_own_source(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let worktree = WorktreeId::from_usize(1);
+        let worktree_kind = TaskSourceKind::Worktree {
+            id: worktree,
+            abs_path: PathBuf::from("worktree_path"),
+            id_base: "test_source",
+        };
+        let task = |label: &str, command: &str, depends_on: &[&str]| TaskTemplate {
+            label: label.to_string(),
+            command: command.to_string(),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.add_source(
+                TaskSourceKind::AbsPath {
+                    id_base: "test source",
+                    abs_path: PathBuf::from("global_path"),
+                },
+                |tx, cx| {
+                    static_template_source(
+                        vec![
+                            task("build", "global build", &[]),
+                            task("lint", "global lint", &[]),
+                        ],
+                        tx,
+                        cx,
+                    )
+                },
+                cx,
+            );
+            inventory.add_source(
+                worktree_kind.clone(),
+                |tx, cx| {
+                    static_template_source(
+                        vec![
+                            task("build", "worktree build", &[]),
+                            task("test", "worktree test", &["build", "lint"]),
+                            task("broken", "worktree broken", &["missing"]),
+                        ],
+                        tx,
+                        cx,
+                    )
+                },
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        let plan_commands = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, _| {
+                let (kind, template) = inventory
+                    .list_tasks(None, Some(worktree))
+                    .into_iter()
+                    .find(|(kind, template)| kind == &worktree_kind && template.label == label)
+                    .unwrap();
+                let task = template
+                    .resolve_task(&kind.to_id_base(), &TaskContext::default())
+                    .unwrap();
+                inventory.task_plan(&kind, &task).map(|plan| {
+                    plan.steps
+                        .into_iter()
+                        .flatten()
+                        .map(|task| task.command)
+                        .collect::<Vec<_>>()
+                })
+            })
+        };
+
+        assert_eq!(
+            plan_commands("test", cx).unwrap(),
+            vec!["worktree build", "global lint", "worktree test"]
+        );
+        assert!(plan_commands("broken", cx).is_err());
+    }
+
+    #[gpui::test]
+    fn test_provided_tasks(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let worktree_1 = WorktreeId::from_usize(1);
+        let worktree_2 = WorktreeId::from_usize(2);
+        let provided_tasks = |labels: &[&str]| {
+            TaskTemplates(
+                labels
+                    .iter()
+                    .map(|label| TaskTemplate {
+                        label: label.to_string(),
+                        command: "test command".to_owned(),
+                        ..TaskTemplate::default()
+                    })
+                    .collect(),
+            )
+        };
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.add_source(
+                TaskSourceKind::AbsPath {
+                    id_base: "test source",
+                    abs_path: PathBuf::from("global_path"),
+                },
+                |tx, cx| static_test_source(vec!["global".to_string()], tx, cx),
+                cx,
+            );
+            inventory.set_provided_tasks(
+                TaskSourceKind::Provider {
+                    name: "gradle".into(),
+                    worktree: worktree_1,
+                },
+                provided_tasks(&["gradle build", "gradle test"]),
+                cx,
+            );
+            inventory.set_provided_tasks(
+                TaskSourceKind::Provider {
+                    name: "bazel".into(),
+                    worktree: worktree_2,
+                },
+                provided_tasks(&["bazel build"]),
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            vec!["global", "gradle build", "gradle test"]
+        );
+        assert_eq!(
+            resolved_task_names(&inventory, Some(worktree_2), cx),
+            vec!["bazel build", "global"]
+        );
+        assert_eq!(
+            task_template_names(&inventory, None, cx),
+            vec!["bazel build", "global", "gradle build", "gradle test"]
+        );
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.set_provided_tasks(
+                TaskSourceKind::Provider {
+                    name: "gradle".into(),
+                    worktree: worktree_1,
+                },
+                provided_tasks(&["gradle assemble"]),
+                cx,
+            );
+            inventory.remove_provided_tasks(worktree_2, cx);
+        });
+        assert_eq!(
+            task_template_names(&inventory, None, cx),
+            vec!["global", "gradle assemble"]
+        );
+    }
 }
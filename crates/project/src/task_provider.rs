@@ -0,0 +1,101 @@
+//! Tasks and task variables, contributed for the worktrees by sources other than the task files in them, e.g. extensions.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{AppContext, Global, Task};
+use language::LspAdapterDelegate;
+use parking_lot::RwLock;
+use postage::watch;
+use task::{TaskContext, TaskTemplates, TaskVariables};
+
+/// A source of tasks and custom task variables for a worktree.
+pub trait TaskProvider: 'static + Send + Sync {
+    /// Lists the tasks available in the worktree, e.g. by discovering the targets in its build files.
+    fn tasks_for_worktree(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskTemplates>>;
+
+    /// Computes the values of the custom variables for the tasks, spawned in the worktree with the context given.
+    fn task_variables(
+        &self,
+        task_context: TaskContext,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskVariables>>;
+}
+
+#[derive(Default)]
+struct GlobalTaskProviderRegistry(Arc<TaskProviderRegistry>);
+
+impl Global for GlobalTaskProviderRegistry {}
+
+struct TaskProviderRegistryState {
+    providers: BTreeMap<Arc<str>, Arc<dyn TaskProvider>>,
+    providers_changed_tx: watch::Sender<()>,
+}
+
+/// The registry of the [`TaskProvider`]s, queried by every local project for its worktrees.
+pub struct TaskProviderRegistry {
+    state: RwLock<TaskProviderRegistryState>,
+    providers_changed_rx: watch::Receiver<()>,
+}
+
+impl Default for TaskProviderRegistry {
+    fn default() -> Self {
+        let (providers_changed_tx, providers_changed_rx) = watch::channel();
+        Self {
+            state: RwLock::new(TaskProviderRegistryState {
+                providers: BTreeMap::default(),
+                providers_changed_tx,
+            }),
+            providers_changed_rx,
+        }
+    }
+}
+
+impl TaskProviderRegistry {
+    /// Returns the global [`TaskProviderRegistry`].
+    ///
+    /// Inserts a default [`TaskProviderRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalTaskProviderRegistry>().0.clone()
+    }
+
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registers the [`TaskProvider`] under the name given, replacing any existing provider with the same name.
+    pub fn register_provider(&self, name: Arc<str>, provider: impl TaskProvider) {
+        let mut state = self.state.write();
+        state.providers.insert(name, Arc::new(provider));
+        *state.providers_changed_tx.borrow_mut() = ();
+    }
+
+    /// Unregisters the [`TaskProvider`] with the given name.
+    pub fn unregister_provider(&self, name: &str) {
+        let mut state = self.state.write();
+        if state.providers.remove(name).is_some() {
+            *state.providers_changed_tx.borrow_mut() = ();
+        }
+    }
+
+    /// Returns the registered [`TaskProvider`]s with their names, in sorted order.
+    pub fn providers(&self) -> Vec<(Arc<str>, Arc<dyn TaskProvider>)> {
+        self.state
+            .read()
+            .providers
+            .iter()
+            .map(|(name, provider)| (name.clone(), provider.clone()))
+            .collect()
+    }
+
+    /// Returns a receiver, notified every time a provider is registered or unregistered.
+    pub fn subscribe(&self) -> watch::Receiver<()> {
+        self.providers_changed_rx.clone()
+    }
+}
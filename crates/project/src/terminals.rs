@@ -1,23 +1,43 @@
-use crate::Project;
-use collections::HashMap;
+use crate::{Event, Project};
+use collections::{HashMap, HashSet};
 use gpui::{
-    AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, SharedString, WeakModel,
+    AnyWindowHandle, AppContext, Context, Entity, EntityId, Model, ModelContext, SharedString,
+    WeakModel,
 };
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
-use std::path::{Path, PathBuf};
-use task::SpawnInTerminal;
+use std::{
+    mem,
+    path::{Path, PathBuf},
+};
+use task::{
+    parse_junit_report, Problem, ProblemSeverity, SpawnInTerminal, TaskId, TestResultsFormat,
+};
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
-use util::ResultExt;
+use util::{post_inc, ResultExt};
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) task_diagnostics: HashMap<TaskId, TaskDiagnostics>,
+}
+
+/// Diagnostics, found by the problem matchers in the output of a task's last run.
+pub(crate) struct TaskDiagnostics {
+    /// Keeps the task's diagnostics apart from the language servers' and other tasks' ones.
+    server_id: LanguageServerId,
+    /// The terminal of the task's last run, whose release removes the diagnostics.
+    terminal_id: EntityId,
+    /// Directory to resolve relative problem paths against.
+    cwd: Option<PathBuf>,
+    diagnostics_by_path: HashMap<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -87,6 +107,26 @@ impl Project {
         });
 
         let is_terminal = spawn_task.is_none() && remote_connection_data.is_none();
+        let local_task = spawn_task
+            .as_ref()
+            .filter(|_| remote_connection_data.is_none())
+            .map(|spawn_task| {
+                let cwd = spawn_task
+                    .cwd
+                    .clone()
+                    .or_else(|| working_directory.clone())
+                    .or_else(|| {
+                        self.visible_worktrees(cx)
+                            .next()
+                            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                    });
+                (
+                    spawn_task.id.clone(),
+                    cwd,
+                    !spawn_task.problem_matcher.is_empty(),
+                    spawn_task.test_results.clone(),
+                )
+            });
         let settings = TerminalSettings::get(settings_location, cx);
         let python_settings = settings.detect_venv.clone();
         let (completion_tx, completion_rx) = bounded(1);
@@ -137,6 +177,8 @@ impl Project {
                     command_label: spawn_task.command_label,
                     status: TaskStatus::Running,
                     completion_rx,
+                    problem_matcher: spawn_task.problem_matcher,
+                    test_results: spawn_task.test_results,
                 }),
                 Shell::WithArguments {
                     program: spawn_task.command,
@@ -155,6 +197,7 @@ impl Project {
             Some(settings.blinking.clone()),
             settings.alternate_scroll,
             settings.max_scroll_history_lines,
+            settings.shell_integration,
             window,
             completion_tx,
         )
@@ -179,6 +222,39 @@ impl Project {
             })
             .detach();
 
+            if let Some((task_id, cwd, has_problem_matchers, test_results)) = local_task {
+                if has_problem_matchers {
+                    self.clear_task_diagnostics(&task_id, cwd.clone(), id, cx);
+                    let task_id = task_id.clone();
+                    cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
+                        project.remove_task_diagnostics(&task_id, id, cx);
+                    })
+                    .detach();
+                }
+                cx.subscribe(&terminal_handle, move |project, _, event, cx| match event {
+                    terminal::Event::ProblemsFound(problems) => {
+                        project.publish_task_problems(&task_id, problems, cx);
+                    }
+                    terminal::Event::TestResultsFound(results) => {
+                        cx.emit(Event::TestResultsFound {
+                            task_id: task_id.clone(),
+                            results: results.clone(),
+                        });
+                    }
+                    terminal::Event::TaskFinished(status) => {
+                        project.report_task_finished(
+                            task_id.clone(),
+                            test_results.as_ref(),
+                            cwd.as_deref(),
+                            *status,
+                            cx,
+                        );
+                    }
+                    _ => {}
+                })
+                .detach();
+            }
+
             // if the terminal is not a task, activate full Python virtual environment
             if is_terminal {
                 if let Some(python_settings) = &python_settings.as_option() {
@@ -200,6 +276,170 @@ impl Project {
         terminal
     }
 
+    /// Removes the diagnostics of the task's previous run, before it runs again.
+    pub(crate) fn clear_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        cwd: Option<PathBuf>,
+        terminal_id: EntityId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let (server_id, cleared_paths) = match self.terminals.task_diagnostics.get_mut(task_id) {
+            Some(task_diagnostics) => {
+                task_diagnostics.terminal_id = terminal_id;
+                task_diagnostics.cwd = cwd;
+                (
+                    task_diagnostics.server_id,
+                    mem::take(&mut task_diagnostics.diagnostics_by_path)
+                        .into_keys()
+                        .collect(),
+                )
+            }
+            None => {
+                let server_id = self.languages.next_language_server_id();
+                self.terminals.task_diagnostics.insert(
+                    task_id.clone(),
+                    TaskDiagnostics {
+                        server_id,
+                        terminal_id,
+                        cwd,
+                        diagnostics_by_path: HashMap::default(),
+                    },
+                );
+                (server_id, Vec::new())
+            }
+        };
+
+        for abs_path in cleared_paths {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+    }
+
+    /// Removes the diagnostics of the task, once the terminal of its last run is closed.
+    pub(crate) fn remove_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        terminal_id: EntityId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        // A rerun of the task may have taken the diagnostics over already.
+        let task_diagnostics = match self.terminals.task_diagnostics.remove(task_id) {
+            Some(task_diagnostics) if task_diagnostics.terminal_id == terminal_id => {
+                task_diagnostics
+            }
+            Some(task_diagnostics) => {
+                self.terminals
+                    .task_diagnostics
+                    .insert(task_id.clone(), task_diagnostics);
+                return;
+            }
+            None => return,
+        };
+        for abs_path in task_diagnostics.diagnostics_by_path.into_keys() {
+            self.update_diagnostic_entries(
+                task_diagnostics.server_id,
+                abs_path,
+                None,
+                Vec::new(),
+                cx,
+            )
+            .log_err();
+        }
+    }
+
+    pub(crate) fn publish_task_problems(
+        &mut self,
+        task_id: &TaskId,
+        problems: &[Problem],
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(task_diagnostics) = self.terminals.task_diagnostics.get_mut(task_id) else {
+            return;
+        };
+        let mut updated_paths = HashSet::default();
+        for problem in problems {
+            let abs_path = if problem.path.is_absolute() {
+                problem.path.clone()
+            } else if let Some(cwd) = &task_diagnostics.cwd {
+                cwd.join(&problem.path)
+            } else {
+                continue;
+            };
+            let group_id = post_inc(&mut self.next_diagnostic_group_id);
+            task_diagnostics
+                .diagnostics_by_path
+                .entry(abs_path.clone())
+                .or_default()
+                .push(problem_diagnostic(problem, group_id));
+            updated_paths.insert(abs_path);
+        }
+
+        let server_id = task_diagnostics.server_id;
+        let updates = updated_paths
+            .into_iter()
+            .filter_map(|abs_path| {
+                let diagnostics = task_diagnostics.diagnostics_by_path.get(&abs_path)?.clone();
+                Some((abs_path, diagnostics))
+            })
+            .collect::<Vec<_>>();
+        for (abs_path, diagnostics) in updates {
+            // Tasks may report problems in files outside of the project, e.g. in dependencies.
+            if let Err(e) =
+                self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)
+            {
+                log::debug!("Skipping task problems: {e:#}");
+            }
+        }
+    }
+
+    /// Reports the test results, written into a report file by the finished task, if any, followed by the task's end.
+    fn report_task_finished(
+        &mut self,
+        task_id: TaskId,
+        test_results: Option<&TestResultsFormat>,
+        cwd: Option<&Path>,
+        status: TaskStatus,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let success = status == TaskStatus::Completed { success: true };
+        let report_path = match test_results {
+            Some(TestResultsFormat::Junit { path }) => Some(match cwd {
+                Some(cwd) => cwd.join(path),
+                None => PathBuf::from(path),
+            }),
+            Some(TestResultsFormat::CargoJson | TestResultsFormat::GoJson) | None => None,
+        };
+        let fs = self.fs.clone();
+        cx.spawn(|project, mut cx| async move {
+            if let Some(report_path) = report_path {
+                match fs
+                    .load(&report_path)
+                    .await
+                    .and_then(|report| parse_junit_report(&report))
+                {
+                    Ok(results) => {
+                        project
+                            .update(&mut cx, |_, cx| {
+                                cx.emit(Event::TestResultsFound {
+                                    task_id: task_id.clone(),
+                                    results,
+                                })
+                            })
+                            .ok();
+                    }
+                    Err(e) => log::warn!("Failed to read test report {report_path:?}: {e:#}"),
+                }
+            }
+            project
+                .update(&mut cx, |_, cx| {
+                    cx.emit(Event::TaskFinished { task_id, success })
+                })
+                .ok();
+        })
+        .detach();
+    }
+
     pub fn find_activate_script_path(
         &mut self,
         settings: &VenvSettingsContent,
@@ -327,3 +567,41 @@ fn escape_path_for_shell(input: &str) -> String {
 }
 
 // TODO: Add a few tests for adding and removing terminal tabs
+
+pub(crate) fn problem_diagnostic(
+    problem: &Problem,
+    group_id: usize,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let start = PointUtf16::new(
+        problem.line.saturating_sub(1),
+        problem.column.unwrap_or(1).saturating_sub(1),
+    );
+    let end = match (problem.end_line, problem.end_column) {
+        (Some(end_line), end_column) => PointUtf16::new(
+            end_line.saturating_sub(1),
+            end_column.unwrap_or(1).saturating_sub(1),
+        ),
+        (None, Some(end_column)) => PointUtf16::new(start.row, end_column.saturating_sub(1)),
+        // Without a column, highlight the whole line.
+        (None, None) if problem.column.is_none() => PointUtf16::new(start.row, u32::MAX),
+        (None, None) => start,
+    };
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end),
+        diagnostic: Diagnostic {
+            source: Some(problem.source.clone()),
+            code: problem.code.clone(),
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message.clone(),
+            group_id,
+            is_primary: true,
+            is_disk_based: true,
+            is_unnecessary: false,
+        },
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{Event, Project};
+use collections::HashMap;
+use gpui::ModelContext;
+use language::{Diagnostic, DiagnosticEntry, OutlineItem, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
+use std::path::{Path, PathBuf};
+use task::TestOutcome;
+use util::post_inc;
+
+/// A status of the test's last run in the project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestStatus {
+    /// The test is running currently.
+    Running,
+    /// The test has finished running.
+    Finished {
+        outcome: TestOutcome,
+        /// Failure message or output of the test, if any was reported.
+        message: Option<String>,
+    },
+}
+
+/// Statuses of the tests, run in the project, by the file and the path of the test.
+#[derive(Default)]
+pub(crate) struct TestStatuses {
+    /// Keeps the test failures apart from the language servers' and tasks' diagnostics.
+    server_id: Option<LanguageServerId>,
+    /// Statuses with the rows the tests were declared at, when they were run.
+    statuses_by_path: HashMap<PathBuf, HashMap<String, (u32, TestStatus)>>,
+}
+
+/// The path of the test named `symbol` in its file: the names of the outline items enclosing the test,
+/// e.g. its module or class, followed by the test's name, separated by `::`.
+/// Tests with the same name in different items of a file have different paths.
+pub fn test_path<T>(symbol: &str, enclosing_items: &[OutlineItem<T>]) -> String {
+    let mut path = enclosing_items
+        .iter()
+        .filter_map(|item| match item.name_ranges.as_slice() {
+            [name_range] => item.text.get(name_range.clone()),
+            _ => None,
+        })
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        .collect::<Vec<_>>();
+    if path.last() != Some(&symbol) {
+        path.push(symbol);
+    }
+    path.join("::")
+}
+
+impl Project {
+    /// The status of the test at `test_path`, declared in the file, if the test was run.
+    pub fn test_status(&self, abs_path: &Path, test_path: &str) -> Option<&TestStatus> {
+        self.test_statuses
+            .statuses_by_path
+            .get(abs_path)?
+            .get(test_path)
+            .map(|(_, status)| status)
+    }
+
+    /// Updates the statuses of the tests, declared in the file at the given rows,
+    /// and shows the failed tests' messages as diagnostics on those rows.
+    pub fn set_test_statuses(
+        &mut self,
+        abs_path: PathBuf,
+        statuses: impl IntoIterator<Item = (String, u32, TestStatus)>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let file_statuses = self
+            .test_statuses
+            .statuses_by_path
+            .entry(abs_path.clone())
+            .or_default();
+        for (test_path, row, status) in statuses {
+            file_statuses.insert(test_path, (row, status));
+        }
+        let mut failures = file_statuses
+            .values()
+            .filter_map(|(row, status)| match status {
+                TestStatus::Finished {
+                    outcome: TestOutcome::Failed,
+                    message,
+                } => Some((*row, message.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        failures.sort_by_key(|(row, _)| *row);
+
+        let diagnostics = failures
+            .into_iter()
+            .map(|(row, message)| DiagnosticEntry {
+                range: Unclipped(PointUtf16::new(row, 0))
+                    ..Unclipped(PointUtf16::new(row, u32::MAX)),
+                diagnostic: Diagnostic {
+                    source: Some("test".to_string()),
+                    code: None,
+                    severity: DiagnosticSeverity::ERROR,
+                    message: message
+                        .map(|message| message.trim_end().to_string())
+                        .unwrap_or_else(|| "Test failed".to_string()),
+                    group_id: post_inc(&mut self.next_diagnostic_group_id),
+                    is_primary: true,
+                    is_disk_based: true,
+                    is_unnecessary: false,
+                },
+            })
+            .collect();
+        let server_id = *self
+            .test_statuses
+            .server_id
+            .get_or_insert_with(|| self.languages.next_language_server_id());
+        if let Err(e) = self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx) {
+            log::debug!("Skipping test failure diagnostics: {e:#}");
+        }
+        cx.emit(Event::TestStatusesUpdated);
+    }
+}
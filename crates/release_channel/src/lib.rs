@@ -74,6 +74,11 @@ impl AppVersion {
     pub fn global(cx: &AppContext) -> SemanticVersion {
         cx.global::<GlobalAppVersion>().0
     }
+
+    /// Returns the global version number, if one is set.
+    pub fn try_global(cx: &AppContext) -> Option<SemanticVersion> {
+        cx.try_global::<GlobalAppVersion>().map(|version| version.0)
+    }
 }
 
 /// A Zed release channel.
@@ -36,9 +36,27 @@ impl JsonSchema for KeymapAction {
     }
 }
 
+impl From<Value> for KeymapAction {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+impl KeymapBlock {
+    pub fn new(context: Option<String>, bindings: BTreeMap<String, KeymapAction>) -> Self {
+        Self { context, bindings }
+    }
+}
+
 #[derive(Deserialize)]
 struct ActionWithData(Box<str>, Value);
 
+impl FromIterator<KeymapBlock> for KeymapFile {
+    fn from_iter<T: IntoIterator<Item = KeymapBlock>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 impl KeymapFile {
     pub fn load_asset(asset_path: &str, cx: &mut AppContext) -> Result<()> {
         let content = asset_str::<SettingsAssets>(asset_path);
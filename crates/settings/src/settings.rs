@@ -7,7 +7,7 @@ use rust_embed::RustEmbed;
 use std::{borrow::Cow, str};
 use util::asset_str;
 
-pub use keymap_file::KeymapFile;
+pub use keymap_file::{KeymapAction, KeymapBlock, KeymapFile};
 pub use settings_file::*;
 pub use settings_store::{
     Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
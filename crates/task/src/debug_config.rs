@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to debug the task: its command is spawned as a debug adapter, that launches or attaches to the debuggee.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugConfig {
+    /// Identifier of the debug adapter kind, sent to the adapter on initialization, e.g. `lldb` or `debugpy`.
+    pub adapter: String,
+    /// Whether to start a new debuggee or to attach to a running one.
+    #[serde(default)]
+    pub request: DebugRequestKind,
+    /// Adapter-specific arguments of the `launch` or `attach` request, e.g. `{ "program": "$ZED_WORKTREE_ROOT/target/debug/app" }`.
+    /// Task variables are substituted in all strings of the configuration.
+    #[serde(default)]
+    pub configuration: serde_json::Value,
+}
+
+/// How a debug adapter gets the debuggee to debug.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugRequestKind {
+    /// Start a new debuggee process.
+    #[default]
+    Launch,
+    /// Attach to an already running process.
+    Attach,
+}
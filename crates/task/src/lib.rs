@@ -1,8 +1,13 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+mod debug_config;
+mod problem_matcher;
 pub mod static_source;
+mod task_input;
+mod task_plan;
 mod task_template;
+mod test_results;
 mod vscode_format;
 
 use collections::{HashMap, HashSet};
@@ -11,8 +16,17 @@ use serde::Serialize;
 use std::borrow::Cow;
 use std::path::PathBuf;
 
-pub use task_template::{RevealStrategy, TaskTemplate, TaskTemplates};
-pub use vscode_format::VsCodeTaskFile;
+pub use debug_config::{DebugConfig, DebugRequestKind};
+pub use problem_matcher::{
+    CustomProblemMatcher, Problem, ProblemMatcher, ProblemPattern, ProblemScanner, ProblemSeverity,
+};
+pub use task_input::{TaskInput, TaskInputKind};
+pub use task_plan::TaskPlan;
+pub use task_template::{DependsOrder, RevealStrategy, TaskTemplate, TaskTemplates};
+pub use test_results::{
+    parse_junit_report, TestOutcome, TestResult, TestResultsFormat, TestResultsScanner,
+};
+pub use vscode_format::{VsCodeLaunchFile, VsCodeTaskFile};
 
 /// Task identifier, unique within the application.
 /// Based on it, task reruns and terminal tabs are managed.
@@ -45,6 +59,12 @@ pub struct SpawnInTerminal {
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// Matchers to find problems in the task output with, to show them as diagnostics.
+    pub problem_matcher: Vec<ProblemMatcher>,
+    /// Format of the test results the task reports, if it runs tests.
+    pub test_results: Option<TestResultsFormat>,
+    /// How to debug the task's program, if the task's command is a debug adapter rather than a terminal command.
+    pub debug: Option<DebugConfig>,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particualar [`TaskContext`] and now is ready to spawn the actual task.
@@ -62,6 +82,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task was resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: Option<SpawnInTerminal>,
@@ -78,6 +100,11 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// The context the task template got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved
@@ -107,13 +134,15 @@ pub enum VariableName {
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `ZED_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
+    /// Value of the [`TaskInput`] with the given id, provided by the user before the task is spawned.
+    Input(Cow<'static, str>),
 }
 
 impl VariableName {
     /// Generates a `$VARIABLE`-like string value to be used in templates.
-    /// Custom variables are wrapped in `${}` to avoid substitution issues with whitespaces.
+    /// Custom and input variables are wrapped in `${}` to avoid substitution issues with whitespaces.
     pub fn template_value(&self) -> String {
-        if matches!(self, Self::Custom(_)) {
+        if matches!(self, Self::Custom(_) | Self::Input(_)) {
             format!("${{{self}}}")
         } else {
             format!("${self}")
@@ -135,6 +164,7 @@ impl std::fmt::Display for VariableName {
             Self::SelectedText => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SELECTED_TEXT"),
             Self::RunnableSymbol => write!(f, "{ZED_VARIABLE_NAME_PREFIX}RUNNABLE_SYMBOL"),
             Self::Custom(s) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}CUSTOM_{s}"),
+            Self::Input(id) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}INPUT_{id}"),
         }
     }
 }
@@ -149,10 +179,19 @@ impl TaskVariables {
         self.0.insert(variable, value)
     }
 
+    /// Returns the value of the variable, if it is set.
+    pub fn get(&self, variable: &VariableName) -> Option<&str> {
+        self.0.get(variable).map(String::as_str)
+    }
+
     /// Extends the container with another one, overwriting the existing variables on collision.
     pub fn extend(&mut self, other: Self) {
         self.0.extend(other.0);
     }
+    /// Iterates over the variables set, in no specific order.
+    pub fn iter(&self) -> impl Iterator<Item = (&VariableName, &str)> {
+        self.0.iter().map(|(name, value)| (name, value.as_str()))
+    }
 }
 
 impl FromIterator<(VariableName, String)> for TaskVariables {
@@ -0,0 +1,544 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find problems (errors, warnings, etc.) in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// Name of a built-in matcher: `$rustc`, `$tsc`, `$gcc` or `$eslint-stylish`.
+    Preset(String),
+    /// A matcher with custom patterns.
+    Custom(CustomProblemMatcher),
+}
+
+/// A problem matcher, defined by the regular expressions to match task output lines with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Name of the diagnostics' source, defaults to the task label.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Severity of the problems, whose pattern does not capture one.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns to match consecutive output lines with, a problem is reported when the last one matches.
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression to match a single output line with, and the capture groups to get problem's data from.
+/// Capture groups are 1-based, as in the regular expression.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// Regular expression to match the output line.
+    pub regexp: String,
+    /// Group with the path of the file with the problem, relative paths are resolved against the task's working directory.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Group with the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Group with the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Group with the 1-based line, the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Group with the 1-based column, the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Group with the severity of the problem: `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Group with the problem message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// For the last pattern only: whether it can match several lines in a row, reporting a problem for each of them.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// How severe a problem is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error.
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message.
+    Info,
+    /// A hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" | "e" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem, found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Name of the matcher that found the problem.
+    pub source: String,
+    /// Path to the file with the problem, as printed by the task.
+    pub path: PathBuf,
+    /// 1-based line of the problem start.
+    pub line: u32,
+    /// 1-based column of the problem start, if reported.
+    pub column: Option<u32>,
+    /// 1-based line of the problem end, if reported.
+    pub end_line: Option<u32>,
+    /// 1-based column of the problem end, if reported.
+    pub end_column: Option<u32>,
+    /// How severe the problem is.
+    pub severity: ProblemSeverity,
+    /// Code of the problem, if reported.
+    pub code: Option<String>,
+    /// Problem description.
+    pub message: String,
+}
+
+impl ProblemMatcher {
+    fn definition(&self) -> anyhow::Result<CustomProblemMatcher> {
+        match self {
+            Self::Preset(name) => {
+                preset(name).with_context(|| format!("Unknown problem matcher `{name}`"))
+            }
+            Self::Custom(matcher) => Ok(matcher.clone()),
+        }
+    }
+}
+
+fn pattern(regexp: &str) -> ProblemPattern {
+    ProblemPattern {
+        regexp: regexp.to_string(),
+        ..ProblemPattern::default()
+    }
+}
+
+fn preset(name: &str) -> Option<CustomProblemMatcher> {
+    let (owner, pattern) = match name {
+        "$rustc" => (
+            "rustc",
+            vec![
+                ProblemPattern {
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..pattern(r"^(warning|error)(?:\[(\w+)\])?: (.*)$")
+                },
+                ProblemPattern {
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..pattern(r"^\s*--> (.*):(\d+):(\d+)$")
+                },
+            ],
+        ),
+        "$tsc" => (
+            "tsc",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..pattern(
+                    r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$",
+                )
+            }],
+        ),
+        "$gcc" => (
+            "gcc",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..pattern(r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$")
+            }],
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            vec![
+                ProblemPattern {
+                    file: Some(1),
+                    ..pattern(r"^((?:[a-zA-Z]:)*[./\\]+.*?)$")
+                },
+                ProblemPattern {
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..pattern(r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$")
+                },
+            ],
+        ),
+        _ => return None,
+    };
+    Some(CustomProblemMatcher {
+        owner: Some(owner.to_string()),
+        severity: ProblemSeverity::Error,
+        pattern,
+    })
+}
+
+/// Finds problems in task output, fed to it line by line.
+#[derive(Debug)]
+pub struct ProblemScanner {
+    matchers: Vec<CompiledMatcher>,
+}
+
+#[derive(Debug)]
+struct CompiledMatcher {
+    source: String,
+    severity: ProblemSeverity,
+    patterns: Vec<(Regex, ProblemPattern)>,
+    /// Index of the pattern to match the next line with, and the data captured so far.
+    pending: Option<(usize, PartialProblem)>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct PartialProblem {
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemScanner {
+    /// Prepares the matchers for scanning, `default_source` names the problems of matchers without an owner.
+    ///
+    /// Fails on unknown presets, matchers without patterns and invalid regular expressions.
+    pub fn new(matchers: &[ProblemMatcher], default_source: &str) -> anyhow::Result<Self> {
+        let matchers = matchers
+            .iter()
+            .map(|matcher| {
+                let definition = matcher.definition()?;
+                if definition.pattern.is_empty() {
+                    return Err(anyhow!("Problem matcher has no patterns"));
+                }
+                let patterns = definition
+                    .pattern
+                    .into_iter()
+                    .map(|pattern| {
+                        let regex = Regex::new(&pattern.regexp).with_context(|| {
+                            format!("Invalid problem pattern `{}`", pattern.regexp)
+                        })?;
+                        anyhow::Ok((regex, pattern))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                Ok(CompiledMatcher {
+                    source: definition
+                        .owner
+                        .unwrap_or_else(|| default_source.to_string()),
+                    severity: definition.severity,
+                    patterns,
+                    pending: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { matchers })
+    }
+
+    /// Matches the next output line, returning the problems it completes.
+    pub fn scan_line(&mut self, line: &str) -> Vec<Problem> {
+        self.matchers
+            .iter_mut()
+            .filter_map(|matcher| matcher.scan_line(line))
+            .collect()
+    }
+}
+
+impl CompiledMatcher {
+    fn scan_line(&mut self, line: &str) -> Option<Problem> {
+        if let Some((pattern_ix, partial)) = self.pending.take() {
+            let (regex, pattern) = &self.patterns[pattern_ix];
+            if let Some(captures) = regex.captures(line) {
+                let mut partial = partial;
+                let base = partial.clone();
+                partial.capture(pattern, &captures);
+                return self.advance(pattern_ix, base, partial);
+            }
+        }
+
+        let (regex, pattern) = &self.patterns[0];
+        let captures = regex.captures(line)?;
+        let mut partial = PartialProblem::default();
+        partial.capture(pattern, &captures);
+        self.advance(0, PartialProblem::default(), partial)
+    }
+
+    /// Moves past the matched pattern, `base` being the data captured before it.
+    fn advance(
+        &mut self,
+        pattern_ix: usize,
+        base: PartialProblem,
+        partial: PartialProblem,
+    ) -> Option<Problem> {
+        if pattern_ix + 1 < self.patterns.len() {
+            self.pending = Some((pattern_ix + 1, partial));
+            return None;
+        }
+        if self.patterns[pattern_ix].1.repeat {
+            self.pending = Some((pattern_ix, base));
+        }
+        partial.into_problem(&self.source, self.severity)
+    }
+}
+
+impl PartialProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            captures
+                .get(ix?)
+                .map(|group| group.as_str().trim())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix)?.parse::<u32>().ok();
+
+        if let Some(path) = group(pattern.file) {
+            self.path = Some(path.to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+
+    fn into_problem(self, source: &str, default_severity: ProblemSeverity) -> Option<Problem> {
+        Some(Problem {
+            source: source.to_string(),
+            path: PathBuf::from(self.path?),
+            line: self.line?,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity.unwrap_or(default_severity),
+            code: self.code,
+            message: self.message?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(matcher: &str, output: &str) -> Vec<Problem> {
+        let mut scanner =
+            ProblemScanner::new(&[ProblemMatcher::Preset(matcher.to_string())], "task").unwrap();
+        output
+            .lines()
+            .flat_map(|line| scanner.scan_line(line))
+            .collect()
+    }
+
+    fn problem(
+        source: &str,
+        path: &str,
+        (line, column): (u32, u32),
+        severity: ProblemSeverity,
+        code: Option<&str>,
+        message: &str,
+    ) -> Problem {
+        Problem {
+            source: source.to_string(),
+            path: PathBuf::from(path),
+            line,
+            column: Some(column),
+            end_line: None,
+            end_column: None,
+            severity,
+            code: code.map(|code| code.to_string()),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let output = r#"   Compiling app v0.1.0 (/work/app)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+error: aborting due to 1 previous error; 1 warning emitted
+"#;
+        assert_eq!(
+            scan("$rustc", output),
+            vec![
+                problem(
+                    "rustc",
+                    "src/main.rs",
+                    (2, 9),
+                    ProblemSeverity::Warning,
+                    None,
+                    "unused variable: `x`"
+                ),
+                problem(
+                    "rustc",
+                    "src/lib.rs",
+                    (10, 18),
+                    ProblemSeverity::Error,
+                    Some("E0308"),
+                    "mismatched types"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_matchers() {
+        assert_eq!(
+            scan(
+                "$tsc",
+                "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nFound 1 error."
+            ),
+            vec![problem(
+                "tsc",
+                "src/index.ts",
+                (3, 7),
+                ProblemSeverity::Error,
+                Some("TS2322"),
+                "Type 'string' is not assignable to type 'number'."
+            )]
+        );
+        assert_eq!(
+            scan(
+                "$gcc",
+                "main.c: In function 'main':\nmain.c:4:5: warning: implicit declaration of function 'foo'"
+            ),
+            vec![problem(
+                "gcc",
+                "main.c",
+                (4, 5),
+                ProblemSeverity::Warning,
+                None,
+                "implicit declaration of function 'foo'"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_looping_matcher() {
+        let output = r#"
+/work/app/src/a.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  2:1   warning  Unexpected console statement     no-console
+
+/work/app/src/b.js
+  7:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+"#;
+        assert_eq!(
+            scan("$eslint-stylish", output),
+            vec![
+                problem(
+                    "eslint",
+                    "/work/app/src/a.js",
+                    (1, 10),
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars"),
+                    "'foo' is defined but never used"
+                ),
+                problem(
+                    "eslint",
+                    "/work/app/src/a.js",
+                    (2, 1),
+                    ProblemSeverity::Warning,
+                    Some("no-console"),
+                    "Unexpected console statement"
+                ),
+                problem(
+                    "eslint",
+                    "/work/app/src/b.js",
+                    (7, 3),
+                    ProblemSeverity::Error,
+                    Some("semi"),
+                    "Missing semicolon"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "owner": "lint",
+                "severity": "warning",
+                "pattern": [{ "regexp": "^(.+):(\\d+): (.+)$", "file": 1, "line": 2, "message": 3 }]
+            }"#,
+        )
+        .unwrap();
+        let mut scanner = ProblemScanner::new(&[matcher], "task").unwrap();
+        assert_eq!(
+            scanner.scan_line("docs/readme.md:12: trailing whitespace"),
+            vec![Problem {
+                source: "lint".to_string(),
+                path: PathBuf::from("docs/readme.md"),
+                line: 12,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "trailing whitespace".to_string(),
+            }]
+        );
+        assert!(scanner.scan_line("all good").is_empty());
+
+        assert_eq!(
+            ProblemScanner::new(&[ProblemMatcher::Preset("$unknown".to_string())], "task")
+                .unwrap_err()
+                .to_string(),
+            "Unknown problem matcher `$unknown`"
+        );
+    }
+}
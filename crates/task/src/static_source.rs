@@ -65,10 +65,11 @@ impl<T: PartialEq + 'static + Sync> TrackedFile<T> {
         Self { parsed_contents }
     }
 
-    /// Initializes new [`TrackedFile`] with a type that's convertible from another deserializable type.
-    pub fn new_convertible<U: for<'a> Deserialize<'a> + TryInto<T, Error = anyhow::Error>>(
+    /// Initializes new [`TrackedFile`] with a type that's converted with `convert` from another deserializable type.
+    pub fn new_convertible<U: for<'a> Deserialize<'a>>(
         mut tracker: UnboundedReceiver<String>,
         notification_outlet: UnboundedSender<()>,
+        convert: impl Fn(U) -> anyhow::Result<T> + Send + 'static,
         cx: &mut AppContext,
     ) -> Self
     where
@@ -91,7 +92,7 @@ impl<T: PartialEq + 'static + Sync> TrackedFile<T> {
                             else {
                                 continue;
                             };
-                            let Some(new_contents) = new_contents.try_into().log_err() else {
+                            let Some(new_contents) = convert(new_contents).log_err() else {
                                 continue;
                             };
                             let mut contents = parsed_contents.write();
@@ -108,9 +109,7 @@ impl<T: PartialEq + 'static + Sync> TrackedFile<T> {
                 }
             })
             .detach_and_log_err(cx);
-        Self {
-            parsed_contents: Default::default(),
-        }
+        Self { parsed_contents }
     }
 }
 
@@ -124,3 +123,61 @@ impl StaticSource {
         self.tasks.parsed_contents.read().clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use gpui::TestAppContext;
+
+    use crate::VsCodeTaskFile;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_convertible_file_updates(cx: &mut TestAppContext) {
+        let (contents_tx, contents_rx) = mpsc::unbounded();
+        let (updates_tx, mut updates_rx) = mpsc::unbounded();
+        let source = cx.update(|cx| {
+            StaticSource::new(TrackedFile::new_convertible(
+                contents_rx,
+                updates_tx,
+                |tasks: VsCodeTaskFile| Ok(tasks.into_zed_templates().0),
+                cx,
+            ))
+        });
+        let labels = |source: &StaticSource| {
+            source
+                .tasks_to_schedule()
+                .0
+                .into_iter()
+                .map(|task| task.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&source), Vec::<String>::new());
+
+        contents_tx
+            .unbounded_send(
+                r#"{ "tasks": [{ "label": "build", "type": "shell", "command": "make" }] }"#
+                    .to_string(),
+            )
+            .unwrap();
+        updates_rx.next().await.unwrap();
+        assert_eq!(
+            labels(&source),
+            ["build"],
+            "The converted tasks should be available from the source"
+        );
+
+        contents_tx
+            .unbounded_send(
+                r#"{ "tasks": [
+                    { "label": "build", "type": "shell", "command": "make" },
+                    { "label": "test", "type": "shell", "command": "make test" }
+                ] }"#
+                    .to_string(),
+            )
+            .unwrap();
+        updates_rx.next().await.unwrap();
+        assert_eq!(labels(&source), ["build", "test"]);
+    }
+}
@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::VariableName;
+
+/// A value to get from the user before the task is spawned.
+/// Its value is available in the task as the `$ZED_INPUT_<id>` variable.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Identifier of the input, used in its variable name.
+    pub id: String,
+    /// Text to show when asking for the value.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// How to get the value.
+    #[serde(flatten)]
+    pub kind: TaskInputKind,
+}
+
+/// A way to get the value of a [`TaskInput`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInputKind {
+    /// Free text, typed by the user.
+    Prompt {
+        /// The text to start with.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// One of the predefined values, picked by the user.
+    Pick {
+        /// The values to pick from.
+        options: Vec<String>,
+        /// The value to select initially, defaults to the first option.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Absolute path to a file in the project, picked by the user.
+    PickFile,
+    /// Trimmed output of a command, run in the task's working directory without asking the user.
+    Command {
+        /// Executable command to spawn.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl TaskInput {
+    /// The task variable to store the input value into.
+    pub fn variable_name(&self) -> VariableName {
+        VariableName::Input(Cow::Owned(self.id.clone()))
+    }
+
+    /// Whether the user is asked for the value.
+    pub fn is_prompted(&self) -> bool {
+        !matches!(self.kind, TaskInputKind::Command { .. })
+    }
+
+    /// The value to suggest to the user, also used when the task is resolved without asking for the input.
+    pub fn default_value(&self) -> Option<&str> {
+        match &self.kind {
+            TaskInputKind::Prompt { default } => default.as_deref(),
+            TaskInputKind::Pick { options, default } => {
+                default.as_deref().or(options.first().map(String::as_str))
+            }
+            TaskInputKind::PickFile | TaskInputKind::Command { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::{bail, Context};
+use collections::HashSet;
+
+use crate::{DependsOrder, ResolvedTask, SpawnInTerminal};
+
+/// A task together with all the tasks it depends on, arranged into steps that run one after another.
+/// Tasks within a step run concurrently; a step starts only after every task of the previous one has finished successfully.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskPlan {
+    /// Tasks to spawn, step by step.
+    pub steps: Vec<Vec<SpawnInTerminal>>,
+}
+
+impl TaskPlan {
+    /// Arranges the task and its (transitive) dependencies into steps, using `find_task` to look up
+    /// the tasks referenced by label in `depends_on`.
+    /// A task that several others depend on runs only once, as early as it is needed.
+    ///
+    /// Fails if a dependency cannot be found, is a debugging task, or the dependencies form a cycle:
+    /// a debugging session has no exit status to wait for, so only the task itself can debug.
+    pub fn new(
+        task: &ResolvedTask,
+        find_task: impl Fn(&str) -> Option<ResolvedTask>,
+    ) -> anyhow::Result<Self> {
+        let mut visiting = Vec::new();
+        let steps = task_steps(task, &find_task, &mut visiting)?;
+
+        let mut scheduled = HashSet::default();
+        let steps = steps
+            .into_iter()
+            .map(|step| {
+                step.into_iter()
+                    .filter(|task| scheduled.insert(task.id.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|step| !step.is_empty())
+            .collect();
+        Ok(Self { steps })
+    }
+
+    /// Whether the plan runs more than the task itself.
+    pub fn has_dependencies(&self) -> bool {
+        self.steps.len() > 1 || self.steps.first().map_or(false, |step| step.len() > 1)
+    }
+}
+
+fn task_steps(
+    task: &ResolvedTask,
+    find_task: &impl Fn(&str) -> Option<ResolvedTask>,
+    visiting: &mut Vec<String>,
+) -> anyhow::Result<Vec<Vec<SpawnInTerminal>>> {
+    let template = task.original_task();
+    let label = &template.label;
+    if visiting.contains(label) {
+        bail!(
+            "Task dependency cycle: {} -> {label}",
+            visiting.join(" -> ")
+        );
+    }
+
+    visiting.push(label.clone());
+    let mut dependency_steps = Vec::with_capacity(template.depends_on.len());
+    for dependency in &template.depends_on {
+        let dependency_task = find_task(dependency)
+            .with_context(|| format!("Task `{label}` depends on unknown task `{dependency}`"))?;
+        if dependency_task.original_task().debug.is_some() {
+            bail!("Task `{label}` depends on debugging task `{dependency}`");
+        }
+        dependency_steps.push(task_steps(&dependency_task, find_task, visiting)?);
+    }
+    visiting.pop();
+
+    let mut steps = match template.depends_order {
+        DependsOrder::Sequence => dependency_steps.into_iter().flatten().collect::<Vec<_>>(),
+        DependsOrder::Parallel => {
+            let mut merged: Vec<Vec<SpawnInTerminal>> = Vec::new();
+            for steps in dependency_steps {
+                for (ix, step) in steps.into_iter().enumerate() {
+                    match merged.get_mut(ix) {
+                        Some(merged_step) => merged_step.extend(step),
+                        None => merged.push(step),
+                    }
+                }
+            }
+            merged
+        }
+    };
+    if !template.is_compound() {
+        if let Some(spawn_in_terminal) = task.resolved.clone() {
+            steps.push(vec![spawn_in_terminal]);
+        }
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DebugConfig, DebugRequestKind, DependsOrder, ResolvedTask, TaskContext, TaskTemplate,
+    };
+
+    use super::*;
+
+    fn task(label: &str, depends_on: &[&str], depends_order: DependsOrder) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            depends_order,
+            ..TaskTemplate::default()
+        }
+    }
+
+    fn plan(tasks: &[TaskTemplate], label: &str) -> anyhow::Result<Vec<Vec<String>>> {
+        let resolve = |label: &str| -> Option<ResolvedTask> {
+            tasks
+                .iter()
+                .find(|task| task.label == label)?
+                .resolve_task("test", &TaskContext::default())
+        };
+        let plan = TaskPlan::new(&resolve(label).unwrap(), resolve)?;
+        Ok(plan
+            .steps
+            .into_iter()
+            .map(|step| step.into_iter().map(|task| task.label).collect())
+            .collect())
+    }
+
+    #[test]
+    fn test_sequential_and_parallel_dependencies() {
+        let tasks = [
+            task("fmt", &[], DependsOrder::Sequence),
+            task("build", &["fmt"], DependsOrder::Sequence),
+            task("lint", &[], DependsOrder::Sequence),
+            task("test", &["build", "lint"], DependsOrder::Sequence),
+            task("check", &["build", "lint"], DependsOrder::Parallel),
+        ];
+
+        assert_eq!(plan(&tasks, "fmt").unwrap(), vec![vec!["fmt"]]);
+        assert_eq!(
+            plan(&tasks, "test").unwrap(),
+            vec![vec!["fmt"], vec!["build"], vec!["lint"], vec!["test"]]
+        );
+        assert_eq!(
+            plan(&tasks, "check").unwrap(),
+            vec![vec!["fmt", "lint"], vec!["build"], vec!["check"]]
+        );
+    }
+
+    #[test]
+    fn test_compound_tasks() {
+        let tasks = [
+            task("build", &[], DependsOrder::Sequence),
+            task("web", &["build"], DependsOrder::Sequence),
+            task("server", &["build"], DependsOrder::Sequence),
+            TaskTemplate {
+                command: String::new(),
+                ..task("all", &["web", "server"], DependsOrder::Parallel)
+            },
+        ];
+
+        // the shared dependency runs once, and the compound task itself spawns nothing.
+        assert_eq!(
+            plan(&tasks, "all").unwrap(),
+            vec![vec!["build"], vec!["web", "server"]]
+        );
+    }
+
+    #[test]
+    fn test_invalid_dependencies() {
+        let tasks = [
+            task("a", &["b"], DependsOrder::Sequence),
+            task("b", &["c"], DependsOrder::Sequence),
+            task("c", &["a"], DependsOrder::Sequence),
+            task("d", &["missing"], DependsOrder::Sequence),
+            TaskTemplate {
+                debug: Some(DebugConfig {
+                    adapter: "lldb".to_string(),
+                    request: DebugRequestKind::Launch,
+                    configuration: serde_json::Value::Null,
+                }),
+                ..task("debug", &[], DependsOrder::Sequence)
+            },
+            task("e", &["debug"], DependsOrder::Sequence),
+        ];
+
+        assert_eq!(
+            plan(&tasks, "a").unwrap_err().to_string(),
+            "Task dependency cycle: a -> b -> c -> a"
+        );
+        assert_eq!(
+            plan(&tasks, "d").unwrap_err().to_string(),
+            "Task `d` depends on unknown task `missing`"
+        );
+        assert_eq!(
+            plan(&tasks, "e").unwrap_err().to_string(),
+            "Task `e` depends on debugging task `debug`"
+        );
+    }
+}
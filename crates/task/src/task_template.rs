@@ -8,7 +8,8 @@ use sha2::{Digest, Sha256};
 use util::{truncate_and_remove_front, ResultExt};
 
 use crate::{
-    ResolvedTask, SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    DebugConfig, ProblemMatcher, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TaskInput,
+    TestResultsFormat, VariableName, ZED_VARIABLE_NAME_PREFIX,
 };
 
 /// A template definition of a Zed task to run.
@@ -48,6 +49,35 @@ pub struct TaskTemplate {
     /// Represents the tags which this template attaches to. Adding this removes this task from other UI.
     #[serde(default)]
     pub tags: Vec<String>,
+
+    /// Labels of the tasks to run before this one; it is not started if any of them fails.
+    /// A task with no `command` only runs its dependencies, grouping them into one compound task.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How the tasks in `depends_on` are run:
+    /// * `sequence` — one after another, in the order listed (default)
+    /// * `parallel` — all at once
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+
+    /// Matchers to find problems (errors, warnings, etc.) in the task output with, reported as project diagnostics.
+    /// Either names of built-in matchers (`$rustc`, `$tsc`, `$gcc`, `$eslint-stylish`), or custom regex-based matchers.
+    #[serde(default)]
+    pub problem_matcher: Vec<ProblemMatcher>,
+
+    /// Values to get from the user before spawning the task, available as `$ZED_INPUT_<id>` variables.
+    /// The last values are remembered per task, so that task reruns do not ask for them again.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+
+    /// Format of the test results the task reports, to show them in the test explorer and the editor gutter.
+    #[serde(default)]
+    pub test_results: Option<TestResultsFormat>,
+
+    /// Makes the task a debugging session: its command is spawned as a debug adapter instead of running in a terminal,
+    /// and the adapter launches or attaches to the debuggee, as configured.
+    #[serde(default)]
+    pub debug: Option<DebugConfig>,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -61,6 +91,17 @@ pub enum RevealStrategy {
     Never,
 }
 
+/// How the tasks a task depends on are run relative to each other.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, in the order they are listed.
+    #[default]
+    Sequence,
+    /// Run all the dependencies at once.
+    Parallel,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -78,6 +119,11 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether the task has no command of its own and only runs the tasks it depends on.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -85,22 +131,36 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (self.command.trim().is_empty() && !self.is_compound()) {
             return None;
         }
 
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
+        // Inputs that were not asked for yet resolve into their defaults, so the task can be listed and spawned still.
+        let input_defaults = self
+            .inputs
+            .iter()
+            .map(|input| {
+                (
+                    input.variable_name(),
+                    input.default_value().unwrap_or_default(),
+                )
+            })
+            .filter(|(variable, _)| cx.task_variables.get(variable).is_none())
+            .collect::<Vec<_>>();
         let task_variables = cx
             .task_variables
             .0
             .iter()
+            .map(|(key, value)| (key, value.as_str()))
+            .chain(input_defaults.iter().map(|(key, value)| (key, *value)))
             .map(|(key, value)| {
                 let key_string = key.to_string();
                 if !variable_names.contains_key(&key_string) {
                     variable_names.insert(key_string.clone(), key.clone());
                 }
-                (key_string, value.as_str())
+                (key_string, value)
             })
             .collect::<HashMap<_, _>>();
         let truncated_variables = truncate_variables(&task_variables);
@@ -152,6 +212,29 @@ impl TaskTemplate {
             &variable_names,
             &mut substituted_variables,
         )?;
+        let test_results = match &self.test_results {
+            Some(TestResultsFormat::Junit { path }) => Some(TestResultsFormat::Junit {
+                path: substitute_all_template_variables_in_str(
+                    path,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?,
+            }),
+            test_results => test_results.clone(),
+        };
+        let debug = match &self.debug {
+            Some(debug) => Some(DebugConfig {
+                configuration: substitute_all_template_variables_in_json(
+                    &debug.configuration,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?,
+                ..debug.clone()
+            }),
+            None => None,
+        };
 
         let task_hash = to_hex_hash(&self)
             .context("hashing task template")
@@ -170,6 +253,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: Some(SpawnInTerminal {
@@ -186,11 +270,19 @@ impl TaskTemplate {
                     },
                 ),
                 command,
-                args: self.args.clone(),
+                // Debug adapters are spawned directly, without a shell to substitute the variables in their arguments.
+                args: if debug.is_some() {
+                    args_with_substitutions
+                } else {
+                    self.args.clone()
+                },
                 env,
                 use_new_terminal: self.use_new_terminal,
                 allow_concurrent_runs: self.allow_concurrent_runs,
                 reveal: self.reveal,
+                problem_matcher: self.problem_matcher.clone(),
+                test_results,
+                debug,
             }),
         })
     }
@@ -298,11 +390,59 @@ fn substitute_all_template_variables_in_map(
     Some(new_map)
 }
 
+fn substitute_all_template_variables_in_json(
+    value: &serde_json::Value,
+    task_variables: &HashMap<String, &str>,
+    variable_names: &HashMap<String, VariableName>,
+    substituted_variables: &mut HashSet<VariableName>,
+) -> Option<serde_json::Value> {
+    Some(match value {
+        serde_json::Value::String(string) => {
+            serde_json::Value::String(substitute_all_template_variables_in_str(
+                string,
+                task_variables,
+                variable_names,
+                substituted_variables,
+            )?)
+        }
+        serde_json::Value::Array(values) => serde_json::Value::Array(
+            values
+                .iter()
+                .map(|value| {
+                    substitute_all_template_variables_in_json(
+                        value,
+                        task_variables,
+                        variable_names,
+                        substituted_variables,
+                    )
+                })
+                .collect::<Option<_>>()?,
+        ),
+        serde_json::Value::Object(object) => serde_json::Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    Some((
+                        key.clone(),
+                        substitute_all_template_variables_in_json(
+                            value,
+                            task_variables,
+                            variable_names,
+                            substituted_variables,
+                        )?,
+                    ))
+                })
+                .collect::<Option<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::{borrow::Cow, path::Path};
 
-    use crate::{TaskVariables, VariableName};
+    use crate::{DebugRequestKind, TaskInputKind, TaskVariables, VariableName};
 
     use super::*;
 
@@ -656,6 +796,115 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_input_variables_resolution() {
+        let profile = TaskInput {
+            id: "profile".to_string(),
+            description: None,
+            kind: TaskInputKind::Pick {
+                options: vec!["debug".to_string(), "release".to_string()],
+                default: None,
+            },
+        };
+        let target = TaskInput {
+            id: "target".to_string(),
+            description: Some("Target to build".to_string()),
+            kind: TaskInputKind::Prompt { default: None },
+        };
+        let task = TaskTemplate {
+            label: format!("build {}", profile.variable_name().template_value()),
+            command: "make".to_string(),
+            args: vec![target.variable_name().template_value()],
+            inputs: vec![profile.clone(), target.clone()],
+            ..TaskTemplate::default()
+        };
+
+        let resolved_task = task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("inputs that were not asked for should resolve into their defaults");
+        assert_substituted_variables(
+            &resolved_task,
+            vec![profile.variable_name(), target.variable_name()],
+        );
+        let resolved = resolved_task.resolved.unwrap();
+        assert_eq!(resolved.label, "build debug");
+        assert_eq!(resolved.command_label, "make ");
+        assert_eq!(
+            resolved.env.get("ZED_INPUT_target").map(String::as_str),
+            Some("")
+        );
+
+        let cx = TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([
+                (profile.variable_name(), "release".to_string()),
+                (target.variable_name(), "all".to_string()),
+            ]),
+        };
+        let resolved = task
+            .resolve_task(TEST_ID_BASE, &cx)
+            .unwrap()
+            .resolved
+            .unwrap();
+        assert_eq!(resolved.label, "build release");
+        assert_eq!(resolved.command_label, "make all");
+        assert_eq!(
+            resolved.env.get("ZED_INPUT_profile").map(String::as_str),
+            Some("release")
+        );
+    }
+
+    #[test]
+    fn test_debug_config_resolution() {
+        let task = TaskTemplate {
+            label: "debug".to_string(),
+            command: "lldb-dap".to_string(),
+            args: vec![format!("--port={}", VariableName::Row.template_value())],
+            debug: Some(DebugConfig {
+                adapter: "lldb".to_string(),
+                request: DebugRequestKind::Launch,
+                configuration: serde_json::json!({
+                    "program": format!("{}/target/debug/app", VariableName::WorktreeRoot.template_value()),
+                    "args": [VariableName::Symbol.template_value()],
+                    "stopOnEntry": true,
+                }),
+            }),
+            ..TaskTemplate::default()
+        };
+        let cx = TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([
+                (VariableName::WorktreeRoot, "/project".to_string()),
+                (VariableName::Symbol, "main".to_string()),
+                (VariableName::Row, "4".to_string()),
+            ]),
+        };
+
+        let resolved_task = task.resolve_task(TEST_ID_BASE, &cx).unwrap();
+        assert_substituted_variables(
+            &resolved_task,
+            vec![
+                VariableName::WorktreeRoot,
+                VariableName::Symbol,
+                VariableName::Row,
+            ],
+        );
+        let resolved = resolved_task.resolved.unwrap();
+        assert_eq!(
+            resolved.args,
+            vec!["--port=4".to_string()],
+            "debug adapter arguments should be substituted in advance"
+        );
+        assert_eq!(
+            resolved.debug.unwrap().configuration,
+            serde_json::json!({
+                "program": "/project/target/debug/app",
+                "args": ["main"],
+                "stopOnEntry": true,
+            })
+        );
+    }
+
     #[track_caller]
     fn assert_substituted_variables(resolved_task: &ResolvedTask, mut expected: Vec<VariableName>) {
         let mut resolved_variables = resolved_task
@@ -0,0 +1,492 @@
+use anyhow::Context;
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A format of the structured test results, reported by a task that runs tests.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum TestResultsFormat {
+    /// The human-readable output of Rust's test harness, printed by `cargo test`.
+    Libtest,
+    /// JSON lines, printed by `cargo test -- -Z unstable-options --format json` on nightly toolchains.
+    CargoJson,
+    /// JSON lines, printed by `go test -json`.
+    GoJson,
+    /// JUnit XML report, written by the task into the file at `path`, e.g. with `pytest --junitxml`.
+    /// Relative paths are resolved against the task's working directory.
+    Junit {
+        /// Path to the report file.
+        path: String,
+    },
+}
+
+impl TestResultsFormat {
+    /// Whether the results are printed in the task output, rather than reported after the task finishes.
+    pub fn is_printed(&self) -> bool {
+        match self {
+            Self::Libtest | Self::CargoJson | Self::GoJson => true,
+            Self::Junit { .. } => false,
+        }
+    }
+}
+
+/// How a test run has ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TestOutcome {
+    /// The test has passed.
+    Passed,
+    /// The test has failed or errored.
+    Failed,
+    /// The test was skipped or ignored.
+    Skipped,
+}
+
+/// A result of a single test, reported by the test runner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestResult {
+    /// Full name of the test, as reported by the test runner, e.g. `module::tests::test_name` or `package.TestName`.
+    pub name: String,
+    /// How the test has ended.
+    pub outcome: TestOutcome,
+    /// Failure message or output of the test, if any was reported.
+    pub message: Option<String>,
+}
+
+impl TestResult {
+    /// Whether the result is reported for the test function (or method) named `symbol`.
+    pub fn is_for_symbol(&self, symbol: &str) -> bool {
+        let name = self.name_without_parameters();
+        name == symbol
+            || name.strip_suffix(symbol).map_or(false, |prefix| {
+                prefix.ends_with("::") || prefix.ends_with('.')
+            })
+    }
+
+    /// Whether the result is reported for the test at `test_path`: the names of the items enclosing
+    /// the test in its file, followed by the test's name, separated by `::`, e.g. `tests::test_name`.
+    /// The result's name has to end with the test's name, and contain the enclosing items' names in order.
+    pub fn is_for_test(&self, test_path: &str) -> bool {
+        let mut name_segments = self
+            .name_without_parameters()
+            .split(|c| c == ':' || c == '.')
+            .filter(|segment| !segment.is_empty())
+            .rev();
+        let mut path_segments = test_path.rsplit("::");
+        match (name_segments.next(), path_segments.next()) {
+            (Some(name), Some(symbol)) if name == symbol => {}
+            _ => return false,
+        }
+        path_segments.all(|path_segment| name_segments.any(|segment| segment == path_segment))
+    }
+
+    fn name_without_parameters(&self) -> &str {
+        // Strip pytest parameters, as in `test_name[1-2]`.
+        match self.name.split_once('[') {
+            Some((name, _)) => name,
+            None => self.name.as_str(),
+        }
+    }
+}
+
+/// Finds test results in the task output, line by line.
+#[derive(Debug)]
+pub struct TestResultsScanner {
+    format: TestResultsFormat,
+    /// Output of the tests that did not finish yet, to report as their failure messages.
+    test_output: HashMap<String, String>,
+    /// The failed test whose output libtest is printing, with its output so far.
+    libtest_failure: Option<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct LibtestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    stdout: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoTestEvent {
+    action: String,
+    package: Option<String>,
+    test: Option<String>,
+    output: Option<String>,
+}
+
+impl TestResultsScanner {
+    /// Creates a scanner for the results, printed in the given format.
+    pub fn new(format: TestResultsFormat) -> Self {
+        Self {
+            format,
+            test_output: HashMap::default(),
+            libtest_failure: None,
+        }
+    }
+
+    /// Returns the test result, reported with the output line, if any.
+    pub fn scan_line(&mut self, line: &str) -> Option<TestResult> {
+        if self.format == TestResultsFormat::Libtest {
+            return self.scan_libtest_line(line.trim_end());
+        }
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        match self.format {
+            TestResultsFormat::CargoJson => {
+                let event = serde_json_lenient::from_str::<LibtestEvent>(line).ok()?;
+                if event.kind != "test" {
+                    return None;
+                }
+                let outcome = match event.event.as_str() {
+                    "ok" => TestOutcome::Passed,
+                    "failed" | "timeout" => TestOutcome::Failed,
+                    "ignored" => TestOutcome::Skipped,
+                    _ => return None,
+                };
+                Some(TestResult {
+                    name: event.name?,
+                    outcome,
+                    message: event
+                        .stdout
+                        .or(event.message)
+                        .filter(|message| !message.trim().is_empty()),
+                })
+            }
+            TestResultsFormat::GoJson => {
+                let event = serde_json_lenient::from_str::<GoTestEvent>(line).ok()?;
+                let name = match (event.package, event.test) {
+                    (Some(package), Some(test)) => format!("{package}.{test}"),
+                    (None, Some(test)) => test,
+                    // Package-level events.
+                    (_, None) => return None,
+                };
+                let outcome = match event.action.as_str() {
+                    "output" => {
+                        if let Some(output) = event.output {
+                            self.test_output.entry(name).or_default().push_str(&output);
+                        }
+                        return None;
+                    }
+                    "pass" => TestOutcome::Passed,
+                    "fail" => TestOutcome::Failed,
+                    "skip" => TestOutcome::Skipped,
+                    _ => return None,
+                };
+                let output = self.test_output.remove(&name);
+                Some(TestResult {
+                    name,
+                    outcome,
+                    message: output
+                        .filter(|_| outcome == TestOutcome::Failed)
+                        .filter(|output| !output.trim().is_empty()),
+                })
+            }
+            TestResultsFormat::Libtest | TestResultsFormat::Junit { .. } => None,
+        }
+    }
+
+    /// Libtest prints a line per finished test, e.g. `test tests::fails ... FAILED`, then the output
+    /// of each failed test under a `---- tests::fails stdout ----` header, which is reported as
+    /// the failure message once the next header or the list of failures is printed.
+    fn scan_libtest_line(&mut self, line: &str) -> Option<TestResult> {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            let failure = self.finish_libtest_failure();
+            self.libtest_failure = Some((name.to_string(), String::new()));
+            return failure;
+        }
+        if line == "failures:" || line == "successes:" {
+            return self.finish_libtest_failure();
+        }
+        if let Some((_, output)) = self.libtest_failure.as_mut() {
+            output.push_str(line);
+            output.push('\n');
+            return None;
+        }
+
+        let (name, result) = line.strip_prefix("test ")?.split_once(" ... ")?;
+        let outcome = match result {
+            "ok" => TestOutcome::Passed,
+            "FAILED" => TestOutcome::Failed,
+            result if result.starts_with("ignored") => TestOutcome::Skipped,
+            _ => return None,
+        };
+        Some(TestResult {
+            name: name.to_string(),
+            outcome,
+            message: None,
+        })
+    }
+
+    fn finish_libtest_failure(&mut self) -> Option<TestResult> {
+        let (name, output) = self.libtest_failure.take()?;
+        let output = output.trim_end();
+        Some(TestResult {
+            name,
+            outcome: TestOutcome::Failed,
+            message: (!output.is_empty()).then(|| output.to_string()),
+        })
+    }
+}
+
+/// Reads the results of all test cases from a JUnit XML report.
+pub fn parse_junit_report(report: &str) -> anyhow::Result<Vec<TestResult>> {
+    let document = roxmltree::Document::parse(report).context("parsing JUnit XML report")?;
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .filter_map(|test_case| {
+            let name = test_case.attribute("name")?;
+            let name = match test_case.attribute("classname") {
+                Some(class_name) if !class_name.is_empty() => format!("{class_name}.{name}"),
+                _ => name.to_string(),
+            };
+            let mut outcome = TestOutcome::Passed;
+            let mut message = None;
+            for child in test_case.children().filter(|child| child.is_element()) {
+                match child.tag_name().name() {
+                    "failure" | "error" => {
+                        outcome = TestOutcome::Failed;
+                        message = junit_failure_message(child);
+                    }
+                    "skipped" if outcome != TestOutcome::Failed => {
+                        outcome = TestOutcome::Skipped;
+                        message = child.attribute("message").map(ToString::to_string);
+                    }
+                    _ => {}
+                }
+            }
+            Some(TestResult {
+                name,
+                outcome,
+                message,
+            })
+        })
+        .collect())
+}
+
+fn junit_failure_message(node: roxmltree::Node) -> Option<String> {
+    let text = node.text().map(str::trim).filter(|text| !text.is_empty());
+    match (node.attribute("message"), text) {
+        (Some(message), Some(text)) if !text.contains(message) => {
+            Some(format!("{message}\n{text}"))
+        }
+        (_, Some(text)) => Some(text.to_string()),
+        (message, None) => message.map(ToString::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libtest_results() {
+        let mut scanner = TestResultsScanner::new(TestResultsFormat::Libtest);
+        let output = [
+            "running 3 tests",
+            "test tests::passes ... ok",
+            "test tests::ignored ... ignored, not implemented yet",
+            "test tests::fails ... FAILED",
+            "",
+            "failures:",
+            "",
+            "---- tests::fails stdout ----",
+            "thread 'tests::fails' panicked at src/lib.rs:10:9:",
+            "assertion failed: false",
+            "",
+            "",
+            "failures:",
+            "    tests::fails",
+            "",
+            "test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out",
+        ];
+        let results = output
+            .iter()
+            .filter_map(|line| scanner.scan_line(line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "tests::passes".to_string(),
+                    outcome: TestOutcome::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "tests::ignored".to_string(),
+                    outcome: TestOutcome::Skipped,
+                    message: None,
+                },
+                TestResult {
+                    name: "tests::fails".to_string(),
+                    outcome: TestOutcome::Failed,
+                    message: None,
+                },
+                TestResult {
+                    name: "tests::fails".to_string(),
+                    outcome: TestOutcome::Failed,
+                    message: Some(
+                        "thread 'tests::fails' panicked at src/lib.rs:10:9:\nassertion failed: false"
+                            .to_string()
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cargo_json_results() {
+        let mut scanner = TestResultsScanner::new(TestResultsFormat::CargoJson);
+        let output = [
+            r#"{ "type": "suite", "event": "started", "test_count": 3 }"#,
+            r#"{ "type": "test", "event": "started", "name": "tests::passes" }"#,
+            r#"{ "type": "test", "name": "tests::passes", "event": "ok" }"#,
+            r#"{ "type": "test", "name": "tests::fails", "event": "failed", "stdout": "thread 'tests::fails' panicked at src/lib.rs:10:9:\nassertion failed: false\n" }"#,
+            r#"{ "type": "test", "name": "tests::ignored", "event": "ignored" }"#,
+            "running 3 tests",
+            r#"{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1 }"#,
+        ];
+        let results = output
+            .iter()
+            .filter_map(|line| scanner.scan_line(line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "tests::passes".to_string(),
+                    outcome: TestOutcome::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "tests::fails".to_string(),
+                    outcome: TestOutcome::Failed,
+                    message: Some(
+                        "thread 'tests::fails' panicked at src/lib.rs:10:9:\nassertion failed: false\n"
+                            .to_string()
+                    ),
+                },
+                TestResult {
+                    name: "tests::ignored".to_string(),
+                    outcome: TestOutcome::Skipped,
+                    message: None,
+                },
+            ]
+        );
+        assert!(results[1].is_for_symbol("fails"));
+        assert!(!results[1].is_for_symbol("ails"));
+    }
+
+    #[test]
+    fn test_result_is_for_test() {
+        let result = |name: &str| TestResult {
+            name: name.to_string(),
+            outcome: TestOutcome::Passed,
+            message: None,
+        };
+        assert!(result("parser::tests::parses").is_for_test("tests::parses"));
+        assert!(result("parser::tests::parses").is_for_test("parses"));
+        // Tests with the same name in other modules of the file.
+        assert!(!result("parser::tests::parses").is_for_test("other_tests::parses"));
+        assert!(!result("parser::tests::parses").is_for_test("tests::other_tests::parses"));
+        assert!(!result("parser::tests::reparses").is_for_test("tests::parses"));
+
+        assert!(result("example.com/calc.TestAdd").is_for_test("TestAdd"));
+        assert!(result("tests.test_math.TestDiv.test_div[0]").is_for_test("TestDiv::test_div"));
+        assert!(!result("tests.test_math.TestMul.test_div[0]").is_for_test("TestDiv::test_div"));
+    }
+
+    #[test]
+    fn test_go_json_results() {
+        let mut scanner = TestResultsScanner::new(TestResultsFormat::GoJson);
+        let output = [
+            r#"{"Action":"run","Package":"example.com/calc","Test":"TestAdd"}"#,
+            r#"{"Action":"output","Package":"example.com/calc","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}"#,
+            r#"{"Action":"pass","Package":"example.com/calc","Test":"TestAdd","Elapsed":0}"#,
+            r#"{"Action":"run","Package":"example.com/calc","Test":"TestDiv"}"#,
+            r#"{"Action":"output","Package":"example.com/calc","Test":"TestDiv","Output":"    calc_test.go:12: expected 2, got 3\n"}"#,
+            r#"{"Action":"fail","Package":"example.com/calc","Test":"TestDiv","Elapsed":0}"#,
+            r#"{"Action":"fail","Package":"example.com/calc","Elapsed":0.01}"#,
+        ];
+        let results = output
+            .iter()
+            .filter_map(|line| scanner.scan_line(line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "example.com/calc.TestAdd".to_string(),
+                    outcome: TestOutcome::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "example.com/calc.TestDiv".to_string(),
+                    outcome: TestOutcome::Failed,
+                    message: Some("    calc_test.go:12: expected 2, got 3\n".to_string()),
+                },
+            ]
+        );
+        assert!(results[1].is_for_symbol("TestDiv"));
+    }
+
+    #[test]
+    fn test_junit_results() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+            <testsuites>
+                <testsuite name="pytest" errors="0" failures="1" skipped="1" tests="4">
+                    <testcase classname="tests.test_math" name="test_add" time="0.001" />
+                    <testcase classname="tests.test_math" name="test_div[0]" time="0.001">
+                        <failure message="ZeroDivisionError: division by zero">def test_div(d):
+&gt;       1 / d
+E       ZeroDivisionError: division by zero</failure>
+                    </testcase>
+                    <testcase classname="tests.test_math" name="test_later" time="0.000">
+                        <skipped type="pytest.skip" message="not implemented" />
+                    </testcase>
+                    <testcase name="test_standalone" />
+                </testsuite>
+            </testsuites>"#;
+        let results = parse_junit_report(report).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "tests.test_math.test_add".to_string(),
+                    outcome: TestOutcome::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "tests.test_math.test_div[0]".to_string(),
+                    outcome: TestOutcome::Failed,
+                    message: Some(
+                        "def test_div(d):\n>       1 / d\nE       ZeroDivisionError: division by zero"
+                            .to_string()
+                    ),
+                },
+                TestResult {
+                    name: "tests.test_math.test_later".to_string(),
+                    outcome: TestOutcome::Skipped,
+                    message: Some("not implemented".to_string()),
+                },
+                TestResult {
+                    name: "test_standalone".to_string(),
+                    outcome: TestOutcome::Passed,
+                    message: None,
+                },
+            ]
+        );
+        assert!(results[1].is_for_symbol("test_div"));
+        assert!(results[3].is_for_symbol("test_standalone"));
+        assert!(parse_junit_report("<testsuite").is_err());
+    }
+}
@@ -1,16 +1,31 @@
-use anyhow::bail;
-use collections::HashMap;
-use serde::Deserialize;
-use util::ResultExt;
+use std::{mem, path::MAIN_SEPARATOR};
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use anyhow::{bail, Context};
+use collections::{HashMap, HashSet};
+use serde::{Deserialize, Deserializer};
+use serde_json_lenient::Value;
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+use crate::{
+    CustomProblemMatcher, DebugConfig, DebugRequestKind, DependsOrder, ProblemMatcher,
+    ProblemPattern, ProblemSeverity, RevealStrategy, TaskInput, TaskInputKind, TaskTemplate,
+    TaskTemplates, VariableName,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 struct TaskOptions {
     cwd: Option<String>,
     #[serde(default)]
     env: HashMap<String, String>,
+    shell: Option<ShellOptions>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ShellOptions {
+    executable: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_args")]
+    args: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -32,29 +47,277 @@ enum Command {
         script: String,
     },
     Shell {
+        #[serde(deserialize_with = "deserialize_arg")]
         command: String,
-        #[serde(default)]
+        #[serde(default, deserialize_with = "deserialize_args")]
+        args: Vec<String>,
+    },
+    Process {
+        #[serde(deserialize_with = "deserialize_arg")]
+        command: String,
+        #[serde(default, deserialize_with = "deserialize_args")]
         args: Vec<String>,
     },
     Gulp {
         task: String,
     },
+    Grunt {
+        task: String,
+    },
+    Cargo {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// Task properties that can be overridden for the current OS, with `linux`, `osx` or `windows` task keys.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PlatformOverrides {
+    #[serde(default, deserialize_with = "deserialize_optional_arg")]
+    command: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_args")]
+    args: Option<Vec<String>>,
+    options: Option<TaskOptions>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Presentation {
+    reveal: Option<String>,
+    panel: Option<String>,
+    #[serde(default)]
+    clear: bool,
+    #[serde(default)]
+    close: bool,
+    echo: Option<bool>,
+    group: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    severity: Option<ProblemSeverity>,
+    pattern: Option<Value>,
+    file_location: Option<Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    location: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+/// A value for the `${input:id}` variables.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeInput {
+    id: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    description: Option<String>,
+    default: Option<Value>,
+    #[serde(default)]
+    options: Vec<Value>,
+}
+
+impl VsCodeInput {
+    fn to_task_input(&self) -> Result<TaskInput, String> {
+        let default = self
+            .default
+            .as_ref()
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+        let kind = match self.kind.as_deref() {
+            Some("promptString") => TaskInputKind::Prompt { default },
+            Some("pickString") => TaskInputKind::Pick {
+                options: self
+                    .options
+                    .iter()
+                    .filter_map(|option| match option {
+                        Value::String(option) => Some(option.clone()),
+                        option => Some(option.get("value")?.as_str()?.to_string()),
+                    })
+                    .collect(),
+                default,
+            },
+            Some(kind) => {
+                return Err(format!(
+                    "Input `{}` of type `{kind}` is not supported",
+                    self.id
+                ))
+            }
+            None => return Err(format!("Input `{}` has no type", self.id)),
+        };
+        Ok(TaskInput {
+            id: self.id.clone(),
+            description: self.description.clone(),
+            kind,
+        })
+    }
+}
+
+/// A command or an argument, either a plain string or a value with the quoting to apply to it.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum QuotedString {
+    Plain(String),
+    Quoted {
+        value: String,
+        #[serde(default)]
+        quoting: Quoting,
+    },
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Quoting {
+    #[default]
+    Escape,
+    Strong,
+    Weak,
+}
+
+impl QuotedString {
+    fn into_string(self) -> String {
+        match self {
+            Self::Plain(value) => value,
+            Self::Quoted {
+                value,
+                quoting: Quoting::Escape,
+            } => value.replace(' ', "\\ "),
+            Self::Quoted {
+                value,
+                quoting: Quoting::Strong,
+            } => quote_strong(&value),
+            Self::Quoted {
+                value,
+                quoting: Quoting::Weak,
+            } => format!("\"{}\"", value.replace('"', "\\\"")),
+        }
+    }
+}
+
+fn quote_strong(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn deserialize_arg<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    QuotedString::deserialize(deserializer).map(QuotedString::into_string)
+}
+
+fn deserialize_args<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let args = Vec::<QuotedString>::deserialize(deserializer)?;
+    Ok(args.into_iter().map(QuotedString::into_string).collect())
+}
+
+fn deserialize_optional_arg<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let arg = Option::<QuotedString>::deserialize(deserializer)?;
+    Ok(arg.map(QuotedString::into_string))
+}
+
+fn deserialize_optional_args<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    let args = Option::<Vec<QuotedString>>::deserialize(deserializer)?;
+    Ok(args.map(|args| args.into_iter().map(QuotedString::into_string).collect()))
 }
 
 type VsCodeEnvVariable = String;
 type ZedEnvVariable = String;
 
+/// VS Code variables that have no Zed counterpart.
+const UNSUPPORTED_VARIABLES: &[&str] = &[
+    "fileBasename",
+    "fileBasenameNoExtension",
+    "fileDirname",
+    "fileExtname",
+    "fileWorkspaceFolder",
+    "relativeFile",
+    "relativeFileDirname",
+    "workspaceFolderBasename",
+    "execPath",
+    "defaultBuildTask",
+];
+
 struct EnvVariableReplacer {
     variables: HashMap<VsCodeEnvVariable, ZedEnvVariable>,
+    /// Task inputs to replace `${input:id}` variables with, or the reasons they cannot be converted.
+    inputs: Vec<(String, Result<TaskInput, String>)>,
 }
 
 impl EnvVariableReplacer {
     fn new(variables: HashMap<VsCodeEnvVariable, ZedEnvVariable>) -> Self {
-        Self { variables }
+        Self {
+            variables,
+            inputs: Vec::new(),
+        }
     }
+
+    fn with_inputs(mut self, inputs: &[VsCodeInput]) -> Self {
+        self.inputs = inputs
+            .iter()
+            .map(|input| (input.id.clone(), input.to_task_input()))
+            .collect();
+        self
+    }
+
     // Replaces occurrences of VsCode-specific environment variables with Zed equivalents.
-    fn replace(&self, input: &str) -> String {
-        shellexpand::env_with_context_no_errors(&input, |var: &str| {
+    fn replace(&self, input: &str, warnings: &mut Vec<String>) -> anyhow::Result<String> {
+        let mut input_error = None;
+        let replaced = shellexpand::env_with_context_no_errors(&input, |var: &str| {
+            if let Some(env_variable) = var.strip_prefix("env:") {
+                return Some(format!("${{{env_variable}}}"));
+            }
+            if let Some(input_id) = var.strip_prefix("input:") {
+                let input = self.inputs.iter().find(|(id, _)| id == input_id);
+                return match input {
+                    Some((_, Ok(task_input))) => Some(task_input.variable_name().template_value()),
+                    Some((_, Err(e))) => {
+                        input_error = Some(e.clone());
+                        None
+                    }
+                    None => {
+                        input_error = Some(format!("Input `{input_id}` is not declared"));
+                        None
+                    }
+                };
+            }
+            if let Some(folder) = var.strip_prefix("workspaceFolder:") {
+                warnings.push(format!(
+                    "`${{workspaceFolder:{folder}}}` is replaced with the root of the worktree the task runs in"
+                ));
+                return Some(format!("${{{}}}", VariableName::WorktreeRoot));
+            }
+            match var {
+                "pathSeparator" => return Some(MAIN_SEPARATOR.to_string()),
+                "userHome" => return Some("${HOME}".to_string()),
+                _ => {}
+            }
+            if var.starts_with("config:")
+                || var.starts_with("command:")
+                || UNSUPPORTED_VARIABLES.contains(&var)
+            {
+                warnings.push(format!("variable `${{{var}}}` is not supported"));
+                return None;
+            }
+
             // Colons denote a default value in case the variable is not set. We want to preserve that default, as otherwise shellexpand will substitute it for us.
             let colon_position = var.find(':').unwrap_or(var.len());
             let (variable_name, default) = var.split_at(colon_position);
@@ -79,73 +342,673 @@ impl EnvVariableReplacer {
             // Else we can just return None and that variable will be left as is.
             None
         })
-        .into_owned()
+        .into_owned();
+        if let Some(e) = input_error {
+            bail!(e);
+        }
+        Ok(replaced)
+    }
+
+    /// Replaces the VS Code variables in all strings of the value.
+    fn replace_in_json(
+        &self,
+        value: serde_json::Value,
+        warnings: &mut Vec<String>,
+    ) -> anyhow::Result<serde_json::Value> {
+        Ok(match value {
+            serde_json::Value::String(value) => {
+                serde_json::Value::String(self.replace(&value, warnings)?)
+            }
+            serde_json::Value::Array(values) => serde_json::Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.replace_in_json(value, warnings))
+                    .collect::<anyhow::Result<_>>()?,
+            ),
+            serde_json::Value::Object(values) => serde_json::Value::Object(
+                values
+                    .into_iter()
+                    .map(|(key, value)| anyhow::Ok((key, self.replace_in_json(value, warnings)?)))
+                    .collect::<anyhow::Result<_>>()?,
+            ),
+            value => value,
+        })
+    }
+
+    /// The task inputs, whose variables are used in the converted task.
+    fn used_inputs(&self, template: &TaskTemplate) -> Vec<TaskInput> {
+        let debug_configuration = template
+            .debug
+            .as_ref()
+            .map(|debug| debug.configuration.to_string());
+        self.inputs
+            .iter()
+            .filter_map(|(_, input)| input.as_ref().ok())
+            .filter(|input| {
+                let variable = input.variable_name().template_value();
+                std::iter::once(&template.command)
+                    .chain(&template.args)
+                    .chain(&template.cwd)
+                    .chain(template.env.values())
+                    .chain(&debug_configuration)
+                    .any(|value| value.contains(&variable))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Task properties that are used during the conversion, or safe to ignore.
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "type",
+    "command",
+    "args",
+    "script",
+    "task",
+    "path",
+    "dependsOn",
+    "dependsOrder",
+    "presentation",
+    "problemMatcher",
+    "group",
+    "isBackground",
+    "linux",
+    "osx",
+    "windows",
+    "detail",
+    "icon",
+    "hide",
+    "promptOnClose",
+];
+
+fn platform_key() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "osx"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else {
+        "linux"
     }
 }
 
 impl VsCodeTaskDefinition {
-    fn to_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
+    /// Replaces the task properties with the ones, specific for the current OS.
+    fn apply_platform_overrides(&mut self) -> anyhow::Result<()> {
+        let Some(overrides) = self.other_attributes.get(platform_key()) else {
+            return Ok(());
+        };
+        let overrides = serde_json_lenient::from_value::<PlatformOverrides>(overrides.clone())
+            .with_context(|| format!("Invalid `{}` task properties", platform_key()))?;
+
+        match &mut self.command {
+            Some(Command::Shell { command, args } | Command::Process { command, args }) => {
+                if let Some(new_command) = overrides.command {
+                    *command = new_command;
+                }
+                if let Some(new_args) = overrides.args {
+                    *args = new_args;
+                }
+            }
+            Some(_) => {}
+            // The command might be defined for some of the platforms only.
+            None => {
+                if let Some(command) = overrides.command {
+                    let args = overrides.args.unwrap_or_default();
+                    self.command = match self.other_attributes.get("type").and_then(Value::as_str) {
+                        Some("shell") => Some(Command::Shell { command, args }),
+                        Some("process") => Some(Command::Process { command, args }),
+                        _ => None,
+                    };
+                }
+            }
         }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+
+        if let Some(new_options) = overrides.options {
+            let options = self.options.get_or_insert_with(TaskOptions::default);
+            if new_options.cwd.is_some() {
+                options.cwd = new_options.cwd;
+            }
+            options.env.extend(new_options.env);
+            if new_options.shell.is_some() {
+                options.shell = new_options.shell;
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the definition into a task template, and warnings about the properties that could not be converted.
+    fn to_zed_format(
+        mut self,
+        replacer: &EnvVariableReplacer,
+    ) -> anyhow::Result<(TaskTemplate, Vec<String>)> {
+        self.apply_platform_overrides()?;
+        let attributes = mem::take(&mut self.other_attributes);
+        let mut warnings = Vec::new();
+
+        let depends_on = match attributes.get("dependsOn") {
+            None => Vec::new(),
+            Some(Value::String(label)) => vec![label.clone()],
+            Some(Value::Array(dependencies)) => dependencies
+                .iter()
+                .filter_map(|dependency| match dependency.as_str() {
+                    Some(label) => Some(label.to_string()),
+                    None => {
+                        warnings.push(format!(
+                            "dependency {dependency} is not a task label and is skipped"
+                        ));
+                        None
+                    }
+                })
+                .collect(),
+            Some(other) => bail!("Unsupported `dependsOn` value {other}"),
+        };
+        // VS Code runs the dependencies in parallel, unless told otherwise.
+        let depends_order = match attributes.get("dependsOrder").and_then(Value::as_str) {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
         };
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let uses_shell = matches!(self.command, Some(Command::Shell { .. }));
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args } | Command::Process { command, args }) => {
+                (command, args)
+            }
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            Some(Command::Grunt { task }) => ("grunt".to_owned(), vec![task]),
+            Some(Command::Cargo { command, args }) => (
+                "cargo".to_owned(),
+                std::iter::once(command).chain(args).collect(),
+            ),
+            // A task that only runs its dependencies.
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => match attributes.get("type").and_then(Value::as_str) {
+                Some(kind @ ("npm" | "shell" | "process" | "gulp" | "grunt" | "cargo")) => {
+                    bail!("Missing or invalid properties of the `{kind}` task")
+                }
+                Some(kind) => bail!("Unsupported task type `{kind}`"),
+                None => bail!("Missing `type` field in task"),
+            },
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
-        let command = replacer.replace(&command);
-        let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let mut command = replacer.replace(&command, &mut warnings)?;
+        let mut args = args
+            .iter()
+            .map(|arg| replacer.replace(arg, &mut warnings))
+            .collect::<anyhow::Result<Vec<_>>>()?;
         let mut ret = TaskTemplate {
             label: self.label,
-            command,
-            args,
+            depends_on,
+            depends_order,
             ..Default::default()
         };
+
         if let Some(options) = self.options {
-            ret.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
-            ret.env = options.env;
+            ret.cwd = options
+                .cwd
+                .map(|cwd| replacer.replace(&cwd, &mut warnings))
+                .transpose()?;
+            ret.env = options
+                .env
+                .into_iter()
+                .map(|(name, value)| anyhow::Ok((name, replacer.replace(&value, &mut warnings)?)))
+                .collect::<anyhow::Result<_>>()?;
+            if let Some(shell) = options.shell.filter(|_| uses_shell) {
+                match shell.executable {
+                    // Zed runs the tasks in the user's shell, so the command line for the custom shell needs quoting.
+                    Some(executable) => {
+                        let command_line = std::iter::once(command)
+                            .chain(args)
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        args = shell
+                            .args
+                            .iter()
+                            .map(|arg| replacer.replace(arg, &mut warnings))
+                            .chain(std::iter::once(Ok(quote_strong(&command_line))))
+                            .collect::<anyhow::Result<Vec<_>>>()?;
+                        command = replacer.replace(&executable, &mut warnings)?;
+                    }
+                    None => warnings
+                        .push("`options.shell` has no `executable` and is ignored".to_string()),
+                }
+            }
+        }
+        if ret.cwd.is_none() {
+            if let Some(path) = attributes.get("path").and_then(Value::as_str) {
+                ret.cwd = Some(format!("${{{}}}/{path}", VariableName::WorktreeRoot));
+            }
+        }
+        ret.command = command;
+        ret.args = args;
+        ret.inputs = replacer.used_inputs(&ret);
+
+        if let Some(presentation) = attributes.get("presentation") {
+            let presentation = serde_json_lenient::from_value::<Presentation>(presentation.clone())
+                .context("Invalid `presentation` value")?;
+            apply_presentation(&presentation, &mut ret, &mut warnings);
+        }
+
+        if let Some(problem_matcher) = attributes.get("problemMatcher") {
+            let cwd = ret
+                .cwd
+                .clone()
+                .unwrap_or_else(|| format!("${{{}}}", VariableName::WorktreeRoot));
+            ret.problem_matcher =
+                convert_problem_matchers(problem_matcher, &cwd, replacer, &mut warnings);
+        }
+
+        let is_default_group = attributes.get("group").map_or(false, |group| {
+            group.get("isDefault").and_then(Value::as_bool) == Some(true)
+        });
+        if is_default_group {
+            warnings.push("default build and test tasks are not supported".to_string());
+        }
+        if attributes.get("isBackground").and_then(Value::as_bool) == Some(true) {
+            warnings.push(
+                "background tasks run as regular ones, tasks depending on them wait for them to exit"
+                    .to_string(),
+            );
+        }
+        let mut unsupported_attributes = attributes
+            .keys()
+            .filter(|key| !KNOWN_ATTRIBUTES.contains(&key.as_str()))
+            .collect::<Vec<_>>();
+        unsupported_attributes.sort();
+        for attribute in unsupported_attributes {
+            warnings.push(format!("`{attribute}` is not supported"));
+        }
+
+        let mut seen_warnings = HashSet::default();
+        warnings.retain(|warning| seen_warnings.insert(warning.clone()));
+        Ok((ret, warnings))
+    }
+}
+
+fn apply_presentation(
+    presentation: &Presentation,
+    template: &mut TaskTemplate,
+    warnings: &mut Vec<String>,
+) {
+    match presentation.reveal.as_deref() {
+        None | Some("always") => template.reveal = RevealStrategy::Always,
+        Some("silent") | Some("never") => template.reveal = RevealStrategy::Never,
+        Some(reveal) => warnings.push(format!(
+            "`presentation.reveal` value `{reveal}` is not supported"
+        )),
+    }
+    match presentation.panel.as_deref() {
+        None | Some("shared") | Some("dedicated") => {}
+        Some("new") => {
+            template.use_new_terminal = true;
+            template.allow_concurrent_runs = true;
+        }
+        Some(panel) => warnings.push(format!(
+            "`presentation.panel` value `{panel}` is not supported"
+        )),
+    }
+    if presentation.clear {
+        warnings.push("`presentation.clear` is not supported".to_string());
+    }
+    if presentation.close {
+        warnings.push("`presentation.close` is not supported".to_string());
+    }
+    if presentation.echo == Some(false) {
+        warnings.push("`presentation.echo` is not supported".to_string());
+    }
+    if presentation.group.is_some() {
+        warnings.push("`presentation.group` is not supported".to_string());
+    }
+}
+
+fn convert_problem_matchers(
+    value: &Value,
+    cwd: &str,
+    replacer: &EnvVariableReplacer,
+    warnings: &mut Vec<String>,
+) -> Vec<ProblemMatcher> {
+    let matchers = match value {
+        Value::Array(matchers) => matchers.iter().collect(),
+        matcher => vec![matcher],
+    };
+    matchers
+        .into_iter()
+        .filter_map(|matcher| match matcher {
+            Value::String(name) => preset_problem_matcher(name, warnings),
+            Value::Object(_) => convert_problem_matcher(matcher.clone(), cwd, replacer, warnings),
+            other => {
+                warnings.push(format!("problem matcher {other} is not supported"));
+                None
+            }
+        })
+        .collect()
+}
+
+fn preset_problem_matcher(name: &str, warnings: &mut Vec<String>) -> Option<ProblemMatcher> {
+    let preset = match name {
+        "$rustc" | "$rustc-watch" => "$rustc",
+        "$tsc" | "$tsc-watch" => "$tsc",
+        "$gcc" => "$gcc",
+        "$eslint-stylish" => "$eslint-stylish",
+        _ => {
+            warnings.push(format!("problem matcher `{name}` is not supported"));
+            return None;
+        }
+    };
+    Some(ProblemMatcher::Preset(preset.to_string()))
+}
+
+fn convert_problem_matcher(
+    value: Value,
+    cwd: &str,
+    replacer: &EnvVariableReplacer,
+    warnings: &mut Vec<String>,
+) -> Option<ProblemMatcher> {
+    let matcher = match serde_json_lenient::from_value::<VsCodeProblemMatcher>(value) {
+        Ok(matcher) => matcher,
+        Err(e) => {
+            warnings.push(format!("invalid problem matcher: {e}"));
+            return None;
+        }
+    };
+    if let Some(file_location) = &matcher.file_location {
+        check_file_location(file_location, cwd, replacer, warnings);
+    }
+
+    let Some(pattern) = matcher.pattern else {
+        let Some(base) = matcher.base else {
+            warnings.push("problem matcher has neither `base` nor `pattern`".to_string());
+            return None;
+        };
+        return preset_problem_matcher(&base, warnings);
+    };
+    let patterns = match pattern {
+        Value::Array(patterns) => patterns,
+        Value::String(name) => {
+            warnings.push(format!("named problem pattern `{name}` is not supported"));
+            return None;
+        }
+        pattern => vec![pattern],
+    };
+    let pattern = patterns
+        .into_iter()
+        .map(|pattern| {
+            let pattern = serde_json_lenient::from_value::<VsCodeProblemPattern>(pattern).ok()?;
+            if pattern.location.is_some() {
+                warnings.push(
+                    "`location` problem pattern groups are not supported, use `line` and `column` instead"
+                        .to_string(),
+                );
+            }
+            Some(ProblemPattern {
+                regexp: pattern.regexp,
+                file: pattern.file,
+                line: pattern.line,
+                column: pattern.column,
+                end_line: pattern.end_line,
+                end_column: pattern.end_column,
+                severity: pattern.severity,
+                code: pattern.code,
+                message: pattern.message,
+                repeat: pattern.repeat,
+            })
+        })
+        .collect::<Option<Vec<_>>>();
+    let Some(pattern) = pattern else {
+        warnings.push("invalid problem pattern".to_string());
+        return None;
+    };
+    Some(ProblemMatcher::Custom(CustomProblemMatcher {
+        owner: matcher.owner,
+        severity: matcher.severity.unwrap_or_default(),
+        pattern,
+    }))
+}
+
+/// Zed resolves relative problem paths against the task's working directory, warn if VS Code would do otherwise.
+fn check_file_location(
+    file_location: &Value,
+    cwd: &str,
+    replacer: &EnvVariableReplacer,
+    warnings: &mut Vec<String>,
+) {
+    let (kind, base) = match file_location {
+        Value::String(kind) => (kind.as_str(), None),
+        Value::Array(location) => (
+            location.first().and_then(Value::as_str).unwrap_or_default(),
+            location.get(1).and_then(Value::as_str),
+        ),
+        _ => return,
+    };
+    match kind {
+        "absolute" | "autoDetect" => {}
+        "relative" => {
+            let worktree_root = format!("${{{}}}", VariableName::WorktreeRoot);
+            let base = match base {
+                Some(base) => replacer
+                    .replace(base, warnings)
+                    .unwrap_or_else(|_| base.to_string()),
+                None => worktree_root,
+            };
+            if base.trim_end_matches('/') != cwd.trim_end_matches('/') {
+                warnings.push(format!(
+                    "problem paths are resolved against the task's working directory, not `{base}`"
+                ));
+            }
         }
-        Ok(ret)
+        kind => warnings.push(format!(
+            "problem matcher `fileLocation` `{kind}` is not supported"
+        )),
     }
 }
 
+/// Debug configuration properties that are handled by VS Code rather than the debug adapter, and are safe to ignore.
+const VSCODE_ONLY_LAUNCH_ATTRIBUTES: &[&str] = &["presentation", "internalConsoleOptions"];
+
+/// Debug configuration properties that have no Zed counterpart.
+const UNSUPPORTED_LAUNCH_ATTRIBUTES: &[&str] =
+    &["postDebugTask", "debugServer", "serverReadyAction"];
+
+/// The debug adapter for the VS Code debug type: the adapter kind, and the command with the arguments to spawn it with.
+fn debug_adapter(kind: &str) -> Option<(&'static str, &'static str, &'static [&'static str])> {
+    match kind {
+        "lldb" | "lldb-dap" => Some(("lldb", "lldb-dap", &[])),
+        "debugpy" | "python" => Some(("debugpy", "python3", &["-m", "debugpy.adapter"])),
+        "go" => Some(("go", "dlv", &["dap"])),
+        "gdb" => Some(("gdb", "gdb", &["--interpreter=dap"])),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeLaunchConfiguration {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    request: String,
+    pre_launch_task: Option<String>,
+    #[serde(flatten)]
+    other_attributes: HashMap<String, Value>,
+}
+
+impl VsCodeLaunchConfiguration {
+    /// Converts the configuration into a debug task template, and warnings about the properties that could not be converted.
+    fn to_zed_format(
+        mut self,
+        replacer: &EnvVariableReplacer,
+    ) -> anyhow::Result<(TaskTemplate, Vec<String>)> {
+        let Some((adapter, command, args)) = debug_adapter(&self.kind) else {
+            bail!("Unsupported debug type `{}`", self.kind);
+        };
+        let request = match self.request.as_str() {
+            "launch" => DebugRequestKind::Launch,
+            "attach" => DebugRequestKind::Attach,
+            request => bail!("Unsupported request `{request}`"),
+        };
+
+        let mut warnings = Vec::new();
+        let overrides = self.other_attributes.remove(platform_key());
+        self.other_attributes
+            .retain(|key, _| !["linux", "osx", "windows"].contains(&key.as_str()));
+        let mut other_attributes = self.other_attributes.into_iter().collect::<Vec<_>>();
+        other_attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut attributes = serde_json::Map::new();
+        for (key, value) in other_attributes {
+            if VSCODE_ONLY_LAUNCH_ATTRIBUTES.contains(&key.as_str()) {
+                continue;
+            }
+            if UNSUPPORTED_LAUNCH_ATTRIBUTES.contains(&key.as_str()) {
+                warnings.push(format!("`{key}` is not supported"));
+                continue;
+            }
+            attributes.insert(key, serde_json::to_value(value)?);
+        }
+        if let Some(overrides) = overrides {
+            match serde_json::to_value(overrides)? {
+                serde_json::Value::Object(overrides) => attributes.extend(overrides),
+                _ => bail!("Invalid `{}` configuration properties", platform_key()),
+            }
+        }
+        let configuration =
+            replacer.replace_in_json(serde_json::Value::Object(attributes), &mut warnings)?;
+
+        let mut template = TaskTemplate {
+            label: self.name,
+            command: command.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            depends_on: self.pre_launch_task.into_iter().collect(),
+            debug: Some(DebugConfig {
+                adapter: adapter.to_string(),
+                request,
+                configuration,
+            }),
+            ..TaskTemplate::default()
+        };
+        template.inputs = replacer.used_inputs(&template);
+
+        let mut seen_warnings = HashSet::default();
+        warnings.retain(|warning| seen_warnings.insert(warning.clone()));
+        Ok((template, warnings))
+    }
+}
+
+/// The replacer of the VS Code variables, with the inputs declared in the file.
+fn vscode_variable_replacer(inputs: &[VsCodeInput]) -> EnvVariableReplacer {
+    EnvVariableReplacer::new(HashMap::from_iter([
+        (
+            "workspaceFolder".to_owned(),
+            VariableName::WorktreeRoot.to_string(),
+        ),
+        (
+            "workspaceRoot".to_owned(),
+            VariableName::WorktreeRoot.to_string(),
+        ),
+        ("cwd".to_owned(), VariableName::WorktreeRoot.to_string()),
+        ("file".to_owned(), VariableName::File.to_string()),
+        ("lineNumber".to_owned(), VariableName::Row.to_string()),
+        (
+            "selectedText".to_owned(),
+            VariableName::SelectedText.to_string(),
+        ),
+    ]))
+    .with_inputs(inputs)
+}
+
+/// Converts the definitions, named by `describe` in the warnings, skipping the ones that cannot be converted.
+fn convert_definitions<D>(
+    definitions: Vec<D>,
+    describe: impl Fn(&D) -> String,
+    convert: impl Fn(D) -> anyhow::Result<(TaskTemplate, Vec<String>)>,
+    all_warnings: &mut Vec<String>,
+) -> Vec<TaskTemplate> {
+    definitions
+        .into_iter()
+        .filter_map(|definition| {
+            let description = describe(&definition);
+            match convert(definition) {
+                Ok((template, warnings)) => {
+                    all_warnings.extend(
+                        warnings
+                            .into_iter()
+                            .map(|warning| format!("{description}: {warning}")),
+                    );
+                    Some(template)
+                }
+                Err(e) => {
+                    all_warnings.push(format!("{description} is skipped: {e:#}"));
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
+}
+
+impl VsCodeTaskFile {
+    /// Converts the tasks into Zed task templates, skipping the ones that cannot be converted.
+    /// Also returns warnings about every skipped task and every task property that could not be converted as is.
+    pub fn into_zed_templates(self) -> (TaskTemplates, Vec<String>) {
+        let replacer = vscode_variable_replacer(&self.inputs);
+        let mut warnings = Vec::new();
+        let templates = convert_definitions(
+            self.tasks,
+            |definition| format!("Task `{}`", definition.label),
+            |definition| definition.to_zed_format(&replacer),
+            &mut warnings,
+        );
+        (TaskTemplates(templates), warnings)
+    }
+}
+
+/// [`VsCodeLaunchFile`] is a superset of Code's debug configuration format, used in `launch.json`.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct VsCodeLaunchFile {
+    #[serde(default)]
+    configurations: Vec<VsCodeLaunchConfiguration>,
+    #[serde(default)]
+    compounds: Vec<Value>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
+}
+
+impl VsCodeLaunchFile {
+    /// Converts the debug configurations into Zed debug task templates, skipping the ones that cannot be converted.
+    /// Also returns warnings about every skipped configuration and every property that could not be converted as is.
+    pub fn into_zed_templates(self) -> (TaskTemplates, Vec<String>) {
+        let replacer = vscode_variable_replacer(&self.inputs);
+        let mut warnings = Vec::new();
+        let templates = convert_definitions(
+            self.configurations,
+            |configuration| format!("Configuration `{}`", configuration.name),
+            |configuration| configuration.to_zed_format(&replacer),
+            &mut warnings,
+        );
+        if !self.compounds.is_empty() {
+            warnings.push("Compound configurations are not supported".to_string());
+        }
+        (TaskTemplates(templates), warnings)
+    }
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
     type Error = anyhow::Error;
 
     fn try_from(value: VsCodeTaskFile) -> Result<Self, Self::Error> {
-        let replacer = EnvVariableReplacer::new(HashMap::from_iter([
-            (
-                "workspaceFolder".to_owned(),
-                VariableName::WorktreeRoot.to_string(),
-            ),
-            ("file".to_owned(), VariableName::File.to_string()),
-            ("lineNumber".to_owned(), VariableName::Row.to_string()),
-            (
-                "selectedText".to_owned(),
-                VariableName::SelectedText.to_string(),
-            ),
-        ]));
-        let templates = value
-            .tasks
-            .into_iter()
-            .filter_map(|vscode_definition| vscode_definition.to_zed_format(&replacer).log_err())
-            .collect();
-        Ok(Self(templates))
+        let (templates, warnings) = value.into_zed_templates();
+        for warning in warnings {
+            log::warn!("VS Code tasks import: {warning}");
+        }
+        Ok(templates)
     }
 }
 
@@ -155,10 +1018,12 @@ mod tests {
 
     use crate::{
         vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        CustomProblemMatcher, DebugConfig, DebugRequestKind, DependsOrder, ProblemMatcher,
+        ProblemPattern, ProblemSeverity, RevealStrategy, TaskInput, TaskInputKind, TaskTemplate,
+        TaskTemplates, VsCodeLaunchFile, VsCodeTaskFile,
     };
 
-    use super::EnvVariableReplacer;
+    use super::{platform_key, EnvVariableReplacer};
 
     fn compare_without_other_attributes(lhs: VsCodeTaskDefinition, rhs: VsCodeTaskDefinition) {
         assert_eq!(
@@ -175,27 +1040,56 @@ mod tests {
 
     #[test]
     fn test_variable_substitution() {
+        let replace = |replacer: &EnvVariableReplacer, input: &str| {
+            let mut warnings = Vec::new();
+            let replaced = replacer.replace(input, &mut warnings).unwrap();
+            assert_eq!(warnings, Vec::<String>::new());
+            replaced
+        };
         let replacer = EnvVariableReplacer::new(Default::default());
-        assert_eq!(replacer.replace("Food"), "Food");
+        assert_eq!(replace(&replacer, "Food"), "Food");
         // Unknown variables are left in tact.
         assert_eq!(
-            replacer.replace("$PATH is an environment variable"),
+            replace(&replacer, "$PATH is an environment variable"),
             "$PATH is an environment variable"
         );
-        assert_eq!(replacer.replace("${PATH}"), "${PATH}");
-        assert_eq!(replacer.replace("${PATH:food}"), "${PATH:food}");
+        assert_eq!(replace(&replacer, "${PATH}"), "${PATH}");
+        assert_eq!(replace(&replacer, "${PATH:food}"), "${PATH:food}");
+        assert_eq!(replace(&replacer, "${env:HOME}/bin"), "${HOME}/bin");
         // And now, the actual replacing
         let replacer = EnvVariableReplacer::new(HashMap::from_iter([(
             "PATH".to_owned(),
             "ZED_PATH".to_owned(),
         )]));
-        assert_eq!(replacer.replace("Food"), "Food");
+        assert_eq!(replace(&replacer, "Food"), "Food");
         assert_eq!(
-            replacer.replace("$PATH is an environment variable"),
+            replace(&replacer, "$PATH is an environment variable"),
             "${ZED_PATH} is an environment variable"
         );
-        assert_eq!(replacer.replace("${PATH}"), "${ZED_PATH}");
-        assert_eq!(replacer.replace("${PATH:food}"), "${ZED_PATH:food}");
+        assert_eq!(replace(&replacer, "${PATH}"), "${ZED_PATH}");
+        assert_eq!(replace(&replacer, "${PATH:food}"), "${ZED_PATH:food}");
+
+        let mut warnings = Vec::new();
+        assert_eq!(
+            replacer
+                .replace("${relativeFile} ${config:editor.tabSize}", &mut warnings)
+                .unwrap(),
+            "${relativeFile} ${config:editor.tabSize}"
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "variable `${relativeFile}` is not supported",
+                "variable `${config:editor.tabSize}` is not supported"
+            ]
+        );
+        assert_eq!(
+            replacer
+                .replace("${input:target}", &mut Vec::new())
+                .unwrap_err()
+                .to_string(),
+            "Input `target` is not declared"
+        );
     }
 
     #[test]
@@ -252,11 +1146,13 @@ mod tests {
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
 
+        let tsc = || vec![ProblemMatcher::Preset("$tsc".to_string())];
         let expected = vec![
             TaskTemplate {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: tsc(),
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +1164,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: tsc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: tsc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: tsc(),
                 ..Default::default()
             },
         ];
@@ -355,37 +1254,305 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let tsc = || vec![ProblemMatcher::Preset("$tsc".to_string())];
+        let rustc = || vec![ProblemMatcher::Preset("$rustc".to_string())];
+        let extension_dir = || Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string());
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                cwd: extension_dir(),
+                problem_matcher: tsc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                cwd: extension_dir(),
+                problem_matcher: tsc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: rustc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: rustc(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                cwd: extension_dir(),
+                problem_matcher: tsc(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: rustc(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: rustc(),
                 ..Default::default()
             },
         ];
-        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        let (tasks, warnings) = vscode_definitions.into_zed_templates();
         assert_eq!(tasks.0, expected);
+        assert_eq!(
+            warnings,
+            vec!["Task `Build Extension in Background`: background tasks run as regular ones, tasks depending on them wait for them to exit"]
+        );
+    }
+
+    #[test]
+    fn can_convert_all_task_properties() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "inputs": [
+                    { "id": "profile", "type": "pickString", "options": ["dev", "release"] },
+                    { "id": "target", "type": "promptString", "description": "Target?" },
+                    { "id": "branch", "type": "command", "command": "git.branch" }
+                ],
+                "tasks": [
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make",
+                        "args": ["PROFILE=${input:profile}", { "value": "my dir", "quoting": "strong" }],
+                        "linux": { "command": "build-linux" },
+                        "osx": { "command": "build-osx" },
+                        "windows": { "command": "build-windows" },
+                        "options": {
+                            "cwd": "${workspaceFolder:app}/src",
+                            "env": { "OUT": "${env:HOME}/out" },
+                            "shell": { "executable": "bash", "args": ["-c"] }
+                        },
+                        "presentation": { "reveal": "silent", "panel": "new", "clear": true },
+                        "group": { "kind": "build", "isDefault": true },
+                        "problemMatcher": [
+                            "$gcc",
+                            "$msCompile",
+                            {
+                                "owner": "lint",
+                                "severity": "warning",
+                                "fileLocation": ["relative", "${workspaceFolder}"],
+                                "pattern": { "regexp": "^(.*):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }
+                            }
+                        ],
+                        "runOptions": { "runOn": "folderOpen" }
+                    },
+                    {
+                        "label": "deploy",
+                        "type": "process",
+                        "command": "deploy",
+                        "args": ["${input:target}"]
+                    },
+                    {
+                        "label": "all",
+                        "dependsOn": "build",
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "checkout",
+                        "type": "shell",
+                        "command": "git checkout ${input:branch}"
+                    },
+                    {
+                        "label": "docker",
+                        "type": "docker-build"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let (tasks, warnings) = vscode_definitions.into_zed_templates();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "bash".to_string(),
+                    args: vec![
+                        "-c".to_string(),
+                        format!(
+                            r"'build-{} PROFILE=${{ZED_INPUT_profile}} '\''my dir'\'''",
+                            platform_key()
+                        ),
+                    ],
+                    cwd: Some("${ZED_WORKTREE_ROOT}/src".to_string()),
+                    env: HashMap::from_iter([("OUT".to_string(), "${HOME}/out".to_string())])
+                        .into_iter()
+                        .collect(),
+                    use_new_terminal: true,
+                    allow_concurrent_runs: true,
+                    reveal: RevealStrategy::Never,
+                    inputs: vec![TaskInput {
+                        id: "profile".to_string(),
+                        description: None,
+                        kind: TaskInputKind::Pick {
+                            options: vec!["dev".to_string(), "release".to_string()],
+                            default: None,
+                        },
+                    }],
+                    problem_matcher: vec![
+                        ProblemMatcher::Preset("$gcc".to_string()),
+                        ProblemMatcher::Custom(CustomProblemMatcher {
+                            owner: Some("lint".to_string()),
+                            severity: ProblemSeverity::Warning,
+                            pattern: vec![ProblemPattern {
+                                regexp: r"^(.*):(\d+): (.*)$".to_string(),
+                                file: Some(1),
+                                line: Some(2),
+                                message: Some(3),
+                                ..Default::default()
+                            }],
+                        }),
+                    ],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "deploy".to_string(),
+                    args: vec!["${ZED_INPUT_target}".to_string()],
+                    inputs: vec![TaskInput {
+                        id: "target".to_string(),
+                        description: Some("Target?".to_string()),
+                        kind: TaskInputKind::Prompt { default: None },
+                    }],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "all".to_string(),
+                    depends_on: vec!["build".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "Task `build`: `${workspaceFolder:app}` is replaced with the root of the worktree the task runs in",
+                "Task `build`: `presentation.clear` is not supported",
+                "Task `build`: problem matcher `$msCompile` is not supported",
+                "Task `build`: problem paths are resolved against the task's working directory, not `${ZED_WORKTREE_ROOT}`",
+                "Task `build`: default build and test tasks are not supported",
+                "Task `build`: `runOptions` is not supported",
+                "Task `checkout` is skipped: Input `branch` of type `command` is not supported",
+                "Task `docker` is skipped: Unsupported task type `docker-build`",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_convert_launch_configurations() {
+        let launch_file = serde_json_lenient::from_str::<VsCodeLaunchFile>(
+            r#"{
+                "version": "0.2.0",
+                "configurations": [
+                    {
+                        "name": "Debug app",
+                        "type": "lldb",
+                        "request": "launch",
+                        "program": "${workspaceFolder}/target/debug/app",
+                        "args": ["--config", "${input:config}"],
+                        "cwd": "${workspaceFolder}",
+                        "preLaunchTask": "cargo build",
+                        "postDebugTask": "clean",
+                        "internalConsoleOptions": "neverOpen",
+                        "linux": { "env": { "DISPLAY": ":0" } },
+                        "osx": { "env": { "DISPLAY": ":0" } },
+                        "windows": { "env": { "DISPLAY": ":0" } },
+                    },
+                    {
+                        "name": "Attach to server",
+                        "type": "debugpy",
+                        "request": "attach",
+                        "connect": { "host": "localhost", "port": 5678 }
+                    },
+                    {
+                        "name": "Chrome",
+                        "type": "chrome",
+                        "request": "launch"
+                    }
+                ],
+                "compounds": [
+                    { "name": "All", "configurations": ["Debug app", "Attach to server"] }
+                ],
+                "inputs": [
+                    { "id": "config", "type": "promptString", "default": "dev.toml" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let (tasks, warnings) = launch_file.into_zed_templates();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "Debug app".to_string(),
+                    command: "lldb-dap".to_string(),
+                    depends_on: vec!["cargo build".to_string()],
+                    inputs: vec![TaskInput {
+                        id: "config".to_string(),
+                        description: None,
+                        kind: TaskInputKind::Prompt {
+                            default: Some("dev.toml".to_string()),
+                        },
+                    }],
+                    debug: Some(DebugConfig {
+                        adapter: "lldb".to_string(),
+                        request: DebugRequestKind::Launch,
+                        configuration: serde_json::json!({
+                            "program": "${ZED_WORKTREE_ROOT}/target/debug/app",
+                            "args": ["--config", "${ZED_INPUT_config}"],
+                            "cwd": "${ZED_WORKTREE_ROOT}",
+                            "env": { "DISPLAY": ":0" },
+                        }),
+                    }),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "Attach to server".to_string(),
+                    command: "python3".to_string(),
+                    args: vec!["-m".to_string(), "debugpy.adapter".to_string()],
+                    debug: Some(DebugConfig {
+                        adapter: "debugpy".to_string(),
+                        request: DebugRequestKind::Attach,
+                        configuration: serde_json::json!({
+                            "connect": { "host": "localhost", "port": 5678 },
+                        }),
+                    }),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "Configuration `Debug app`: `postDebugTask` is not supported",
+                "Configuration `Chrome` is skipped: Unsupported debug type `chrome`",
+                "Compound configurations are not supported",
+            ]
+        );
     }
 }
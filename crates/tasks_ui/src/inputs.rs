@@ -0,0 +1,660 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AsyncWindowContext, DismissEvent, EventEmitter, FocusableView, Model, ParentElement,
+    Render, SharedString, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{highlighted_match_with_paths::HighlightedText, Picker, PickerDelegate};
+use project::{Project, TaskSourceKind};
+use task::{TaskContext, TaskInput, TaskInputKind, TaskTemplate};
+use ui::{v_flex, Color, ListItem, ListItemSpacing, RenderOnce, Selectable, WindowContext};
+use util::ResultExt;
+use workspace::{notifications::NotificationId, tasks::schedule_task, ModalView, Toast, Workspace};
+
+/// Asks for the task inputs, if it has any, and spawns the task with them.
+///
+/// Unless `ask_again` is set, the inputs that have a value in the task context or were answered for the same task before
+/// are not asked for.
+pub(crate) fn schedule_task_with_inputs(
+    workspace: &mut Workspace,
+    task_source_kind: TaskSourceKind,
+    task_to_resolve: &TaskTemplate,
+    task_cx: &TaskContext,
+    ask_again: bool,
+    omit_history: bool,
+    cx: &mut ViewContext<'_, Workspace>,
+) {
+    if task_to_resolve.inputs.is_empty() {
+        schedule_task(
+            workspace,
+            task_source_kind,
+            task_to_resolve,
+            task_cx,
+            omit_history,
+            cx,
+        );
+        return;
+    }
+
+    let template = task_to_resolve.clone();
+    let mut task_cx = task_cx.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let answers_key = answers_key(&task_source_kind, &template);
+        let mut answers = {
+            let answers_key = answers_key.clone();
+            cx.background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(&answers_key) })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|answers| {
+                    serde_json::from_str::<HashMap<String, String>>(&answers).log_err()
+                })
+                .unwrap_or_default()
+        };
+
+        for input in &template.inputs {
+            let variable = input.variable_name();
+            let value = match &input.kind {
+                TaskInputKind::Command { command, args } => {
+                    match input_command_output(command, args, task_cx.cwd.clone()).await {
+                        Ok(output) => output,
+                        Err(e) => {
+                            workspace.update(&mut cx, |workspace, cx| {
+                                struct TaskInputError;
+                                workspace.show_toast(
+                                    Toast::new(
+                                        NotificationId::unique::<TaskInputError>(),
+                                        format!(
+                                            "Cannot get input `{}` of task `{}`: {e:#}",
+                                            input.id, template.label
+                                        ),
+                                    ),
+                                    cx,
+                                );
+                            })?;
+                            return Ok(());
+                        }
+                    }
+                }
+                TaskInputKind::Prompt { .. }
+                | TaskInputKind::Pick { .. }
+                | TaskInputKind::PickFile => {
+                    let last_value = task_cx
+                        .task_variables
+                        .get(&variable)
+                        .map(ToString::to_string)
+                        .or_else(|| answers.get(&input.id).cloned());
+                    let value = match last_value.clone().filter(|_| !ask_again) {
+                        Some(value) => value,
+                        None => {
+                            match ask_for_input(&workspace, input, &template, last_value, &mut cx)
+                                .await?
+                            {
+                                Some(value) => value,
+                                // The user has dismissed the prompt, do not spawn the task.
+                                None => return Ok(()),
+                            }
+                        }
+                    };
+                    answers.insert(input.id.clone(), value.clone());
+                    value
+                }
+            };
+            task_cx.task_variables.insert(variable, value);
+        }
+
+        let answers = serde_json::to_string(&answers)?;
+        workspace.update(&mut cx, |workspace, cx| {
+            db::write_and_log(cx, move || KEY_VALUE_STORE.write_kvp(answers_key, answers));
+            schedule_task(
+                workspace,
+                task_source_kind,
+                &template,
+                &task_cx,
+                omit_history,
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The key to store the last input values of the task by: tasks from the same file and with the same label share them.
+fn answers_key(task_source_kind: &TaskSourceKind, template: &TaskTemplate) -> String {
+    let source = match task_source_kind.abs_path() {
+        Some(abs_path) => abs_path.display().to_string(),
+        None => task_source_kind.to_id_base(),
+    };
+    format!("task_inputs_{source}_{}", template.label)
+}
+
+async fn input_command_output(
+    command: &str,
+    args: &[String],
+    cwd: Option<PathBuf>,
+) -> anyhow::Result<String> {
+    let mut command = smol::process::Command::new(command);
+    command.args(args);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let output = command.output().await.context("spawning the command")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "command failed with {}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn ask_for_input(
+    workspace: &WeakView<Workspace>,
+    input: &TaskInput,
+    template: &TaskTemplate,
+    last_value: Option<String>,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<Option<String>> {
+    let (answer_tx, answer_rx) = oneshot::channel();
+    workspace.update(cx, |workspace, cx| {
+        let project = workspace.project().clone();
+        let input = input.clone();
+        let task_label = template.label.clone();
+        workspace.toggle_modal(cx, move |cx| {
+            TaskInputModal::new(input, task_label, last_value, project, answer_tx, cx)
+        });
+    })?;
+    Ok(answer_rx.await.ok())
+}
+
+/// A modal to get the value of a single [`TaskInput`] with.
+pub(crate) struct TaskInputModal {
+    picker: View<Picker<TaskInputModalDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputModal {
+    fn new(
+        input: TaskInput,
+        task_label: String,
+        last_value: Option<String>,
+        project: Model<Project>,
+        answer_tx: oneshot::Sender<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let candidates = match &input.kind {
+            TaskInputKind::Pick { options, .. } => options
+                .iter()
+                .map(|option| (option.clone(), option.clone()))
+                .collect(),
+            TaskInputKind::PickFile => project_files(&project, cx),
+            TaskInputKind::Prompt { .. } | TaskInputKind::Command { .. } => Vec::new(),
+        };
+        let initial_value = last_value.or_else(|| input.default_value().map(ToString::to_string));
+        let placeholder_text = Arc::from(match &input.description {
+            Some(description) => description.clone(),
+            None => format!("Enter `{}` for task `{task_label}`", input.id),
+        });
+        let is_prompt = matches!(input.kind, TaskInputKind::Prompt { .. });
+        let delegate = TaskInputModalDelegate {
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+            selected_value: if is_prompt {
+                None
+            } else {
+                initial_value.clone()
+            },
+            query: String::new(),
+            is_prompt,
+            placeholder_text,
+            answer_tx: Some(answer_tx),
+        };
+        let picker = cx.new_view(|cx| {
+            let picker = Picker::uniform_list(delegate, cx);
+            if is_prompt {
+                if let Some(initial_value) = initial_value {
+                    picker.set_query(initial_value, cx);
+                }
+            }
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+/// Files of the visible project worktrees: absolute paths and the worktree-relative ones, to display.
+fn project_files(project: &Model<Project>, cx: &mut WindowContext) -> Vec<(String, String)> {
+    let project = project.read(cx);
+    let include_root_name = project.visible_worktrees(cx).count() > 1;
+    project
+        .visible_worktrees(cx)
+        .flat_map(|worktree| {
+            let worktree = worktree.read(cx);
+            let abs_path = worktree.abs_path();
+            let root_name = worktree.root_name().to_string();
+            worktree
+                .files(false, 0)
+                .map(|entry| {
+                    let display_path = if include_root_name {
+                        format!("{root_name}/{}", entry.path.display())
+                    } else {
+                        entry.path.display().to_string()
+                    };
+                    (
+                        display_path,
+                        abs_path.join(&entry.path).display().to_string(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+impl Render for TaskInputModal {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl gpui::prelude::IntoElement {
+        v_flex()
+            .key_context("TaskInputModal")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputModal {}
+
+impl FocusableView for TaskInputModal {
+    fn focus_handle(&self, cx: &gpui::AppContext) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for TaskInputModal {}
+
+pub(crate) struct TaskInputModalDelegate {
+    /// Labels to display and values to answer with, for the inputs that are picked from a list.
+    candidates: Vec<(String, String)>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    /// The value to select after the matches are updated for the first time.
+    selected_value: Option<String>,
+    query: String,
+    is_prompt: bool,
+    placeholder_text: Arc<str>,
+    answer_tx: Option<oneshot::Sender<String>>,
+}
+
+impl PickerDelegate for TaskInputModalDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        self.placeholder_text.clone()
+    }
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        if self.is_prompt {
+            "Press enter to use the typed value".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, (label, _))| StringMatchCandidate::new(id, label.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.0,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.query = query;
+                    let selected_value = delegate.selected_value.take();
+                    delegate.selected_index = selected_value
+                        .and_then(|selected_value| {
+                            delegate.matches.iter().position(|string_match| {
+                                delegate.candidates[string_match.candidate_id].1 == selected_value
+                            })
+                        })
+                        .unwrap_or(0);
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let answer = if self.is_prompt {
+            Some(self.query.clone())
+        } else {
+            self.matches
+                .get(self.selected_index)
+                .map(|string_match| self.candidates[string_match.candidate_id].1.clone())
+        };
+        let Some(answer) = answer else {
+            return;
+        };
+        if let Some(answer_tx) = self.answer_tx.take() {
+            answer_tx.send(answer).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let highlighted_text = HighlightedText {
+            text: hit.string.clone(),
+            highlight_positions: hit.positions.clone(),
+            char_count: hit.string.chars().count(),
+            color: Color::Default,
+        };
+        Some(
+            ListItem::new(SharedString::from(format!("task-input-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(highlighted_text.render(cx)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc, StreamExt as _};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+
+    use crate::tests::init_test;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_prompt_input(cx: &mut TestAppContext) {
+        let (workspace, mut spawned, cx) = init_workspace(cx).await;
+        let input = TaskInput {
+            id: "name".to_string(),
+            description: Some("Who to greet".to_string()),
+            kind: TaskInputKind::Prompt {
+                default: Some("world".to_string()),
+            },
+        };
+        let task = task_with_input("greet the prompted name", input);
+
+        schedule(&workspace, &task, false, cx);
+        let picker = input_picker(&workspace, cx).expect("no modal to ask for the prompt input");
+        assert_eq!(
+            picker.update(cx, |picker, cx| picker.query(cx)),
+            "world",
+            "The prompt should start with the default value"
+        );
+        assert_eq!(
+            picker
+                .update(cx, |picker, cx| picker.delegate.placeholder_text(cx))
+                .as_ref(),
+            "Who to greet"
+        );
+        cx.simulate_input("s");
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(input_picker(&workspace, cx).is_none());
+        assert_eq!(spawned.next().await.unwrap(), "echo worlds");
+
+        schedule(&workspace, &task, false, cx);
+        assert!(
+            input_picker(&workspace, cx).is_none(),
+            "The answer should be remembered for the task"
+        );
+        assert_eq!(spawned.next().await.unwrap(), "echo worlds");
+
+        schedule(&workspace, &task, true, cx);
+        let picker = input_picker(&workspace, cx).expect("no modal to ask for the input again");
+        assert_eq!(
+            picker.update(cx, |picker, cx| picker.query(cx)),
+            "worlds",
+            "The prompt should start with the remembered answer"
+        );
+        cx.dispatch_action(menu::Cancel);
+        cx.run_until_parked();
+        assert!(input_picker(&workspace, cx).is_none());
+        assert!(
+            spawned.try_next().is_err(),
+            "The task should not be spawned when the input is dismissed"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_pick_input(cx: &mut TestAppContext) {
+        let (workspace, mut spawned, cx) = init_workspace(cx).await;
+        let input = TaskInput {
+            id: "profile".to_string(),
+            description: None,
+            kind: TaskInputKind::Pick {
+                options: vec![
+                    "debug".to_string(),
+                    "release".to_string(),
+                    "release-lto".to_string(),
+                ],
+                default: Some("release".to_string()),
+            },
+        };
+        let task = task_with_input("build the picked profile", input);
+
+        schedule(&workspace, &task, false, cx);
+        let picker = input_picker(&workspace, cx).expect("no modal to ask for the pick input");
+        assert_eq!(
+            picker
+                .update(cx, |picker, cx| picker.delegate.placeholder_text(cx))
+                .as_ref(),
+            "Enter `profile` for task `build the picked profile`"
+        );
+        assert_eq!(
+            selected_value(&picker, cx).as_deref(),
+            Some("release"),
+            "The default option should be selected"
+        );
+        cx.simulate_input("lto");
+        assert_eq!(selected_value(&picker, cx).as_deref(), Some("release-lto"));
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert_eq!(spawned.next().await.unwrap(), "echo release-lto");
+
+        schedule(&workspace, &task, true, cx);
+        let picker = input_picker(&workspace, cx).expect("no modal to ask for the input again");
+        assert_eq!(
+            selected_value(&picker, cx).as_deref(),
+            Some("release-lto"),
+            "The remembered answer should be selected"
+        );
+        cx.dispatch_action(menu::Cancel);
+        cx.run_until_parked();
+        assert!(spawned.try_next().is_err());
+    }
+
+    #[gpui::test]
+    async fn test_pick_file_input(cx: &mut TestAppContext) {
+        let (workspace, mut spawned, cx) = init_workspace(cx).await;
+        let input = TaskInput {
+            id: "file".to_string(),
+            description: None,
+            kind: TaskInputKind::PickFile,
+        };
+        let task = task_with_input("print the picked file", input);
+
+        schedule(&workspace, &task, false, cx);
+        let picker = input_picker(&workspace, cx).expect("no modal to ask for the file input");
+        cx.simulate_input("b.rs");
+        assert_eq!(
+            selected_value(&picker, cx).as_deref(),
+            Some("/dir/src/b.rs")
+        );
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert_eq!(spawned.next().await.unwrap(), "echo /dir/src/b.rs");
+    }
+
+    #[gpui::test]
+    async fn test_command_input(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let (workspace, mut spawned, cx) = init_workspace(cx).await;
+        let input = TaskInput {
+            id: "greeting".to_string(),
+            description: None,
+            kind: TaskInputKind::Command {
+                command: "echo".to_string(),
+                args: vec!["hello from a command".to_string()],
+            },
+        };
+        let task = task_with_input("print the command output", input);
+
+        schedule(&workspace, &task, true, cx);
+        assert!(
+            input_picker(&workspace, cx).is_none(),
+            "The command input should not be asked for"
+        );
+        assert_eq!(spawned.next().await.unwrap(), "echo hello from a command");
+    }
+
+    /// Opens a workspace, returning the command labels of the tasks it spawns.
+    async fn init_workspace(
+        cx: &mut TestAppContext,
+    ) -> (
+        View<Workspace>,
+        mpsc::UnboundedReceiver<String>,
+        &mut VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.rs": "", "src": { "b.rs": "" } }))
+            .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (spawned_tx, spawned_rx) = mpsc::unbounded();
+        cx.update(|cx| {
+            cx.subscribe(&workspace, move |_, event: &workspace::Event, _| {
+                if let workspace::Event::SpawnTask(spawn_in_terminal) = event {
+                    spawned_tx
+                        .unbounded_send(spawn_in_terminal.command_label.clone())
+                        .ok();
+                }
+            })
+            .detach();
+        });
+        (workspace, spawned_rx, cx)
+    }
+
+    /// A task echoing its input; the answers are remembered by the label, so each test uses its own.
+    fn task_with_input(label: &str, input: TaskInput) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: "echo".to_string(),
+            args: vec![input.variable_name().template_value()],
+            inputs: vec![input],
+            ..TaskTemplate::default()
+        }
+    }
+
+    fn schedule(
+        workspace: &View<Workspace>,
+        task: &TaskTemplate,
+        ask_again: bool,
+        cx: &mut VisualTestContext,
+    ) {
+        workspace.update(cx, |workspace, cx| {
+            schedule_task_with_inputs(
+                workspace,
+                TaskSourceKind::UserInput,
+                task,
+                &TaskContext::default(),
+                ask_again,
+                false,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+    }
+
+    fn input_picker(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Option<View<Picker<TaskInputModalDelegate>>> {
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<TaskInputModal>(cx)
+                .map(|modal| modal.read(cx).picker.clone())
+        })
+    }
+
+    fn selected_value(
+        picker: &View<Picker<TaskInputModalDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Option<String> {
+        picker.update(cx, |picker, _| {
+            let delegate = &picker.delegate;
+            delegate
+                .matches
+                .get(delegate.selected_index)
+                .map(|string_match| delegate.candidates[string_match.candidate_id].1.clone())
+        })
+    }
+}
@@ -2,13 +2,15 @@ use std::sync::Arc;
 
 use ::settings::Settings;
 use editor::{tasks::task_context, Editor};
-use gpui::{AppContext, ViewContext, WindowContext};
+use gpui::{AppContext, Task, ViewContext, WindowContext};
+use inputs::schedule_task_with_inputs;
 use language::Language;
 use modal::TasksModal;
 use project::WorktreeId;
-use workspace::tasks::schedule_task;
+use task::TaskContext;
 use workspace::{tasks::schedule_resolved_task, Workspace};
 
+mod inputs;
 mod modal;
 mod settings;
 
@@ -34,12 +36,40 @@ pub fn init(cx: &mut AppContext) {
                             if let Some(use_new_terminal) = action.use_new_terminal {
                                 original_task.use_new_terminal = use_new_terminal;
                             }
-                            let task_context = task_context(workspace, cx);
-                            schedule_task(
+                            let task_context =
+                                provided_task_context(workspace, task_context(workspace, cx), cx);
+                            let reprompt_inputs = action.reprompt_inputs;
+                            cx.spawn(|workspace, mut cx| async move {
+                                let task_context = task_context.await;
+                                workspace.update(&mut cx, |workspace, cx| {
+                                    schedule_task_with_inputs(
+                                        workspace,
+                                        task_source_kind,
+                                        &original_task,
+                                        &task_context,
+                                        reprompt_inputs,
+                                        false,
+                                        cx,
+                                    )
+                                })
+                            })
+                            .detach_and_log_err(cx);
+                        } else if action.reprompt_inputs
+                            && !last_scheduled_task.original_task().inputs.is_empty()
+                        {
+                            let mut original_task = last_scheduled_task.original_task().clone();
+                            if let Some(allow_concurrent_runs) = action.allow_concurrent_runs {
+                                original_task.allow_concurrent_runs = allow_concurrent_runs;
+                            }
+                            if let Some(use_new_terminal) = action.use_new_terminal {
+                                original_task.use_new_terminal = use_new_terminal;
+                            }
+                            schedule_task_with_inputs(
                                 workspace,
                                 task_source_kind,
                                 &original_task,
-                                &task_context,
+                                last_scheduled_task.task_context(),
+                                true,
                                 false,
                                 cx,
                             )
@@ -81,14 +111,24 @@ fn toggle_modal(workspace: &mut Workspace, cx: &mut ViewContext<'_, Workspace>)
     let inventory = workspace.project().read(cx).task_inventory().clone();
     let workspace_handle = workspace.weak_handle();
     let task_context = task_context(workspace, cx);
+    let provided_task_context = provided_task_context(workspace, task_context.clone(), cx);
     workspace.toggle_modal(cx, |cx| {
         TasksModal::new(inventory, task_context, workspace_handle, cx)
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let task_context = provided_task_context.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            if let Some(modal) = workspace.active_modal::<TasksModal>(cx) {
+                modal.update(cx, |modal, cx| modal.set_task_context(task_context, cx));
+            }
+        })
     })
+    .detach_and_log_err(cx);
 }
 
 fn spawn_task_with_name(name: String, cx: &mut ViewContext<Workspace>) {
     cx.spawn(|workspace, mut cx| async move {
-        let did_spawn = workspace
+        let target_task = workspace
             .update(&mut cx, |workspace, cx| {
                 let (worktree, language) = active_item_selection_properties(workspace, cx);
                 let tasks = workspace.project().update(cx, |project, cx| {
@@ -98,20 +138,31 @@ fn spawn_task_with_name(name: String, cx: &mut ViewContext<Workspace>) {
                 });
                 let (task_source_kind, target_task) =
                     tasks.into_iter().find(|(_, task)| task.label == name)?;
-                let task_context = task_context(workspace, cx);
-                schedule_task(
-                    workspace,
-                    task_source_kind,
-                    &target_task,
-                    &task_context,
-                    false,
-                    cx,
-                );
-                Some(())
+                let task_context =
+                    provided_task_context(workspace, task_context(workspace, cx), cx);
+                Some((task_source_kind, target_task, task_context))
             })
             .ok()
-            .flatten()
-            .is_some();
+            .flatten();
+        let did_spawn = match target_task {
+            Some((task_source_kind, target_task, task_context)) => {
+                let task_context = task_context.await;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        schedule_task_with_inputs(
+                            workspace,
+                            task_source_kind,
+                            &target_task,
+                            &task_context,
+                            false,
+                            false,
+                            cx,
+                        );
+                    })
+                    .is_ok()
+            }
+            None => false,
+        };
         if !did_spawn {
             workspace
                 .update(&mut cx, |workspace, cx| {
@@ -123,6 +174,18 @@ fn spawn_task_with_name(name: String, cx: &mut ViewContext<Workspace>) {
     .detach();
 }
 
+/// Extends the task context with the variables of the project's task providers, for the worktree of the active item.
+fn provided_task_context(
+    workspace: &Workspace,
+    task_context: TaskContext,
+    cx: &mut WindowContext,
+) -> Task<TaskContext> {
+    let (worktree, _) = active_item_selection_properties(workspace, cx);
+    workspace.project().update(cx, |project, cx| {
+        project.task_context_with_provided_variables(worktree, task_context, cx)
+    })
+}
+
 fn active_item_selection_properties(
     workspace: &Workspace,
     cx: &mut WindowContext,
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::active_item_selection_properties;
+use crate::{active_item_selection_properties, inputs::schedule_task_with_inputs};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     impl_actions, rems, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusableView,
@@ -54,6 +54,10 @@ pub struct Rerun {
     /// Default: null
     #[serde(default)]
     pub use_new_terminal: Option<bool>,
+    /// Controls whether the task inputs are asked for again, instead of reusing the values from the last run.
+    /// default: false
+    #[serde(default)]
+    pub reprompt_inputs: bool,
 }
 
 impl_actions!(task, [Rerun, Spawn]);
@@ -153,6 +157,21 @@ impl TasksModal {
             _subscription,
         }
     }
+
+    /// Replaces the context to resolve the tasks with, relisting them if it has changed.
+    pub(crate) fn set_task_context(
+        &mut self,
+        task_context: TaskContext,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            if picker.delegate.task_context != task_context {
+                picker.delegate.task_context = task_context;
+                picker.delegate.candidates = None;
+                picker.refresh(cx);
+            }
+        });
+    }
 }
 
 impl Render for TasksModal {
@@ -282,22 +301,46 @@ impl PickerDelegate for TasksModalDelegate {
 
     fn confirm(&mut self, omit_history_entry: bool, cx: &mut ViewContext<picker::Picker<Self>>) {
         let current_match_index = self.selected_index();
-        let task = self
+        let Some(ix) = self
             .matches
             .get(current_match_index)
-            .and_then(|current_match| {
-                let ix = current_match.candidate_id;
-                self.candidates
-                    .as_ref()
-                    .map(|candidates| candidates[ix].clone())
-            });
+            .map(|current_match| current_match.candidate_id)
+        else {
+            return;
+        };
+        let task = self
+            .candidates
+            .as_ref()
+            .map(|candidates| candidates[ix].clone());
         let Some((task_source_kind, task)) = task else {
             return;
         };
+        // Previously used tasks are rerun with the same inputs, new ones ask for them.
+        let is_previously_used = self
+            .last_used_candidate_index
+            .map_or(false, |last_used_index| ix <= last_used_index);
 
         self.workspace
             .update(cx, |workspace, cx| {
-                schedule_resolved_task(workspace, task_source_kind, task, omit_history_entry, cx);
+                if is_previously_used || task.original_task().inputs.is_empty() {
+                    schedule_resolved_task(
+                        workspace,
+                        task_source_kind,
+                        task,
+                        omit_history_entry,
+                        cx,
+                    );
+                } else {
+                    schedule_task_with_inputs(
+                        workspace,
+                        task_source_kind,
+                        task.original_task(),
+                        &self.task_context,
+                        true,
+                        omit_history_entry,
+                        cx,
+                    );
+                }
             })
             .ok();
         cx.emit(DismissEvent);
@@ -350,6 +393,7 @@ impl PickerDelegate for TasksModalDelegate {
             TaskSourceKind::UserInput => Some(Icon::new(IconName::Terminal)),
             TaskSourceKind::AbsPath { .. } => Some(Icon::new(IconName::Settings)),
             TaskSourceKind::Worktree { .. } => Some(Icon::new(IconName::FileTree)),
+            TaskSourceKind::Provider { .. } => Some(Icon::new(IconName::Library)),
             TaskSourceKind::Language { name } => file_icons::FileIcons::get(cx)
                 .get_type_icon(&name.to_lowercase())
                 .map(|icon_path| Icon::from_path(icon_path)),
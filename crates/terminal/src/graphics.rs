@@ -0,0 +1,1379 @@
+//! Inline images, sent with the sixel and the kitty graphics protocol sequences, which alacritty ignores.
+//!
+//! The PTY output is scanned for the image sequences before it reaches alacritty's parser.
+//! Every image is replaced with a block of blank cells, as large as the image, hyperlinked to the image.
+//! The cells keep the image anchored to its place in the grid, as the output moves into the scrollback history,
+//! and the terminal element draws the image over them.
+
+#![cfg_attr(not(unix), allow(dead_code))]
+
+#[cfg(unix)]
+use std::io;
+use std::{
+    collections::VecDeque,
+    io::{Cursor, Read as _, Write as _},
+    mem,
+    path::Path,
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::WindowSize,
+    index::{Line, Point as AlacPoint},
+    term::cell::Hyperlink,
+};
+use collections::{HashMap, HashSet};
+use gpui::ImageData;
+use image::{codecs::png::PngDecoder, Bgra, DynamicImage, ImageBuffer, ImageDecoder as _};
+use util::ResultExt;
+
+use crate::IndexedCell;
+
+/// The scheme of the hyperlinks, that anchor the images to the cells.
+const IMAGE_ANCHOR_SCHEME: &str = "zed-terminal-image:";
+/// Longer image sequences are dropped.
+const MAX_SEQUENCE_LEN: usize = 64 * 1024 * 1024;
+/// Sixel parameters are short, longer device control strings are not sixel images.
+const MAX_SIXEL_PARAMS_LEN: usize = 16;
+/// Larger images are not decoded.
+const MAX_IMAGE_DIMENSION: u32 = 4096;
+/// Temporary files, sent with the kitty graphics protocol, must have this in their names to be
+/// read and deleted by the terminal.
+const KITTY_TEMP_FILE_MARKER: &str = "tty-graphics-protocol";
+/// How many bytes of the placed images are kept per terminal; the oldest images are dropped first.
+const MAX_STORED_IMAGES_LEN: usize = 320 * 1024 * 1024;
+/// How many images, transmitted with the kitty graphics protocol, are kept per terminal for later placements.
+const MAX_KITTY_IMAGES: usize = 128;
+/// The cell size, assumed until the terminal is resized.
+const DEFAULT_CELL_WIDTH: u32 = 8;
+const DEFAULT_CELL_HEIGHT: u32 = 16;
+
+/// The default colors of the sixel color registers, as on VT340, in percents.
+const SIXEL_DEFAULT_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+/// An image, placed in the viewport.
+#[derive(Clone, Debug)]
+pub struct TerminalImagePlacement {
+    pub data: Arc<ImageData>,
+    /// The top left cell of the image; it is above the viewport, if the image is scrolled out partially.
+    pub point: AlacPoint,
+    /// How many cells the image covers horizontally.
+    pub columns: usize,
+    /// How many cells the image covers vertically.
+    pub rows: usize,
+}
+
+/// Whether the hyperlink anchors an image to the cells, rather than being printed by the program in the terminal.
+pub fn is_image_anchor(hyperlink: &Hyperlink) -> bool {
+    hyperlink.uri().starts_with(IMAGE_ANCHOR_SCHEME)
+}
+
+/// The anchor of an image, set as the hyperlink of every cell in the given row of the image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ImageAnchor {
+    image_id: u64,
+    row: usize,
+}
+
+impl ImageAnchor {
+    fn parse(uri: &str) -> Option<Self> {
+        let (image_id, row) = uri.strip_prefix(IMAGE_ANCHOR_SCHEME)?.split_once(':')?;
+        Some(Self {
+            image_id: image_id.parse().ok()?,
+            row: row.parse().ok()?,
+        })
+    }
+
+    fn uri(&self) -> String {
+        format!("{IMAGE_ANCHOR_SCHEME}{}:{}", self.image_id, self.row)
+    }
+}
+
+/// Where the cursor is moved, after the image is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorPlacement {
+    /// To the line below the image, as after a sixel image.
+    BelowImage,
+    /// To the cell after the last row of the image, as after a kitty image.
+    AfterImage,
+    /// To where the image starts, as requested with `C=1` in a kitty command.
+    Unchanged,
+}
+
+struct StoredImage {
+    data: Arc<ImageData>,
+    columns: usize,
+    rows: usize,
+    kitty_image_id: Option<u32>,
+}
+
+/// What the image sequence is replaced with.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct SequenceOutput {
+    /// The output, passed to alacritty instead of the sequence.
+    pub placeholder: Vec<u8>,
+    /// The response to the program in the terminal.
+    pub response: Option<Vec<u8>>,
+}
+
+/// Images of the terminal, shared by the terminal and its PTY reader.
+#[derive(Default)]
+pub(crate) struct GraphicsState {
+    images: HashMap<u64, StoredImage>,
+    /// Ids of the placed images, from the oldest one.
+    image_ids: VecDeque<u64>,
+    stored_len: usize,
+    next_image_id: u64,
+    kitty: KittyGraphics,
+    window_size: Option<WindowSize>,
+}
+
+impl GraphicsState {
+    /// Finds the images, anchored to the given cells.
+    pub fn placements(&self, cells: &[IndexedCell]) -> Vec<TerminalImagePlacement> {
+        let mut placed_images = HashSet::default();
+        let mut placements = Vec::new();
+        for cell in cells {
+            let Some(anchor) = cell
+                .hyperlink()
+                .and_then(|hyperlink| ImageAnchor::parse(hyperlink.uri()))
+            else {
+                continue;
+            };
+            // Cells go from the top left one, so the first cell of the image is in its top left corner.
+            if !placed_images.insert(anchor.image_id) {
+                continue;
+            }
+            if let Some(image) = self.images.get(&anchor.image_id) {
+                placements.push(TerminalImagePlacement {
+                    data: image.data.clone(),
+                    point: AlacPoint::new(
+                        Line(cell.point.line.0 - anchor.row as i32),
+                        cell.point.column,
+                    ),
+                    columns: image.columns,
+                    rows: image.rows,
+                });
+            }
+        }
+        placements
+    }
+
+    pub fn handle_sequence(&mut self, sequence: GraphicsSequence) -> SequenceOutput {
+        match sequence {
+            GraphicsSequence::Sixel(data) => SequenceOutput {
+                placeholder: decode_sixel(&data)
+                    .map(|image| {
+                        self.place_image(Arc::new(image), 0, 0, None, CursorPlacement::BelowImage)
+                    })
+                    .unwrap_or_default(),
+                response: None,
+            },
+            GraphicsSequence::Kitty(sequence) => self.handle_kitty_sequence(&sequence),
+        }
+    }
+
+    fn set_window_size(&mut self, window_size: WindowSize) {
+        self.window_size = Some(window_size);
+    }
+
+    /// Stores the image and returns the placeholder to anchor it to.
+    /// The size is measured in cells, the image size in pixels is used for the zero dimensions.
+    fn place_image(
+        &mut self,
+        data: Arc<ImageData>,
+        columns: u32,
+        rows: u32,
+        kitty_image_id: Option<u32>,
+        cursor: CursorPlacement,
+    ) -> Vec<u8> {
+        let (cell_width, cell_height, max_columns) = match self.window_size {
+            Some(size) if size.cell_width > 0 && size.cell_height > 0 => (
+                size.cell_width as u32,
+                size.cell_height as u32,
+                (size.num_cols as u32).max(1),
+            ),
+            _ => (DEFAULT_CELL_WIDTH, DEFAULT_CELL_HEIGHT, u32::MAX),
+        };
+        let image_size = data.size();
+        let natural_columns = u32::from(image_size.width).div_ceil(cell_width);
+        let natural_rows = u32::from(image_size.height).div_ceil(cell_height);
+        let columns = if columns > 0 {
+            columns
+        } else {
+            natural_columns
+        };
+        let columns = columns.clamp(1, MAX_IMAGE_DIMENSION);
+        let rows = if rows > 0 { rows } else { natural_rows };
+        let mut rows = rows.clamp(1, MAX_IMAGE_DIMENSION);
+        if columns > max_columns {
+            // The image is scaled down to fit the terminal width.
+            rows = (rows * max_columns).div_ceil(columns).max(1);
+        }
+        let columns = columns.min(max_columns) as usize;
+        let rows = rows as usize;
+
+        let image_id = self.next_image_id;
+        self.next_image_id += 1;
+        self.stored_len += data.as_bytes().len();
+        self.images.insert(
+            image_id,
+            StoredImage {
+                data,
+                columns,
+                rows,
+                kitty_image_id,
+            },
+        );
+        self.image_ids.push_back(image_id);
+        while self.stored_len > MAX_STORED_IMAGES_LEN {
+            let Some(oldest_image_id) = self.image_ids.pop_front() else {
+                break;
+            };
+            self.remove_image(oldest_image_id);
+        }
+
+        placeholder(image_id, columns, rows, cursor)
+    }
+
+    fn remove_image(&mut self, image_id: u64) {
+        if let Some(image) = self.images.remove(&image_id) {
+            self.stored_len -= image.data.as_bytes().len();
+        }
+    }
+
+    fn handle_kitty_sequence(&mut self, sequence: &[u8]) -> SequenceOutput {
+        let (control, payload) = match sequence.iter().position(|byte| *byte == b';') {
+            Some(ix) => (&sequence[..ix], &sequence[ix + 1..]),
+            None => (sequence, &[][..]),
+        };
+        let Some(command) = KittyCommand::parse(control) else {
+            return SequenceOutput::default();
+        };
+
+        let (mut command, payload) = match self.kitty.chunked.take() {
+            Some((first_command, mut received)) => {
+                received.extend_from_slice(payload);
+                if received.len() > MAX_SEQUENCE_LEN {
+                    return SequenceOutput::default();
+                }
+                if command.more {
+                    self.kitty.chunked = Some((first_command, received));
+                    return SequenceOutput::default();
+                }
+                (first_command, received)
+            }
+            None if command.more => {
+                self.kitty.chunked = Some((command, payload.to_vec()));
+                return SequenceOutput::default();
+            }
+            None => (command, payload.to_vec()),
+        };
+
+        if command.image_id == 0 && command.image_number != 0 {
+            command.image_id = self.kitty.next_image_id();
+        }
+        let result = self.run_kitty_command(&command, &payload);
+        let response = kitty_response(&command, &result);
+        SequenceOutput {
+            placeholder: result.unwrap_or_default(),
+            response,
+        }
+    }
+
+    fn run_kitty_command(
+        &mut self,
+        command: &KittyCommand,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let cursor = if command.keep_cursor {
+            CursorPlacement::Unchanged
+        } else {
+            CursorPlacement::AfterImage
+        };
+        match command.action {
+            b'q' => {
+                decode_kitty_image(command, payload)?;
+                Ok(Vec::new())
+            }
+            b't' | b'T' => {
+                let data = Arc::new(decode_kitty_image(command, payload)?);
+                let image_id = if command.image_id == 0 {
+                    self.kitty.next_image_id()
+                } else {
+                    command.image_id
+                };
+                self.kitty.store(image_id, data.clone());
+                if command.action == b'T' {
+                    Ok(self.place_image(
+                        data,
+                        command.columns,
+                        command.rows,
+                        Some(image_id),
+                        cursor,
+                    ))
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+            b'p' => {
+                let data = self
+                    .kitty
+                    .images
+                    .get(&command.image_id)
+                    .cloned()
+                    .ok_or_else(|| "ENOENT:image not found".to_string())?;
+                Ok(self.place_image(
+                    data,
+                    command.columns,
+                    command.rows,
+                    Some(command.image_id),
+                    cursor,
+                ))
+            }
+            b'd' => {
+                self.delete_kitty_images(command);
+                Ok(Vec::new())
+            }
+            _ => Err("EINVAL:unsupported action".to_string()),
+        }
+    }
+
+    /// Deletes the placements, and the image data for the uppercase deletion targets.
+    /// Only deleting all images and deleting by the image id are supported.
+    fn delete_kitty_images(&mut self, command: &KittyCommand) {
+        let deleted_image_id = match command.delete {
+            b'a' | b'A' => None,
+            b'i' | b'I' => Some(command.image_id),
+            _ => return,
+        };
+        let deleted_placements = self
+            .images
+            .iter()
+            .filter(
+                |(_, image)| match (image.kitty_image_id, deleted_image_id) {
+                    (Some(_), None) => true,
+                    (Some(image_id), Some(deleted_image_id)) => image_id == deleted_image_id,
+                    (None, _) => false,
+                },
+            )
+            .map(|(image_id, _)| *image_id)
+            .collect::<Vec<_>>();
+        for image_id in deleted_placements {
+            self.remove_image(image_id);
+        }
+        if command.delete.is_ascii_uppercase() {
+            match deleted_image_id {
+                Some(image_id) => {
+                    self.kitty.images.remove(&image_id);
+                }
+                None => self.kitty.images.clear(),
+            }
+        }
+    }
+}
+
+/// Returns the cells, that the image is drawn over: one hyperlinked row of blank cells per image row.
+/// The cursor and its attributes are saved before each row and restored after it.
+fn placeholder(image_id: u64, columns: usize, rows: usize, cursor: CursorPlacement) -> Vec<u8> {
+    let blank_row = " ".repeat(columns);
+    let mut placeholder = Vec::new();
+    for row in 0..rows {
+        if row > 0 {
+            placeholder.push(b'\n');
+        }
+        let uri = ImageAnchor { image_id, row }.uri();
+        write!(
+            placeholder,
+            "\x1b7\x1b[0m\x1b]8;;{uri}\x1b\\{blank_row}\x1b]8;;\x1b\\\x1b8"
+        )
+        .ok();
+    }
+    match cursor {
+        CursorPlacement::BelowImage => placeholder.push(b'\n'),
+        CursorPlacement::AfterImage => {
+            write!(placeholder, "\x1b[{columns}C").ok();
+        }
+        CursorPlacement::Unchanged => {
+            if rows > 1 {
+                write!(placeholder, "\x1b[{}A", rows - 1).ok();
+            }
+        }
+    }
+    placeholder
+}
+
+/// An image sequence, found in the PTY output.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum GraphicsSequence {
+    /// `ESC P <parameters> q <sixel data> ESC \`, with the sixel data.
+    Sixel(Vec<u8>),
+    /// `ESC _ G <control data> ; <payload> ESC \`, with the control data and the payload.
+    Kitty(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SequenceKind {
+    Sixel,
+    Kitty,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    /// `ESC P` with the following parameters, that may turn out to start a sixel image.
+    DcsParams,
+    /// `ESC _`, that may be followed by `G`, starting a kitty graphics command.
+    Apc,
+    Sequence(SequenceKind),
+    SequenceEscape(SequenceKind),
+}
+
+/// Finds the image sequences in the PTY output, passing the rest of the output through.
+#[derive(Debug, Default)]
+struct GraphicsScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    overflown: bool,
+}
+
+impl GraphicsScanner {
+    /// Passes the output up to the next escape sequence through, returns how many bytes were passed.
+    fn pass_through(&mut self, output: &[u8], scanned: &mut VecDeque<u8>) -> usize {
+        if self.state != ScanState::Ground {
+            return 0;
+        }
+        let len = output
+            .iter()
+            .position(|byte| *byte == 0x1b)
+            .unwrap_or(output.len());
+        scanned.extend(&output[..len]);
+        len
+    }
+
+    fn advance(&mut self, byte: u8, scanned: &mut VecDeque<u8>) -> Option<GraphicsSequence> {
+        match self.state {
+            ScanState::Ground => {
+                if byte == 0x1b {
+                    self.state = ScanState::Escape;
+                } else {
+                    scanned.push_back(byte);
+                }
+            }
+            ScanState::Escape => match byte {
+                b'P' => {
+                    self.sequence.clear();
+                    self.state = ScanState::DcsParams;
+                }
+                b'_' => self.state = ScanState::Apc,
+                0x1b => scanned.push_back(0x1b),
+                _ => {
+                    scanned.extend([0x1b, byte]);
+                    self.state = ScanState::Ground;
+                }
+            },
+            ScanState::DcsParams => match byte {
+                b'0'..=b'9' | b';' if self.sequence.len() < MAX_SIXEL_PARAMS_LEN => {
+                    self.sequence.push(byte)
+                }
+                b'q' => self.start_sequence(SequenceKind::Sixel),
+                _ => {
+                    // Other device control strings are left to alacritty.
+                    scanned.extend([0x1b, b'P']);
+                    scanned.extend(self.sequence.drain(..));
+                    self.state = ScanState::Ground;
+                    return self.advance(byte, scanned);
+                }
+            },
+            ScanState::Apc => {
+                if byte == b'G' {
+                    self.start_sequence(SequenceKind::Kitty);
+                } else {
+                    scanned.extend([0x1b, b'_']);
+                    self.state = ScanState::Ground;
+                    return self.advance(byte, scanned);
+                }
+            }
+            ScanState::Sequence(kind) => match byte {
+                0x1b => self.state = ScanState::SequenceEscape(kind),
+                // CAN and SUB cancel the sequence.
+                0x18 | 0x1a => self.state = ScanState::Ground,
+                _ => {
+                    if self.sequence.len() < MAX_SEQUENCE_LEN {
+                        self.sequence.push(byte);
+                    } else {
+                        self.overflown = true;
+                    }
+                }
+            },
+            ScanState::SequenceEscape(kind) => {
+                if byte != b'\\' {
+                    // Any other escape sequence cancels the image one.
+                    self.state = ScanState::Escape;
+                    return self.advance(byte, scanned);
+                }
+                self.state = ScanState::Ground;
+                if self.overflown {
+                    return None;
+                }
+                let sequence = mem::take(&mut self.sequence);
+                return Some(match kind {
+                    SequenceKind::Sixel => GraphicsSequence::Sixel(sequence),
+                    SequenceKind::Kitty => GraphicsSequence::Kitty(sequence),
+                });
+            }
+        }
+        None
+    }
+
+    fn start_sequence(&mut self, kind: SequenceKind) {
+        self.sequence.clear();
+        self.overflown = false;
+        self.state = ScanState::Sequence(kind);
+    }
+}
+
+/// Decodes the sixel data into an image; the pixels, that were not painted, are transparent.
+fn decode_sixel(data: &[u8]) -> Option<ImageData> {
+    let mut palette = [[0u8; 3]; 256];
+    for (color, default_color) in palette.iter_mut().zip(SIXEL_DEFAULT_PALETTE) {
+        *color = default_color.map(percent_to_channel);
+    }
+    let mut canvas = SixelCanvas::default();
+    let mut color = 0;
+    let (mut x, mut y) = (0u32, 0u32);
+    let mut ix = 0;
+    while ix < data.len() {
+        let byte = data[ix];
+        ix += 1;
+        match byte {
+            b'"' => {
+                let (params, next_ix) = parse_sixel_params(data, ix);
+                ix = next_ix;
+                if let [_, _, width, height, ..] = params[..] {
+                    canvas.raster_width = width.min(MAX_IMAGE_DIMENSION);
+                    canvas.raster_height = height.min(MAX_IMAGE_DIMENSION);
+                }
+            }
+            b'#' => {
+                let (params, next_ix) = parse_sixel_params(data, ix);
+                ix = next_ix;
+                let Some(register) = params.first() else {
+                    continue;
+                };
+                color = *register as usize % palette.len();
+                match params[1..] {
+                    [1, hue, lightness, saturation] => {
+                        palette[color] = hls_to_rgb(hue, lightness, saturation)
+                    }
+                    [2, red, green, blue] => {
+                        palette[color] = [red, green, blue].map(percent_to_channel)
+                    }
+                    _ => {}
+                }
+            }
+            b'!' => {
+                let (params, next_ix) = parse_sixel_params(data, ix);
+                ix = next_ix;
+                let repeat = params.first().copied().unwrap_or(1).max(1);
+                if let Some(sixel @ 0x3f..=0x7e) = data.get(ix).copied() {
+                    ix += 1;
+                    canvas.paint(x, y, sixel, palette[color], repeat);
+                    x = x.saturating_add(repeat);
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            0x3f..=0x7e => {
+                canvas.paint(x, y, byte, palette[color], 1);
+                x = x.saturating_add(1);
+            }
+            _ => {}
+        }
+    }
+    canvas.into_image()
+}
+
+/// Parses the numeric parameters, separated with semicolons, returns them with the index of the first byte after them.
+fn parse_sixel_params(data: &[u8], mut ix: usize) -> (Vec<u32>, usize) {
+    let mut params = Vec::new();
+    let mut param: Option<u32> = None;
+    while let Some(byte) = data.get(ix).copied() {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = (byte - b'0') as u32;
+                param = Some(param.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            b';' => params.push(param.take().unwrap_or(0)),
+            _ => break,
+        }
+        ix += 1;
+    }
+    if let Some(param) = param {
+        params.push(param);
+    }
+    (params, ix)
+}
+
+fn percent_to_channel(percent: u32) -> u8 {
+    ((percent.min(100) * 255 + 50) / 100) as u8
+}
+
+/// Converts the sixel HLS color, where the hue of 0° is blue rather than red, to RGB.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue % 360 + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [channel(hue + 1. / 3.), channel(hue), channel(hue - 1. / 3.)]
+}
+
+/// The pixels of a sixel image, growing as they are painted.
+#[derive(Default)]
+struct SixelCanvas {
+    width: u32,
+    height: u32,
+    /// BGRA pixels.
+    pixels: Vec<u8>,
+    painted_width: u32,
+    painted_height: u32,
+    raster_width: u32,
+    raster_height: u32,
+}
+
+impl SixelCanvas {
+    fn paint(&mut self, x: u32, y: u32, sixel: u8, [red, green, blue]: [u8; 3], repeat: u32) {
+        let bits = sixel - 0x3f;
+        let end_x = x.saturating_add(repeat).min(MAX_IMAGE_DIMENSION);
+        if bits == 0 || x >= end_x || y >= MAX_IMAGE_DIMENSION {
+            return;
+        }
+        let end_y = (y + 6).min(MAX_IMAGE_DIMENSION);
+        self.grow(end_x, end_y);
+        for pixel_y in y..end_y {
+            if bits & (1 << (pixel_y - y)) == 0 {
+                continue;
+            }
+            let row_start = pixel_y as usize * self.width as usize;
+            for pixel_x in x..end_x {
+                let offset = (row_start + pixel_x as usize) * 4;
+                self.pixels[offset..offset + 4].copy_from_slice(&[blue, green, red, 0xff]);
+            }
+            self.painted_height = self.painted_height.max(pixel_y + 1);
+        }
+        self.painted_width = self.painted_width.max(end_x);
+    }
+
+    fn grow(&mut self, width: u32, height: u32) {
+        if width <= self.width && height <= self.height {
+            return;
+        }
+        let new_width = width
+            .max(self.width.saturating_mul(2))
+            .min(MAX_IMAGE_DIMENSION);
+        let new_height = height
+            .max(self.height.saturating_mul(2))
+            .min(MAX_IMAGE_DIMENSION);
+        let pixels = mem::replace(
+            &mut self.pixels,
+            vec![0; new_width as usize * new_height as usize * 4],
+        );
+        copy_pixels(
+            &pixels,
+            self.width,
+            (self.width, self.height),
+            &mut self.pixels,
+            new_width,
+        );
+        self.width = new_width;
+        self.height = new_height;
+    }
+
+    fn into_image(self) -> Option<ImageData> {
+        let width = self.painted_width.max(self.raster_width);
+        let height = self.painted_height.max(self.raster_height);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut pixels = vec![0; width as usize * height as usize * 4];
+        copy_pixels(
+            &self.pixels,
+            self.width,
+            (self.width.min(width), self.height.min(height)),
+            &mut pixels,
+            width,
+        );
+        ImageBuffer::from_raw(width, height, pixels).map(ImageData::new)
+    }
+}
+
+/// Copies the top left pixels of the given size between the BGRA buffers of the given widths.
+fn copy_pixels(
+    source: &[u8],
+    source_width: u32,
+    (width, height): (u32, u32),
+    target: &mut [u8],
+    target_width: u32,
+) {
+    let row_len = width as usize * 4;
+    for y in 0..height as usize {
+        let source_start = y * source_width as usize * 4;
+        let target_start = y * target_width as usize * 4;
+        target[target_start..target_start + row_len]
+            .copy_from_slice(&source[source_start..source_start + row_len]);
+    }
+}
+
+/// Images, transmitted with the kitty graphics protocol, by their ids.
+#[derive(Default)]
+struct KittyGraphics {
+    images: HashMap<u32, Arc<ImageData>>,
+    /// Ids of the transmitted images, from the oldest one.
+    image_ids: VecDeque<u32>,
+    /// The first command of the chunked transmission in progress, with the payload received so far.
+    chunked: Option<(KittyCommand, Vec<u8>)>,
+    last_image_id: u32,
+}
+
+impl KittyGraphics {
+    /// Allocates an id for the image, transmitted without one.
+    fn next_image_id(&mut self) -> u32 {
+        self.last_image_id = self.last_image_id.wrapping_add(1).max(1);
+        // Ids, picked by the programs, are usually small, so the allocated ones go from the other end.
+        u32::MAX - self.last_image_id
+    }
+
+    fn store(&mut self, image_id: u32, data: Arc<ImageData>) {
+        if self.images.insert(image_id, data).is_none() {
+            self.image_ids.push_back(image_id);
+        }
+        while self.images.len() > MAX_KITTY_IMAGES {
+            let Some(oldest_image_id) = self.image_ids.pop_front() else {
+                break;
+            };
+            self.images.remove(&oldest_image_id);
+        }
+    }
+}
+
+/// The control data of a kitty graphics command, with the defaults for the keys that are not set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    /// `a`: transmit (`t`), transmit and display (`T`), display (`p`), delete (`d`) or query (`q`).
+    action: u8,
+    /// `f`: RGB (24), RGBA (32) or PNG (100).
+    format: u32,
+    /// `t`: direct (`d`), a file (`f`) or a temporary file (`t`).
+    medium: u8,
+    /// `s`: the width of the raw image, in pixels.
+    width: u32,
+    /// `v`: the height of the raw image, in pixels.
+    height: u32,
+    /// `o`: the compression of the payload.
+    compression: Option<u8>,
+    /// `i`
+    image_id: u32,
+    /// `I`
+    image_number: u32,
+    /// `p`
+    placement_id: u32,
+    /// `m`: whether more chunks of the payload follow.
+    more: bool,
+    /// `q`: 1 suppresses the OK responses, 2 suppresses the error responses too.
+    quiet: u32,
+    /// `c`: the width of the displayed image, in cells.
+    columns: u32,
+    /// `r`: the height of the displayed image, in cells.
+    rows: u32,
+    /// `C`: whether the cursor stays in place after the image is displayed.
+    keep_cursor: bool,
+    /// `d`: what to delete.
+    delete: u8,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            width: 0,
+            height: 0,
+            compression: None,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            more: false,
+            quiet: 0,
+            columns: 0,
+            rows: 0,
+            keep_cursor: false,
+            delete: b'a',
+        }
+    }
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Option<Self> {
+        let control = std::str::from_utf8(control).ok()?;
+        let mut command = Self::default();
+        for key_value in control.split(',').filter(|key_value| !key_value.is_empty()) {
+            let (key, value) = key_value.split_once('=')?;
+            let number = || value.parse::<u32>().ok();
+            let byte = || value.bytes().next();
+            match key {
+                "a" => command.action = byte()?,
+                "f" => command.format = number()?,
+                "t" => command.medium = byte()?,
+                "s" => command.width = number()?,
+                "v" => command.height = number()?,
+                "o" => command.compression = Some(byte()?),
+                "i" => command.image_id = number()?,
+                "I" => command.image_number = number()?,
+                "p" => command.placement_id = number()?,
+                "m" => command.more = number()? == 1,
+                "q" => command.quiet = number()?,
+                "c" => command.columns = number()?,
+                "r" => command.rows = number()?,
+                "C" => command.keep_cursor = number()? == 1,
+                "d" => command.delete = byte()?,
+                _ => {}
+            }
+        }
+        Some(command)
+    }
+}
+
+/// Responds to the command with an image id or number, unless the responses are suppressed.
+fn kitty_response(command: &KittyCommand, result: &Result<Vec<u8>, String>) -> Option<Vec<u8>> {
+    if command.image_id == 0 || command.action == b'd' {
+        return None;
+    }
+    let message = match result {
+        Ok(_) if command.quiet == 0 => "OK",
+        Err(error) if command.quiet < 2 => error.as_str(),
+        _ => return None,
+    };
+    let mut response = format!("\x1b_Gi={}", command.image_id);
+    if command.image_number != 0 {
+        response.push_str(&format!(",I={}", command.image_number));
+    }
+    if command.placement_id != 0 {
+        response.push_str(&format!(",p={}", command.placement_id));
+    }
+    response.push_str(&format!(";{message}\x1b\\"));
+    Some(response.into_bytes())
+}
+
+fn decode_kitty_image(command: &KittyCommand, payload: &[u8]) -> Result<ImageData, String> {
+    if command.compression.is_some() {
+        return Err("ENOTSUPPORTED:compressed images are not supported".to_string());
+    }
+    let payload =
+        base64::decode(payload).map_err(|error| format!("EINVAL:invalid payload: {error}"))?;
+    let bytes = match command.medium {
+        b'd' => payload,
+        b'f' | b't' => {
+            let path =
+                String::from_utf8(payload).map_err(|_| "EINVAL:invalid file path".to_string())?;
+            let path = Path::new(&path);
+            // Temporary files are deleted by the terminal, so only the ones named as the protocol
+            // requires, directly inside the temporary directory, are accepted.
+            if command.medium == b't' && !is_kitty_temp_file(path) {
+                return Err("EPERM:not a temporary file for the graphics protocol".to_string());
+            }
+            let bytes = read_kitty_file(path);
+            if command.medium == b't' {
+                std::fs::remove_file(path).log_err();
+            }
+            bytes?
+        }
+        _ => return Err("ENOTSUPPORTED:unsupported transmission medium".to_string()),
+    };
+
+    let too_large = || "EFBIG:the image is too large".to_string();
+    let image = match command.format {
+        100 => {
+            // The size is checked before decoding, as a small PNG can declare a huge image.
+            let decoder =
+                PngDecoder::new(Cursor::new(&bytes)).map_err(|error| format!("EBADPNG:{error}"))?;
+            let (width, height) = decoder.dimensions();
+            if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+                return Err(too_large());
+            }
+            DynamicImage::from_decoder(decoder)
+                .map_err(|error| format!("EBADPNG:{error}"))?
+                .into_bgra8()
+        }
+        format @ (24 | 32) => {
+            let (width, height) = (command.width, command.height);
+            let bytes_per_pixel = if format == 24 { 3 } else { 4 };
+            if width == 0 || height == 0 {
+                return Err("EINVAL:the image size is not set".to_string());
+            }
+            if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+                return Err(too_large());
+            }
+            if bytes.len() < width as usize * height as usize * bytes_per_pixel {
+                return Err("ENODATA:insufficient image data".to_string());
+            }
+            let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+            for pixel in bytes
+                .chunks_exact(bytes_per_pixel)
+                .take(width as usize * height as usize)
+            {
+                let alpha = pixel.get(3).copied().unwrap_or(0xff);
+                pixels.extend([pixel[2], pixel[1], pixel[0], alpha]);
+            }
+            ImageBuffer::<Bgra<u8>, Vec<u8>>::from_raw(width, height, pixels)
+                .ok_or_else(|| "EINVAL:invalid image size".to_string())?
+        }
+        _ => return Err("EINVAL:unsupported image format".to_string()),
+    };
+    Ok(ImageData::new(image))
+}
+
+fn is_kitty_temp_file(path: &Path) -> bool {
+    let is_marked = path.file_name().map_or(false, |name| {
+        name.to_string_lossy().contains(KITTY_TEMP_FILE_MARKER)
+    });
+    let parent = path.parent().and_then(|parent| parent.canonicalize().ok());
+    is_marked && parent.is_some() && parent == std::env::temp_dir().canonicalize().ok()
+}
+
+/// Reads an image file named by a program's output, which must be a regular file no larger
+/// than an image sequence, so that reading it neither blocks nor exhausts the memory.
+fn read_kitty_file(path: &Path) -> Result<Vec<u8>, String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
+    // Opening a FIFO blocks until it has a writer.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.custom_flags(libc::O_NONBLOCK);
+    }
+    let file = options
+        .open(path)
+        .map_err(|error| format!("EBADF:{error}"))?;
+    let metadata = file.metadata().map_err(|error| format!("EBADF:{error}"))?;
+    if !metadata.is_file() {
+        return Err("EBADF:not a regular file".to_string());
+    }
+    if metadata.len() > MAX_SEQUENCE_LEN as u64 {
+        return Err("EFBIG:the file is too large".to_string());
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_SEQUENCE_LEN as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("EBADF:{error}"))?;
+    if bytes.len() > MAX_SEQUENCE_LEN {
+        return Err("EFBIG:the file is too large".to_string());
+    }
+    Ok(bytes)
+}
+
+/// The PTY, with its output scanned for the image sequences.
+#[cfg(unix)]
+pub(crate) struct GraphicsPty<P> {
+    pty: P,
+    state: Arc<parking_lot::Mutex<GraphicsState>>,
+    scanner: GraphicsScanner,
+    /// Scanned output, to pass to alacritty.
+    scanned: VecDeque<u8>,
+    read_buffer: Box<[u8]>,
+    /// Wakes the event loop up to read the scanned output, that did not fit into its buffer.
+    wakeup: std::os::unix::net::UnixStream,
+    wakeup_tx: std::os::unix::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl<P> GraphicsPty<P> {
+    pub fn new(pty: P, state: Arc<parking_lot::Mutex<GraphicsState>>) -> io::Result<Self> {
+        let (wakeup, wakeup_tx) = std::os::unix::net::UnixStream::pair()?;
+        wakeup.set_nonblocking(true)?;
+        wakeup_tx.set_nonblocking(true)?;
+        Ok(Self {
+            pty,
+            state,
+            scanner: GraphicsScanner::default(),
+            scanned: VecDeque::new(),
+            read_buffer: vec![0; 0x1_0000].into_boxed_slice(),
+            wakeup,
+            wakeup_tx,
+        })
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedReadWrite> io::Read for GraphicsPty<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut wakeups = [0; 64];
+        while matches!(io::Read::read(&mut self.wakeup, &mut wakeups), Ok(read) if read > 0) {}
+
+        // The output may consist of the image sequences only, keep reading until there is something to pass.
+        while self.scanned.is_empty() {
+            let read = io::Read::read(self.pty.reader(), &mut self.read_buffer)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            let mut ix = 0;
+            while ix < read {
+                ix += self
+                    .scanner
+                    .pass_through(&self.read_buffer[ix..read], &mut self.scanned);
+                let Some(byte) = self.read_buffer[..read].get(ix).copied() else {
+                    break;
+                };
+                ix += 1;
+                let Some(sequence) = self.scanner.advance(byte, &mut self.scanned) else {
+                    continue;
+                };
+                let output = self.state.lock().handle_sequence(sequence);
+                self.scanned.extend(output.placeholder);
+                if let Some(response) = output.response {
+                    io::Write::write_all(self.pty.writer(), &response).log_err();
+                }
+            }
+        }
+
+        let len = buf.len().min(self.scanned.len());
+        for (target, byte) in buf.iter_mut().zip(self.scanned.drain(..len)) {
+            *target = byte;
+        }
+        if !self.scanned.is_empty() {
+            io::Write::write(&mut &self.wakeup_tx, &[0]).ok();
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedReadWrite> alacritty_terminal::tty::EventedReadWrite
+    for GraphicsPty<P>
+{
+    type Reader = Self;
+    type Writer = P::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<polling::Poller>,
+        interest: polling::Event,
+        mode: polling::PollMode,
+    ) -> io::Result<()> {
+        use std::os::fd::AsRawFd as _;
+        unsafe {
+            self.pty.register(poll, interest, mode)?;
+            poll.add_with_mode(
+                self.wakeup.as_raw_fd(),
+                polling::Event::readable(interest.key),
+                mode,
+            )
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<polling::Poller>,
+        interest: polling::Event,
+        mode: polling::PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)?;
+        poll.modify_with_mode(&self.wakeup, polling::Event::readable(interest.key), mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<polling::Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)?;
+        poll.delete(&self.wakeup)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedPty> alacritty_terminal::tty::EventedPty
+    for GraphicsPty<P>
+{
+    fn next_child_event(&mut self) -> Option<alacritty_terminal::tty::ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::event::OnResize> alacritty_terminal::event::OnResize
+    for GraphicsPty<P>
+{
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.state.lock().set_window_size(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use alacritty_terminal::{
+        event::VoidListener, grid::Scroll, index::Column, term::Config, vte::ansi::Processor, Term,
+    };
+    use gpui::{px, size};
+
+    use super::*;
+    use crate::TerminalSize;
+
+    const RED: [u8; 4] = [0, 0, 0xff, 0xff];
+    const GREEN: [u8; 4] = [0, 0xff, 0, 0xff];
+    const BLUE: [u8; 4] = [0xff, 0, 0, 0xff];
+
+    fn read_test_data(filename: &str) -> Vec<u8> {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("test_data");
+        path.push(filename);
+        std::fs::read(&path).unwrap_or_else(|_| panic!("Could not read test data at {:?}", path))
+    }
+
+    /// Scans the output and handles its image sequences, as the PTY reader does.
+    fn scan(state: &mut GraphicsState, output: &[u8]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut scanner = GraphicsScanner::default();
+        let mut scanned = VecDeque::new();
+        let mut responses = Vec::new();
+        for byte in output {
+            if let Some(sequence) = scanner.advance(*byte, &mut scanned) {
+                let output = state.handle_sequence(sequence);
+                scanned.extend(output.placeholder);
+                responses.extend(output.response);
+            }
+        }
+        (scanned.into_iter().collect(), responses)
+    }
+
+    fn image_size(image: &ImageData) -> (u32, u32) {
+        let size = image.size();
+        (u32::from(size.width), u32::from(size.height))
+    }
+
+    /// Returns the BGRA pixel.
+    fn pixel(image: &ImageData, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * image_size(image).0 as usize + x) * 4;
+        image.as_bytes()[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_scan_passes_other_sequences_through() {
+        let output = b"plain \x1b[1mbold\x1b[0m \x1b]0;title\x07 \x1bP$qm\x1b\\ \x1bP1$r0m\x1b\\ \x1b_Xapc\x1b\\";
+        let mut state = GraphicsState::default();
+        assert_eq!(scan(&mut state, output), (output.to_vec(), Vec::new()));
+
+        // The image sequence is cancelled by another escape sequence.
+        assert_eq!(
+            scan(&mut state, b"\x1bPq#0~~\x1b[0m text"),
+            (b"\x1b[0m text".to_vec(), Vec::new())
+        );
+        assert!(state.images.is_empty());
+    }
+
+    #[test]
+    fn test_decode_sixel_image() {
+        let mut scanner = GraphicsScanner::default();
+        let mut scanned = VecDeque::new();
+        let sequences = read_test_data("image.sixel")
+            .into_iter()
+            .filter_map(|byte| scanner.advance(byte, &mut scanned))
+            .collect::<Vec<_>>();
+        assert!(scanned.is_empty());
+        let [GraphicsSequence::Sixel(data)] = &sequences[..] else {
+            panic!("expected a sixel image, got {sequences:?}");
+        };
+
+        let image = decode_sixel(data).unwrap();
+        assert_eq!(image_size(&image), (4, 12));
+        assert_eq!(pixel(&image, 0, 0), RED);
+        assert_eq!(pixel(&image, 1, 5), RED);
+        assert_eq!(pixel(&image, 2, 0), BLUE);
+        assert_eq!(pixel(&image, 3, 5), BLUE);
+        assert_eq!(pixel(&image, 0, 6), BLUE);
+        assert_eq!(pixel(&image, 3, 11), BLUE);
+    }
+
+    #[test]
+    fn test_kitty_commands() {
+        let mut state = GraphicsState::default();
+        let (output, responses) = scan(&mut state, &read_test_data("image.kitty"));
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "\x1b7\x1b[0m\x1b]8;;{IMAGE_ANCHOR_SCHEME}0:0\x1b\\ \x1b]8;;\x1b\\\x1b8\x1b[1C"
+            )
+        );
+        assert!(responses.is_empty());
+        let image = &state.images[&0].data;
+        assert_eq!(image_size(image), (2, 2));
+        assert_eq!(pixel(image, 0, 0), RED);
+        assert_eq!(pixel(image, 1, 0), GREEN);
+        assert_eq!(pixel(image, 0, 1), BLUE);
+        assert_eq!(pixel(image, 1, 1), [0xff, 0xff, 0xff, 0x80]);
+
+        // Programs detect the protocol support with a query.
+        let (output, responses) = scan(&mut state, b"\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\");
+        assert!(output.is_empty());
+        assert_eq!(responses, vec![b"\x1b_Gi=31;OK\x1b\\".to_vec()]);
+
+        let (output, responses) = scan(&mut state, b"\x1b_Ga=p,i=7\x1b\\\x1b_Ga=p,i=8,q=2\x1b\\");
+        assert!(output.is_empty());
+        assert_eq!(
+            responses,
+            vec![b"\x1b_Gi=7;ENOENT:image not found\x1b\\".to_vec()]
+        );
+
+        let (_, responses) = scan(&mut state, b"\x1b_Ga=t,i=7,f=24,s=1,v=1;AAAA\x1b\\");
+        assert_eq!(responses, vec![b"\x1b_Gi=7;OK\x1b\\".to_vec()]);
+        let (output, _) = scan(&mut state, b"\x1b_Ga=p,i=7,q=1,C=1\x1b\\");
+        assert!(!output.is_empty());
+        assert_eq!(state.images.len(), 2);
+
+        scan(&mut state, b"\x1b_Ga=d,d=i,i=7\x1b\\");
+        assert_eq!(state.images.len(), 1);
+        assert!(state.kitty.images.contains_key(&7));
+        scan(&mut state, b"\x1b_Ga=d,d=A\x1b\\");
+        assert!(state.images.is_empty());
+        assert!(state.kitty.images.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kitty_file_media() {
+        let mut state = GraphicsState::default();
+        let mut transmit = |medium: &str, size: u32, path: &Path| {
+            let command = format!(
+                "\x1b_Ga=t,i=9,f=24,s={size},v={size},t={medium};{}\x1b\\",
+                base64::encode(path.to_str().unwrap())
+            );
+            let (_, responses) = scan(&mut state, command.as_bytes());
+            String::from_utf8(responses.concat()).unwrap()
+        };
+
+        // Temporary files are read and deleted.
+        let temp_file = std::env::temp_dir().join(format!(
+            "zed-{KITTY_TEMP_FILE_MARKER}-{}",
+            std::process::id()
+        ));
+        std::fs::write(&temp_file, [0, 0, 0xff]).unwrap();
+        assert_eq!(transmit("t", 1, &temp_file), "\x1b_Gi=9;OK\x1b\\");
+        assert!(!temp_file.exists());
+
+        // But only the ones directly inside the temporary directory.
+        let nested_file = std::env::temp_dir()
+            .join("zed-nested")
+            .join(KITTY_TEMP_FILE_MARKER);
+        assert!(transmit("t", 1, &nested_file).contains("EPERM"));
+        assert!(transmit("t", 1, Path::new("/dev/zero")).contains("EPERM"));
+
+        // Files that are not regular files are not read.
+        assert!(transmit("f", 1, Path::new("/dev/zero")).contains("EBADF:not a regular file"));
+
+        // The image size is checked before the image is allocated.
+        std::fs::write(&temp_file, [0, 0, 0xff]).unwrap();
+        assert!(transmit("t", 100_000, &temp_file).contains("EFBIG"));
+    }
+
+    #[test]
+    fn test_images_anchored_to_grid() {
+        // 20 columns and 10 lines of 2×4 pixel cells, so that the 4×12 pixel image covers 2×3 cells.
+        let terminal_size = TerminalSize::new(px(4.), px(2.), size(px(40.), px(40.)));
+        let mut state = GraphicsState::default();
+        state.set_window_size(terminal_size.into());
+        let mut output = b"ab".to_vec();
+        output.extend(read_test_data("image.sixel"));
+        output.extend(b"c");
+        let (output, _) = scan(&mut state, &output);
+
+        let mut term = Term::new(Config::default(), &terminal_size, VoidListener);
+        let mut processor: Processor = Processor::new();
+        for byte in output {
+            processor.advance(&mut term, byte);
+        }
+        let placements = |term: &Term<VoidListener>| {
+            let cells = term
+                .renderable_content()
+                .display_iter
+                .map(|cell| IndexedCell {
+                    point: cell.point,
+                    cell: cell.cell.clone(),
+                })
+                .collect::<Vec<_>>();
+            state
+                .placements(&cells)
+                .into_iter()
+                .map(|placement| (placement.point, placement.columns, placement.rows))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            placements(&term),
+            vec![(AlacPoint::new(Line(0), Column(2)), 2, 3)]
+        );
+        assert_eq!(term.grid()[Line(0)][Column(1)].c, 'b');
+        assert!(term.grid()[Line(2)][Column(3)]
+            .hyperlink()
+            .map_or(false, |hyperlink| is_image_anchor(&hyperlink)));
+        assert!(term.grid()[Line(0)][Column(4)].hyperlink().is_none());
+        // The cursor is moved below the image.
+        assert_eq!(term.grid()[Line(3)][Column(2)].c, 'c');
+
+        // The image stays anchored, as it is scrolled into the scrollback history.
+        for byte in b"\r\n".repeat(10) {
+            processor.advance(&mut term, byte);
+        }
+        assert_eq!(placements(&term), Vec::new());
+        term.scroll_display(Scroll::Delta(3));
+        assert_eq!(
+            placements(&term),
+            vec![(AlacPoint::new(Line(-4), Column(2)), 2, 3)]
+        );
+    }
+}
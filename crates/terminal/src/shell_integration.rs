@@ -0,0 +1,770 @@
+//! Shell integration: the shell reports its prompts and commands with the semantic prompt escape sequences,
+//! `OSC 133` (and `OSC 633`, as emitted by VS Code's scripts), which alacritty ignores.
+//!
+//! The PTY output is scanned for the sequences before it reaches alacritty's parser.
+//! The output is paused after every mark until the terminal records the mark at the cursor position,
+//! so that the marks are placed precisely, regardless of how the output is batched.
+
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use collections::HashMap;
+use parking_lot::Mutex;
+use util::ResultExt;
+
+use crate::terminal_settings::Shell;
+
+/// A request of the cursor position, injected after every mark: alacritty responds to it as soon as it parses the mark.
+const CURSOR_POSITION_REQUEST: &[u8] = b"\x1b[6n";
+/// Longer escape sequences are not buffered, as they can not be our marks, except for the excessively long command lines.
+const MAX_OSC_LEN: usize = 16 * 1024;
+/// How much output is buffered, while the terminal has not recorded the last mark, before the PTY reading pauses.
+const MAX_PENDING_OUTPUT_LEN: usize = 1024 * 1024;
+/// How long the PTY reader waits before polling again, while its buffer is full.
+const PENDING_MARK_POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// How many commands are remembered per terminal.
+const MAX_COMMANDS: usize = 1000;
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSH_SCRIPT: &str = include_str!("shell_integration/zed.zsh");
+const ZSH_ENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// A semantic prompt mark, reported by the shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ShellMark {
+    /// `A`: the prompt is about to be printed.
+    PromptStart,
+    /// `B`: the prompt is printed, the user types the command after it.
+    CommandStart,
+    /// `C`: the command is submitted, its output follows.
+    CommandExecuted,
+    /// `D;<exit code>`: the command has finished.
+    CommandFinished { exit_code: Option<i32> },
+    /// `633;E;<command line>`: the text of the submitted command.
+    CommandLine(String),
+}
+
+/// A position in the terminal grid, with lines counted from the top of the scrollback history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ShellPoint {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A command, run by the terminal's shell, as reported by the shell integration.
+/// Positions are forgotten when the scrollback history gets cleared, reflowed or overflows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShellCommand {
+    /// Where the prompt, printed before the command, starts.
+    pub prompt_start: Option<ShellPoint>,
+    /// Where the command line, typed after the prompt, starts.
+    pub command_start: Option<ShellPoint>,
+    /// Where the command's output starts.
+    pub output_start: Option<ShellPoint>,
+    /// Where the command's output ends, after the command has finished.
+    pub output_end: Option<ShellPoint>,
+    /// The text of the command, if it is known.
+    pub command_line: Option<String>,
+    /// When the command was submitted.
+    pub started_at: Option<Instant>,
+    /// How long the command was running for.
+    pub duration: Option<Duration>,
+    /// The exit code of the finished command; may be unknown even after the command has finished.
+    pub exit_code: Option<i32>,
+    /// Whether the command was submitted and has finished running.
+    pub finished: bool,
+}
+
+impl ShellCommand {
+    /// Whether the command was submitted and is running currently.
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some() && !self.finished
+    }
+
+    /// Whether the command has finished with a non-zero exit code.
+    pub fn has_failed(&self) -> bool {
+        self.finished && self.exit_code.map_or(false, |exit_code| exit_code != 0)
+    }
+
+    fn forget_positions(&mut self) {
+        self.prompt_start = None;
+        self.command_start = None;
+        self.output_start = None;
+        self.output_end = None;
+    }
+}
+
+/// Commands of the terminal's shell, in the order they were run.
+#[derive(Debug, Default)]
+pub struct ShellCommands {
+    commands: VecDeque<ShellCommand>,
+}
+
+impl ShellCommands {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &ShellCommand> {
+        self.commands.iter()
+    }
+
+    /// The command, that is running currently, if any.
+    pub fn running(&self) -> Option<&ShellCommand> {
+        self.commands.back().filter(|command| command.is_running())
+    }
+
+    /// Records the mark at the given position.
+    /// Returns the command, if it has finished with this mark.
+    pub(crate) fn record(
+        &mut self,
+        mark: ShellMark,
+        point: ShellPoint,
+        received_at: Instant,
+    ) -> Option<&ShellCommand> {
+        match mark {
+            ShellMark::PromptStart => {
+                if self.commands.len() == MAX_COMMANDS {
+                    self.commands.pop_front();
+                }
+                self.commands.push_back(ShellCommand {
+                    prompt_start: Some(point),
+                    ..ShellCommand::default()
+                });
+            }
+            ShellMark::CommandStart => {
+                let command = self.current_command();
+                if command.started_at.is_none() {
+                    command.command_start = Some(point);
+                }
+            }
+            ShellMark::CommandLine(command_line) => {
+                let command = self.current_command();
+                if !command.finished {
+                    command.command_line = Some(command_line);
+                }
+            }
+            ShellMark::CommandExecuted => {
+                let command = self.current_command();
+                if command.started_at.is_none() {
+                    command.output_start = Some(point);
+                    command.started_at = Some(received_at);
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                let command = self.commands.back_mut()?;
+                // Shells may report the status of the previous command without running a new one, e.g. on an empty line.
+                let started_at = command.started_at.filter(|_| !command.finished)?;
+                command.output_end = Some(point);
+                command.exit_code = exit_code;
+                command.duration = Some(received_at.saturating_duration_since(started_at));
+                command.finished = true;
+                return Some(command);
+            }
+        }
+        None
+    }
+
+    /// Forgets the positions of all commands, after the grid lines have moved in the scrollback history.
+    pub(crate) fn forget_positions(&mut self) {
+        for command in &mut self.commands {
+            command.forget_positions();
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let running = self
+            .commands
+            .pop_back()
+            .filter(|command| command.is_running());
+        self.commands.clear();
+        if let Some(mut running) = running {
+            running.forget_positions();
+            self.commands.push_back(running);
+        }
+    }
+
+    fn current_command(&mut self) -> &mut ShellCommand {
+        if self.commands.is_empty() {
+            // The shell may start reporting in the middle of the prompt.
+            self.commands.push_back(ShellCommand::default());
+        }
+        self.commands.back_mut().unwrap()
+    }
+}
+
+/// An item, found in the PTY output by the [`ShellMarkScanner`].
+#[derive(Debug, PartialEq, Eq)]
+enum ScannedItem {
+    Mark(ShellMark),
+    /// The program in the terminal requested the cursor position.
+    CursorPositionRequest,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+    Csi,
+}
+
+/// Finds the shell marks and the cursor position requests in the PTY output, byte by byte.
+#[derive(Debug, Default)]
+struct ShellMarkScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    overflown: bool,
+}
+
+impl ShellMarkScanner {
+    fn advance(&mut self, byte: u8) -> Option<ScannedItem> {
+        match self.state {
+            ScanState::Ground => {
+                if byte == 0x1b {
+                    self.state = ScanState::Escape;
+                }
+            }
+            ScanState::Escape => {
+                self.sequence.clear();
+                self.overflown = false;
+                self.state = match byte {
+                    b']' => ScanState::Osc,
+                    b'[' => ScanState::Csi,
+                    0x1b => ScanState::Escape,
+                    _ => ScanState::Ground,
+                };
+            }
+            ScanState::Osc => match byte {
+                0x07 => {
+                    self.state = ScanState::Ground;
+                    return self.finish_osc();
+                }
+                0x1b => self.state = ScanState::OscEscape,
+                0x18 | 0x1a => self.state = ScanState::Ground,
+                _ => self.push(byte),
+            },
+            ScanState::OscEscape => {
+                if byte == b'\\' {
+                    self.state = ScanState::Ground;
+                    return self.finish_osc();
+                }
+                // Any other escape sequence cancels the OSC one.
+                self.state = ScanState::Escape;
+                return self.advance(byte);
+            }
+            ScanState::Csi => match byte {
+                0x20..=0x3f => self.push(byte),
+                0x40..=0x7e => {
+                    self.state = ScanState::Ground;
+                    if byte == b'n' && self.sequence == b"6" {
+                        return Some(ScannedItem::CursorPositionRequest);
+                    }
+                }
+                0x1b => self.state = ScanState::Escape,
+                _ => self.state = ScanState::Ground,
+            },
+        }
+        None
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.sequence.len() < MAX_OSC_LEN {
+            self.sequence.push(byte);
+        } else {
+            self.overflown = true;
+        }
+    }
+
+    fn finish_osc(&mut self) -> Option<ScannedItem> {
+        if self.overflown {
+            return None;
+        }
+        parse_mark(&self.sequence).map(ScannedItem::Mark)
+    }
+}
+
+fn parse_mark(sequence: &[u8]) -> Option<ShellMark> {
+    let sequence = std::str::from_utf8(sequence).ok()?;
+    let (code, params) = sequence.split_once(';')?;
+    if code != "133" && code != "633" {
+        return None;
+    }
+    let mut params = params.split(';');
+    Some(match params.next()? {
+        "A" => ShellMark::PromptStart,
+        "B" => ShellMark::CommandStart,
+        "C" => ShellMark::CommandExecuted,
+        "D" => ShellMark::CommandFinished {
+            exit_code: params.next().and_then(|exit_code| exit_code.parse().ok()),
+        },
+        "E" if code == "633" => ShellMark::CommandLine(unescape_command_line(params.next()?)),
+        _ => return None,
+    })
+}
+
+/// Unescapes the command line, reported with `\\` for backslashes and `\xAB` for the other escaped bytes.
+fn unescape_command_line(escaped: &str) -> String {
+    let escaped = escaped.as_bytes();
+    let mut command_line = Vec::with_capacity(escaped.len());
+    let mut ix = 0;
+    while ix < escaped.len() {
+        if escaped[ix] == b'\\' {
+            if escaped.get(ix + 1) == Some(&b'\\') {
+                command_line.push(b'\\');
+                ix += 2;
+                continue;
+            }
+            if escaped.get(ix + 1) == Some(&b'x') {
+                let byte = escaped
+                    .get(ix + 2..ix + 4)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = byte {
+                    command_line.push(byte);
+                    ix += 4;
+                    continue;
+                }
+            }
+        }
+        command_line.push(escaped[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&command_line).into_owned()
+}
+
+/// A mark, read from the PTY, that the terminal has not recorded yet.
+#[derive(Debug)]
+pub(crate) struct PendingMark {
+    pub mark: ShellMark,
+    pub received_at: Instant,
+    /// Whether alacritty has parsed the output up to the mark, so its cursor is at the mark's position.
+    pub parsed: bool,
+}
+
+/// Who has requested the cursor position, in the order of the requests in the PTY output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CursorPositionRequest {
+    Program,
+    Mark,
+}
+
+/// State, shared by the terminal, its PTY reader and its alacritty event listener.
+#[derive(Default)]
+pub(crate) struct ShellIntegrationState {
+    cursor_position_requests: VecDeque<CursorPositionRequest>,
+    pending_mark: Option<PendingMark>,
+    #[cfg(unix)]
+    wakeup: Option<std::os::unix::net::UnixStream>,
+}
+
+impl ShellIntegrationState {
+    /// Handles alacritty's response to a cursor position request.
+    /// Returns whether the response is for the request after a mark, rather than for the program in the terminal.
+    pub fn handle_cursor_position_report(&mut self, report: &str) -> bool {
+        if !(report.starts_with("\x1b[") && report.ends_with('R')) {
+            return false;
+        }
+        match self.cursor_position_requests.pop_front() {
+            Some(CursorPositionRequest::Mark) => {
+                if let Some(pending_mark) = self.pending_mark.as_mut() {
+                    pending_mark.parsed = true;
+                }
+                true
+            }
+            Some(CursorPositionRequest::Program) | None => false,
+        }
+    }
+
+    /// Takes the pending mark, if alacritty has parsed the output up to it, letting the rest of the output through.
+    pub fn take_parsed_mark(&mut self) -> Option<PendingMark> {
+        if !self.pending_mark.as_ref()?.parsed {
+            return None;
+        }
+        let pending_mark = self.pending_mark.take();
+        #[cfg(unix)]
+        if let Some(wakeup) = self.wakeup.as_ref() {
+            // The rest of the output may be read from the PTY already, make the event loop read it from us.
+            io::Write::write(&mut &*wakeup, &[0]).ok();
+        }
+        pending_mark
+    }
+}
+
+/// The PTY, with its output scanned for the shell marks.
+#[cfg(unix)]
+pub(crate) struct ShellIntegrationPty<P> {
+    pty: P,
+    state: Arc<Mutex<ShellIntegrationState>>,
+    scanner: ShellMarkScanner,
+    /// Output, read from the PTY, that was not scanned yet.
+    unscanned: VecDeque<u8>,
+    /// Scanned output, to pass to alacritty.
+    scanned: VecDeque<u8>,
+    read_buffer: Box<[u8]>,
+    /// Wakes the event loop up to read the output, left after the last mark, once the mark is recorded.
+    wakeup: std::os::unix::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl<P> ShellIntegrationPty<P> {
+    pub fn new(pty: P, state: Arc<Mutex<ShellIntegrationState>>) -> io::Result<Self> {
+        let (wakeup, wakeup_tx) = std::os::unix::net::UnixStream::pair()?;
+        wakeup.set_nonblocking(true)?;
+        wakeup_tx.set_nonblocking(true)?;
+        state.lock().wakeup = Some(wakeup_tx);
+        Ok(Self {
+            pty,
+            state,
+            scanner: ShellMarkScanner::default(),
+            unscanned: VecDeque::new(),
+            scanned: VecDeque::new(),
+            read_buffer: vec![0; 0x1_0000].into_boxed_slice(),
+            wakeup,
+        })
+    }
+
+    fn scan(&mut self) {
+        while let Some(byte) = self.unscanned.pop_front() {
+            self.scanned.push_back(byte);
+            match self.scanner.advance(byte) {
+                Some(ScannedItem::CursorPositionRequest) => self
+                    .state
+                    .lock()
+                    .cursor_position_requests
+                    .push_back(CursorPositionRequest::Program),
+                Some(ScannedItem::Mark(mark)) => {
+                    self.scanned.extend(CURSOR_POSITION_REQUEST);
+                    let mut state = self.state.lock();
+                    state
+                        .cursor_position_requests
+                        .push_back(CursorPositionRequest::Mark);
+                    state.pending_mark = Some(PendingMark {
+                        mark,
+                        received_at: Instant::now(),
+                        parsed: false,
+                    });
+                    break;
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedReadWrite> io::Read for ShellIntegrationPty<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut wakeups = [0; 64];
+        while matches!(io::Read::read(&mut self.wakeup, &mut wakeups), Ok(read) if read > 0) {}
+
+        let mark_pending = self.state.lock().pending_mark.is_some();
+        if self.scanned.is_empty() && !mark_pending {
+            self.scan();
+        }
+        if self.scanned.is_empty() {
+            // Keep reading the PTY while the mark is pending, so that the event loop waits for the wakeup instead of spinning.
+            if self.unscanned.len() >= MAX_PENDING_OUTPUT_LEN {
+                std::thread::sleep(PENDING_MARK_POLL_INTERVAL);
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let read = io::Read::read(self.pty.reader(), &mut self.read_buffer)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            self.unscanned.extend(&self.read_buffer[..read]);
+            if mark_pending {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.scan();
+        }
+
+        let len = buf.len().min(self.scanned.len());
+        for (target, byte) in buf.iter_mut().zip(self.scanned.drain(..len)) {
+            *target = byte;
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedReadWrite> alacritty_terminal::tty::EventedReadWrite
+    for ShellIntegrationPty<P>
+{
+    type Reader = Self;
+    type Writer = P::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<polling::Poller>,
+        interest: polling::Event,
+        mode: polling::PollMode,
+    ) -> io::Result<()> {
+        use std::os::fd::AsRawFd as _;
+        unsafe {
+            self.pty.register(poll, interest, mode)?;
+            poll.add_with_mode(
+                self.wakeup.as_raw_fd(),
+                polling::Event::readable(interest.key),
+                mode,
+            )
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<polling::Poller>,
+        interest: polling::Event,
+        mode: polling::PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)?;
+        poll.modify_with_mode(&self.wakeup, polling::Event::readable(interest.key), mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<polling::Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)?;
+        poll.delete(&self.wakeup)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::tty::EventedPty> alacritty_terminal::tty::EventedPty
+    for ShellIntegrationPty<P>
+{
+    fn next_child_event(&mut self) -> Option<alacritty_terminal::tty::ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+#[cfg(unix)]
+impl<P: alacritty_terminal::event::OnResize> alacritty_terminal::event::OnResize
+    for ShellIntegrationPty<P>
+{
+    fn on_resize(&mut self, window_size: alacritty_terminal::event::WindowSize) {
+        self.pty.on_resize(window_size)
+    }
+}
+
+/// Makes the shell load Zed's shell integration script on startup, if it is bash, zsh or fish.
+/// Returns `None` if the shell is not supported.
+pub(crate) fn inject_shell_integration(
+    shell: &Shell,
+    env: &mut HashMap<String, String>,
+) -> Option<Shell> {
+    if cfg!(windows) {
+        return None;
+    }
+    let (program, args) = match shell {
+        Shell::System => (std::env::var("SHELL").ok()?, None),
+        Shell::Program(program) => (program.clone(), None),
+        Shell::WithArguments { program, args } => (program.clone(), Some(args)),
+    };
+    let shell_name = Path::new(&program)
+        .file_name()?
+        .to_str()?
+        .trim_start_matches('-')
+        .to_string();
+    let scripts_dir = install_scripts().log_err()?;
+
+    match shell_name.as_str() {
+        "bash" => {
+            // Bash ignores the init file in login shells.
+            if args.map_or(false, |args| !args.is_empty()) {
+                return None;
+            }
+            if cfg!(target_os = "macos") && matches!(shell, Shell::System) {
+                env.insert("ZED_BASH_LOGIN".to_string(), "1".to_string());
+            }
+            Some(Shell::WithArguments {
+                program,
+                args: vec![
+                    "--init-file".to_string(),
+                    scripts_dir.join("zed.bash").to_string_lossy().into_owned(),
+                ],
+            })
+        }
+        "zsh" => {
+            if let Some(user_zdotdir) = env
+                .get("ZDOTDIR")
+                .cloned()
+                .or_else(|| std::env::var("ZDOTDIR").ok())
+            {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                scripts_dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            Some(shell.clone())
+        }
+        "fish" => {
+            let user_data_dirs = env
+                .get("XDG_DATA_DIRS")
+                .cloned()
+                .or_else(|| std::env::var("XDG_DATA_DIRS").ok());
+            let data_dirs = user_data_dirs
+                .clone()
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            if let Some(user_data_dirs) = user_data_dirs {
+                env.insert("ZED_USER_XDG_DATA_DIRS".to_string(), user_data_dirs);
+            }
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{}:{data_dirs}", scripts_dir.to_string_lossy()),
+            );
+            Some(shell.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Writes the shell integration scripts into Zed's support directory, if they are outdated.
+fn install_scripts() -> io::Result<PathBuf> {
+    let scripts_dir = util::paths::SHELL_INTEGRATION_DIR.clone();
+    for (path, script) in [
+        (scripts_dir.join("zed.bash"), BASH_SCRIPT),
+        (scripts_dir.join("zed.zsh"), ZSH_SCRIPT),
+        (scripts_dir.join("zsh").join(".zshenv"), ZSH_ENV_SCRIPT),
+        (
+            scripts_dir
+                .join("fish")
+                .join("vendor_conf.d")
+                .join("zed.fish"),
+            FISH_SCRIPT,
+        ),
+    ] {
+        if fs::read_to_string(&path).ok().as_deref() == Some(script) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, script)?;
+    }
+    Ok(scripts_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(output: &[u8]) -> Vec<ScannedItem> {
+        let mut scanner = ShellMarkScanner::default();
+        output
+            .iter()
+            .filter_map(|byte| scanner.advance(*byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_marks() {
+        assert_eq!(
+            scan(b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]633;E;ls\\x3b echo \\\\ok\x07\x1b]133;C\x07out\r\n\x1b]133;D;1\x07"),
+            vec![
+                ScannedItem::Mark(ShellMark::PromptStart),
+                ScannedItem::Mark(ShellMark::CommandStart),
+                ScannedItem::Mark(ShellMark::CommandLine("ls; echo \\ok".to_string())),
+                ScannedItem::Mark(ShellMark::CommandExecuted),
+                ScannedItem::Mark(ShellMark::CommandFinished { exit_code: Some(1) }),
+            ]
+        );
+        assert_eq!(
+            scan(b"\x1b]133;D\x07\x1b]0;title\x07\x1b[6n\x1b[1;6n\x1b]133;X\x07\x1b]133;A\x1b[m"),
+            vec![
+                ScannedItem::Mark(ShellMark::CommandFinished { exit_code: None }),
+                ScannedItem::CursorPositionRequest,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_commands() {
+        let now = Instant::now();
+        let point = |line| ShellPoint { line, column: 0 };
+        let mut commands = ShellCommands::default();
+
+        // The status of a command is reported before every prompt, even if no command was run.
+        assert!(commands
+            .record(
+                ShellMark::CommandFinished { exit_code: Some(0) },
+                point(0),
+                now
+            )
+            .is_none());
+        commands.record(ShellMark::PromptStart, point(0), now);
+        commands.record(
+            ShellMark::CommandStart,
+            ShellPoint { line: 0, column: 2 },
+            now,
+        );
+        commands.record(ShellMark::CommandExecuted, point(1), now);
+        assert!(commands.running().is_some());
+        let finished = commands
+            .record(
+                ShellMark::CommandFinished { exit_code: Some(2) },
+                point(3),
+                now + Duration::from_secs(1),
+            )
+            .cloned()
+            .unwrap();
+        assert!(commands.running().is_none());
+        assert!(finished.has_failed());
+        assert_eq!(finished.duration, Some(Duration::from_secs(1)));
+        assert_eq!(finished.output_start, Some(point(1)));
+        assert_eq!(finished.output_end, Some(point(3)));
+
+        commands.record(ShellMark::PromptStart, point(3), now);
+        assert!(commands
+            .record(
+                ShellMark::CommandFinished { exit_code: Some(0) },
+                point(3),
+                now
+            )
+            .is_none());
+        assert_eq!(commands.iter().count(), 2);
+
+        commands.forget_positions();
+        assert!(commands
+            .iter()
+            .all(|command| command.prompt_start.is_none()));
+    }
+
+    #[test]
+    fn test_cursor_position_reports() {
+        let mut state = ShellIntegrationState::default();
+        state
+            .cursor_position_requests
+            .extend([CursorPositionRequest::Program, CursorPositionRequest::Mark]);
+        state.pending_mark = Some(PendingMark {
+            mark: ShellMark::PromptStart,
+            received_at: Instant::now(),
+            parsed: false,
+        });
+
+        assert!(!state.handle_cursor_position_report("\x1b[>0;1c"));
+        assert!(state.take_parsed_mark().is_none());
+        assert!(!state.handle_cursor_position_report("\x1b[1;1R"));
+        assert!(state.take_parsed_mark().is_none());
+        assert!(state.handle_cursor_position_report("\x1b[2;3R"));
+        assert_eq!(
+            state
+                .take_parsed_mark()
+                .map(|pending_mark| pending_mark.mark),
+            Some(ShellMark::PromptStart)
+        );
+    }
+}
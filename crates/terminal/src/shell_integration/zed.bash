@@ -0,0 +1,46 @@
+# Zed's shell integration for bash, loaded instead of the usual init files with `--init-file`.
+# Reports the prompts and the commands with the `OSC 133` semantic prompt marks.
+
+if [[ -n "$ZED_BASH_LOGIN" ]]; then
+    unset ZED_BASH_LOGIN
+    # Terminals on macOS run login shells.
+    [[ -r /etc/profile ]] && builtin source /etc/profile
+    for __zed_profile in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [[ -r "$__zed_profile" ]]; then
+            builtin source "$__zed_profile"
+            break
+        fi
+    done
+    unset __zed_profile
+else
+    [[ -r /etc/bash.bashrc ]] && builtin source /etc/bash.bashrc
+    [[ -r ~/.bashrc ]] && builtin source ~/.bashrc
+fi
+
+if [[ -z "$__zed_shell_integration" ]]; then
+    __zed_shell_integration=1
+
+    __zed_prompt_start() {
+        local last_status=$?
+        builtin printf '\e]133;D;%s\a' "$last_status"
+        return $last_status
+    }
+
+    # Prompts may be recomputed by the other prompt commands, so they are wrapped before every prompt.
+    __zed_prompt_end() {
+        local last_status=$?
+        if [[ "$PS1" != *'133;B'* ]]; then
+            PS1="\[\e]133;A\a\]${PS1}\[\e]133;B\a\]"
+        fi
+        if [[ "$PS0" != *'133;C'* ]]; then
+            PS0="${PS0}\e]133;C\a"
+        fi
+        return $last_status
+    }
+
+    if [[ "$(builtin declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(__zed_prompt_start "${PROMPT_COMMAND[@]}" __zed_prompt_end)
+    else
+        PROMPT_COMMAND="__zed_prompt_start${PROMPT_COMMAND:+$'\n'$PROMPT_COMMAND}"$'\n'"__zed_prompt_end"
+    fi
+fi
@@ -0,0 +1,32 @@
+# Zed's shell integration for fish, loaded with `XDG_DATA_DIRS` pointing at this directory.
+# Reports the prompts and the commands with the `OSC 133` semantic prompt marks.
+
+if set -q ZED_USER_XDG_DATA_DIRS
+    set -gx XDG_DATA_DIRS $ZED_USER_XDG_DATA_DIRS
+    set -e ZED_USER_XDG_DATA_DIRS
+else
+    set -e XDG_DATA_DIRS
+end
+
+status is-interactive; or exit
+
+function __zed_prompt_start --on-event fish_prompt
+    printf '\e]133;A\a'
+    # The prompt is defined by the user's config, loaded after this file.
+    if not functions -q __zed_original_fish_prompt; and functions -q fish_prompt
+        functions -c fish_prompt __zed_original_fish_prompt
+        function fish_prompt
+            __zed_original_fish_prompt
+            printf '\e]133;B\a'
+        end
+    end
+end
+
+function __zed_preexec --on-event fish_preexec
+    set -l command_line (string replace -a '\\' '\\\\' -- $argv | string replace -a ';' '\\x3b')
+    printf '\e]633;E;%s\a\e]133;C\a' (string join '\\x0a' -- $command_line)
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
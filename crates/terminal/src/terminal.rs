@@ -2,29 +2,34 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod graphics;
 mod pty_info;
+mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
-    event_loop::{EventLoop, Msg, Notifier},
-    grid::{Dimensions, Scroll as AlacScroll},
+    event_loop::{EventLoop, EventLoopSender, Msg, Notifier},
+    grid::{Dimensions, Grid, Row, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Processor, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
 
 use futures::{
-    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    channel::{
+        mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
     FutureExt,
 };
 
@@ -35,14 +40,22 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use graphics::GraphicsState;
+pub use graphics::{is_image_anchor, TerminalImagePlacement};
+use parking_lot::Mutex;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+pub use shell_integration::{ShellCommand, ShellCommands, ShellPoint};
+use shell_integration::{ShellIntegrationState, ShellMark};
 use smol::channel::{Receiver, Sender};
-use task::TaskId;
+use task::{
+    Problem, ProblemMatcher, ProblemScanner, TaskId, TestResult, TestResultsFormat,
+    TestResultsScanner,
+};
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
-use util::truncate_and_trailoff;
+use util::{truncate_and_trailoff, ResultExt};
 
 use std::{
     cmp::{self, min},
@@ -64,7 +77,16 @@ use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
 actions!(
     terminal,
-    [Clear, Copy, Paste, ShowCharacterPalette, SearchTest,]
+    [
+        Clear,
+        Copy,
+        Paste,
+        ShowCharacterPalette,
+        SearchTest,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        CopyLastCommandOutput,
+    ]
 );
 
 ///Scrolling is unbearably sluggish by default. Alacritty supports a configurable
@@ -92,6 +114,12 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// Problems, found in the task output since the previous event of this kind.
+    ProblemsFound(Vec<Problem>),
+    /// Test results, found in the task output since the previous event of this kind.
+    TestResultsFound(Vec<TestResult>),
+    /// The task of the terminal stopped running, all of its output is scanned already.
+    TaskFinished(TaskStatus),
 }
 
 #[derive(Clone, Debug)]
@@ -130,11 +158,22 @@ enum InternalEvent {
 
 ///A translation struct for Alacritty to communicate with us from their event loop
 #[derive(Clone)]
-pub struct ZedListener(UnboundedSender<AlacTermEvent>);
+pub struct ZedListener {
+    events_tx: UnboundedSender<AlacTermEvent>,
+    shell_integration: Option<Arc<Mutex<ShellIntegrationState>>>,
+}
 
 impl EventListener for ZedListener {
     fn send_event(&self, event: AlacTermEvent) {
-        self.0.unbounded_send(event).ok();
+        if let (AlacTermEvent::PtyWrite(text), Some(shell_integration)) =
+            (&event, &self.shell_integration)
+        {
+            // Cursor positions, requested after the shell marks, are not meant for the shell.
+            if shell_integration.lock().handle_cursor_position_report(text) {
+                return;
+            }
+        }
+        self.events_tx.unbounded_send(event).ok();
     }
 }
 
@@ -305,6 +344,7 @@ impl TerminalBuilder {
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
+        shell_integration: bool,
         window: AnyWindowHandle,
         completion_tx: Sender<()>,
     ) -> Result<TerminalBuilder> {
@@ -314,8 +354,18 @@ impl TerminalBuilder {
 
         env.insert("ZED_TERM".to_string(), "true".to_string());
 
+        let integrated_shell = if shell_integration && task.is_none() {
+            shell_integration::inject_shell_integration(&shell, &mut env)
+        } else {
+            None
+        };
+        let shell_integration = integrated_shell
+            .is_some()
+            .then(|| Arc::new(Mutex::new(ShellIntegrationState::default())));
+
         let pty_options = {
-            let alac_shell = match shell.clone() {
+            let shell = integrated_shell.unwrap_or_else(|| shell.clone());
+            let alac_shell = match shell {
                 Shell::System => None,
                 Shell::Program(program) => {
                     Some(alacritty_terminal::tty::Shell::new(program, Vec::new()))
@@ -354,12 +404,12 @@ impl TerminalBuilder {
         //Spawn a task so the Alacritty EventLoop can communicate with us in a view context
         //TODO: Remove with a bounded sender which can be dispatched on &self
         let (events_tx, events_rx) = unbounded();
+        let listener = ZedListener {
+            events_tx,
+            shell_integration: shell_integration.clone(),
+        };
         //Set up the terminal...
-        let mut term = Term::new(
-            config,
-            &TerminalSize::default(),
-            ZedListener(events_tx.clone()),
-        );
+        let mut term = Term::new(config, &TerminalSize::default(), listener.clone());
 
         //Start off blinking if we need to
         if let Some(TerminalBlink::On) = blink_settings {
@@ -391,24 +441,54 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let graphics = Arc::new(Mutex::new(GraphicsState::default()));
+
         //And connect them together
-        let event_loop = EventLoop::new(
-            term.clone(),
-            ZedListener(events_tx.clone()),
-            pty,
-            pty_options.hold,
-            false,
-        )?;
-
-        //Kick things off
-        let pty_tx = event_loop.channel();
-        let _io_thread = event_loop.spawn(); // DANGER
+        #[cfg(unix)]
+        let pty_tx = {
+            let pty = graphics::GraphicsPty::new(pty, graphics.clone())?;
+            match shell_integration.clone() {
+                Some(state) => spawn_event_loop(
+                    term.clone(),
+                    listener,
+                    shell_integration::ShellIntegrationPty::new(pty, state)?,
+                    pty_options.hold,
+                )?,
+                None => spawn_event_loop(term.clone(), listener, pty, pty_options.hold)?,
+            }
+        };
+        #[cfg(not(unix))]
+        let pty_tx = spawn_event_loop(term.clone(), listener, pty, pty_options.hold)?;
 
         let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
         let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/@\-~]+"#).unwrap();
 
+        let task_output_scanner = task.as_ref().and_then(|task| {
+            let problem_scanner = if task.problem_matcher.is_empty() {
+                None
+            } else {
+                ProblemScanner::new(&task.problem_matcher, &task.label).log_err()
+            };
+            let test_results_scanner = task
+                .test_results
+                .clone()
+                .filter(TestResultsFormat::is_printed)
+                .map(TestResultsScanner::new);
+            if problem_scanner.is_none() && test_results_scanner.is_none() {
+                return None;
+            }
+            Some(TaskOutputScanner {
+                problem_scanner,
+                test_results_scanner,
+                scanned_lines: 0,
+                history_size: 0,
+                last_scanned_rows: VecDeque::new(),
+            })
+        });
+
         let terminal = Terminal {
             task,
+            task_output_scanner,
             pty_tx: Notifier(pty_tx),
             completion_tx,
             term,
@@ -427,6 +507,12 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            shell_integration,
+            shell_commands: ShellCommands::default(),
+            shell_history_size: 0,
+            scrolling_history,
+            shell_command_waiters: Vec::new(),
+            graphics,
         };
 
         Ok(TerminalBuilder {
@@ -498,6 +584,40 @@ impl TerminalBuilder {
     }
 }
 
+/// Text of the terminal grid from the start point up to the end point, exclusive.
+fn shell_text(term: &Term<ZedListener>, start: ShellPoint, end: ShellPoint) -> String {
+    let grid = term.grid();
+    let history_size = grid.history_size() as i32;
+    let start = AlacPoint::new(Line(start.line as i32 - history_size), Column(start.column));
+    let end = if end.column == 0 {
+        AlacPoint::new(Line(end.line as i32 - history_size - 1), grid.last_column())
+    } else {
+        AlacPoint::new(Line(end.line as i32 - history_size), Column(end.column - 1))
+    };
+    if end < start {
+        return String::new();
+    }
+    term.bounds_to_string(start, end).trim_end().to_string()
+}
+
+/// Connects the terminal to its PTY and starts the event loop, passing the PTY output to the terminal.
+fn spawn_event_loop<T>(
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    listener: ZedListener,
+    pty: T,
+    hold: bool,
+) -> Result<EventLoopSender>
+where
+    T: tty::EventedPty + alacritty_terminal::event::OnResize + Send + 'static,
+{
+    let event_loop = EventLoop::new(term, listener, pty, hold, false)?;
+
+    //Kick things off
+    let pty_tx = event_loop.channel();
+    let _io_thread = event_loop.spawn(); // DANGER
+    Ok(pty_tx)
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IndexedCell {
     pub point: AlacPoint,
@@ -525,6 +645,10 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    /// Lines of the viewport with the prompts of the commands that have failed, as reported by the shell integration.
+    pub failed_command_lines: Vec<usize>,
+    /// Images, sent with the sixel or the kitty graphics protocol, that are visible in the viewport.
+    pub images: Vec<TerminalImagePlacement>,
 }
 
 #[derive(Clone)]
@@ -549,6 +673,8 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            failed_command_lines: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -581,6 +707,14 @@ pub struct Terminal {
     url_regex: RegexSearch,
     word_regex: RegexSearch,
     task: Option<TaskState>,
+    task_output_scanner: Option<TaskOutputScanner>,
+    shell_integration: Option<Arc<Mutex<ShellIntegrationState>>>,
+    shell_commands: ShellCommands,
+    /// Size of the scrollback history when the last shell mark was recorded.
+    shell_history_size: usize,
+    scrolling_history: usize,
+    shell_command_waiters: Vec<oneshot::Sender<()>>,
+    graphics: Arc<Mutex<GraphicsState>>,
 }
 
 pub struct TaskState {
@@ -590,6 +724,109 @@ pub struct TaskState {
     pub command_label: String,
     pub status: TaskStatus,
     pub completion_rx: Receiver<()>,
+    pub problem_matcher: Vec<ProblemMatcher>,
+    pub test_results: Option<TestResultsFormat>,
+}
+
+/// Feeds the task output into the problem matchers and the test results scanner, line by line, as it gets printed.
+struct TaskOutputScanner {
+    problem_scanner: Option<ProblemScanner>,
+    test_results_scanner: Option<TestResultsScanner>,
+    /// Lines of the grid, counting from its topmost line, that were already scanned.
+    scanned_lines: usize,
+    /// Size of the scrollback history at the last scan.
+    history_size: usize,
+    /// Text of the last scanned rows, to find them again after the oldest lines of a full history were dropped.
+    last_scanned_rows: VecDeque<String>,
+}
+
+/// How many of the last scanned rows are remembered to find them again.
+const SCANNED_ROWS_TO_FIND: usize = 3;
+
+impl TaskOutputScanner {
+    fn reset(&mut self) {
+        self.scanned_lines = 0;
+        self.history_size = 0;
+        self.last_scanned_rows.clear();
+    }
+
+    /// Shifts the scanned lines up by the number of lines the grid has scrolled out of its history
+    /// since the last scan. With the history growing, no lines were dropped; once it is full,
+    /// the last scanned rows are looked up to see how far they moved.
+    fn shift_scanned_lines(&mut self, grid: &Grid<Cell>, scrolling_history: usize) {
+        let history_size = grid.history_size();
+        if history_size < self.history_size {
+            // The history was cleared, along with the scanned lines.
+            self.reset();
+        } else if history_size >= scrolling_history && !self.last_scanned_rows.is_empty() {
+            let rows = self.last_scanned_rows.len();
+            let first_row = self.scanned_lines - rows;
+            let shift = (0..=first_row).find(|shift| {
+                self.last_scanned_rows.iter().enumerate().all(|(ix, text)| {
+                    let line = (first_row - shift + ix) as i32 - history_size as i32;
+                    row_text(&grid[Line(line)]) == *text
+                })
+            });
+            match shift {
+                Some(shift) => self.scanned_lines -= shift,
+                // More lines than the whole history were printed, the scanned ones are gone.
+                None => self.reset(),
+            }
+        }
+        self.history_size = history_size;
+    }
+
+    /// Returns the output lines completed since the last scan, with the last line
+    /// counting as complete once the task finished.
+    fn scan_lines(
+        &mut self,
+        grid: &Grid<Cell>,
+        scrolling_history: usize,
+        task_finished: bool,
+    ) -> Vec<String> {
+        self.shift_scanned_lines(grid, scrolling_history);
+        let history_size = grid.history_size() as i32;
+        let last_column = grid.last_column();
+        let scan_end = history_size + grid.cursor.point.line.0 + task_finished as i32;
+
+        let mut lines = Vec::new();
+        let mut line_rows = Vec::new();
+        let mut line_ix = self.scanned_lines as i32;
+        while line_ix < scan_end {
+            let row = &grid[Line(line_ix - history_size)];
+            line_rows.push(row_text(row));
+            line_ix += 1;
+
+            // Wrapped rows continue the same output line.
+            let wrapped = row[last_column].flags.contains(Flags::WRAPLINE);
+            if !wrapped || (task_finished && line_ix == scan_end) {
+                lines.push(line_rows.concat().trim_end().to_string());
+                self.last_scanned_rows.extend(line_rows.drain(..));
+                while self.last_scanned_rows.len() > SCANNED_ROWS_TO_FIND {
+                    self.last_scanned_rows.pop_front();
+                }
+                self.scanned_lines = line_ix as usize;
+            }
+        }
+        lines
+    }
+}
+
+fn row_text(row: &Row<Cell>) -> String {
+    let mut text = String::new();
+    for cell in row[..Column(row.len())].iter() {
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        text.push(cell.c);
+        if let Some(zero_width) = cell.zerowidth() {
+            text.extend(zero_width);
+        }
+    }
+    text
 }
 
 /// A status of the current terminal tab's task.
@@ -653,6 +890,8 @@ impl Terminal {
             }
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
+                self.record_shell_mark();
+                self.scan_task_output(false, cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -698,6 +937,12 @@ impl Terminal {
 
                 self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
 
+                if new_size.num_lines() != term.screen_lines()
+                    || new_size.num_columns() != term.columns()
+                {
+                    // Lines are reflowed on resize.
+                    self.shell_commands.forget_positions();
+                }
                 term.resize(new_size);
             }
             InternalEvent::Clear => {
@@ -730,6 +975,12 @@ impl Terminal {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
 
+                self.shell_commands.clear();
+                self.shell_history_size = 0;
+                if let Some(output_scanner) = self.task_output_scanner.as_mut() {
+                    output_scanner.reset();
+                }
+
                 cx.emit(Event::Wakeup);
             }
             InternalEvent::Scroll(scroll) => {
@@ -789,7 +1040,11 @@ impl Terminal {
                 )
                 .grid_clamp(term, Boundary::Grid);
 
-                let link = term.grid().index(point).hyperlink();
+                let link = term
+                    .grid()
+                    .index(point)
+                    .hyperlink()
+                    .filter(|link| !is_image_anchor(link));
                 let found_word = if link.is_some() {
                     let mut min_index = point;
                     loop {
@@ -1052,26 +1307,46 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content = Self::make_content(
+            &terminal,
+            &self.last_content,
+            &self.shell_commands,
+            &self.graphics.lock(),
+        );
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        shell_commands: &ShellCommands,
+        graphics: &GraphicsState,
+    ) -> TerminalContent {
         let content = term.renderable_content();
+        let viewport_top = term.grid().history_size() - content.display_offset;
+        let failed_command_lines = shell_commands
+            .iter()
+            .filter(|command| command.has_failed())
+            .filter_map(|command| command.prompt_start)
+            .filter_map(|prompt_start| prompt_start.line.checked_sub(viewport_top))
+            .filter(|line| *line < term.screen_lines())
+            .collect();
+        let cells = content
+            .display_iter
+            //TODO: Add this once there's a way to retain empty lines
+            // .filter(|ic| {
+            //     !ic.flags.contains(Flags::HIDDEN)
+            //         && !(ic.bg == Named(NamedColor::Background)
+            //             && ic.c == ' '
+            //             && !ic.flags.contains(Flags::INVERSE))
+            // })
+            .map(|ic| IndexedCell {
+                point: ic.point,
+                cell: ic.cell.clone(),
+            })
+            .collect::<Vec<IndexedCell>>();
+        let images = graphics.placements(&cells);
         TerminalContent {
-            cells: content
-                .display_iter
-                //TODO: Add this once there's a way to retain empty lines
-                // .filter(|ic| {
-                //     !ic.flags.contains(Flags::HIDDEN)
-                //         && !(ic.bg == Named(NamedColor::Background)
-                //             && ic.c == ' '
-                //             && !ic.flags.contains(Flags::INVERSE))
-                // })
-                .map(|ic| IndexedCell {
-                    point: ic.point,
-                    cell: ic.cell.clone(),
-                })
-                .collect::<Vec<IndexedCell>>(),
+            cells,
             mode: content.mode,
             display_offset: content.display_offset,
             selection_text: term.selection_to_string(),
@@ -1080,6 +1355,8 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            failed_command_lines,
+            images,
         }
     }
 
@@ -1277,7 +1554,10 @@ impl Terminal {
             //Hyperlinks
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index = content_index_for_mouse(position, &self.last_content.size);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                let link = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !is_image_anchor(link));
+                if let Some(link) = link {
                     cx.open_url(link.uri());
                 } else if self.secondary_pressed {
                     self.events
@@ -1422,7 +1702,9 @@ impl Terminal {
         self.task.as_ref()
     }
 
-    pub fn wait_for_completed_task(&self, cx: &mut AppContext) -> Task<()> {
+    /// Waits for the terminal's task to finish, or, in a shell with the shell integration,
+    /// for the command that is running currently to finish.
+    pub fn wait_for_completed_task(&mut self, cx: &mut AppContext) -> Task<()> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
                 let mut completion_receiver = task.completion_rx.clone();
@@ -1430,10 +1712,250 @@ impl Terminal {
                     completion_receiver.next().await;
                 });
             }
+        } else if self.shell_commands.running().is_some() {
+            let (tx, rx) = oneshot::channel();
+            self.shell_command_waiters.push(tx);
+            return cx.spawn(|_| async move {
+                rx.await.ok();
+            });
         }
         Task::ready(())
     }
 
+    /// Commands, run in the terminal's shell, as reported by the shell integration.
+    pub fn shell_commands(&self) -> &ShellCommands {
+        &self.shell_commands
+    }
+
+    /// Records the shell mark, that alacritty has parsed, at the terminal cursor position.
+    fn record_shell_mark(&mut self) {
+        let Some(shell_integration) = self.shell_integration.as_ref() else {
+            return;
+        };
+        // The PTY output stays paused after the mark until it is taken, so the cursor is at the mark.
+        let term = self.term.lock_unfair();
+        let Some(pending_mark) = shell_integration.lock().take_parsed_mark() else {
+            return;
+        };
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let grid = term.grid();
+        let history_size = grid.history_size();
+        if history_size < self.shell_history_size || history_size >= self.scrolling_history {
+            // The lines were cleared or the oldest lines were dropped, the recorded lines are shifted.
+            self.shell_commands.forget_positions();
+        }
+        self.shell_history_size = history_size;
+        let cursor = grid.cursor.point;
+        let point = ShellPoint {
+            line: history_size + cursor.line.0.max(0) as usize,
+            column: cursor.column.0,
+        };
+
+        let mark = pending_mark.mark;
+        if mark == ShellMark::CommandExecuted {
+            // Not every shell reports the command line, read it from the prompt line instead.
+            let typed_command = self
+                .shell_commands
+                .iter()
+                .next_back()
+                .filter(|command| command.command_line.is_none())
+                .and_then(|command| command.command_start)
+                .map(|command_start| shell_text(&term, command_start, point));
+            if let Some(typed_command) = typed_command.filter(|text| !text.is_empty()) {
+                self.shell_commands.record(
+                    ShellMark::CommandLine(typed_command),
+                    point,
+                    pending_mark.received_at,
+                );
+            }
+        }
+
+        let finished = self
+            .shell_commands
+            .record(mark, point, pending_mark.received_at)
+            .is_some();
+        if finished {
+            for waiter in self.shell_command_waiters.drain(..) {
+                waiter.send(()).ok();
+            }
+        }
+    }
+
+    /// Scrolls the terminal, so that the closest prompt above the viewport is at its top.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let history_size = self.term.lock().grid().history_size();
+        let viewport_top = history_size - self.last_content.display_offset;
+        let previous_prompt = self
+            .shell_commands
+            .iter()
+            .rev()
+            .filter_map(|command| command.prompt_start)
+            .find(|prompt_start| prompt_start.line < viewport_top);
+        if let Some(prompt_start) = previous_prompt {
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    (viewport_top - prompt_start.line) as i32,
+                )));
+        }
+    }
+
+    /// Scrolls the terminal, so that the closest prompt below the viewport top is at its top.
+    pub fn scroll_to_next_prompt(&mut self) {
+        let history_size = self.term.lock().grid().history_size();
+        let viewport_top = history_size - self.last_content.display_offset;
+        let next_prompt = self
+            .shell_commands
+            .iter()
+            .filter_map(|command| command.prompt_start)
+            .find(|prompt_start| prompt_start.line > viewport_top);
+        match next_prompt {
+            Some(prompt_start) => self
+                .events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    -((prompt_start.line - viewport_top).min(self.last_content.display_offset)
+                        as i32),
+                ))),
+            None => self
+                .events
+                .push_back(InternalEvent::Scroll(AlacScroll::Bottom)),
+        }
+    }
+
+    /// Copies the output of the last command, run in the shell, into the clipboard.
+    /// Returns whether any command output was found.
+    pub fn copy_last_command_output(&mut self, cx: &mut ModelContext<Self>) -> bool {
+        let Some(command) = self
+            .shell_commands
+            .iter()
+            .rev()
+            .find(|command| command.output_start.is_some())
+        else {
+            return false;
+        };
+        let Some(output_start) = command.output_start else {
+            return false;
+        };
+        let output = {
+            let term = self.term.lock();
+            let output_end = command.output_end.unwrap_or_else(|| {
+                let grid = term.grid();
+                ShellPoint {
+                    line: grid.history_size() + grid.cursor.point.line.0.max(0) as usize,
+                    column: grid.cursor.point.column.0,
+                }
+            });
+            shell_text(&term, output_start, output_end)
+        };
+        cx.write_to_clipboard(ClipboardItem::new(output));
+        true
+    }
+
+    /// Text of the terminal scrollback history and screen, up to the cursor line, limited to the last `max_lines` lines.
+    /// The text is collected on a background thread, as the history can be long.
+    pub fn scrollback_text(&self, max_lines: usize, cx: &AppContext) -> Task<String> {
+        let term = self.term.clone();
+        cx.background_executor().spawn(async move {
+            let term = term.lock();
+            let grid = term.grid();
+            let end = AlacPoint::new(grid.cursor.point.line, grid.last_column());
+            let start_line = cmp::max(
+                term.topmost_line(),
+                Line(end.line.0 - max_lines.saturating_sub(1) as i32),
+            );
+            term.bounds_to_string(AlacPoint::new(start_line, Column(0)), end)
+                .trim_end()
+                .to_string()
+        })
+    }
+
+    /// Prints the text, saved from a terminal of the previous session, as if it came from the PTY.
+    /// Is meant to be called right after the terminal is created, so the text ends up above the shell output.
+    pub fn restore_scrollback(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let mut parser: Processor = Processor::new();
+        let mut term = self.term.lock();
+        for line in text.lines() {
+            for byte in line.bytes().chain(*b"\r\n") {
+                parser.advance(&mut *term, byte);
+            }
+        }
+    }
+
+    /// Restores the title, set by the shell in a terminal of the previous session, until the shell sets a new one.
+    pub fn restore_title(&mut self, title: String, cx: &mut ModelContext<Self>) {
+        if self.breadcrumb_text.is_empty() {
+            self.breadcrumb_text = title;
+            cx.emit(Event::BreadcrumbsChanged);
+        }
+    }
+
+    /// Associates the terminal with the task that was run in its place in the previous session,
+    /// so that the task reruns reuse this terminal.
+    pub fn restore_task(
+        &mut self,
+        id: TaskId,
+        full_label: String,
+        label: String,
+        command_label: String,
+        mut status: TaskStatus,
+    ) {
+        status.register_terminal_exit();
+        let (_, completion_rx) = smol::channel::bounded(1);
+        self.task = Some(TaskState {
+            id,
+            full_label,
+            label,
+            command_label,
+            status,
+            completion_rx,
+            problem_matcher: Vec::new(),
+            test_results: None,
+        });
+    }
+
+    /// Passes the task output lines, printed since the last scan, to the problem matchers and the test results scanner.
+    /// The line under the cursor may still be printed, so it is scanned only when the task has finished.
+    fn scan_task_output(&mut self, task_finished: bool, cx: &mut ModelContext<Self>) {
+        let Some(output_scanner) = self.task_output_scanner.as_mut() else {
+            return;
+        };
+        if !task_finished
+            && self
+                .task
+                .as_ref()
+                .map_or(true, |task| task.status != TaskStatus::Running)
+        {
+            return;
+        }
+
+        let lines = {
+            let term = self.term.lock_unfair();
+            output_scanner.scan_lines(term.grid(), self.scrolling_history, task_finished)
+        };
+        let mut problems = Vec::new();
+        let mut test_results = Vec::new();
+        for line in &lines {
+            if let Some(problem_scanner) = output_scanner.problem_scanner.as_mut() {
+                problems.extend(problem_scanner.scan_line(line));
+            }
+            if let Some(test_results_scanner) = output_scanner.test_results_scanner.as_mut() {
+                test_results.extend(test_results_scanner.scan_line(line));
+            }
+        }
+
+        if !problems.is_empty() {
+            cx.emit(Event::ProblemsFound(problems));
+        }
+        if !test_results.is_empty() {
+            cx.emit(Event::TestResultsFound(test_results));
+        }
+    }
+
     fn register_task_finished(
         &mut self,
         error_code: Option<i32>,
@@ -1452,6 +1974,11 @@ impl Terminal {
         if task.status != TaskStatus::Running {
             return;
         }
+        // Scan the rest of the output, before the task summary gets appended to it.
+        self.scan_task_output(true, cx);
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
         match error_code {
             Some(error_code) => {
                 task.status.register_task_exit(error_code);
@@ -1461,6 +1988,8 @@ impl Terminal {
             }
         };
 
+        cx.emit(Event::TaskFinished(task.status));
+
         let (task_line, command_line) = task_summary(task, error_code);
         // SAFETY: the invocation happens on non `TaskStatus::Running` tasks, once,
         // after either `AlacTermEvent::Exit` or `AlacTermEvent::ChildExit` events that are spawned
@@ -1667,15 +2196,21 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::VecDeque;
+
     use alacritty_terminal::{
+        event::VoidListener,
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::{cell::Cell, Config},
+        vte::ansi::Processor,
+        Term,
     };
-    use gpui::{point, size, Pixels};
+    use gpui::{point, px, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, rgb_for_index, IndexedCell, TaskOutputScanner, TerminalContent,
+        TerminalSize,
     };
 
     #[test]
@@ -1687,6 +2222,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scan_lines_with_full_history() {
+        // 10 columns and 4 lines, with 5 lines of history.
+        let terminal_size = TerminalSize::new(px(1.), px(1.), size(px(10.), px(4.)));
+        let config = Config {
+            scrolling_history: 5,
+            ..Config::default()
+        };
+        let mut term = Term::new(config, &terminal_size, VoidListener);
+        let mut processor: Processor = Processor::new();
+        let mut scanner = TaskOutputScanner {
+            problem_scanner: None,
+            test_results_scanner: None,
+            scanned_lines: 0,
+            history_size: 0,
+            last_scanned_rows: VecDeque::new(),
+        };
+        let mut print = |term: &mut Term<VoidListener>, text: &str| {
+            for byte in text.bytes() {
+                processor.advance(term, byte);
+            }
+        };
+        let lines = |lines: std::ops::Range<usize>| {
+            lines.map(|ix| format!("line {ix}\r\n")).collect::<String>()
+        };
+
+        // Lines keep being scanned once, while the oldest ones are dropped from the history.
+        let mut scanned = Vec::new();
+        for start in (0..30).step_by(3) {
+            print(&mut term, &lines(start..start + 3));
+            scanned.extend(scanner.scan_lines(term.grid(), 5, false));
+        }
+        let expected = (0..30).map(|ix| format!("line {ix}")).collect::<Vec<_>>();
+        assert_eq!(scanned, expected);
+
+        // A wrapped line is scanned once it's complete.
+        print(&mut term, "0123456789abc");
+        assert!(scanner.scan_lines(term.grid(), 5, false).is_empty());
+        print(&mut term, "\r\n");
+        assert_eq!(scanner.scan_lines(term.grid(), 5, false), ["0123456789abc"]);
+
+        // When more lines than the whole grid were printed, the ones still in it are scanned.
+        print(&mut term, &lines(30..42));
+        let expected = (34..42).map(|ix| format!("line {ix}")).collect::<Vec<_>>();
+        assert_eq!(scanner.scan_lines(term.grid(), 5, false), expected);
+    }
+
     #[test]
     fn test_mouse_to_cell_test() {
         let mut rng = thread_rng();
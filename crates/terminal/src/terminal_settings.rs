@@ -43,6 +43,8 @@ pub struct TerminalSettings {
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
     pub toolbar: Toolbar,
+    pub shell_integration: bool,
+    pub restore_scrollback: bool,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -168,6 +170,17 @@ pub struct TerminalSettingsContent {
     pub max_scroll_history_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
+    /// Whether to load Zed's shell integration into bash, zsh and fish, to mark the prompts and the commands
+    /// in the terminal. Other shells can report them with the `OSC 133` escape sequences on their own.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Whether to save the output of the terminals in Zed's database, and print it back into
+    /// the terminals restored on the next start. The saved output may include secrets printed in the terminal.
+    ///
+    /// Default: false
+    pub restore_scrollback: Option<bool>,
 }
 
 impl settings::Settings for TerminalSettings {
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use db::{define_connection, query, sqlez_macros::sql};
+use serde::{Deserialize, Serialize};
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
 define_connection! {
@@ -34,6 +35,11 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN title TEXT;
+            ALTER TABLE terminals ADD COLUMN task TEXT;
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
         )];
 }
 
@@ -56,8 +62,27 @@ impl TerminalDb {
             workspace_id: WorkspaceId,
             working_directory: PathBuf
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals(item_id, workspace_id, working_directory)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                working_directory = ?3
+        }
+    }
+
+    query! {
+        pub async fn save_session(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            title: Option<String>,
+            task: Option<String>,
+            scrollback: Option<String>
+        ) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, title, task, scrollback)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT DO UPDATE SET
+                title = ?3,
+                task = ?4,
+                scrollback = ?5
         }
     }
 
@@ -68,4 +93,26 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        pub fn get_session(
+            item_id: ItemId,
+            workspace_id: WorkspaceId
+        ) -> Result<Option<(Option<String>, Option<String>, Option<String>)>> {
+            SELECT title, task, scrollback
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+}
+
+/// The task, run in a terminal, saved to associate the restored terminal with it.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SerializedTerminalTask {
+    pub id: String,
+    pub full_label: String,
+    pub label: String,
+    pub command_label: String,
+    /// `None` if the task was still running when the session was saved.
+    pub success: Option<bool>,
 }
@@ -1,9 +1,9 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, AnyElement, Bounds, DispatchPhase, Element, ElementId,
-    FocusHandle, Font, FontStyle, FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla,
-    InputHandler, InteractiveElement, Interactivity, IntoElement, LayoutId, Model, ModelContext,
-    ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels, Point, ShapedLine,
+    div, fill, img, point, px, relative, size, AnyElement, Bounds, ContentMask, DispatchPhase,
+    Element, ElementId, FocusHandle, Font, FontStyle, FontWeight, GlobalElementId, HighlightStyle,
+    Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement, LayoutId, Model,
+    ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels, Point, ShapedLine,
     StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle, UnderlineStyle,
     WeakView, WhiteSpace, WindowContext, WindowTextSystem,
 };
@@ -20,6 +20,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    is_image_anchor,
     terminal_settings::TerminalSettings,
     HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalSize,
 };
@@ -42,8 +43,11 @@ pub struct LayoutState {
     mode: TermMode,
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
+    images: Vec<AnyElement>,
     gutter: Pixels,
     last_hovered_word: Option<HoveredWord>,
+    failed_command_lines: Vec<usize>,
+    error_color: Hsla,
 }
 
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
@@ -324,6 +328,42 @@ impl TerminalElement {
         }
     }
 
+    /// Lays out the images, drawn over the cells they are anchored to.
+    /// Images are scaled down to fit the cells, keeping their aspect ratio.
+    fn layout_images(
+        &self,
+        origin: Point<Pixels>,
+        dimensions: TerminalSize,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        let content = &self.terminal.read(cx).last_content;
+        let display_offset = content.display_offset as i32;
+        let images = content.images.clone();
+        images
+            .into_iter()
+            .map(|image| {
+                let image_origin = origin
+                    + point(
+                        dimensions.cell_width() * image.point.column.0 as f32,
+                        dimensions.line_height() * (image.point.line.0 + display_offset) as f32,
+                    );
+                let image_size = image.data.size();
+                let width = px(u32::from(image_size.width) as f32);
+                let height = px(u32::from(image_size.height) as f32);
+                let scale = (dimensions.cell_width() * image.columns as f32 / width)
+                    .min(dimensions.line_height() * image.rows as f32 / height)
+                    .min(1.);
+                let image_size = size(width * scale, height * scale);
+                let mut element = img(image.data)
+                    .w(image_size.width)
+                    .h(image_size.height)
+                    .into_any_element();
+                element.prepaint_as_root(image_origin, image_size.into(), cx);
+                element
+            })
+            .collect()
+    }
+
     /// Converts the Alacritty cell styles to GPUI text styles and background color.
     fn cell_style(
         indexed: &IndexedCell,
@@ -343,7 +383,10 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || indexed
+                .cell
+                .hyperlink()
+                .map_or(false, |hyperlink| !is_image_anchor(&hyperlink)))
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -685,6 +728,9 @@ impl Element for TerminalElement {
                     element
                 });
 
+                let images =
+                    self.layout_images(bounds.origin + Point::new(gutter, px(0.)), dimensions, cx);
+
                 let TerminalContent {
                     cells,
                     mode,
@@ -692,6 +738,7 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    failed_command_lines,
                     ..
                 } = &self.terminal.read(cx).last_content;
 
@@ -778,8 +825,11 @@ impl Element for TerminalElement {
                     mode: *mode,
                     display_offset: *display_offset,
                     hyperlink_tooltip,
+                    images,
                     gutter,
                     last_hovered_word,
+                    failed_command_lines: failed_command_lines.clone(),
+                    error_color: theme.status().error,
                 }
             })
     }
@@ -813,6 +863,7 @@ impl Element for TerminalElement {
 
         let cursor = layout.cursor.take();
         let hyperlink_tooltip = layout.hyperlink_tooltip.take();
+        let mut images = mem::take(&mut layout.images);
         self.interactivity
             .paint(global_id, bounds, Some(&layout.hitbox), cx, |_, cx| {
                 cx.handle_input(&self.focus, terminal_input_handler);
@@ -833,6 +884,19 @@ impl Element for TerminalElement {
                     }
                 });
 
+                // Mark the prompts of the failed commands in the gutter.
+                for line in &layout.failed_command_lines {
+                    let marker_origin = bounds.origin
+                        + Point::new(px(0.), layout.dimensions.line_height * *line as f32);
+                    cx.paint_quad(fill(
+                        Bounds::new(
+                            marker_origin,
+                            size(layout.gutter / 3., layout.dimensions.line_height),
+                        ),
+                        layout.error_color,
+                    ));
+                }
+
                 for rect in &layout.rects {
                     rect.paint(origin, &layout, cx);
                 }
@@ -858,6 +922,13 @@ impl Element for TerminalElement {
                     cell.paint(origin, &layout, bounds, cx);
                 }
 
+                // Images, scrolled out of the viewport partially, are clipped.
+                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                    for image in &mut images {
+                        image.paint(cx);
+                    }
+                });
+
                 if self.cursor_visible {
                     if let Some(mut cursor) = cursor {
                         cursor.paint(origin, cx);
@@ -959,7 +1030,10 @@ fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell
+        .hyperlink()
+        .map_or(false, |hyperlink| !is_image_anchor(&hyperlink))
+    {
         return false;
     }
 
@@ -0,0 +1,334 @@
+use gpui::{AnyElement, Axis, View};
+use serde::{Deserialize, Serialize};
+use ui::prelude::*;
+use workspace::{Pane, SplitDirection};
+
+/// Panes of the terminal panel, split horizontally or vertically.
+/// Unlike the workspace center, splits are laid out evenly and are not resizable.
+pub(crate) struct TerminalPaneGroup {
+    root: Member<View<Pane>>,
+}
+
+/// A node of the split layout, generic over the panes so that the layout can be tested without windows.
+enum Member<P> {
+    Axis(Axis, Vec<Member<P>>),
+    Pane(P),
+}
+
+impl TerminalPaneGroup {
+    pub fn new(pane: View<Pane>) -> Self {
+        Self {
+            root: Member::Pane(pane),
+        }
+    }
+
+    /// Places the new pane next to the old one, in the given direction.
+    /// Returns `false` if the old pane is not in the group.
+    pub fn split(
+        &mut self,
+        old_pane: &View<Pane>,
+        new_pane: &View<Pane>,
+        direction: SplitDirection,
+    ) -> bool {
+        self.root.split(old_pane, new_pane, direction)
+    }
+
+    /// Removes the pane from the group, unless it is the last pane left.
+    /// Returns whether the pane was removed.
+    pub fn remove(&mut self, pane: &View<Pane>) -> bool {
+        self.root.remove(pane)
+    }
+
+    pub fn panes(&self) -> Vec<View<Pane>> {
+        let mut panes = Vec::new();
+        self.root.collect_panes(&mut panes);
+        panes
+    }
+
+    pub fn render(&self, cx: &WindowContext) -> AnyElement {
+        self.root.render(cx)
+    }
+
+    pub fn serialize(
+        &self,
+        serialize_pane: &mut impl FnMut(&View<Pane>) -> SerializedTerminalPane,
+    ) -> SerializedTerminalPaneGroup {
+        self.root.serialize(serialize_pane)
+    }
+
+    /// Recreates the group with the same layout, creating the panes with the given function.
+    pub fn deserialize(
+        serialized: &SerializedTerminalPaneGroup,
+        deserialize_pane: &mut impl FnMut(&SerializedTerminalPane) -> View<Pane>,
+    ) -> Self {
+        Self {
+            root: Member::deserialize(serialized, deserialize_pane),
+        }
+    }
+}
+
+impl<P: Clone + PartialEq> Member<P> {
+    fn split(&mut self, old_pane: &P, new_pane: &P, direction: SplitDirection) -> bool {
+        match self {
+            Member::Pane(pane) => {
+                if *pane != *old_pane {
+                    return false;
+                }
+                let old_pane = Member::Pane(old_pane.clone());
+                let new_pane = Member::Pane(new_pane.clone());
+                let members = if direction.increasing() {
+                    vec![old_pane, new_pane]
+                } else {
+                    vec![new_pane, old_pane]
+                };
+                *self = Member::Axis(direction.axis(), members);
+                true
+            }
+            Member::Axis(axis, members) => {
+                if *axis == direction.axis() {
+                    let old_pane_ix = members.iter().position(
+                        |member| matches!(member, Member::Pane(pane) if pane == old_pane),
+                    );
+                    if let Some(ix) = old_pane_ix {
+                        let new_pane_ix = if direction.increasing() { ix + 1 } else { ix };
+                        members.insert(new_pane_ix, Member::Pane(new_pane.clone()));
+                        return true;
+                    }
+                }
+                members
+                    .iter_mut()
+                    .any(|member| member.split(old_pane, new_pane, direction))
+            }
+        }
+    }
+
+    fn remove(&mut self, pane_to_remove: &P) -> bool {
+        let Member::Axis(_, members) = self else {
+            return false;
+        };
+        let pane_ix = members
+            .iter()
+            .position(|member| matches!(member, Member::Pane(pane) if pane == pane_to_remove));
+        let removed = match pane_ix {
+            Some(ix) => {
+                members.remove(ix);
+                true
+            }
+            None => members
+                .iter_mut()
+                .any(|member| member.remove(pane_to_remove)),
+        };
+        if members.len() == 1 {
+            *self = members.pop().expect("checked the length above");
+        }
+        removed
+    }
+
+    fn collect_panes(&self, panes: &mut Vec<P>) {
+        match self {
+            Member::Axis(_, members) => {
+                for member in members {
+                    member.collect_panes(panes);
+                }
+            }
+            Member::Pane(pane) => panes.push(pane.clone()),
+        }
+    }
+
+    fn serialize(
+        &self,
+        serialize_pane: &mut impl FnMut(&P) -> SerializedTerminalPane,
+    ) -> SerializedTerminalPaneGroup {
+        match self {
+            Member::Axis(axis, members) => SerializedTerminalPaneGroup::Axis {
+                axis: match axis {
+                    Axis::Horizontal => SerializedAxis::Horizontal,
+                    Axis::Vertical => SerializedAxis::Vertical,
+                },
+                members: members
+                    .iter()
+                    .map(|member| member.serialize(serialize_pane))
+                    .collect(),
+            },
+            Member::Pane(pane) => SerializedTerminalPaneGroup::Pane(serialize_pane(pane)),
+        }
+    }
+
+    fn deserialize(
+        serialized: &SerializedTerminalPaneGroup,
+        deserialize_pane: &mut impl FnMut(&SerializedTerminalPane) -> P,
+    ) -> Self {
+        match serialized {
+            SerializedTerminalPaneGroup::Axis { axis, members } => {
+                let mut members = members
+                    .iter()
+                    .map(|member| Member::deserialize(member, deserialize_pane))
+                    .collect::<Vec<_>>();
+                if members.len() == 1 {
+                    return members.pop().expect("checked the length above");
+                }
+                let axis = match axis {
+                    SerializedAxis::Horizontal => Axis::Horizontal,
+                    SerializedAxis::Vertical => Axis::Vertical,
+                };
+                Member::Axis(axis, members)
+            }
+            SerializedTerminalPaneGroup::Pane(pane) => Member::Pane(deserialize_pane(pane)),
+        }
+    }
+}
+
+impl Member<View<Pane>> {
+    fn render(&self, cx: &WindowContext) -> AnyElement {
+        match self {
+            Member::Pane(pane) => div().size_full().child(pane.clone()).into_any_element(),
+            Member::Axis(axis, members) => {
+                let border_color = cx.theme().colors().border;
+                let container = match axis {
+                    Axis::Horizontal => h_flex(),
+                    Axis::Vertical => v_flex(),
+                };
+                container
+                    .size_full()
+                    .children(members.iter().enumerate().map(|(ix, member)| {
+                        div()
+                            .flex_1()
+                            .size_full()
+                            .overflow_hidden()
+                            .when(ix > 0, |this| match axis {
+                                Axis::Horizontal => this.border_l_1().border_color(border_color),
+                                Axis::Vertical => this.border_t_1().border_color(border_color),
+                            })
+                            .child(member.render(cx))
+                    }))
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SerializedTerminalPaneGroup {
+    Axis {
+        axis: SerializedAxis,
+        members: Vec<SerializedTerminalPaneGroup>,
+    },
+    Pane(SerializedTerminalPane),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SerializedTerminalPane {
+    pub items: Vec<u64>,
+    pub active_item_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SerializedAxis {
+    Horizontal,
+    Vertical,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Describes the layout, e.g. `h(1, v(2, 3))` for a pane next to two panes above one another.
+    fn layout(member: &Member<u64>) -> String {
+        match member {
+            Member::Pane(pane) => pane.to_string(),
+            Member::Axis(axis, members) => {
+                let axis = match axis {
+                    Axis::Horizontal => "h",
+                    Axis::Vertical => "v",
+                };
+                let members = members.iter().map(layout).collect::<Vec<_>>();
+                format!("{axis}({})", members.join(", "))
+            }
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let mut root = Member::Pane(1);
+        assert!(root.split(&1, &2, SplitDirection::Right));
+        assert_eq!(layout(&root), "h(1, 2)");
+
+        // Splitting along the same axis adds a sibling, instead of nesting.
+        assert!(root.split(&2, &3, SplitDirection::Left));
+        assert_eq!(layout(&root), "h(1, 3, 2)");
+
+        assert!(root.split(&3, &4, SplitDirection::Down));
+        assert_eq!(layout(&root), "h(1, v(3, 4), 2)");
+        assert!(root.split(&4, &5, SplitDirection::Up));
+        assert_eq!(layout(&root), "h(1, v(3, 5, 4), 2)");
+
+        assert!(!root.split(&6, &7, SplitDirection::Right));
+        assert_eq!(layout(&root), "h(1, v(3, 5, 4), 2)");
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut root = Member::Pane(1);
+        root.split(&1, &2, SplitDirection::Right);
+        root.split(&2, &3, SplitDirection::Down);
+        root.split(&3, &4, SplitDirection::Right);
+        assert_eq!(layout(&root), "h(1, v(2, h(3, 4)))");
+
+        // Axes left with a single member are replaced by that member.
+        assert!(root.remove(&4));
+        assert_eq!(layout(&root), "h(1, v(2, 3))");
+        assert!(root.remove(&2));
+        assert_eq!(layout(&root), "h(1, 3)");
+        assert!(!root.remove(&2));
+        assert!(root.remove(&1));
+        assert_eq!(layout(&root), "3");
+
+        // The last pane is never removed.
+        assert!(!root.remove(&3));
+        assert_eq!(layout(&root), "3");
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let mut root = Member::Pane(1);
+        root.split(&1, &2, SplitDirection::Down);
+        root.split(&2, &3, SplitDirection::Right);
+
+        let serialized = root.serialize(&mut |pane| SerializedTerminalPane {
+            items: vec![pane * 10, pane * 10 + 1],
+            active_item_id: Some(pane * 10 + 1),
+        });
+        let serialized = serde_json::from_str::<SerializedTerminalPaneGroup>(
+            &serde_json::to_string(&serialized).unwrap(),
+        )
+        .unwrap();
+
+        let mut items = Vec::new();
+        let deserialized = Member::deserialize(&serialized, &mut |pane| {
+            items.push((pane.items.clone(), pane.active_item_id));
+            pane.items[0] / 10
+        });
+        assert_eq!(layout(&deserialized), "v(1, h(2, 3))");
+        assert_eq!(
+            items,
+            [
+                (vec![10, 11], Some(11)),
+                (vec![20, 21], Some(21)),
+                (vec![30, 31], Some(31)),
+            ]
+        );
+
+        // Axes with a single member, e.g. left by the panes without restorable items, are collapsed.
+        let serialized = SerializedTerminalPaneGroup::Axis {
+            axis: SerializedAxis::Vertical,
+            members: vec![SerializedTerminalPaneGroup::Pane(SerializedTerminalPane {
+                items: vec![10],
+                active_item_id: None,
+            })],
+        };
+        let deserialized = Member::deserialize(&serialized, &mut |pane| pane.items[0] / 10);
+        assert_eq!(layout(&deserialized), "1");
+    }
+}
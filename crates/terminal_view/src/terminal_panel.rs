@@ -1,21 +1,27 @@
 use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
 
-use crate::TerminalView;
-use collections::{HashMap, HashSet};
+use crate::{
+    terminal_pane_group::{SerializedTerminalPane, SerializedTerminalPaneGroup, TerminalPaneGroup},
+    TerminalView,
+};
+use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{channel::oneshot, future::join_all};
 use gpui::{
-    actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, ParentElement, Pixels, Render, Styled,
-    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EntityId, EventEmitter,
+    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
+    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use task::{RevealStrategy, SpawnInTerminal, TaskId};
-use terminal::terminal_settings::{Shell, TerminalDockPosition, TerminalSettings};
+use task::{RevealStrategy, SpawnInTerminal, TaskId, TaskPlan};
+use terminal::{
+    terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
+    TaskStatus, Terminal,
+};
 use ui::{
     h_flex, ButtonCommon, Clickable, ContextMenu, FluentBuilder, IconButton, IconSize, Selectable,
     Tooltip,
@@ -24,12 +30,14 @@ use util::{ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
     item::Item,
+    notifications::NotificationId,
     pane,
     ui::IconName,
-    DraggedTab, NewTerminal, Pane, ToggleZoom, Workspace,
+    DraggedTab, NewTerminal, Pane, SplitDirection, SplitDown, SplitLeft, SplitRight, SplitUp,
+    Toast, ToggleZoom, Workspace,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
@@ -49,158 +57,106 @@ pub fn init(cx: &mut AppContext) {
 }
 
 pub struct TerminalPanel {
-    pane: View<Pane>,
+    center: TerminalPaneGroup,
+    active_pane: View<Pane>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
+    project: Model<Project>,
     width: Option<Pixels>,
     height: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
     pending_terminals_to_add: usize,
+    pane_subscriptions: HashMap<EntityId, Vec<Subscription>>,
     _subscriptions: Vec<Subscription>,
     deferred_tasks: HashMap<TaskId, Task<()>>,
 }
 
 impl TerminalPanel {
     fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
-        let pane = cx.new_view(|cx| {
-            let mut pane = Pane::new(
-                workspace.weak_handle(),
-                workspace.project().clone(),
-                Default::default(),
-                None,
-                NewTerminal.boxed_clone(),
-                cx,
-            );
-            pane.set_can_split(false, cx);
-            pane.set_can_navigate(false, cx);
-            pane.display_nav_history_buttons(None);
-            pane.set_should_display_tab_bar(|_| true);
-            pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
-                h_flex()
-                    .gap_2()
-                    .child(
-                        IconButton::new("plus", IconName::Plus)
-                            .icon_size(IconSize::Small)
-                            .on_click(cx.listener(|pane, _, cx| {
-                                let focus_handle = pane.focus_handle(cx);
-                                let menu = ContextMenu::build(cx, |menu, _| {
-                                    menu.action(
-                                        "New Terminal",
-                                        workspace::NewTerminal.boxed_clone(),
-                                    )
-                                    .entry(
-                                        "Spawn task",
-                                        Some(tasks_ui::Spawn::modal().boxed_clone()),
-                                        move |cx| {
-                                            // We want the focus to go back to terminal panel once task modal is dismissed,
-                                            // hence we focus that first. Otherwise, we'd end up without a focused element, as
-                                            // context menu will be gone the moment we spawn the modal.
-                                            cx.focus(&focus_handle);
-                                            cx.dispatch_action(
-                                                tasks_ui::Spawn::modal().boxed_clone(),
-                                            );
-                                        },
-                                    )
-                                });
-                                cx.subscribe(&menu, |pane, _, _: &DismissEvent, _| {
-                                    pane.new_item_menu = None;
-                                })
-                                .detach();
-                                pane.new_item_menu = Some(menu);
-                            }))
-                            .tooltip(|cx| Tooltip::text("New...", cx)),
-                    )
-                    .when_some(pane.new_item_menu.as_ref(), |el, new_item_menu| {
-                        el.child(Pane::render_menu_overlay(new_item_menu))
-                    })
-                    .child({
-                        let zoomed = pane.is_zoomed();
-                        IconButton::new("toggle_zoom", IconName::Maximize)
-                            .icon_size(IconSize::Small)
-                            .selected(zoomed)
-                            .selected_icon(IconName::Minimize)
-                            .on_click(cx.listener(|pane, _, cx| {
-                                pane.toggle_zoom(&workspace::ToggleZoom, cx);
-                            }))
-                            .tooltip(move |cx| {
-                                Tooltip::for_action(
-                                    if zoomed { "Zoom Out" } else { "Zoom In" },
-                                    &ToggleZoom,
-                                    cx,
-                                )
-                            })
-                    })
-                    .into_any_element()
-            });
-
-            let workspace = workspace.weak_handle();
-            pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
-                if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
-                    let item = if &tab.pane == cx.view() {
-                        pane.item_for_index(tab.ix)
-                    } else {
-                        tab.pane.read(cx).item_for_index(tab.ix)
-                    };
-                    if let Some(item) = item {
-                        if item.downcast::<TerminalView>().is_some() {
-                            return ControlFlow::Continue(());
-                        } else if let Some(project_path) = item.project_path(cx) {
-                            if let Some(entry_path) = workspace
-                                .update(cx, |workspace, cx| {
-                                    workspace
-                                        .project()
-                                        .read(cx)
-                                        .absolute_path(&project_path, cx)
-                                })
-                                .log_err()
-                                .flatten()
-                            {
-                                add_paths_to_terminal(pane, &[entry_path], cx);
-                            }
-                        }
-                    }
-                } else if let Some(&entry_id) = dropped_item.downcast_ref::<ProjectEntryId>() {
-                    if let Some(entry_path) = workspace
-                        .update(cx, |workspace, cx| {
-                            let project = workspace.project().read(cx);
-                            project
-                                .path_for_entry(entry_id, cx)
-                                .and_then(|project_path| project.absolute_path(&project_path, cx))
-                        })
-                        .log_err()
-                        .flatten()
-                    {
-                        add_paths_to_terminal(pane, &[entry_path], cx);
-                    }
-                } else if let Some(paths) = dropped_item.downcast_ref::<ExternalPaths>() {
-                    add_paths_to_terminal(pane, paths.paths(), cx);
-                }
-
-                ControlFlow::Break(())
-            });
-            let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
-            pane.toolbar()
-                .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
-            pane
-        });
-        let subscriptions = vec![
-            cx.observe(&pane, |_, _, cx| cx.notify()),
-            cx.subscribe(&pane, Self::handle_pane_event),
-        ];
-        let this = Self {
-            pane,
+        let project = workspace.project().clone();
+        let pane = new_terminal_pane(workspace.weak_handle(), project.clone(), cx);
+        let mut this = Self {
+            center: TerminalPaneGroup::new(pane.clone()),
+            active_pane: pane.clone(),
             fs: workspace.app_state().fs.clone(),
             workspace: workspace.weak_handle(),
+            project,
             pending_serialization: Task::ready(None),
             width: None,
             height: None,
             pending_terminals_to_add: 0,
+            pane_subscriptions: HashMap::default(),
             deferred_tasks: HashMap::default(),
-            _subscriptions: subscriptions,
+            _subscriptions: Vec::new(),
         };
+        this.subscribe_to_pane(&pane, cx);
         this
     }
 
+    fn subscribe_to_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        self.pane_subscriptions.insert(
+            pane.entity_id(),
+            vec![
+                cx.observe(pane, |_, _, cx| cx.notify()),
+                cx.subscribe(pane, Self::handle_pane_event),
+            ],
+        );
+    }
+
+    fn add_pane(&mut self, cx: &mut ViewContext<Self>) -> View<Pane> {
+        let pane = new_terminal_pane(self.workspace.clone(), self.project.clone(), cx);
+        let zoomed = self.active_pane.read(cx).is_zoomed();
+        pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        self.subscribe_to_pane(&pane, cx);
+        pane
+    }
+
+    /// Recreates the panes of the serialized panel, and starts deserializing their terminals,
+    /// returned along with their serialized ids.
+    fn deserialize_panes(
+        &mut self,
+        serialized_panel: &SerializedTerminalPanel,
+        workspace: &Workspace,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<(
+        View<Pane>,
+        Option<u64>,
+        Vec<(u64, Task<Result<View<TerminalView>>>)>,
+    )> {
+        let serialized_panes = serialized_panel.panes.clone().unwrap_or_else(|| {
+            SerializedTerminalPaneGroup::Pane(SerializedTerminalPane {
+                items: serialized_panel.items.clone(),
+                active_item_id: serialized_panel.active_item_id,
+            })
+        });
+
+        let mut first_pane = Some(self.active_pane.clone());
+        let mut items = Vec::new();
+        let center = TerminalPaneGroup::deserialize(&serialized_panes, &mut |serialized_pane| {
+            let pane = first_pane.take().unwrap_or_else(|| self.add_pane(cx));
+            let pane_items = pane.update(cx, |_, cx| {
+                serialized_pane
+                    .items
+                    .iter()
+                    .map(|item_id| {
+                        let item = TerminalView::deserialize(
+                            workspace.project().clone(),
+                            workspace.weak_handle(),
+                            workspace.database_id(),
+                            *item_id,
+                            cx,
+                        );
+                        (*item_id, item)
+                    })
+                    .collect::<Vec<_>>()
+            });
+            items.push((pane.clone(), serialized_pane.active_item_id, pane_items));
+            pane
+        });
+        self.center = center;
+        items
+    }
+
     pub async fn load(
         workspace: WeakView<Workspace>,
         mut cx: AsyncWindowContext,
@@ -216,34 +172,19 @@ impl TerminalPanel {
             .log_err()
             .flatten();
 
-        let (panel, pane, items) = workspace.update(&mut cx, |workspace, cx| {
+        let (panel, items) = workspace.update(&mut cx, |workspace, cx| {
             let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
             let items = if let Some(serialized_panel) = serialized_panel.as_ref() {
                 panel.update(cx, |panel, cx| {
                     cx.notify();
                     panel.height = serialized_panel.height.map(|h| h.round());
                     panel.width = serialized_panel.width.map(|w| w.round());
-                    panel.pane.update(cx, |_, cx| {
-                        serialized_panel
-                            .items
-                            .iter()
-                            .map(|item_id| {
-                                TerminalView::deserialize(
-                                    workspace.project().clone(),
-                                    workspace.weak_handle(),
-                                    workspace.database_id(),
-                                    *item_id,
-                                    cx,
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                    })
+                    panel.deserialize_panes(serialized_panel, workspace, cx)
                 })
             } else {
                 Vec::new()
             };
-            let pane = panel.read(cx).pane.clone();
-            (panel, pane, items)
+            (panel, items)
         })?;
 
         if let Some(workspace) = workspace.upgrade() {
@@ -251,36 +192,65 @@ impl TerminalPanel {
                 .update(&mut cx, |panel, cx| {
                     panel._subscriptions.push(cx.subscribe(
                         &workspace,
-                        |terminal_panel, _, e, cx| {
-                            if let workspace::Event::SpawnTask(spawn_in_terminal) = e {
-                                terminal_panel.spawn_task(spawn_in_terminal, cx);
-                            };
+                        |terminal_panel, _, e, cx| match e {
+                            workspace::Event::SpawnTask(spawn_in_terminal) => {
+                                if let Some(terminal) =
+                                    terminal_panel.spawn_task(spawn_in_terminal, cx)
+                                {
+                                    terminal.detach_and_log_err(cx);
+                                }
+                            }
+                            workspace::Event::SpawnTaskPlan(task_plan) => {
+                                terminal_panel.spawn_task_plan(task_plan, cx);
+                            }
+                            _ => {}
                         },
                     ))
                 })
                 .ok();
         }
 
-        let pane = pane.downgrade();
-        let items = futures::future::join_all(items).await;
-        pane.update(&mut cx, |pane, cx| {
-            let active_item_id = serialized_panel
-                .as_ref()
-                .and_then(|panel| panel.active_item_id);
-            let mut active_ix = None;
-            for item in items {
-                if let Some(item) = item.log_err() {
-                    let item_id = item.entity_id().as_u64();
-                    pane.add_item(Box::new(item), false, false, None, cx);
-                    if Some(item_id) == active_item_id {
-                        active_ix = Some(pane.items_len() - 1);
+        let mut panes = Vec::new();
+        for (pane, active_item_id, pane_items) in items {
+            let (item_ids, pane_items): (Vec<_>, Vec<_>) = pane_items.into_iter().unzip();
+            let pane_items = item_ids.into_iter().zip(join_all(pane_items).await);
+            panes.push((pane, active_item_id, pane_items.collect::<Vec<_>>()));
+        }
+        let panel_active_item_id = serialized_panel
+            .as_ref()
+            .and_then(|panel| panel.active_item_id);
+        panel.update(&mut cx, |panel, cx| {
+            // The restored terminals get new ids, so the active ones are found by their serialized ids.
+            let mut active_pane = None;
+            for (pane, active_item_id, pane_items) in panes {
+                pane.update(cx, |pane, cx| {
+                    let mut active_ix = None;
+                    for (item_id, item) in pane_items {
+                        if let Some(item) = item.log_err() {
+                            pane.add_item(Box::new(item), false, false, None, cx);
+                            if Some(item_id) == active_item_id {
+                                active_ix = Some(pane.items_len() - 1);
+                            }
+                            if Some(item_id) == panel_active_item_id {
+                                active_pane = Some(cx.view().clone());
+                            }
+                        }
+                    }
+
+                    if let Some(active_ix) = active_ix {
+                        pane.activate_item(active_ix, false, false, cx)
                     }
+                });
+                if pane.read(cx).items_len() == 0 && panel.center.remove(&pane) {
+                    panel.pane_subscriptions.remove(&pane.entity_id());
                 }
             }
 
-            if let Some(active_ix) = active_ix {
-                pane.activate_item(active_ix, false, false, cx)
-            }
+            let panes = panel.center.panes();
+            panel.active_pane = active_pane
+                .filter(|active_pane| panes.contains(active_pane))
+                .or_else(|| panes.first().cloned())
+                .expect("the pane group has at least one pane");
         })?;
 
         Ok(panel)
@@ -288,20 +258,26 @@ impl TerminalPanel {
 
     fn handle_pane_event(
         &mut self,
-        _pane: View<Pane>,
+        pane: View<Pane>,
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
             pane::Event::ActivateItem { .. } => self.serialize(cx),
             pane::Event::RemoveItem { .. } => self.serialize(cx),
-            pane::Event::Remove => cx.emit(PanelEvent::Close),
+            pane::Event::Remove => self.remove_pane(pane, cx),
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
+            pane::Event::Split(direction) => self.split_pane(pane, *direction, cx),
+            pane::Event::Focus => {
+                if self.active_pane != pane {
+                    self.active_pane = pane;
+                    cx.notify();
+                }
+            }
 
             pane::Event::AddItem { item } => {
                 if let Some(workspace) = self.workspace.upgrade() {
-                    let pane = self.pane.clone();
                     workspace.update(cx, |workspace, cx| item.added_to_pane(workspace, pane, cx))
                 }
             }
@@ -310,6 +286,48 @@ impl TerminalPanel {
         }
     }
 
+    /// Opens a new terminal next to the pane, in the same directory as the pane's active terminal.
+    fn split_pane(
+        &mut self,
+        pane: View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let workspace_id = workspace.read(cx).database_id();
+        let Some(clone) = item.clone_on_split(workspace_id, cx) else {
+            return;
+        };
+        let new_pane = self.add_pane(cx);
+        self.center.split(&pane, &new_pane, direction);
+        new_pane.update(cx, |pane, cx| pane.add_item(clone, true, true, None, cx));
+        self.active_pane = new_pane;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    /// Removes the emptied pane from the split, closing the panel when its last pane is emptied.
+    fn remove_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
+        if !self.center.remove(&pane) {
+            cx.emit(PanelEvent::Close);
+            return;
+        }
+        self.pane_subscriptions.remove(&pane.entity_id());
+        if self.active_pane == pane {
+            if let Some(next_pane) = self.center.panes().into_iter().next() {
+                self.active_pane = next_pane;
+                cx.focus_view(&self.active_pane);
+            }
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
     pub fn open_terminal(
         workspace: &mut Workspace,
         action: &workspace::OpenTerminal,
@@ -320,16 +338,24 @@ impl TerminalPanel {
         };
 
         terminal_panel.update(cx, |panel, cx| {
-            panel.add_terminal(
-                Some(action.working_directory.clone()),
-                None,
-                RevealStrategy::Always,
-                cx,
-            )
+            panel
+                .add_terminal(
+                    Some(action.working_directory.clone()),
+                    None,
+                    RevealStrategy::Always,
+                    cx,
+                )
+                .detach_and_log_err(cx)
         });
     }
 
-    fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+    /// Spawns the task in a terminal, resolving to the terminal once it's created.
+    /// Returns `None` if there is no shell to spawn the task with.
+    fn spawn_task(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<Model<Terminal>>>> {
         let mut spawn_task = spawn_in_terminal.clone();
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
         let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
@@ -337,7 +363,7 @@ impl TerminalPanel {
             Shell::Program(shell) => Some((shell, Vec::new())),
             Shell::WithArguments { program, args } => Some((program, args)),
         }) else {
-            return;
+            return None;
         };
 
         spawn_task.command_label = format!("{shell} -i -c `{}`", spawn_task.command_label);
@@ -360,16 +386,14 @@ impl TerminalPanel {
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            return Some(self.spawn_in_new_terminal(spawn_task, working_directory, cx));
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.full_label, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            return Some(self.spawn_in_new_terminal(spawn_task, working_directory, cx));
         }
-        let (existing_item_index, existing_terminal) = terminals_for_task
+        let existing_terminal = terminals_for_task
             .last()
             .expect("covered no terminals case above")
             .clone();
@@ -378,43 +402,40 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(
+            Some(Task::ready(self.replace_terminal(
                 working_directory,
                 spawn_task,
-                existing_item_index,
                 existing_terminal,
                 cx,
-            );
+            )))
         } else {
+            let (terminal_tx, terminal_rx) = oneshot::channel();
+            let terminal_to_replace = existing_terminal.clone();
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
                 cx.spawn(|terminal_panel, mut cx| async move {
                     wait_for_terminals_tasks(terminals_for_task, &mut cx).await;
-                    terminal_panel
-                        .update(&mut cx, |terminal_panel, cx| {
-                            if use_new_terminal {
-                                terminal_panel.spawn_in_new_terminal(
-                                    spawn_task,
-                                    working_directory,
-                                    cx,
-                                );
-                            } else {
-                                terminal_panel.replace_terminal(
-                                    working_directory,
-                                    spawn_task,
-                                    existing_item_index,
-                                    existing_terminal,
-                                    cx,
-                                );
-                            }
-                        })
-                        .ok();
+                    let Ok(terminal) = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        if use_new_terminal {
+                            terminal_panel.spawn_in_new_terminal(spawn_task, working_directory, cx)
+                        } else {
+                            Task::ready(terminal_panel.replace_terminal(
+                                working_directory,
+                                spawn_task,
+                                terminal_to_replace,
+                                cx,
+                            ))
+                        }
+                    }) else {
+                        return;
+                    };
+                    terminal_tx.send(terminal.await).ok();
                 }),
             );
 
             match reveal {
                 RevealStrategy::Always => {
-                    self.activate_terminal_view(existing_item_index, cx);
+                    self.activate_terminal_view(&existing_terminal, cx);
                     let task_workspace = self.workspace.clone();
                     cx.spawn(|_, mut cx| async move {
                         task_workspace
@@ -425,17 +446,112 @@ impl TerminalPanel {
                 }
                 RevealStrategy::Never => {}
             }
+
+            Some(cx.spawn(|_, _| async move {
+                terminal_rx
+                    .await
+                    .context("the task was not spawned, as its next run was started")?
+            }))
         }
     }
 
+    /// Hands the debugging task over to the debugger, instead of running it in a terminal.
+    fn start_debug_session(&self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        let spawn_in_terminal = spawn_in_terminal.clone();
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.emit(workspace::Event::StartDebugSession(spawn_in_terminal))
+            })
+            .ok();
+    }
+
+    /// Spawns the plan's steps one after another, stopping at the first step that has a task
+    /// that did not finish successfully.
+    fn spawn_task_plan(&mut self, task_plan: &TaskPlan, cx: &mut ViewContext<Self>) {
+        let steps = task_plan.steps.clone();
+        cx.spawn(|terminal_panel, mut cx| async move {
+            for step in steps {
+                let spawned = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                    step.iter()
+                        .map(|spawn_in_terminal| {
+                            if spawn_in_terminal.debug.is_some() {
+                                terminal_panel.start_debug_session(spawn_in_terminal, cx);
+                                return None;
+                            }
+                            terminal_panel.spawn_task(spawn_in_terminal, cx)
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                let results = join_all(step.iter().zip(spawned).map(
+                    |(spawn_in_terminal, terminal)| {
+                        // Only the planned task itself can debug, so nothing waits for its session to end.
+                        let is_debugged = spawn_in_terminal.debug.is_some();
+                        let cx = cx.clone();
+                        async move {
+                            if is_debugged {
+                                return true;
+                            }
+                            match terminal {
+                                Some(terminal) => wait_for_task_success(terminal, cx).await,
+                                None => false,
+                            }
+                        }
+                    },
+                ))
+                .await;
+
+                let failed_tasks = step
+                    .iter()
+                    .zip(results)
+                    .filter(|(_, succeeded)| !succeeded)
+                    .map(|(spawn_in_terminal, _)| format!("`{}`", spawn_in_terminal.label))
+                    .collect::<Vec<_>>();
+                if !failed_tasks.is_empty() {
+                    let workspace =
+                        terminal_panel.update(&mut cx, |panel, _| panel.workspace.clone())?;
+                    workspace.update(&mut cx, |workspace, cx| {
+                        struct TaskPlanFailed;
+                        workspace.show_toast(
+                            Toast::new(
+                                NotificationId::unique::<TaskPlanFailed>(),
+                                format!(
+                                    "Not running the dependent tasks, as {} did not succeed",
+                                    failed_tasks.join(", ")
+                                ),
+                            ),
+                            cx,
+                        )
+                    })?;
+                    break;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Terminal views of all panes of the panel.
+    fn terminal_views(&self, cx: &AppContext) -> Vec<View<TerminalView>> {
+        self.center
+            .panes()
+            .iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .filter_map(|item| item.act_as::<TerminalView>(cx))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
         working_directory: Option<PathBuf>,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let reveal = spawn_task.reveal;
-        self.add_terminal(working_directory, Some(spawn_task), reveal, cx);
+        self.add_terminal(working_directory, Some(spawn_task), reveal, cx)
     }
 
     /// Create a new Terminal in the current working directory or the user's home directory
@@ -450,34 +566,38 @@ impl TerminalPanel {
 
         terminal_panel.update(cx, |this, cx| {
             this.add_terminal(None, None, RevealStrategy::Always, cx)
+                .detach_and_log_err(cx)
         });
     }
 
-    fn terminals_for_task(
-        &self,
-        label: &str,
-        cx: &mut AppContext,
-    ) -> Vec<(usize, View<TerminalView>)> {
-        self.pane
-            .read(cx)
-            .items()
-            .enumerate()
-            .filter_map(|(index, item)| Some((index, item.act_as::<TerminalView>(cx)?)))
-            .filter_map(|(index, terminal_view)| {
-                let task_state = terminal_view.read(cx).terminal().read(cx).task()?;
-                if &task_state.full_label == label {
-                    Some((index, terminal_view))
-                } else {
-                    None
-                }
+    fn terminals_for_task(&self, label: &str, cx: &mut AppContext) -> Vec<View<TerminalView>> {
+        self.terminal_views(cx)
+            .into_iter()
+            .filter(|terminal_view| {
+                terminal_view
+                    .read(cx)
+                    .terminal()
+                    .read(cx)
+                    .task()
+                    .map_or(false, |task_state| task_state.full_label == label)
             })
             .collect()
     }
 
-    fn activate_terminal_view(&self, item_index: usize, cx: &mut WindowContext) {
-        self.pane.update(cx, |pane, cx| {
-            pane.activate_item(item_index, true, true, cx)
-        })
+    fn activate_terminal_view(
+        &mut self,
+        terminal_view: &View<TerminalView>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        for pane in self.center.panes() {
+            if let Some(item_index) = pane.read(cx).index_for_item(terminal_view) {
+                pane.update(cx, |pane, cx| {
+                    pane.activate_item(item_index, true, true, cx)
+                });
+                self.active_pane = pane;
+                return;
+            }
+        }
     }
 
     fn add_terminal(
@@ -486,13 +606,13 @@ impl TerminalPanel {
         spawn_task: Option<SpawnInTerminal>,
         reveal_strategy: RevealStrategy,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let workspace = self.workspace.clone();
         self.pending_terminals_to_add += 1;
 
         cx.spawn(|terminal_panel, mut cx| async move {
-            let pane = terminal_panel.update(&mut cx, |this, _| this.pane.clone())?;
-            workspace.update(&mut cx, |workspace, cx| {
+            let pane = terminal_panel.update(&mut cx, |this, _| this.active_pane.clone())?;
+            let terminal = workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
                 } else {
@@ -502,14 +622,13 @@ impl TerminalPanel {
                 };
 
                 let window = cx.window_handle();
-                if let Some(terminal) = workspace.project().update(cx, |project, cx| {
-                    project
-                        .create_terminal(working_directory, spawn_task, window, cx)
-                        .log_err()
-                }) {
-                    let terminal = Box::new(cx.new_view(|cx| {
+                let terminal = workspace.project().update(cx, |project, cx| {
+                    project.create_terminal(working_directory, spawn_task, window, cx)
+                });
+                if let Ok(terminal) = &terminal {
+                    let terminal_view = Box::new(cx.new_view(|cx| {
                         TerminalView::new(
-                            terminal,
+                            terminal.clone(),
                             workspace.weak_handle(),
                             workspace.database_id(),
                             cx,
@@ -517,45 +636,45 @@ impl TerminalPanel {
                     }));
                     pane.update(cx, |pane, cx| {
                         let focus = pane.has_focus(cx);
-                        pane.add_item(terminal, true, focus, None, cx);
+                        pane.add_item(terminal_view, true, focus, None, cx);
                     });
                 }
                 if reveal_strategy == RevealStrategy::Always {
                     workspace.focus_panel::<Self>(cx);
                 }
+                terminal
             })?;
             terminal_panel.update(&mut cx, |this, cx| {
                 this.pending_terminals_to_add = this.pending_terminals_to_add.saturating_sub(1);
                 this.serialize(cx)
             })?;
-            anyhow::Ok(())
+            terminal
         })
-        .detach_and_log_err(cx);
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
-        let mut items_to_serialize = HashSet::default();
+        let panes = self.center.serialize(&mut |pane| {
+            let pane = pane.read(cx);
+            SerializedTerminalPane {
+                items: pane
+                    .items()
+                    .filter(|item| item.act_as::<TerminalView>(cx).is_some())
+                    .map(|item| item.item_id().as_u64())
+                    .collect(),
+                active_item_id: pane.active_item().map(|item| item.item_id().as_u64()),
+            }
+        });
         let items = self
-            .pane
-            .read(cx)
-            .items()
-            .filter_map(|item| {
-                let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
-                    None
-                } else {
-                    let id = item.item_id().as_u64();
-                    items_to_serialize.insert(id);
-                    Some(id)
-                }
-            })
+            .terminal_views(cx)
+            .iter()
+            .map(|terminal_view| terminal_view.entity_id().as_u64())
             .collect::<Vec<_>>();
         let active_item_id = self
-            .pane
+            .active_pane
             .read(cx)
             .active_item()
             .map(|item| item.item_id().as_u64())
-            .filter(|active_id| items_to_serialize.contains(active_id));
+            .filter(|active_id| items.contains(active_id));
         let height = self.height;
         let width = self.width;
         self.pending_serialization = cx.background_executor().spawn(
@@ -566,6 +685,7 @@ impl TerminalPanel {
                         serde_json::to_string(&SerializedTerminalPanel {
                             items,
                             active_item_id,
+                            panes: Some(panes),
                             height,
                             width,
                         })?,
@@ -578,32 +698,28 @@ impl TerminalPanel {
     }
 
     fn replace_terminal(
-        &self,
+        &mut self,
         working_directory: Option<PathBuf>,
         spawn_task: SpawnInTerminal,
-        terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
-    ) -> Option<()> {
+    ) -> Result<Model<Terminal>> {
         let project = self
             .workspace
-            .update(cx, |workspace, _| workspace.project().clone())
-            .ok()?;
+            .update(cx, |workspace, _| workspace.project().clone())?;
 
         let reveal = spawn_task.reveal;
         let window = cx.window_handle();
         let new_terminal = project.update(cx, |project, cx| {
-            project
-                .create_terminal(working_directory, Some(spawn_task), window, cx)
-                .log_err()
+            project.create_terminal(working_directory, Some(spawn_task), window, cx)
         })?;
         terminal_to_replace.update(cx, |terminal_to_replace, cx| {
-            terminal_to_replace.set_terminal(new_terminal, cx);
+            terminal_to_replace.set_terminal(new_terminal.clone(), cx);
         });
 
         match reveal {
             RevealStrategy::Always => {
-                self.activate_terminal_view(terminal_item_index, cx);
+                self.activate_terminal_view(&terminal_to_replace, cx);
                 let task_workspace = self.workspace.clone();
                 cx.spawn(|_, mut cx| async move {
                     task_workspace
@@ -615,23 +731,42 @@ impl TerminalPanel {
             RevealStrategy::Never => {}
         }
 
-        Some(())
+        Ok(new_terminal)
     }
 
+    /// The pane of the panel, that was focused last.
     pub fn pane(&self) -> &View<Pane> {
-        &self.pane
+        &self.active_pane
     }
 
     fn has_no_terminals(&mut self, cx: &mut ViewContext<'_, Self>) -> bool {
-        self.pane.read(cx).items_len() == 0 && self.pending_terminals_to_add == 0
+        self.terminal_views(cx).is_empty() && self.pending_terminals_to_add == 0
     }
 }
 
+/// Waits for the task to run to completion in its terminal, returns whether the task succeeded.
+async fn wait_for_task_success(
+    terminal: Task<Result<Model<Terminal>>>,
+    mut cx: AsyncWindowContext,
+) -> bool {
+    let Some(terminal) = terminal.await.log_err() else {
+        return false;
+    };
+    let Ok(completion) =
+        terminal.update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+    else {
+        return false;
+    };
+    completion.await;
+    let status = terminal.read_with(&cx, |terminal, _| terminal.task().map(|task| task.status));
+    status == Ok(Some(TaskStatus::Completed { success: true }))
+}
+
 async fn wait_for_terminals_tasks(
-    terminals_for_task: Vec<(usize, View<TerminalView>)>,
+    terminals_for_task: Vec<View<TerminalView>>,
     cx: &mut AsyncWindowContext,
 ) {
-    let pending_tasks = terminals_for_task.iter().filter_map(|(_, terminal)| {
+    let pending_tasks = terminals_for_task.iter().filter_map(|terminal| {
         terminal
             .update(cx, |terminal_view, cx| {
                 terminal_view
@@ -643,6 +778,154 @@ async fn wait_for_terminals_tasks(
     let _: Vec<()> = join_all(pending_tasks).await;
 }
 
+/// Creates a pane for the terminal panel, that accepts the dropped paths and project entries
+/// by pasting them into its active terminal.
+fn new_terminal_pane(
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> View<Pane> {
+    cx.new_view(|cx| {
+        let mut pane = Pane::new(
+            workspace.clone(),
+            project,
+            Default::default(),
+            None,
+            NewTerminal.boxed_clone(),
+            cx,
+        );
+        pane.set_can_split(false, cx);
+        pane.set_can_navigate(false, cx);
+        pane.display_nav_history_buttons(None);
+        pane.set_should_display_tab_bar(|_| true);
+        pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
+            h_flex()
+                .gap_2()
+                .child(
+                    IconButton::new("plus", IconName::Plus)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(|pane, _, cx| {
+                            let focus_handle = pane.focus_handle(cx);
+                            let menu = ContextMenu::build(cx, |menu, _| {
+                                menu.action("New Terminal", workspace::NewTerminal.boxed_clone())
+                                    .entry(
+                                        "Spawn task",
+                                        Some(tasks_ui::Spawn::modal().boxed_clone()),
+                                        move |cx| {
+                                            // We want the focus to go back to terminal panel once task modal is dismissed,
+                                            // hence we focus that first. Otherwise, we'd end up without a focused element, as
+                                            // context menu will be gone the moment we spawn the modal.
+                                            cx.focus(&focus_handle);
+                                            cx.dispatch_action(
+                                                tasks_ui::Spawn::modal().boxed_clone(),
+                                            );
+                                        },
+                                    )
+                            });
+                            cx.subscribe(&menu, |pane, _, _: &DismissEvent, _| {
+                                pane.new_item_menu = None;
+                            })
+                            .detach();
+                            pane.new_item_menu = Some(menu);
+                        }))
+                        .tooltip(|cx| Tooltip::text("New...", cx)),
+                )
+                .when_some(pane.new_item_menu.as_ref(), |el, new_item_menu| {
+                    el.child(Pane::render_menu_overlay(new_item_menu))
+                })
+                .child(
+                    IconButton::new("split", IconName::Split)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(|pane, _, cx| {
+                            let menu = ContextMenu::build(cx, |menu, _| {
+                                menu.action("Split Right", SplitRight.boxed_clone())
+                                    .action("Split Left", SplitLeft.boxed_clone())
+                                    .action("Split Up", SplitUp.boxed_clone())
+                                    .action("Split Down", SplitDown.boxed_clone())
+                            });
+                            cx.subscribe(&menu, |pane, _, _: &DismissEvent, cx| {
+                                pane.focus(cx);
+                                pane.split_item_menu = None;
+                            })
+                            .detach();
+                            pane.split_item_menu = Some(menu);
+                        }))
+                        .tooltip(|cx| Tooltip::text("Split Pane", cx)),
+                )
+                .when_some(pane.split_item_menu.as_ref(), |el, split_item_menu| {
+                    el.child(Pane::render_menu_overlay(split_item_menu))
+                })
+                .child({
+                    let zoomed = pane.is_zoomed();
+                    IconButton::new("toggle_zoom", IconName::Maximize)
+                        .icon_size(IconSize::Small)
+                        .selected(zoomed)
+                        .selected_icon(IconName::Minimize)
+                        .on_click(cx.listener(|pane, _, cx| {
+                            pane.toggle_zoom(&workspace::ToggleZoom, cx);
+                        }))
+                        .tooltip(move |cx| {
+                            Tooltip::for_action(
+                                if zoomed { "Zoom Out" } else { "Zoom In" },
+                                &ToggleZoom,
+                                cx,
+                            )
+                        })
+                })
+                .into_any_element()
+        });
+
+        pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
+            if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
+                let item = if &tab.pane == cx.view() {
+                    pane.item_for_index(tab.ix)
+                } else {
+                    tab.pane.read(cx).item_for_index(tab.ix)
+                };
+                if let Some(item) = item {
+                    if item.downcast::<TerminalView>().is_some() {
+                        return ControlFlow::Continue(());
+                    } else if let Some(project_path) = item.project_path(cx) {
+                        if let Some(entry_path) = workspace
+                            .update(cx, |workspace, cx| {
+                                workspace
+                                    .project()
+                                    .read(cx)
+                                    .absolute_path(&project_path, cx)
+                            })
+                            .log_err()
+                            .flatten()
+                        {
+                            add_paths_to_terminal(pane, &[entry_path], cx);
+                        }
+                    }
+                }
+            } else if let Some(&entry_id) = dropped_item.downcast_ref::<ProjectEntryId>() {
+                if let Some(entry_path) = workspace
+                    .update(cx, |workspace, cx| {
+                        let project = workspace.project().read(cx);
+                        project
+                            .path_for_entry(entry_id, cx)
+                            .and_then(|project_path| project.absolute_path(&project_path, cx))
+                    })
+                    .log_err()
+                    .flatten()
+                {
+                    add_paths_to_terminal(pane, &[entry_path], cx);
+                }
+            } else if let Some(paths) = dropped_item.downcast_ref::<ExternalPaths>() {
+                add_paths_to_terminal(pane, paths.paths(), cx);
+            }
+
+            ControlFlow::Break(())
+        });
+        let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
+        pane.toolbar()
+            .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
+        pane
+    })
+}
+
 fn add_paths_to_terminal(pane: &mut Pane, paths: &[PathBuf], cx: &mut ViewContext<'_, Pane>) {
     if let Some(terminal_view) = pane
         .active_item()
@@ -666,7 +949,7 @@ impl Render for TerminalPanel {
         let mut registrar = DivRegistrar::new(
             |panel, cx| {
                 panel
-                    .pane
+                    .active_pane
                     .read(cx)
                     .toolbar()
                     .read(cx)
@@ -675,13 +958,16 @@ impl Render for TerminalPanel {
             cx,
         );
         BufferSearchBar::register(&mut registrar);
-        registrar.into_div().size_full().child(self.pane.clone())
+        registrar
+            .into_div()
+            .size_full()
+            .child(self.center.render(cx))
     }
 }
 
 impl FocusableView for TerminalPanel {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
-        self.pane.focus_handle(cx)
+        self.active_pane.focus_handle(cx)
     }
 }
 
@@ -729,21 +1015,24 @@ impl Panel for TerminalPanel {
     }
 
     fn is_zoomed(&self, cx: &WindowContext) -> bool {
-        self.pane.read(cx).is_zoomed()
+        self.active_pane.read(cx).is_zoomed()
     }
 
     fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
-        self.pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        for pane in self.center.panes() {
+            pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        }
     }
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.has_no_terminals(cx) {
-            self.add_terminal(None, None, RevealStrategy::Never, cx);
+            self.add_terminal(None, None, RevealStrategy::Never, cx)
+                .detach_and_log_err(cx);
         }
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.pane.read(cx).items_len();
+        let count = self.terminal_views(cx).len();
         if count == 0 {
             None
         } else {
@@ -774,6 +1063,111 @@ impl Panel for TerminalPanel {
 struct SerializedTerminalPanel {
     items: Vec<u64>,
     active_item_id: Option<u64>,
+    /// Layout of the split panes, `None` for the panels saved before the splits were supported.
+    #[serde(default)]
+    panes: Option<SerializedTerminalPaneGroup>,
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use workspace::{AppState, WORKSPACE_DB};
+
+    use super::*;
+    use crate::persistence::{SerializedTerminalTask, TERMINAL_DB};
+    use crate::terminal_pane_group::SerializedAxis;
+
+    #[gpui::test]
+    async fn test_restore_split_panel_with_task_terminal(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let app_state = cx.update(AppState::test);
+        cx.update(|cx| {
+            theme::init(theme::LoadThemes::JustBase, cx);
+            Project::init_settings(cx);
+            language::init(cx);
+            terminal::init(cx);
+        });
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let workspace_id = WORKSPACE_DB.next_id().await.unwrap();
+        let window = cx
+            .add_window(|cx| Workspace::new(workspace_id, project.clone(), app_state.clone(), cx));
+        let workspace = window.root_view(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(window.into(), cx);
+
+        // A shell terminal on the left, and the terminal of a failed task on the right.
+        let (shell_item_id, task_item_id) = (1, 2);
+        let task = serde_json::to_string(&SerializedTerminalTask {
+            id: "cargo test".to_string(),
+            full_label: "cargo test".to_string(),
+            label: "cargo test".to_string(),
+            command_label: "cargo test".to_string(),
+            success: Some(false),
+        })
+        .unwrap();
+        TERMINAL_DB
+            .save_session(
+                task_item_id,
+                workspace_id,
+                Some("cargo".to_string()),
+                Some(task),
+                None,
+            )
+            .await
+            .unwrap();
+        let pane = |item_id: u64| {
+            SerializedTerminalPaneGroup::Pane(SerializedTerminalPane {
+                items: vec![item_id],
+                active_item_id: Some(item_id),
+            })
+        };
+        KEY_VALUE_STORE
+            .write_kvp(
+                TERMINAL_PANEL_KEY.into(),
+                serde_json::to_string(&SerializedTerminalPanel {
+                    items: vec![shell_item_id, task_item_id],
+                    active_item_id: Some(task_item_id),
+                    panes: Some(SerializedTerminalPaneGroup::Axis {
+                        axis: SerializedAxis::Horizontal,
+                        members: vec![pane(shell_item_id), pane(task_item_id)],
+                    }),
+                    width: None,
+                    height: None,
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let panel = cx
+            .update(|cx| TerminalPanel::load(workspace.downgrade(), cx.to_async()))
+            .await
+            .unwrap();
+        panel.update(cx, |panel, cx| {
+            let panes = panel.center.panes();
+            assert_eq!(panes.len(), 2);
+            assert_eq!(panel.active_pane, panes[1]);
+            let terminals = panes
+                .iter()
+                .map(|pane| {
+                    let pane = pane.read(cx);
+                    assert_eq!(pane.items_len(), 1);
+                    pane.active_item()
+                        .and_then(|item| item.act_as::<TerminalView>(cx))
+                        .expect("the restored terminal is active")
+                        .read(cx)
+                        .terminal()
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+
+            assert!(terminals[0].read(cx).task().is_none());
+            let task_terminal = terminals[1].read(cx);
+            let task = task_terminal.task().expect("the task is restored");
+            assert_eq!(task.id, TaskId("cargo test".to_string()));
+            assert_eq!(task.status, TaskStatus::Completed { success: false });
+        });
+    }
+}
@@ -1,5 +1,6 @@
 mod persistence;
 pub mod terminal_element;
+mod terminal_pane_group;
 pub mod terminal_panel;
 
 use collections::HashSet;
@@ -11,16 +12,18 @@ use gpui::{
     MouseDownEvent, Pixels, Render, Styled, Subscription, Task, View, VisualContext, WeakView,
 };
 use language::Bias;
-use persistence::TERMINAL_DB;
+use persistence::{SerializedTerminalTask, TERMINAL_DB};
 use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
 use settings::SettingsStore;
+use task::TaskId;
 use terminal::{
     alacritty_terminal::{
         index::Point,
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, TaskStatus, Terminal,
+    Clear, Copy, CopyLastCommandOutput, Event, MaybeNavigationTarget, Paste, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ShowCharacterPalette, TaskStatus, Terminal,
 };
 use terminal_element::TerminalElement;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
@@ -30,8 +33,8 @@ use workspace::{
     notifications::NotifyResultExt,
     register_deserializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, SplitDown, SplitRight,
+    ToolbarItemLocation, Workspace, WorkspaceId,
 };
 
 use anyhow::Context;
@@ -52,6 +55,9 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 ];
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SESSION_SERIALIZATION_THROTTLE: Duration = Duration::from_secs(2);
+/// How many of the last scrollback lines are restored when the terminal is recreated on startup.
+const SERIALIZED_SCROLLBACK_LINES: usize = 5_000;
 
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -92,6 +98,7 @@ pub struct TerminalView {
     can_navigate_to_selected_word: bool,
     workspace_id: WorkspaceId,
     show_title: bool,
+    session_serialization_scheduled: bool,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -168,6 +175,7 @@ impl TerminalView {
             can_navigate_to_selected_word: false,
             workspace_id,
             show_title: TerminalSettings::get_global(cx).toolbar.title,
+            session_serialization_scheduled: false,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -197,6 +205,11 @@ impl TerminalView {
     ) {
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.action("Clear", Box::new(Clear))
+                .action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
+                .separator()
+                .action("Split Right", Box::new(SplitRight))
+                .action("Split Down", Box::new(SplitDown))
+                .separator()
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
 
@@ -250,6 +263,27 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(&mut self, _: &ScrollToNextPrompt, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(&mut self, _: &CopyLastCommandOutput, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_last_command_output(cx));
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -427,6 +461,102 @@ impl TerminalView {
             subscribe_for_terminal_events(&terminal, self.workspace.clone(), cx);
         self.terminal = terminal;
     }
+
+    /// Saves the title, the task and the scrollback of the terminal, so it can be restored on startup.
+    /// Saving happens at most once per [`SESSION_SERIALIZATION_THROTTLE`], as the terminal output changes often.
+    fn serialize_session(&mut self, cx: &mut ViewContext<Self>) {
+        if self.session_serialization_scheduled {
+            return;
+        }
+        self.session_serialization_scheduled = true;
+        cx.spawn(|terminal_view, mut cx| async move {
+            cx.background_executor()
+                .timer(SESSION_SERIALIZATION_THROTTLE)
+                .await;
+            let Ok((item_id, workspace_id, title, task, scrollback)) =
+                terminal_view.update(&mut cx, |terminal_view, cx| {
+                    terminal_view.session_serialization_scheduled = false;
+                    let terminal = terminal_view.terminal.read(cx);
+                    let title =
+                        Some(terminal.breadcrumb_text.clone()).filter(|title| !title.is_empty());
+                    let task = terminal.task().and_then(|task| {
+                        serde_json::to_string(&SerializedTerminalTask {
+                            id: task.id.0.clone(),
+                            full_label: task.full_label.clone(),
+                            label: task.label.clone(),
+                            command_label: task.command_label.clone(),
+                            success: match task.status {
+                                TaskStatus::Completed { success } => Some(success),
+                                TaskStatus::Running | TaskStatus::Unknown => None,
+                            },
+                        })
+                        .log_err()
+                    });
+                    let scrollback = TerminalSettings::get_global(cx)
+                        .restore_scrollback
+                        .then(|| terminal.scrollback_text(SERIALIZED_SCROLLBACK_LINES, cx));
+                    (
+                        cx.entity_id().as_u64(),
+                        terminal_view.workspace_id,
+                        title,
+                        task,
+                        scrollback,
+                    )
+                })
+            else {
+                return;
+            };
+            let scrollback = match scrollback {
+                Some(scrollback) => Some(scrollback.await),
+                None => None,
+            };
+            TERMINAL_DB
+                .save_session(item_id, workspace_id, title, task, scrollback)
+                .await
+                .log_err();
+        })
+        .detach();
+    }
+
+    /// Restores the title, the task and the scrollback, saved for the terminal in the previous session.
+    fn restore_session(
+        terminal: &Model<Terminal>,
+        item_id: workspace::ItemId,
+        workspace_id: WorkspaceId,
+        cx: &mut AppContext,
+    ) {
+        let Some((title, task, scrollback)) = TERMINAL_DB
+            .get_session(item_id, workspace_id)
+            .log_err()
+            .flatten()
+        else {
+            return;
+        };
+        let task =
+            task.and_then(|task| serde_json::from_str::<SerializedTerminalTask>(&task).log_err());
+        terminal.update(cx, |terminal, cx| {
+            if let Some(scrollback) =
+                scrollback.filter(|_| TerminalSettings::get_global(cx).restore_scrollback)
+            {
+                terminal.restore_scrollback(&scrollback);
+            }
+            if let Some(title) = title {
+                terminal.restore_title(title, cx);
+            }
+            if let Some(task) = task {
+                terminal.restore_task(
+                    TaskId(task.id),
+                    task.full_label,
+                    task.label,
+                    task.command_label,
+                    match task.success {
+                        Some(success) => TaskStatus::Completed { success },
+                        None => TaskStatus::Unknown,
+                    },
+                );
+            }
+        });
+    }
 }
 
 fn subscribe_for_terminal_events(
@@ -442,6 +572,7 @@ fn subscribe_for_terminal_events(
                 cx.emit(Event::Wakeup);
                 cx.emit(ItemEvent::UpdateTab);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                this.serialize_session(cx);
             }
 
             Event::Bell => {
@@ -453,20 +584,17 @@ fn subscribe_for_terminal_events(
 
             Event::TitleChanged => {
                 cx.emit(ItemEvent::UpdateTab);
-                let terminal = this.terminal().read(cx);
-                if terminal.task().is_none() {
-                    if let Some(cwd) = terminal.get_cwd() {
-                        let item_id = cx.entity_id();
-                        let workspace_id = this.workspace_id;
-                        cx.background_executor()
-                            .spawn(async move {
-                                TERMINAL_DB
-                                    .save_working_directory(item_id.as_u64(), workspace_id, cwd)
-                                    .await
-                                    .log_err();
-                            })
-                            .detach();
-                    }
+                if let Some(cwd) = this.terminal().read(cx).get_cwd() {
+                    let item_id = cx.entity_id();
+                    let workspace_id = this.workspace_id;
+                    cx.background_executor()
+                        .spawn(async move {
+                            TERMINAL_DB
+                                .save_working_directory(item_id.as_u64(), workspace_id, cwd)
+                                .await
+                                .log_err();
+                        })
+                        .detach();
                 }
             }
 
@@ -586,9 +714,14 @@ fn subscribe_for_terminal_events(
                     .detach_and_log_err(cx)
                 }
             },
-            Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
+            Event::BreadcrumbsChanged => {
+                cx.emit(ItemEvent::UpdateBreadcrumbs);
+                this.serialize_session(cx);
+            }
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => cx.emit(SearchEvent::ActiveMatchChanged),
+            Event::TaskFinished(_) => this.serialize_session(cx),
+            Event::ProblemsFound(_) | Event::TestResultsFound(_) => {}
         });
     vec![terminal_subscription, terminal_events_subscription]
 }
@@ -690,12 +823,31 @@ fn regex_to_literal(regex: &str) -> String {
 }
 
 pub fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<RegexSearch> {
-    let query = query.as_str();
-    if query == "." {
+    let pattern = search_pattern(query)?;
+    RegexSearch::new(&pattern).ok()
+}
+
+/// Builds the terminal regex for the query, honoring the regex, whole word and case sensitivity options.
+/// Alacritty picks case sensitivity based on the query casing, so it is always set explicitly.
+fn search_pattern(query: &SearchQuery) -> Option<String> {
+    let query_str = query.as_str();
+    if query_str.is_empty() || query_str == "." {
         return None;
     }
-    let searcher = RegexSearch::new(&query);
-    searcher.ok()
+    let mut pattern = if query.is_regex() {
+        query_str.to_string()
+    } else {
+        regex_to_literal(query_str)
+    };
+    if query.whole_word() {
+        pattern = format!(r"(?-u:\b)(?:{pattern})(?-u:\b)");
+    }
+    let case_flag = if query.case_sensitive() {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+    Some(format!("{case_flag}{pattern}"))
 }
 
 impl TerminalView {
@@ -741,6 +893,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::copy))
             .on_action(cx.listener(TerminalView::paste))
             .on_action(cx.listener(TerminalView::clear))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_key_down(cx.listener(Self::key_down))
@@ -817,21 +972,21 @@ impl Item for TerminalView {
 
     fn clone_on_split(
         &self,
-        _workspace_id: WorkspaceId,
-        _cx: &mut ViewContext<Self>,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
     ) -> Option<View<Self>> {
-        //From what I can tell, there's no  way to tell the current working
-        //Directory of the terminal from outside the shell. There might be
-        //solutions to this, but they are non-trivial and require more IPC
-
-        // Some(TerminalContainer::new(
-        //     Err(anyhow::anyhow!("failed to instantiate terminal")),
-        //     workspace_id,
-        //     cx,
-        // ))
-
-        // TODO
-        None
+        // The split gets a new shell, started in the directory the current one is in.
+        let project = self.workspace.upgrade()?.read(cx).project().clone();
+        let working_directory = self.terminal.read(cx).get_cwd();
+        let window = cx.window_handle();
+        let terminal = project
+            .update(cx, |project, cx| {
+                project.create_terminal(working_directory, None, window, cx)
+            })
+            .log_err()?;
+        Some(
+            cx.new_view(|cx| TerminalView::new(terminal, self.workspace.clone(), workspace_id, cx)),
+        )
     }
 
     fn is_dirty(&self, cx: &gpui::AppContext) -> bool {
@@ -897,6 +1052,7 @@ impl Item for TerminalView {
             let terminal = project.update(&mut cx, |project, cx| {
                 project.create_terminal(cwd, None, window, cx)
             })??;
+            cx.update(|cx| TerminalView::restore_session(&terminal, item_id, workspace_id, cx))?;
             pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
             })
@@ -904,16 +1060,14 @@ impl Item for TerminalView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
-        if self.terminal().read(cx).task().is_none() {
-            cx.background_executor()
-                .spawn(TERMINAL_DB.update_workspace_id(
-                    workspace.database_id(),
-                    self.workspace_id,
-                    cx.entity_id().as_u64(),
-                ))
-                .detach();
-            self.workspace_id = workspace.database_id();
-        }
+        cx.background_executor()
+            .spawn(TERMINAL_DB.update_workspace_id(
+                workspace.database_id(),
+                self.workspace_id,
+                cx.entity_id().as_u64(),
+            ))
+            .detach();
+        self.workspace_id = workspace.database_id();
     }
 
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
@@ -926,8 +1080,8 @@ impl SearchableItem for TerminalView {
 
     fn supported_options() -> SearchOptions {
         SearchOptions {
-            case: false,
-            word: false,
+            case: true,
+            word: true,
             regex: true,
             replacement: false,
         }
@@ -974,20 +1128,7 @@ impl SearchableItem for TerminalView {
         query: Arc<SearchQuery>,
         cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Self::Match>> {
-        let searcher = match &*query {
-            SearchQuery::Text { .. } => regex_search_for_query(
-                &(SearchQuery::text(
-                    regex_to_literal(&query.as_str()),
-                    query.whole_word(),
-                    query.case_sensitive(),
-                    query.include_ignored(),
-                    query.files_to_include().to_vec(),
-                    query.files_to_exclude().to_vec(),
-                )
-                .unwrap()),
-            ),
-            SearchQuery::Regex { .. } => regex_search_for_query(&query),
-        };
+        let searcher = regex_search_for_query(&query);
 
         if let Some(s) = searcher {
             self.terminal()
@@ -1292,4 +1433,44 @@ mod tests {
     fn empty_string_stays_empty() {
         assert_eq!(regex_to_literal(""), "".to_string());
     }
+
+    #[test]
+    fn search_pattern_honors_options() {
+        let text_query = |query: &str, whole_word, case_sensitive| {
+            SearchQuery::text(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap()
+        };
+        let regex_query = |query: &str, whole_word, case_sensitive| {
+            SearchQuery::regex(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            search_pattern(&text_query("a.b", false, false)).as_deref(),
+            Some(r"(?i)a\.b")
+        );
+        assert_eq!(
+            search_pattern(&text_query("Foo", false, true)).as_deref(),
+            Some("(?-i)Foo")
+        );
+        assert_eq!(
+            search_pattern(&regex_query("a.b", true, false)).as_deref(),
+            Some(r"(?i)(?-u:\b)(?:a.b)(?-u:\b)")
+        );
+        assert_eq!(search_pattern(&regex_query(".", false, false)), None);
+    }
 }
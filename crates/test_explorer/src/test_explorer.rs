@@ -0,0 +1,1160 @@
+mod test_explorer_settings;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, tasks::task_context_for_location, Editor};
+use gpui::{
+    actions, uniform_list, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, KeyContext, Model, ParentElement, Pixels, Render, Styled,
+    Subscription, Task, UniformListScrollHandle, View, ViewContext, VisualContext as _, WeakView,
+    WindowContext,
+};
+use language::{Anchor, Bias, Buffer, BufferSnapshot, Language, Point};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{Fs, Location, Project, ProjectPath, TaskSourceKind, TestStatus, WorktreeId};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use task::{ResolvedTask, TaskId, TaskTemplate, TestOutcome, TestResult, VariableName};
+use test_explorer_settings::{TestExplorerDockPosition, TestExplorerSettings};
+use ui::{prelude::*, Icon, IconButton, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotificationId,
+    Toast, Workspace,
+};
+
+const TEST_EXPLORER_KEY: &str = "TestExplorerPanel";
+/// Delay before discovering the tests again, after the project files change.
+const DISCOVERY_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Suffix of the runnable tags of the tasks, that run all tests in a test's file.
+const FILE_TAG_SUFFIX: &str = "-file";
+/// Suffix of the runnable tags of the tasks, that run all tests a test's file belongs to, e.g. its package.
+const ALL_TAG_SUFFIX: &str = "-all";
+
+actions!(test_explorer, [ToggleFocus, RefreshTests, RunAllTests]);
+
+pub fn init_settings(cx: &mut AppContext) {
+    TestExplorerSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<TestExplorerPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// A panel with the tests of the project, found by the runnables queries of the languages.
+pub struct TestExplorerPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    files: Vec<TestFile>,
+    collapsed_files: HashSet<PathBuf>,
+    visible_entries: Vec<TestEntry>,
+    selected_entry: Option<usize>,
+    test_runs: HashMap<TaskId, TestRun>,
+    discovering: bool,
+    /// The files to look for tests in, once the project files stop changing.
+    pending_discovery: Option<DiscoveryScope>,
+    discovery_in_progress: Option<DiscoveryScope>,
+    discovery_task: Task<()>,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTestExplorerPanel {
+    width: Option<Pixels>,
+}
+
+/// A project file with tests.
+struct TestFile {
+    worktree_id: WorktreeId,
+    path: Arc<Path>,
+    abs_path: PathBuf,
+    language: Arc<Language>,
+    tests: Vec<Test>,
+}
+
+/// A test, found by a runnable with a test tag.
+#[derive(Clone)]
+struct Test {
+    /// Text of the runnable's `@run` capture, e.g. the test function name.
+    symbol: String,
+    /// The path of the test in its file, e.g. `tests::test_name`, to tell apart tests with the same name.
+    test_path: String,
+    /// Tags of the runnable, to find the tasks to run the test with.
+    tags: Vec<String>,
+    /// Start of the runnable's `@run` capture.
+    position: Point,
+    /// Other captures of the runnable, available to its tasks as custom variables.
+    extra_captures: HashMap<String, String>,
+}
+
+/// The files to look for tests in.
+#[derive(Clone)]
+enum DiscoveryScope {
+    All,
+    /// The files at or below the paths.
+    Paths(HashSet<(WorktreeId, Arc<Path>)>),
+}
+
+enum DiscoveryCandidate {
+    Open {
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        abs_path: PathBuf,
+        snapshot: BufferSnapshot,
+    },
+    Closed {
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        abs_path: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TestEntry {
+    File { file_ix: usize },
+    Test { file_ix: usize, test_ix: usize },
+}
+
+/// Tests, run by a task.
+struct TestRun {
+    /// Whether the task reports the results of the separate tests, rather than only its exit status.
+    reports_results: bool,
+    tests: Vec<RunTest>,
+}
+
+struct RunTest {
+    abs_path: PathBuf,
+    test_path: String,
+    row: u32,
+    reported: bool,
+}
+
+impl TestExplorerPanel {
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = cx.view().downgrade();
+        cx.new_view(|cx| {
+            let mut test_explorer_settings = *TestExplorerSettings::get_global(cx);
+            let subscriptions = vec![
+                cx.subscribe(&project, |panel, _, event, cx| match event {
+                    project::Event::WorktreeAdded | project::Event::WorktreeRemoved(_) => {
+                        panel.discover_tests(DISCOVERY_DEBOUNCE, cx);
+                    }
+                    project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+                        let paths = changes.iter().map(|(path, _, _)| path.clone());
+                        panel.rediscover_tests(*worktree_id, paths, cx);
+                    }
+                    project::Event::TestResultsFound { task_id, results } => {
+                        panel.report_test_results(task_id, results, cx);
+                    }
+                    project::Event::TaskFinished { task_id, success } => {
+                        panel.finish_test_run(task_id, *success, cx);
+                    }
+                    project::Event::TestStatusesUpdated => cx.notify(),
+                    _ => {}
+                }),
+                cx.observe_global::<SettingsStore>(move |_, cx| {
+                    let new_settings = *TestExplorerSettings::get_global(cx);
+                    if test_explorer_settings != new_settings {
+                        test_explorer_settings = new_settings;
+                        cx.notify();
+                    }
+                }),
+            ];
+
+            let mut panel = Self {
+                workspace,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                files: Vec::new(),
+                collapsed_files: HashSet::default(),
+                visible_entries: Vec::new(),
+                selected_entry: None,
+                test_runs: HashMap::default(),
+                discovering: false,
+                pending_discovery: None,
+                discovery_in_progress: None,
+                discovery_task: Task::ready(()),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            panel.discover_tests(Duration::ZERO, cx);
+            panel
+        })
+    }
+
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(TEST_EXPLORER_KEY) })
+            .await
+            .map_err(|e| anyhow!("Failed to load test explorer: {}", e))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedTestExplorerPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = TestExplorerPanel::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        TEST_EXPLORER_KEY.into(),
+                        serde_json::to_string(&SerializedTestExplorerPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Finds the tests in the files of the visible worktrees, whose languages have runnables.
+    /// The files are parsed without opening them in the project.
+    fn discover_tests(&mut self, debounce: Duration, cx: &mut ViewContext<Self>) {
+        self.pending_discovery = Some(DiscoveryScope::All);
+        self.schedule_discovery(debounce, cx);
+    }
+
+    /// Looks for tests again in the files at or below the given paths of the worktree.
+    fn rediscover_tests(
+        &mut self,
+        worktree_id: WorktreeId,
+        paths: impl IntoIterator<Item = Arc<Path>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match self
+            .pending_discovery
+            .get_or_insert_with(|| DiscoveryScope::Paths(HashSet::default()))
+        {
+            DiscoveryScope::All => {}
+            DiscoveryScope::Paths(pending_paths) => {
+                pending_paths.extend(paths.into_iter().map(|path| (worktree_id, path)))
+            }
+        }
+        self.schedule_discovery(DISCOVERY_DEBOUNCE, cx);
+    }
+
+    /// Discovers the tests in the pending scope, once no more changes come in for the debounce duration.
+    /// Open buffers are queried as they are, other files are loaded and parsed in the background.
+    fn schedule_discovery(&mut self, debounce: Duration, cx: &mut ViewContext<Self>) {
+        if !self.project.read(cx).is_local() {
+            self.pending_discovery = None;
+            return;
+        }
+        // A discovery in progress is cancelled, so its scope is discovered again with the new one.
+        if let Some(scope) = self.discovery_in_progress.take() {
+            self.add_pending_discovery(scope);
+        }
+
+        self.discovering = true;
+        cx.notify();
+        self.discovery_task = cx.spawn(|panel, mut cx| async move {
+            cx.background_executor().timer(debounce).await;
+            let Ok(Some((scope, candidates, languages, fs))) =
+                panel.update(&mut cx, |panel, cx| {
+                    let Some(scope) = panel.pending_discovery.take() else {
+                        panel.discovering = false;
+                        cx.notify();
+                        return None;
+                    };
+                    panel.discovery_in_progress = Some(scope.clone());
+                    let candidates = panel.discovery_candidates(&scope, cx);
+                    let languages = panel.project.read(cx).languages().clone();
+                    Some((scope, candidates, languages, panel.fs.clone()))
+                })
+            else {
+                return;
+            };
+
+            let mut files = Vec::new();
+            for candidate in candidates {
+                let (worktree_id, path, abs_path, language, snapshot) = match candidate {
+                    DiscoveryCandidate::Open {
+                        worktree_id,
+                        path,
+                        abs_path,
+                        snapshot,
+                    } => {
+                        let Some(language) = snapshot.language().cloned() else {
+                            continue;
+                        };
+                        (worktree_id, path, abs_path, language, snapshot)
+                    }
+                    DiscoveryCandidate::Closed {
+                        worktree_id,
+                        path,
+                        abs_path,
+                    } => {
+                        let Ok(language) = languages.language_for_file_path(&path).await else {
+                            continue;
+                        };
+                        if !language.has_runnables() {
+                            continue;
+                        }
+                        let Some(text) = fs.load(&abs_path).await.log_err() else {
+                            continue;
+                        };
+                        let snapshot = Buffer::build_snapshot(
+                            text,
+                            Some(language.clone()),
+                            Some(languages.clone()),
+                        );
+                        let snapshot = cx.background_executor().spawn(snapshot).await;
+                        (worktree_id, path, abs_path, language, snapshot)
+                    }
+                };
+                if !language.has_runnables() {
+                    continue;
+                }
+                let tests = cx
+                    .background_executor()
+                    .spawn(async move { find_tests(&snapshot) })
+                    .await;
+                if !tests.is_empty() {
+                    files.push(TestFile {
+                        worktree_id,
+                        path,
+                        abs_path,
+                        language,
+                        tests,
+                    });
+                }
+            }
+
+            panel
+                .update(&mut cx, |panel, cx| {
+                    match scope {
+                        DiscoveryScope::All => panel.files = files,
+                        DiscoveryScope::Paths(paths) => {
+                            panel.files.retain(|file| {
+                                !paths.iter().any(|(worktree_id, path)| {
+                                    file.worktree_id == *worktree_id && file.path.starts_with(path)
+                                })
+                            });
+                            panel.files.extend(files);
+                            panel.files.sort_by(|a, b| {
+                                (a.worktree_id, &a.path).cmp(&(b.worktree_id, &b.path))
+                            });
+                        }
+                    }
+                    panel.discovery_in_progress = None;
+                    panel.discovering = false;
+                    panel.update_visible_entries(cx);
+                })
+                .ok();
+        });
+    }
+
+    fn add_pending_discovery(&mut self, scope: DiscoveryScope) {
+        match (&mut self.pending_discovery, scope) {
+            (pending @ None, scope) => *pending = Some(scope),
+            (Some(DiscoveryScope::All), _) => {}
+            (pending @ Some(_), DiscoveryScope::All) => *pending = Some(DiscoveryScope::All),
+            (Some(DiscoveryScope::Paths(pending_paths)), DiscoveryScope::Paths(paths)) => {
+                pending_paths.extend(paths)
+            }
+        }
+    }
+
+    /// The files of the visible worktrees in the scope, with the snapshots of the ones open in buffers.
+    fn discovery_candidates(
+        &self,
+        scope: &DiscoveryScope,
+        cx: &AppContext,
+    ) -> Vec<DiscoveryCandidate> {
+        let project = self.project.read(cx);
+        let open_buffers = project
+            .opened_buffers()
+            .into_iter()
+            .filter_map(|buffer| {
+                let buffer = buffer.read(cx);
+                let file = buffer.file()?;
+                Some((
+                    (
+                        WorktreeId::from_usize(file.worktree_id()),
+                        file.path().clone(),
+                    ),
+                    buffer.snapshot(),
+                ))
+            })
+            .collect::<HashMap<_, _>>();
+        project
+            .visible_worktrees(cx)
+            .flat_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let worktree_id = worktree.id();
+                let root_path = worktree.abs_path();
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| match scope {
+                        DiscoveryScope::All => true,
+                        DiscoveryScope::Paths(paths) => {
+                            paths.iter().any(|(path_worktree_id, path)| {
+                                *path_worktree_id == worktree_id && entry.path.starts_with(path)
+                            })
+                        }
+                    })
+                    .map(|entry| {
+                        let path = entry.path.clone();
+                        let abs_path = root_path.join(&path);
+                        match open_buffers.get(&(worktree_id, path.clone())) {
+                            Some(snapshot) => DiscoveryCandidate::Open {
+                                worktree_id,
+                                path,
+                                abs_path,
+                                snapshot: snapshot.clone(),
+                            },
+                            None => DiscoveryCandidate::Closed {
+                                worktree_id,
+                                path,
+                                abs_path,
+                            },
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let selected_entry = self
+            .selected_entry
+            .and_then(|ix| self.visible_entries.get(ix).copied());
+        self.visible_entries.clear();
+        for (file_ix, file) in self.files.iter().enumerate() {
+            self.visible_entries.push(TestEntry::File { file_ix });
+            if !self.collapsed_files.contains(&file.abs_path) {
+                self.visible_entries.extend(
+                    (0..file.tests.len()).map(|test_ix| TestEntry::Test { file_ix, test_ix }),
+                );
+            }
+        }
+        self.selected_entry = selected_entry
+            .and_then(|entry| self.visible_entries.iter().position(|e| e == &entry))
+            .or_else(|| (!self.visible_entries.is_empty()).then_some(0));
+        cx.notify();
+    }
+
+    fn refresh_tests(&mut self, _: &RefreshTests, cx: &mut ViewContext<Self>) {
+        self.discover_tests(Duration::ZERO, cx);
+    }
+
+    fn run_all_tests(&mut self, _: &RunAllTests, cx: &mut ViewContext<Self>) {
+        let mut resolved_tasks = Vec::new();
+        for file_ix in 0..self.files.len() {
+            match self.test_template(file_ix, 0, ALL_TAG_SUFFIX, cx) {
+                Some((kind, template)) => {
+                    let tests = self.run_tests_of_file(file_ix);
+                    let resolved_task = self.resolve_test_task(file_ix, 0, &kind, template, cx);
+                    resolved_tasks.push((kind, resolved_task, tests));
+                }
+                None => self.run_file(file_ix, cx),
+            }
+        }
+        if resolved_tasks.is_empty() {
+            return;
+        }
+
+        cx.spawn(|panel, mut cx| async move {
+            // Tests of the same package resolve into the same command, run it once for all of them.
+            let mut runs = Vec::<(TaskSourceKind, ResolvedTask, Vec<RunTest>)>::new();
+            for (kind, resolved_task, tests) in resolved_tasks {
+                let Some(resolved_task) = resolved_task.await.log_err() else {
+                    continue;
+                };
+                match runs
+                    .iter_mut()
+                    .find(|(_, run_task, _)| same_command(run_task, &resolved_task))
+                {
+                    Some((_, _, run_tests)) => run_tests.extend(tests),
+                    None => runs.push((kind, resolved_task, tests)),
+                }
+            }
+            panel.update(&mut cx, |panel, cx| {
+                for (kind, resolved_task, tests) in runs {
+                    panel.start_test_run(kind, resolved_task, tests, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn run_file(&mut self, file_ix: usize, cx: &mut ViewContext<Self>) {
+        match self.test_template(file_ix, 0, FILE_TAG_SUFFIX, cx) {
+            Some((kind, template)) => {
+                let tests = self.run_tests_of_file(file_ix);
+                self.run_test_task(file_ix, 0, kind, template, tests, cx);
+            }
+            None => {
+                for test_ix in 0..self.files[file_ix].tests.len() {
+                    self.run_test(file_ix, test_ix, cx);
+                }
+            }
+        }
+    }
+
+    fn run_test(&mut self, file_ix: usize, test_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        let Some(test) = file.tests.get(test_ix) else {
+            return;
+        };
+        let tests = vec![RunTest {
+            abs_path: file.abs_path.clone(),
+            test_path: test.test_path.clone(),
+            row: test.position.row,
+            reported: false,
+        }];
+        match self.test_template(file_ix, test_ix, "", cx) {
+            Some((kind, template)) => {
+                self.run_test_task(file_ix, test_ix, kind, template, tests, cx)
+            }
+            None => {
+                let message = format!("No task to run test `{}` with", test.symbol);
+                self.show_error(message, cx);
+            }
+        }
+    }
+
+    fn run_test_task(
+        &mut self,
+        file_ix: usize,
+        test_ix: usize,
+        kind: TaskSourceKind,
+        template: TaskTemplate,
+        tests: Vec<RunTest>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let resolved_task = self.resolve_test_task(file_ix, test_ix, &kind, template, cx);
+        cx.spawn(|panel, mut cx| async move {
+            let resolved_task = resolved_task.await?;
+            panel.update(&mut cx, |panel, cx| {
+                panel.start_test_run(kind, resolved_task, tests, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn run_tests_of_file(&self, file_ix: usize) -> Vec<RunTest> {
+        let file = &self.files[file_ix];
+        file.tests
+            .iter()
+            .map(|test| RunTest {
+                abs_path: file.abs_path.clone(),
+                test_path: test.test_path.clone(),
+                row: test.position.row,
+                reported: false,
+            })
+            .collect()
+    }
+
+    /// The task to run the test with, tagged with one of the test's tags, followed by the suffix.
+    /// As for the runnables in the editor, the strongest task source wins;
+    /// among its tasks, the ones that report the test results are preferred.
+    fn test_template(
+        &self,
+        file_ix: usize,
+        test_ix: usize,
+        tag_suffix: &str,
+        cx: &AppContext,
+    ) -> Option<(TaskSourceKind, TaskTemplate)> {
+        let file = self.files.get(file_ix)?;
+        let test = file.tests.get(test_ix)?;
+        let tags = test
+            .tags
+            .iter()
+            .map(|tag| format!("{tag}{tag_suffix}"))
+            .collect::<Vec<_>>();
+        let mut templates = self
+            .project
+            .read(cx)
+            .task_inventory()
+            .read(cx)
+            .list_tasks(Some(file.language.clone()), Some(file.worktree_id))
+            .into_iter()
+            .filter(|(_, template)| template.tags.iter().any(|tag| tags.contains(tag)))
+            .collect::<Vec<_>>();
+        templates.sort_by(|(kind_a, _), (kind_b, _)| kind_a.cmp(kind_b));
+        let strongest_source = templates.first()?.0.clone();
+        templates
+            .into_iter()
+            .take_while(|(kind, _)| kind == &strongest_source)
+            .min_by_key(|(_, template)| template.test_results.is_none())
+    }
+
+    /// Resolves the task in the context of the test, as if it was run from the test's runnable in the editor.
+    fn resolve_test_task(
+        &mut self,
+        file_ix: usize,
+        test_ix: usize,
+        kind: &TaskSourceKind,
+        template: TaskTemplate,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<ResolvedTask>> {
+        let file = &self.files[file_ix];
+        let test = file.tests[test_ix].clone();
+        let buffer = self.project.update(cx, |project, cx| {
+            project.open_local_buffer(&file.abs_path, cx)
+        });
+        let workspace = self.workspace.clone();
+        let project = self.project.clone();
+        let worktree_id = file.worktree_id;
+        let id_base = kind.to_id_base();
+        cx.spawn(|_, mut cx| async move {
+            let buffer = buffer.await?;
+            let task_context = workspace.update(&mut cx, |workspace, cx| {
+                let position = {
+                    let snapshot = buffer.read(cx).snapshot();
+                    snapshot.anchor_before(snapshot.clip_point(test.position, Bias::Left))
+                };
+                let location = Location {
+                    buffer,
+                    range: position..position,
+                };
+                task_context_for_location(workspace, location, cx)
+            })?;
+            let mut task_context = task_context
+                .with_context(|| format!("building context for test `{}`", test.symbol))?;
+            for (capture_name, value) in test.extra_captures {
+                task_context
+                    .task_variables
+                    .insert(VariableName::Custom(capture_name.into()), value);
+            }
+            let task_context = project
+                .update(&mut cx, |project, cx| {
+                    project.task_context_with_provided_variables(
+                        Some(worktree_id),
+                        task_context,
+                        cx,
+                    )
+                })?
+                .await;
+            template
+                .resolve_task(&id_base, &task_context)
+                .with_context(|| format!("resolving task `{}`", template.label))
+        })
+    }
+
+    fn start_test_run(
+        &mut self,
+        kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        tests: Vec<RunTest>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let reports_results = resolved_task
+            .resolved
+            .as_ref()
+            .map_or(false, |spawn_in_terminal| {
+                spawn_in_terminal.test_results.is_some()
+            });
+        let mut statuses = HashMap::<PathBuf, Vec<_>>::default();
+        for test in &tests {
+            statuses.entry(test.abs_path.clone()).or_default().push((
+                test.test_path.clone(),
+                test.row,
+                TestStatus::Running,
+            ));
+        }
+        self.project.update(cx, |project, cx| {
+            for (abs_path, statuses) in statuses {
+                project.set_test_statuses(abs_path, statuses, cx);
+            }
+        });
+        self.test_runs.insert(
+            resolved_task.id.clone(),
+            TestRun {
+                reports_results,
+                tests,
+            },
+        );
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace::tasks::schedule_resolved_task(workspace, kind, resolved_task, false, cx)
+            })
+            .ok();
+    }
+
+    fn report_test_results(
+        &mut self,
+        task_id: &TaskId,
+        results: &[TestResult],
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(test_run) = self.test_runs.get_mut(task_id) else {
+            return;
+        };
+        let mut statuses = HashMap::<PathBuf, Vec<_>>::default();
+        for result in results {
+            for test in test_run
+                .tests
+                .iter_mut()
+                .filter(|test| result.is_for_test(&test.test_path))
+            {
+                test.reported = true;
+                statuses.entry(test.abs_path.clone()).or_default().push((
+                    test.test_path.clone(),
+                    test.row,
+                    TestStatus::Finished {
+                        outcome: result.outcome,
+                        message: result.message.clone(),
+                    },
+                ));
+            }
+        }
+        self.project.update(cx, |project, cx| {
+            for (abs_path, statuses) in statuses {
+                project.set_test_statuses(abs_path, statuses, cx);
+            }
+        });
+    }
+
+    fn finish_test_run(&mut self, task_id: &TaskId, success: bool, cx: &mut ViewContext<Self>) {
+        let Some(test_run) = self.test_runs.remove(task_id) else {
+            return;
+        };
+        let mut statuses = HashMap::<PathBuf, Vec<_>>::default();
+        for test in test_run.tests.into_iter().filter(|test| !test.reported) {
+            // Tests without reported results did not run, unless the task does not report results at all.
+            let outcome = if test_run.reports_results {
+                TestOutcome::Skipped
+            } else if success {
+                TestOutcome::Passed
+            } else {
+                TestOutcome::Failed
+            };
+            statuses.entry(test.abs_path).or_default().push((
+                test.test_path,
+                test.row,
+                TestStatus::Finished {
+                    outcome,
+                    message: None,
+                },
+            ));
+        }
+        self.project.update(cx, |project, cx| {
+            for (abs_path, statuses) in statuses {
+                project.set_test_statuses(abs_path, statuses, cx);
+            }
+        });
+    }
+
+    fn show_error(&self, message: String, cx: &mut ViewContext<Self>) {
+        struct TestExplorerError;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<TestExplorerError>(), message),
+                    cx,
+                )
+            })
+            .ok();
+    }
+
+    fn open_test(&mut self, file_ix: usize, test_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        let Some(test) = file.tests.get(test_ix) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id,
+            path: file.path.clone(),
+        };
+        let position = test.position;
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(project_path, None, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([position..position])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_file(&mut self, file_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        if !self.collapsed_files.remove(&file.abs_path) {
+            self.collapsed_files.insert(file.abs_path.clone());
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if self.visible_entries.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_entry
+            .map_or(0, |ix| (ix + 1).min(self.visible_entries.len() - 1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if self.visible_entries.is_empty() {
+            return;
+        }
+        let ix = self.selected_entry.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_entry = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        match self
+            .selected_entry
+            .and_then(|ix| self.visible_entries.get(ix).copied())
+        {
+            Some(TestEntry::File { file_ix }) => self.run_file(file_ix, cx),
+            Some(TestEntry::Test { file_ix, test_ix }) => self.run_test(file_ix, test_ix, cx),
+            None => {}
+        }
+    }
+
+    fn file_status(&self, file: &TestFile, cx: &AppContext) -> Option<TestStatus> {
+        let project = self.project.read(cx);
+        let statuses = file
+            .tests
+            .iter()
+            .filter_map(|test| project.test_status(&file.abs_path, &test.test_path))
+            .collect::<Vec<_>>();
+        if statuses.contains(&&TestStatus::Running) {
+            return Some(TestStatus::Running);
+        }
+        let outcomes = statuses.iter().filter_map(|status| match status {
+            TestStatus::Running => None,
+            TestStatus::Finished { outcome, .. } => Some(*outcome),
+        });
+        let outcome = outcomes.fold(None, |file_outcome, outcome| {
+            Some(match (file_outcome, outcome) {
+                (Some(TestOutcome::Failed), _) | (_, TestOutcome::Failed) => TestOutcome::Failed,
+                (Some(TestOutcome::Passed), _) | (_, TestOutcome::Passed) => TestOutcome::Passed,
+                _ => TestOutcome::Skipped,
+            })
+        })?;
+        Some(TestStatus::Finished {
+            outcome,
+            message: None,
+        })
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let is_selected = self.selected_entry == Some(ix);
+        match self.visible_entries[ix] {
+            TestEntry::File { file_ix } => {
+                let file = &self.files[file_ix];
+                let is_expanded = !self.collapsed_files.contains(&file.abs_path);
+                ListItem::new(ix)
+                    .selected(is_selected)
+                    .toggle(is_expanded)
+                    .on_toggle(cx.listener(move |panel, _, cx| panel.toggle_file(file_ix, cx)))
+                    .start_slot(status_icon(self.file_status(file, cx).as_ref()))
+                    .child(Label::new(file.path.to_string_lossy().to_string()))
+                    .end_hover_slot(
+                        IconButton::new(("run-file", ix), IconName::Play)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Run Tests in File", cx))
+                            .on_click(cx.listener(move |panel, _, cx| panel.run_file(file_ix, cx))),
+                    )
+                    .on_click(cx.listener(move |panel, _, cx| {
+                        panel.selected_entry = Some(ix);
+                        panel.toggle_file(file_ix, cx);
+                    }))
+            }
+            TestEntry::Test { file_ix, test_ix } => {
+                let file = &self.files[file_ix];
+                let test = &file.tests[test_ix];
+                let status = self
+                    .project
+                    .read(cx)
+                    .test_status(&file.abs_path, &test.test_path)
+                    .cloned();
+                let failure_message = match &status {
+                    Some(TestStatus::Finished {
+                        outcome: TestOutcome::Failed,
+                        message: Some(message),
+                    }) => Some(SharedString::from(message.trim_end().to_string())),
+                    _ => None,
+                };
+                ListItem::new(ix)
+                    .selected(is_selected)
+                    .indent_level(1)
+                    .start_slot(status_icon(status.as_ref()))
+                    .child(Label::new(test.symbol.clone()))
+                    .end_hover_slot(
+                        IconButton::new(("run-test", ix), IconName::Play)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Run Test", cx))
+                            .on_click(cx.listener(move |panel, _, cx| {
+                                panel.run_test(file_ix, test_ix, cx)
+                            })),
+                    )
+                    .when_some(failure_message, |item, message| {
+                        item.tooltip(move |cx| Tooltip::text(message.clone(), cx))
+                    })
+                    .on_click(cx.listener(move |panel, _, cx| {
+                        panel.selected_entry = Some(ix);
+                        panel.open_test(file_ix, test_ix, cx);
+                    }))
+            }
+        }
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new("Tests")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("refresh-tests", IconName::ArrowCircle)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.discovering)
+                            .tooltip(|cx| Tooltip::for_action("Refresh Tests", &RefreshTests, cx))
+                            .on_click(|_, cx| cx.dispatch_action(RefreshTests.boxed_clone())),
+                    )
+                    .child(
+                        IconButton::new("run-all-tests", IconName::Play)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.files.is_empty())
+                            .tooltip(|cx| Tooltip::for_action("Run All Tests", &RunAllTests, cx))
+                            .on_click(|_, cx| cx.dispatch_action(RunAllTests.boxed_clone())),
+                    ),
+            )
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("TestExplorerPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+}
+
+fn find_tests(snapshot: &BufferSnapshot) -> Vec<Test> {
+    snapshot
+        .runnable_ranges(Anchor::MIN..Anchor::MAX)
+        .filter_map(|runnable| {
+            let tags = runnable
+                .runnable
+                .tags
+                .iter()
+                .map(|tag| tag.0.to_string())
+                .collect::<Vec<_>>();
+            if !tags.iter().any(|tag| tag.contains("test")) {
+                return None;
+            }
+            let symbol = snapshot
+                .text_for_range(runnable.run_range.clone())
+                .collect::<String>();
+            let enclosing_items = snapshot
+                .symbols_containing(runnable.run_range.start, None)
+                .unwrap_or_default();
+            Some(Test {
+                test_path: project::test_path(&symbol, &enclosing_items),
+                symbol,
+                tags,
+                position: snapshot.offset_to_point(runnable.run_range.start),
+                extra_captures: runnable.extra_captures,
+            })
+        })
+        .collect()
+}
+
+fn same_command(a: &ResolvedTask, b: &ResolvedTask) -> bool {
+    match (&a.resolved, &b.resolved) {
+        (Some(a), Some(b)) => a.command_label == b.command_label && a.cwd == b.cwd,
+        _ => false,
+    }
+}
+
+fn status_icon(status: Option<&TestStatus>) -> Icon {
+    let (icon, color) = match status {
+        None => (IconName::Dash, Color::Muted),
+        Some(TestStatus::Running) => (IconName::ArrowCircle, Color::Accent),
+        Some(TestStatus::Finished { outcome, .. }) => match outcome {
+            TestOutcome::Passed => (IconName::Check, Color::Success),
+            TestOutcome::Failed => (IconName::XCircle, Color::Error),
+            TestOutcome::Skipped => (IconName::Dash, Color::Warning),
+        },
+    };
+    Icon::new(icon).size(IconSize::Small).color(color)
+}
+
+impl Render for TestExplorerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if !self.visible_entries.is_empty() {
+            uniform_list(
+                cx.view().clone(),
+                "test-explorer-entries",
+                self.visible_entries.len(),
+                |panel, range, cx| range.map(|ix| panel.render_entry(ix, cx)).collect(),
+            )
+            .size_full()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        } else {
+            let message = if self.discovering {
+                "Looking for tests…"
+            } else if self.project.read(cx).is_local() {
+                "No tests found in the project."
+            } else {
+                "Tests are only discovered in local projects."
+            };
+            v_flex()
+                .p_4()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        };
+
+        v_flex()
+            .id("test-explorer")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::refresh_tests))
+            .on_action(cx.listener(Self::run_all_tests))
+            .track_focus(&self.focus_handle)
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+impl EventEmitter<PanelEvent> for TestExplorerPanel {}
+
+impl FocusableView for TestExplorerPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for TestExplorerPanel {
+    fn persistent_name() -> &'static str {
+        "Test Explorer"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match TestExplorerSettings::get_global(cx).dock {
+            TestExplorerDockPosition::Left => DockPosition::Left,
+            TestExplorerDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<TestExplorerSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => TestExplorerDockPosition::Left,
+                    DockPosition::Right => TestExplorerDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TestExplorerSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        TestExplorerSettings::get_global(cx)
+            .button
+            .then(|| IconName::Check)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Test Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
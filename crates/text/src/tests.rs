@@ -604,6 +604,204 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(0..1, "a")]);
+    let (transaction_1, _) = buffer.end_transaction_at(now).unwrap();
+    now += Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(1..2, "b")]);
+    let (transaction_2, _) = buffer.end_transaction_at(now).unwrap();
+    assert_eq!(buffer.text(), "ab3456");
+
+    // Editing after an undo starts a new branch instead of discarding the undone edit.
+    buffer.undo();
+    now += Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(2..3, "c")]);
+    let (transaction_3, _) = buffer.end_transaction_at(now).unwrap();
+    assert_eq!(buffer.text(), "a2c456");
+    assert_eq!(buffer.current_undo_state(), Some(transaction_3));
+    assert!(buffer.peek_redo_stack().is_none());
+    assert_eq!(
+        buffer
+            .undo_tree()
+            .into_iter()
+            .map(|node| (node.transaction_id, node.parent))
+            .collect::<Vec<_>>(),
+        [
+            (transaction_1, None),
+            (transaction_2, Some(transaction_1)),
+            (transaction_3, Some(transaction_1)),
+        ]
+    );
+
+    // Travel across branches.
+    buffer.travel_to_undo_state(Some(transaction_2));
+    assert_eq!(buffer.text(), "ab3456");
+    assert_eq!(buffer.current_undo_state(), Some(transaction_2));
+
+    buffer.travel_to_undo_state(None);
+    assert_eq!(buffer.text(), "123456");
+
+    // Redo follows the most recently created branch.
+    buffer.redo();
+    buffer.redo();
+    assert_eq!(buffer.text(), "a2c456");
+    assert_eq!(buffer.current_undo_state(), Some(transaction_3));
+
+    buffer.travel_to_undo_state(Some(transaction_2));
+    buffer.undo();
+    buffer.redo();
+    assert_eq!(buffer.text(), "ab3456");
+    assert_eq!(buffer.undo_tree().len(), 3);
+}
+
+#[test]
+fn test_undo_tree_after_undoing_transaction() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+
+    let mut transactions = Vec::new();
+    for (ix, text) in ["a", "b", "c"].into_iter().enumerate() {
+        buffer.start_transaction_at(now);
+        buffer.edit([(ix..ix + 1, text)]);
+        transactions.push(buffer.end_transaction_at(now).unwrap().0);
+        now += Duration::from_millis(1);
+    }
+    let [transaction_1, transaction_2, transaction_3] = transactions[..] else {
+        unreachable!()
+    };
+
+    // Undoing a transaction in the middle of the stack keeps the later ones applied, so
+    // they hang off its parent, while redoing it would apply it on top of them.
+    buffer.undo_transaction(transaction_2);
+    assert_eq!(buffer.text(), "a2c456");
+    assert_eq!(
+        buffer
+            .undo_tree()
+            .into_iter()
+            .map(|node| (node.transaction_id, node.parent))
+            .collect::<Vec<_>>(),
+        [
+            (transaction_1, None),
+            (transaction_2, Some(transaction_3)),
+            (transaction_3, Some(transaction_1)),
+        ]
+    );
+
+    buffer.travel_to_undo_state(Some(transaction_1));
+    assert_eq!(buffer.text(), "a23456");
+    buffer.travel_to_undo_state(Some(transaction_3));
+    assert_eq!(buffer.text(), "a2c456");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abc456");
+    assert_eq!(buffer.current_undo_state(), Some(transaction_2));
+}
+
+#[test]
+fn test_undo_tree_abandoned_limit() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(0..1, "a")]);
+    let (root, _) = buffer.end_transaction_at(now).unwrap();
+
+    // Each undo followed by an edit abandons one more branch off the same state.
+    let mut branches = Vec::new();
+    for _ in 0..MAX_ABANDONED_TRANSACTIONS + 2 {
+        now += Duration::from_millis(1);
+        buffer.start_transaction_at(now);
+        buffer.edit([(1..2, "b")]);
+        branches.push(buffer.end_transaction_at(now).unwrap().0);
+        buffer.undo();
+    }
+    now += Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(1..2, "c")]);
+    buffer.end_transaction_at(now).unwrap();
+
+    // The least recently edited branches are dropped first.
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.len(), MAX_ABANDONED_TRANSACTIONS + 2);
+    assert!(tree.iter().any(|node| node.transaction_id == root));
+    assert!(!tree.iter().any(|node| node.transaction_id == branches[1]));
+    assert!(tree
+        .iter()
+        .any(|node| node.transaction_id == branches[2] && node.parent == Some(root)));
+    buffer.travel_to_undo_state(Some(*branches.last().unwrap()));
+    assert_eq!(buffer.text(), "ab3456");
+}
+
+#[test]
+fn test_restore_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(0..1, "a")]);
+    let (transaction_1, _) = buffer.end_transaction_at(now).unwrap();
+    now += Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(1..2, "b")]);
+    let (transaction_2, _) = buffer.end_transaction_at(now).unwrap();
+    buffer.undo();
+    now += Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(2..3, "c")]);
+    let (transaction_3, _) = buffer.end_transaction_at(now).unwrap();
+    buffer.undo();
+    assert_eq!(buffer.text(), "a23456");
+
+    let restore = |history: SavedHistory| {
+        let mut restored = Buffer::new_normalized(
+            0,
+            BufferId::new(2).unwrap(),
+            buffer.line_ending(),
+            buffer.base_text().clone(),
+        );
+        restored
+            .apply_ops(buffer.operations().values().cloned())
+            .unwrap();
+        restored.restore_history(history).map(|_| restored)
+    };
+
+    let mut restored = restore(buffer.saved_history()).unwrap();
+    assert_eq!(restored.text(), "a23456");
+    let tree_shape = |buffer: &Buffer| {
+        buffer
+            .undo_tree()
+            .into_iter()
+            .map(|node| (node.transaction_id, node.parent))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(tree_shape(&restored), tree_shape(&buffer));
+    assert_eq!(restored.current_undo_state(), Some(transaction_1));
+
+    restored.redo();
+    assert_eq!(restored.text(), "a2c456");
+    restored.travel_to_undo_state(Some(transaction_2));
+    assert_eq!(restored.text(), "ab3456");
+
+    // New transactions never reuse the ids of restored ones.
+    restored.start_transaction();
+    restored.edit([(3..4, "d")]);
+    let (transaction_4, _) = restored.end_transaction().unwrap();
+    assert!(transaction_4 > transaction_3);
+    restored.undo();
+    assert_eq!(restored.text(), "ab3456");
+
+    // A history whose undone transactions don't match the operations is rejected.
+    let mut history = buffer.saved_history();
+    std::mem::swap(&mut history.undo_stack, &mut history.redo_stack);
+    assert!(restore(history).is_err());
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -33,7 +33,7 @@ use std::{
     ops::{self, Deref, Range, Sub},
     str,
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 pub use subscription::*;
 pub use sum_tree::Bias;
@@ -50,6 +50,10 @@ lazy_static! {
 
 pub type TransactionId = clock::Lamport;
 
+/// The number of abandoned undo tree branches' transactions kept per buffer, matching
+/// vim's default `undolevels`. The least recently edited leaves are dropped first.
+const MAX_ABANDONED_TRANSACTIONS: usize = 1000;
+
 pub struct Buffer {
     snapshot: BufferSnapshot,
     history: History,
@@ -117,6 +121,7 @@ pub struct HistoryEntry {
     first_edit_at: Instant,
     last_edit_at: Instant,
     suppress_grouping: bool,
+    parent: Option<TransactionId>,
 }
 
 #[derive(Clone, Debug)]
@@ -132,12 +137,41 @@ impl HistoryEntry {
     }
 }
 
+/// A state in a buffer's undo tree, identified by the transaction that produced it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The state this transaction was applied on top of, or `None` for the buffer's base text.
+    pub parent: Option<TransactionId>,
+    pub edited_at: Instant,
+}
+
+/// A buffer's undo tree in a form that outlives the buffer. Restoring it requires a buffer
+/// built from the same base text and operations, see [`Buffer::restore_history`].
+#[derive(Clone, Debug, Default)]
+pub struct SavedHistory {
+    pub undo_stack: Vec<SavedHistoryEntry>,
+    pub redo_stack: Vec<SavedHistoryEntry>,
+    pub abandoned: Vec<SavedHistoryEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SavedHistoryEntry {
+    pub transaction: Transaction,
+    pub parent: Option<TransactionId>,
+    pub first_edit_at: SystemTime,
+    pub last_edit_at: SystemTime,
+}
+
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     insertion_slices: HashMap<clock::Lamport, Vec<InsertionSlice>>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Undone transactions that are no longer reachable via redo, because a new
+    /// transaction was started on top of one of their ancestors.
+    abandoned: Vec<HistoryEntry>,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -156,6 +190,7 @@ impl History {
             insertion_slices: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            abandoned: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -178,6 +213,7 @@ impl History {
         self.transaction_depth += 1;
         if self.transaction_depth == 1 {
             let id = clock.tick();
+            let parent = self.undo_stack.last().map(|entry| entry.transaction.id);
             self.undo_stack.push(HistoryEntry {
                 transaction: Transaction {
                     id,
@@ -187,6 +223,7 @@ impl History {
                 first_edit_at: now,
                 last_edit_at: now,
                 suppress_grouping: false,
+                parent,
             });
             Some(id)
         } else {
@@ -209,7 +246,8 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                let parent = self.undo_stack.last().unwrap().parent;
+                self.abandon_redo_stack(parent);
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -259,6 +297,11 @@ impl History {
                 for edit_id in &entry.transaction.edit_ids {
                     last_entry.transaction.edit_ids.push(*edit_id);
                 }
+                for abandoned in &mut self.abandoned {
+                    if abandoned.parent == Some(entry.transaction.id) {
+                        abandoned.parent = Some(last_entry.transaction.id);
+                    }
+                }
             }
 
             if let Some(entry) = entries_to_merge.last_mut() {
@@ -279,13 +322,68 @@ impl History {
 
     fn push_transaction(&mut self, transaction: Transaction, now: Instant) {
         assert_eq!(self.transaction_depth, 0);
+        let parent = self.undo_stack.last().map(|entry| entry.transaction.id);
+        self.abandon_redo_stack(parent);
         self.undo_stack.push(HistoryEntry {
             transaction,
             first_edit_at: now,
             last_edit_at: now,
             suppress_grouping: false,
+            parent,
         });
-        self.redo_stack.clear();
+    }
+
+    /// Keeps the undone transactions around as a branch of the undo tree instead of
+    /// discarding them. The state being branched from must never be grouped with the
+    /// new transaction, or the abandoned branch would lose its parent.
+    fn abandon_redo_stack(&mut self, branch_point: Option<TransactionId>) {
+        if self.redo_stack.is_empty() {
+            return;
+        }
+
+        if let Some(entry) = self
+            .undo_stack
+            .iter_mut()
+            .rfind(|entry| Some(entry.transaction.id) == branch_point)
+        {
+            entry.suppress_grouping = true;
+        }
+        self.abandoned.append(&mut self.redo_stack);
+        self.prune_abandoned();
+    }
+
+    fn prune_abandoned(&mut self) {
+        while self.abandoned.len() > MAX_ABANDONED_TRANSACTIONS {
+            let parents = self
+                .abandoned
+                .iter()
+                .filter_map(|entry| entry.parent)
+                .collect::<HashSet<_>>();
+            let Some(leaf_ix) = self
+                .abandoned
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| !parents.contains(&entry.transaction.id))
+                .min_by_key(|(_, entry)| entry.last_edit_at)
+                .map(|(ix, _)| ix)
+            else {
+                break;
+            };
+            self.abandoned.remove(leaf_ix);
+        }
+    }
+
+    fn reparent_children(&mut self, transaction_id: TransactionId, parent: Option<TransactionId>) {
+        for child in self
+            .undo_stack
+            .iter_mut()
+            .chain(&mut self.redo_stack)
+            .chain(&mut self.abandoned)
+        {
+            if child.parent == Some(transaction_id) {
+                child.parent = parent;
+            }
+        }
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -313,7 +411,18 @@ impl History {
             .undo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)?;
-        let entry = self.undo_stack.remove(entry_ix);
+        let mut entry = self.undo_stack.remove(entry_ix);
+
+        // The transactions applied after this one stay applied, so they now sit on top of
+        // its parent, and redoing it will apply it on top of the current state.
+        if let Some(child) = self.undo_stack.get_mut(entry_ix) {
+            child.parent = entry.parent;
+        }
+        entry.parent = self.undo_stack.last().map(|entry| entry.transaction.id);
+        if let Some(next_redo) = self.redo_stack.last_mut() {
+            next_redo.parent = Some(entry.transaction.id);
+        }
+
         self.redo_stack.push(entry);
         self.redo_stack.last()
     }
@@ -335,23 +444,140 @@ impl History {
 
     fn forget(&mut self, transaction_id: TransactionId) -> Option<Transaction> {
         assert_eq!(self.transaction_depth, 0);
-        if let Some(entry_ix) = self
+        let entry = if let Some(entry_ix) = self
             .undo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
-            Some(self.undo_stack.remove(entry_ix).transaction)
+            self.undo_stack.remove(entry_ix)
         } else if let Some(entry_ix) = self
             .redo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
-            Some(self.redo_stack.remove(entry_ix).transaction)
+            self.redo_stack.remove(entry_ix)
+        } else if let Some(entry_ix) = self
+            .abandoned
+            .iter()
+            .position(|entry| entry.transaction.id == transaction_id)
+        {
+            self.abandoned.remove(entry_ix)
         } else {
-            None
+            return None;
+        };
+
+        self.reparent_children(transaction_id, entry.parent);
+        Some(entry.transaction)
+    }
+
+    fn entry(&self, transaction_id: TransactionId) -> Option<&HistoryEntry> {
+        self.undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .chain(&self.abandoned)
+            .find(|entry| entry.transaction.id == transaction_id)
+    }
+
+    fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        let mut nodes = self
+            .undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .chain(&self.abandoned)
+            .map(|entry| UndoTreeNode {
+                transaction_id: entry.transaction.id,
+                parent: entry.parent,
+                edited_at: entry.last_edit_at,
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.transaction_id);
+        nodes
+    }
+
+    fn save(&self) -> SavedHistory {
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let save_entries = |entries: &[HistoryEntry]| {
+            entries
+                .iter()
+                .map(|entry| SavedHistoryEntry {
+                    transaction: entry.transaction.clone(),
+                    parent: entry.parent,
+                    first_edit_at: system_time_at(entry.first_edit_at, now, system_now),
+                    last_edit_at: system_time_at(entry.last_edit_at, now, system_now),
+                })
+                .collect::<Vec<_>>()
+        };
+        SavedHistory {
+            undo_stack: save_entries(&self.undo_stack),
+            redo_stack: save_entries(&self.redo_stack),
+            abandoned: save_entries(&self.abandoned),
         }
     }
 
+    /// Rearranges the stacks so that `target` is on top of the undo stack, returning the
+    /// transactions that must be undone and then redone to get there, in order.
+    fn travel_to(
+        &mut self,
+        target: Option<TransactionId>,
+    ) -> Option<(Vec<Transaction>, Vec<Transaction>)> {
+        assert_eq!(self.transaction_depth, 0);
+
+        let mut path = Vec::new();
+        let mut ancestor = target;
+        while let Some(transaction_id) = ancestor {
+            if self
+                .undo_stack
+                .iter()
+                .any(|entry| entry.transaction.id == transaction_id)
+            {
+                break;
+            }
+            path.push(transaction_id);
+            ancestor = self.entry(transaction_id)?.parent;
+        }
+
+        let mut to_undo = Vec::new();
+        while self.undo_stack.last().map(|entry| entry.transaction.id) != ancestor {
+            let entry = self.undo_stack.pop().unwrap();
+            to_undo.push(entry.transaction.clone());
+            self.abandoned.push(entry);
+        }
+        self.abandoned.append(&mut self.redo_stack);
+
+        let mut to_redo = Vec::new();
+        for transaction_id in path.into_iter().rev() {
+            let entry_ix = self
+                .abandoned
+                .iter()
+                .position(|entry| entry.transaction.id == transaction_id)?;
+            let entry = self.abandoned.remove(entry_ix);
+            to_redo.push(entry.transaction.clone());
+            self.undo_stack.push(entry);
+        }
+
+        // Keep redo working linearly from the new state by following the most recent branch.
+        let mut parent = target;
+        let mut redo_stack = Vec::new();
+        while let Some(entry_ix) = self
+            .abandoned
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.parent == parent)
+            .max_by_key(|(_, entry)| entry.transaction.id)
+            .map(|(ix, _)| ix)
+        {
+            let entry = self.abandoned.remove(entry_ix);
+            parent = Some(entry.transaction.id);
+            redo_stack.push(entry);
+        }
+        redo_stack.reverse();
+        self.redo_stack = redo_stack;
+        self.prune_abandoned();
+
+        Some((to_undo, to_redo))
+    }
+
     fn transaction_mut(&mut self, transaction_id: TransactionId) -> Option<&mut Transaction> {
         let entry = self
             .undo_stack
@@ -361,6 +587,11 @@ impl History {
                 self.redo_stack
                     .iter_mut()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.abandoned
+                    .iter_mut()
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&mut entry.transaction)
     }
@@ -399,6 +630,17 @@ impl History {
     }
 }
 
+fn system_time_at(instant: Instant, now: Instant, system_now: SystemTime) -> SystemTime {
+    system_now
+        .checked_sub(now.saturating_duration_since(instant))
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn instant_at(time: SystemTime, now: Instant, system_now: SystemTime) -> Instant {
+    now.checked_sub(system_now.duration_since(time).unwrap_or_default())
+        .unwrap_or(now)
+}
+
 struct Edits<'a, D: TextDimension, F: FnMut(&FragmentSummary) -> bool> {
     visible_cursor: rope::Cursor<'a>,
     deleted_cursor: rope::Cursor<'a>,
@@ -1177,6 +1419,100 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// Returns every state in the undo tree, including undone branches, in the order
+    /// they were created.
+    pub fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        self.history.undo_tree()
+    }
+
+    /// Returns the transaction that produced the current state of the undo tree, or
+    /// `None` if every transaction has been undone.
+    pub fn current_undo_state(&self) -> Option<TransactionId> {
+        self.history
+            .undo_stack
+            .last()
+            .map(|entry| entry.transaction.id)
+    }
+
+    /// Returns the undo tree, to be saved along with the buffer's base text and operations.
+    pub fn saved_history(&self) -> SavedHistory {
+        self.history.save()
+    }
+
+    /// Replaces the undo tree with one returned by [`Buffer::saved_history`] on a buffer that
+    /// had the same base text and operations as this one.
+    pub fn restore_history(&mut self, history: SavedHistory) -> Result<()> {
+        if self.history.transaction_depth > 0 {
+            return Err(anyhow!("can't restore the history during a transaction"));
+        }
+
+        let mut transaction_ids = HashSet::default();
+        for entry in history
+            .undo_stack
+            .iter()
+            .chain(&history.redo_stack)
+            .chain(&history.abandoned)
+        {
+            if !transaction_ids.insert(entry.transaction.id) {
+                return Err(anyhow!("duplicate transaction {:?}", entry.transaction.id));
+            }
+        }
+
+        let stacks = [
+            (&history.undo_stack, false),
+            (&history.redo_stack, true),
+            (&history.abandoned, true),
+        ];
+        for (entries, undone) in stacks {
+            for entry in entries {
+                if let Some(parent) = entry.parent {
+                    if !transaction_ids.contains(&parent) {
+                        return Err(anyhow!("unknown parent transaction {:?}", parent));
+                    }
+                }
+                for edit_id in &entry.transaction.edit_ids {
+                    if !matches!(
+                        self.history.operations.get(edit_id),
+                        Some(Operation::Edit(_))
+                    ) {
+                        return Err(anyhow!("unknown edit {:?}", edit_id));
+                    }
+                    if self.undo_map.is_undone(*edit_id) != undone {
+                        return Err(anyhow!(
+                            "edit {:?} doesn't match the state of its transaction",
+                            edit_id
+                        ));
+                    }
+                }
+            }
+        }
+
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let mut restore_entries = |entries: Vec<SavedHistoryEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| {
+                    self.lamport_clock.observe(entry.transaction.id);
+                    HistoryEntry {
+                        transaction: entry.transaction,
+                        first_edit_at: instant_at(entry.first_edit_at, now, system_now),
+                        last_edit_at: instant_at(entry.last_edit_at, now, system_now),
+                        suppress_grouping: true,
+                        parent: entry.parent,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let undo_stack = restore_entries(history.undo_stack);
+        let redo_stack = restore_entries(history.redo_stack);
+        let abandoned = restore_entries(history.abandoned);
+        self.history.undo_stack = undo_stack;
+        self.history.redo_stack = redo_stack;
+        self.history.abandoned = abandoned;
+        Ok(())
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -1269,6 +1605,21 @@ impl Buffer {
         }
     }
 
+    /// Undoes and redoes transactions until the buffer is in the state produced by
+    /// `target`, which may live on a different branch of the undo tree. Passing `None`
+    /// travels back to the state before any transaction.
+    pub fn travel_to_undo_state(&mut self, target: Option<TransactionId>) -> Vec<Operation> {
+        let Some((to_undo, to_redo)) = self.history.travel_to(target) else {
+            return Vec::new();
+        };
+
+        to_undo
+            .into_iter()
+            .chain(to_redo)
+            .map(|transaction| self.undo_or_redo(transaction).unwrap())
+            .collect()
+    }
+
     pub fn redo_to_transaction(&mut self, transaction_id: TransactionId) -> Vec<Operation> {
         let transactions = self
             .history
@@ -108,6 +108,14 @@ pub enum IconName {
     Copy,
     CountdownTimer,
     Dash,
+    Debug,
+    DebugBreakpoint,
+    DebugContinue,
+    DebugPause,
+    DebugStepInto,
+    DebugStepOut,
+    DebugStepOver,
+    DebugStop,
     Delete,
     Disconnected,
     Ellipsis,
@@ -224,6 +232,14 @@ impl IconName {
             IconName::Copy => "icons/copy.svg",
             IconName::CountdownTimer => "icons/countdown_timer.svg",
             IconName::Dash => "icons/dash.svg",
+            IconName::Debug => "icons/debug.svg",
+            IconName::DebugBreakpoint => "icons/debug_breakpoint.svg",
+            IconName::DebugContinue => "icons/debug_continue.svg",
+            IconName::DebugPause => "icons/debug_pause.svg",
+            IconName::DebugStepInto => "icons/debug_step_into.svg",
+            IconName::DebugStepOut => "icons/debug_step_out.svg",
+            IconName::DebugStepOver => "icons/debug_step_over.svg",
+            IconName::DebugStop => "icons/debug_stop.svg",
             IconName::Delete => "icons/delete.svg",
             IconName::Disconnected => "icons/disconnected.svg",
             IconName::Ellipsis => "icons/ellipsis.svg",
@@ -59,7 +59,9 @@ lazy_static::lazy_static! {
     pub static ref COPILOT_DIR: PathBuf = SUPPORT_DIR.join("copilot");
     pub static ref SUPERMAVEN_DIR: PathBuf = SUPPORT_DIR.join("supermaven");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = SUPPORT_DIR.join("prettier");
+    pub static ref SHELL_INTEGRATION_DIR: PathBuf = SUPPORT_DIR.join("shell_integration");
     pub static ref DB_DIR: PathBuf = SUPPORT_DIR.join("db");
+    pub static ref UNDO_DIR: PathBuf = SUPPORT_DIR.join("undo");
     pub static ref CRASHES_DIR: Option<PathBuf> = cfg!(target_os = "macos")
         .then_some(HOME.join("Library/Logs/DiagnosticReports"));
     pub static ref CRASHES_RETIRED_DIR: Option<PathBuf> = CRASHES_DIR
@@ -68,6 +70,7 @@ lazy_static::lazy_static! {
 
     pub static ref SETTINGS: PathBuf = CONFIG_DIR.join("settings.json");
     pub static ref KEYMAP: PathBuf = CONFIG_DIR.join("keymap.json");
+    pub static ref VIMRC: PathBuf = CONFIG_DIR.join("vimrc");
     pub static ref TASKS: PathBuf = CONFIG_DIR.join("tasks.json");
     pub static ref LAST_USERNAME: PathBuf = CONFIG_DIR.join("last-username.txt");
     pub static ref LOG: PathBuf = LOGS_DIR.join("Zed.log");
@@ -75,6 +78,7 @@ lazy_static::lazy_static! {
     pub static ref LOCAL_SETTINGS_RELATIVE_PATH: &'static Path = Path::new(".zed/settings.json");
     pub static ref LOCAL_TASKS_RELATIVE_PATH: &'static Path = Path::new(".zed/tasks.json");
     pub static ref LOCAL_VSCODE_TASKS_RELATIVE_PATH: &'static Path = Path::new(".vscode/tasks.json");
+    pub static ref LOCAL_VSCODE_LAUNCH_RELATIVE_PATH: &'static Path = Path::new(".vscode/launch.json");
     pub static ref TEMP_DIR: PathBuf = if cfg!(target_os = "windows") {
         dirs::cache_dir()
             .expect("failed to determine LocalAppData directory")
@@ -12,6 +12,7 @@ use crate::{
         JoinLines,
     },
     state::Mode,
+    undo_tree::{Earlier, Later, ToggleUndoTree, UndoDistance},
     Vim,
 };
 
@@ -315,6 +316,11 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
             cx.build_action("assistant::ToggleFocus", None).unwrap(),
         ),
 
+        // undo tree
+        "undot" | "undotr" | "undotre" | "undotree" | "UndotreeToggle" => {
+            ("undotree", ToggleUndoTree.boxed_clone())
+        }
+
         // goto (other ranges handled under _ => )
         "$" => ("$", EndOfDocument.boxed_clone()),
         "%" => ("%", EndOfDocument.boxed_clone()),
@@ -340,6 +346,8 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
                 )
             } else if let Ok(line) = query.parse::<u32>() {
                 (query, GoToLine { line }.boxed_clone())
+            } else if let Some(action) = undo_tree_command(query) {
+                (query, action)
             } else if range_regex().is_match(query) {
                 (
                     query,
@@ -364,6 +372,21 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
     })
 }
 
+fn undo_tree_command(query: &str) -> Option<Box<dyn Action>> {
+    let name_len = query
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(query.len());
+    let (name, argument) = query.split_at(name_len);
+    let distance = UndoDistance::parse(argument)?;
+    if name.len() >= 2 && "earlier".starts_with(name) {
+        Some(Earlier { distance }.boxed_clone())
+    } else if name.len() >= 3 && "later".starts_with(name) {
+        Some(Later { distance }.boxed_clone())
+    } else {
+        None
+    }
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
@@ -0,0 +1,580 @@
+use std::mem;
+
+use editor::{display_map::DisplaySnapshot, movement, scroll::Autoscroll, Bias, DisplayPoint};
+use gpui::{actions, impl_actions, ViewContext, WindowContext};
+use language::{char_kind, Point, Selection, SelectionGoal};
+use multi_buffer::ToOffset;
+use regex::Regex;
+use search::{buffer_search, BufferSearchBar, SearchOptions};
+use serde::Deserialize;
+use workspace::{searchable::Direction, Workspace};
+
+use crate::{
+    motion::Motion,
+    normal::paste::register_contents,
+    state::{Mode, Register, SearchState},
+    utils::{coerce_punctuation, yank_selections_content},
+    Vim,
+};
+
+/// What `s` and `S` do with the regex matches inside the current selections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HelixRegexOperation {
+    /// Select each match.
+    Select,
+    /// Select the text between matches.
+    Split,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct HelixPaste {
+    #[serde(default)]
+    before: bool,
+}
+
+actions!(
+    vim,
+    [
+        HelixSelectLine,
+        HelixInsert,
+        HelixAppend,
+        HelixYank,
+        HelixSelectRegex,
+        HelixSplitRegex,
+        HelixCollapseSelection,
+        HelixFlipSelections,
+        HelixKeepPrimarySelection,
+        ToggleHelixSelect,
+    ]
+);
+
+impl_actions!(vim, [HelixPaste]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(select_line);
+    workspace.register_action(|_, _: &HelixInsert, cx| {
+        insert_at_selections(cx, |selection| selection.start)
+    });
+    workspace.register_action(|_, _: &HelixAppend, cx| {
+        insert_at_selections(cx, |selection| selection.end)
+    });
+    workspace.register_action(|_, _: &HelixYank, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                yank_selections_content(vim, editor, false, cx)
+            });
+        })
+    });
+    workspace.register_action(paste);
+    workspace.register_action(|workspace, _: &HelixSelectRegex, cx| {
+        deploy_regex_prompt(workspace, HelixRegexOperation::Select, cx)
+    });
+    workspace.register_action(|workspace, _: &HelixSplitRegex, cx| {
+        deploy_regex_prompt(workspace, HelixRegexOperation::Split, cx)
+    });
+    workspace.register_action(collapse_selections);
+    workspace.register_action(|_, _: &HelixFlipSelections, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.move_with(|_, selection| selection.reversed = !selection.reversed)
+                })
+            });
+        })
+    });
+    workspace.register_action(|_, _: &HelixKeepPrimarySelection, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    let newest = s.newest_anchor().clone();
+                    s.select_anchors(vec![newest]);
+                })
+            });
+        })
+    });
+    workspace.register_action(|_, _: &ToggleHelixSelect, cx| {
+        Vim::update(cx, |vim, cx| {
+            let mode = if vim.state().mode == Mode::HelixSelect {
+                Mode::HelixNormal
+            } else {
+                Mode::HelixSelect
+            };
+            vim.switch_mode(mode, true, cx);
+        })
+    });
+}
+
+/// Expands empty selections to cover the character after them, as helix
+/// never has a selection without a character in it.
+pub(crate) fn select_cursor_characters(vim: &mut Vim, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        if editor
+            .selections
+            .all_adjusted(cx)
+            .iter()
+            .all(|s| !s.is_empty())
+        {
+            return;
+        }
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                if selection.is_empty() {
+                    selection.end = movement::right(map, selection.start);
+                    selection.reversed = false;
+                }
+            })
+        })
+    });
+}
+
+/// The character the cursor is on: the last one in a forward selection, or the
+/// first one in a backward selection.
+fn cursor_character(map: &DisplaySnapshot, selection: &Selection<DisplayPoint>) -> DisplayPoint {
+    if !selection.reversed && !selection.is_empty() {
+        movement::left(map, selection.end)
+    } else {
+        selection.head()
+    }
+}
+
+fn at_word_boundary(map: &DisplaySnapshot, point: DisplayPoint, ignore_punctuation: bool) -> bool {
+    let offset = point.to_offset(map, Bias::Left);
+    let scope = map.buffer_snapshot.language_scope_at(offset);
+    let kind = |c| coerce_punctuation(char_kind(&scope, c), ignore_punctuation);
+    match (
+        map.reverse_buffer_chars_at(offset).next(),
+        map.buffer_chars_at(offset).next(),
+    ) {
+        (Some((left, _)), Some((right, _))) => kind(left) != kind(right),
+        _ => true,
+    }
+}
+
+/// In helix's normal mode, word and find motions select the text they move
+/// over, while every other motion just moves the one-character selection.
+pub(crate) fn helix_normal_motion(motion: Motion, times: Option<usize>, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = cursor_character(map, selection);
+                    // a word motion that starts at the edge of a word selects the
+                    // next word rather than the gap between the two.
+                    let start = match motion {
+                        Motion::NextWordStart { ignore_punctuation }
+                        | Motion::NextWordEnd { ignore_punctuation }
+                        | Motion::NextSubwordStart { ignore_punctuation }
+                        | Motion::NextSubwordEnd { ignore_punctuation }
+                            if at_word_boundary(
+                                map,
+                                movement::right(map, cursor),
+                                ignore_punctuation,
+                            ) =>
+                        {
+                            movement::right(map, cursor)
+                        }
+                        Motion::PreviousWordStart { ignore_punctuation }
+                        | Motion::PreviousSubwordStart { ignore_punctuation }
+                            if at_word_boundary(map, cursor, ignore_punctuation) =>
+                        {
+                            movement::left(map, cursor)
+                        }
+                        _ => cursor,
+                    };
+
+                    let Some((new_head, goal)) =
+                        motion.move_point(map, start, selection.goal, times, &text_layout_details)
+                    else {
+                        return;
+                    };
+
+                    let selects = matches!(
+                        motion,
+                        Motion::NextWordStart { .. }
+                            | Motion::NextWordEnd { .. }
+                            | Motion::PreviousWordStart { .. }
+                            | Motion::PreviousWordEnd { .. }
+                            | Motion::NextSubwordStart { .. }
+                            | Motion::NextSubwordEnd { .. }
+                            | Motion::PreviousSubwordStart { .. }
+                            | Motion::PreviousSubwordEnd { .. }
+                            | Motion::FindForward { .. }
+                            | Motion::FindBackward { .. }
+                            | Motion::RepeatFind { .. }
+                            | Motion::RepeatFindReversed { .. }
+                    );
+
+                    if !selects {
+                        selection.start = new_head;
+                        selection.end = movement::right(map, new_head);
+                        selection.reversed = false;
+                    } else if new_head >= start {
+                        let end = if motion.inclusive() {
+                            movement::right(map, new_head)
+                        } else {
+                            new_head
+                        };
+                        selection.start = start;
+                        selection.end = end.max(movement::right(map, start));
+                        selection.reversed = false;
+                    } else {
+                        selection.start = new_head;
+                        selection.end = movement::right(map, start);
+                        selection.reversed = true;
+                    }
+                    selection.goal = goal;
+                })
+            });
+        });
+    });
+}
+
+fn select_line(_: &mut Workspace, _: &HelixSelectLine, cx: &mut ViewContext<Workspace>) {
+    Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1) as u32;
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let max_point = editor.buffer().read(cx).snapshot(cx).max_point();
+            let selections = editor.selections.all::<Point>(cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(
+                    selections
+                        .into_iter()
+                        .map(|mut selection| {
+                            let already_full = selection.start.column == 0
+                                && selection.end.column == 0
+                                && selection.end.row > selection.start.row;
+                            let last_row = if selection.end.column == 0
+                                && selection.end.row > selection.start.row
+                            {
+                                selection.end.row - 1
+                            } else {
+                                selection.end.row
+                            };
+                            // pressing x on whole lines adds the next line.
+                            let extra_rows = if already_full { count } else { count - 1 };
+                            let end_row = last_row + extra_rows + 1;
+
+                            selection.start = Point::new(selection.start.row, 0);
+                            selection.end = if end_row > max_point.row {
+                                max_point
+                            } else {
+                                Point::new(end_row, 0)
+                            };
+                            selection.reversed = false;
+                            selection.goal = SelectionGoal::None;
+                            selection
+                        })
+                        .collect(),
+                )
+            });
+        });
+    });
+}
+
+fn insert_at_selections(
+    cx: &mut ViewContext<Workspace>,
+    position: impl Fn(&Selection<DisplayPoint>) -> DisplayPoint,
+) {
+    Vim::update(cx, |vim, cx| {
+        vim.start_recording(cx);
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|_, selection| {
+                    selection.collapse_to(position(selection), SelectionGoal::None)
+                })
+            });
+        });
+        vim.switch_mode(Mode::Insert, true, cx);
+    });
+}
+
+fn paste(_: &mut Workspace, action: &HelixPaste, cx: &mut ViewContext<Workspace>) {
+    Vim::update(cx, |vim, cx| {
+        vim.record_current_action(cx);
+        let count = vim.take_count(cx).unwrap_or(1);
+        vim.update_active_editor(cx, |vim, editor, cx| {
+            let Register {
+                text,
+                clipboard_selections,
+            } = register_contents(vim, cx);
+            if text.is_empty() {
+                return;
+            }
+
+            let selections = editor.selections.all::<usize>(cx);
+            // text copied from as many selections as there are now is pasted one per selection.
+            let texts: Vec<String> = match clipboard_selections {
+                Some(clipboard_selections) if clipboard_selections.len() == selections.len() => {
+                    let mut start_offset = 0;
+                    clipboard_selections
+                        .iter()
+                        .map(|clipboard_selection| {
+                            let end_offset = start_offset + clipboard_selection.len;
+                            let text = text[start_offset..end_offset].repeat(count);
+                            start_offset = end_offset + 1;
+                            text
+                        })
+                        .collect()
+                }
+                _ => vec![text.repeat(count); selections.len()],
+            };
+
+            let edits: Vec<_> = selections
+                .iter()
+                .zip(texts)
+                .map(|(selection, text)| {
+                    let position = if action.before {
+                        selection.start
+                    } else {
+                        selection.end
+                    };
+                    (position..position, text)
+                })
+                .collect();
+
+            let mut inserted = 0;
+            let new_ranges: Vec<_> = edits
+                .iter()
+                .map(|(range, text)| {
+                    let start = range.start + inserted;
+                    inserted += text.len();
+                    start..start + text.len()
+                })
+                .collect();
+
+            editor.transact(cx, |editor, cx| {
+                editor.edit(edits, cx);
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.select_ranges(new_ranges)
+                });
+            });
+        });
+    });
+}
+
+fn collapse_selections(
+    _: &mut Workspace,
+    _: &HelixCollapseSelection,
+    cx: &mut ViewContext<Workspace>,
+) {
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = cursor_character(map, selection);
+                    selection.start = cursor;
+                    selection.end = movement::right(map, cursor);
+                    selection.reversed = false;
+                })
+            })
+        });
+    });
+}
+
+fn deploy_regex_prompt(
+    workspace: &mut Workspace,
+    operation: HelixRegexOperation,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let pane = workspace.active_pane().clone();
+    Vim::update(cx, |vim, cx| {
+        let prior_selections = vim.editor_selections(cx);
+        pane.update(cx, |pane, cx| {
+            if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
+                search_bar.update(cx, |search_bar, cx| {
+                    if !search_bar.show(cx) {
+                        return;
+                    }
+                    search_bar.select_query(cx);
+                    cx.focus_self();
+                    search_bar.set_replacement(None, cx);
+                    search_bar.set_search_options(SearchOptions::REGEX, cx);
+
+                    vim.workspace_state.search = SearchState {
+                        direction: Direction::Next,
+                        count: 1,
+                        initial_query: search_bar.query(cx),
+                        helix_regex: Some(operation),
+                        prior_selections,
+                        prior_operator: None,
+                        prior_mode: vim.state().mode,
+                    };
+                });
+            }
+        });
+    });
+}
+
+/// Called when the search bar opened by `s` or `S` is submitted: replaces the
+/// selections with the matches of the query inside them (or the gaps between
+/// the matches), keeping the selections as they were if there are none.
+pub(crate) fn regex_prompt_submit(
+    workspace: &mut Workspace,
+    operation: HelixRegexOperation,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let mut query = None;
+    workspace.active_pane().update(cx, |pane, cx| {
+        if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
+            search_bar.update(cx, |search_bar, cx| {
+                query = Some(search_bar.query(cx));
+                search_bar.dismiss(&buffer_search::Dismiss, cx);
+            });
+        }
+    });
+
+    Vim::update(cx, |vim, cx| {
+        let prior_selections = mem::take(&mut vim.workspace_state.search.prior_selections);
+        vim.workspace_state.search.helix_regex = None;
+        let regex = query.and_then(|query| Regex::new(&query).ok());
+
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let prior_ranges: Vec<_> = prior_selections
+                .iter()
+                .filter(|range| range.start.is_valid(&buffer) && range.end.is_valid(&buffer))
+                .map(|range| range.start.to_offset(&buffer)..range.end.to_offset(&buffer))
+                .collect();
+
+            let mut ranges = Vec::new();
+            if let Some(regex) = regex {
+                for range in &prior_ranges {
+                    let text = buffer.text_for_range(range.clone()).collect::<String>();
+                    match operation {
+                        HelixRegexOperation::Select => {
+                            for found in regex.find_iter(&text) {
+                                if !found.is_empty() {
+                                    ranges.push(
+                                        range.start + found.start()..range.start + found.end(),
+                                    );
+                                }
+                            }
+                        }
+                        HelixRegexOperation::Split => {
+                            let mut start = 0;
+                            for found in regex.find_iter(&text) {
+                                if found.start() > start {
+                                    ranges.push(range.start + start..range.start + found.start());
+                                }
+                                start = found.end();
+                            }
+                            if start < text.len() {
+                                ranges.push(range.start + start..range.end);
+                            }
+                        }
+                    }
+                }
+            }
+            if ranges.is_empty() {
+                ranges = prior_ranges;
+            }
+            if !ranges.is_empty() {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges(ranges));
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_helix_word_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("ˇhello world again", Mode::HelixNormal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("«hello ˇ»world again", Mode::HelixNormal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("hello «world ˇ»again", Mode::HelixNormal);
+        cx.simulate_keystrokes("b");
+        cx.assert_state("hello «ˇworld» again", Mode::HelixNormal);
+        cx.simulate_keystrokes("e");
+        cx.assert_state("hello «worldˇ» again", Mode::HelixNormal);
+
+        // plain cursor motions just move the selection.
+        cx.simulate_keystrokes("l");
+        cx.assert_state("hello world« ˇ»again", Mode::HelixNormal);
+
+        // v extends instead of replacing.
+        cx.simulate_keystrokes("v w");
+        cx.assert_state("hello world« aˇ»gain", Mode::HelixSelect);
+        cx.simulate_keystrokes("escape");
+        assert_eq!(cx.mode(), Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_helix_select_line_and_delete(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state(
+            indoc! {"
+                onˇe
+                two
+                three"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+                «one
+                ˇ»two
+                three"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("x");
+        cx.assert_state(
+            indoc! {"
+                «one
+                two
+                ˇ»three"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("d");
+        cx.assert_state("«tˇ»hree", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_helix_select_regex(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("«one, two, threeˇ» four", Mode::HelixNormal);
+        cx.simulate_keystrokes("s");
+        cx.simulate_input("\\w+");
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ», «twoˇ», «threeˇ» four", Mode::HelixNormal);
+
+        cx.set_state("«one, two, threeˇ» four", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-s");
+        cx.simulate_input(", ");
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ», «twoˇ», «threeˇ» four", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("c");
+        cx.simulate_input("x");
+        cx.simulate_keystrokes("escape");
+        assert_eq!(cx.buffer_text(), "x, x, x four");
+    }
+
+    #[gpui::test]
+    async fn test_helix_surround_selection(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("say «helloˇ» there", Mode::HelixNormal);
+        cx.simulate_keystrokes("m s (");
+        assert_eq!(cx.buffer_text(), "say ( hello ) there");
+
+        cx.set_state("say «helloˇ» there", Mode::HelixNormal);
+        cx.simulate_keystrokes("m s )");
+        assert_eq!(cx.buffer_text(), "say (hello) there");
+    }
+}
@@ -7,13 +7,14 @@ use editor::{
     Anchor, Bias, DisplayPoint, RowExt, ToOffset,
 };
 use gpui::{actions, impl_actions, px, ViewContext, WindowContext};
-use language::{char_kind, CharKind, Point, Selection, SelectionGoal};
+use language::{char_kind, CharKind, Point, Selection, SelectionGoal, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 use std::ops::Range;
 use workspace::Workspace;
 
 use crate::{
+    helix::helix_normal_motion,
     normal::{mark, normal_motion},
     state::{Mode, Operator},
     surrounds::SurroundsType,
@@ -98,6 +99,12 @@ pub enum Motion {
     WindowTop,
     WindowMiddle,
     WindowBottom,
+    NextMethodStart,
+    NextMethodEnd,
+    PreviousMethodStart,
+    PreviousMethodEnd,
+    NextComment,
+    PreviousComment,
 
     // we don't have a good way to run a search syncronously, so
     // we handle search motions by running the search async and then
@@ -244,6 +251,12 @@ actions!(
         WindowTop,
         WindowMiddle,
         WindowBottom,
+        NextMethodStart,
+        NextMethodEnd,
+        PreviousMethodStart,
+        PreviousMethodEnd,
+        NextComment,
+        PreviousComment,
     ]
 );
 
@@ -310,6 +323,24 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, _: &Matching, cx: _| motion(Motion::Matching, cx));
+    workspace.register_action(|_: &mut Workspace, _: &NextMethodStart, cx: _| {
+        motion(Motion::NextMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, _: &NextMethodEnd, cx: _| {
+        motion(Motion::NextMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, _: &PreviousMethodStart, cx: _| {
+        motion(Motion::PreviousMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, _: &PreviousMethodEnd, cx: _| {
+        motion(Motion::PreviousMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, _: &NextComment, cx: _| {
+        motion(Motion::NextComment, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, _: &PreviousComment, cx: _| {
+        motion(Motion::PreviousComment, cx)
+    });
 
     workspace.register_action(
         |_: &mut Workspace, &NextWordStart { ignore_punctuation }: &NextWordStart, cx: _| {
@@ -399,7 +430,11 @@ pub(crate) fn search_motion(m: Motion, cx: &mut WindowContext) {
     } = &m
     {
         match Vim::read(cx).state().mode {
-            Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
+            Mode::Visual
+            | Mode::VisualLine
+            | Mode::VisualBlock
+            | Mode::HelixNormal
+            | Mode::HelixSelect => {
                 if !prior_selections.is_empty() {
                     Vim::update(cx, |vim, cx| {
                         vim.update_active_editor(cx, |_, editor, cx| {
@@ -441,9 +476,10 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
                 normal_motion(motion.clone(), active_operator.clone(), count, cx)
             }
         }
-        Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
+        Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::HelixSelect => {
             visual_motion(motion.clone(), count, cx)
         }
+        Mode::HelixNormal => helix_normal_motion(motion.clone(), count, cx),
         Mode::Insert => {
             // Shouldn't execute a motion in insert mode. Ignoring
         }
@@ -499,6 +535,12 @@ impl Motion {
             | RepeatFind { .. }
             | RepeatFindReversed { .. }
             | Jump { line: false, .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextComment
+            | PreviousComment
             | ZedSearchResult { .. } => false,
         }
     }
@@ -538,6 +580,12 @@ impl Motion {
             | WindowMiddle
             | WindowBottom
             | NextLineStart
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextComment
+            | PreviousComment
             | ZedSearchResult { .. }
             | Jump { .. } => false,
         }
@@ -579,6 +627,12 @@ impl Motion {
             | FirstNonWhitespace { .. }
             | FindBackward { .. }
             | Jump { .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextComment
+            | PreviousComment
             | ZedSearchResult { .. } => false,
             RepeatFind { last_find: motion } | RepeatFindReversed { last_find: motion } => {
                 motion.inclusive()
@@ -771,6 +825,30 @@ impl Motion {
             WindowMiddle => window_middle(map, point, &text_layout_details),
             WindowBottom => window_bottom(map, point, &text_layout_details, times - 1),
             Jump { line, anchor } => mark::jump_motion(map, *anchor, *line),
+            NextMethodStart => (
+                text_object_boundary(map, point, TextObject::AroundFunction, true, true, times),
+                SelectionGoal::None,
+            ),
+            NextMethodEnd => (
+                text_object_boundary(map, point, TextObject::AroundFunction, false, true, times),
+                SelectionGoal::None,
+            ),
+            PreviousMethodStart => (
+                text_object_boundary(map, point, TextObject::AroundFunction, true, false, times),
+                SelectionGoal::None,
+            ),
+            PreviousMethodEnd => (
+                text_object_boundary(map, point, TextObject::AroundFunction, false, false, times),
+                SelectionGoal::None,
+            ),
+            NextComment => (
+                text_object_boundary(map, point, TextObject::AroundComment, true, true, times),
+                SelectionGoal::None,
+            ),
+            PreviousComment => (
+                text_object_boundary(map, point, TextObject::AroundComment, true, false, times),
+                SelectionGoal::None,
+            ),
             ZedSearchResult { new_selections, .. } => {
                 // There will be only one selection, as
                 // Search::SelectNextMatch selects a single match.
@@ -1559,6 +1637,57 @@ fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint
     }
 }
 
+/// Moves to the start (or end) of the `times`th text object of the given kind after (or before)
+/// the cursor, as described by the language's textobjects query.
+fn text_object_boundary(
+    map: &DisplaySnapshot,
+    display_point: DisplayPoint,
+    target: TextObject,
+    start: bool,
+    forward: bool,
+    times: usize,
+) -> DisplayPoint {
+    let offset = display_point.to_offset(map, Bias::Left);
+    let Some(excerpt) = map.buffer_snapshot.excerpt_containing(offset..offset) else {
+        return display_point;
+    };
+    let buffer = excerpt.buffer();
+    let buffer_offset = excerpt.map_offset_to_buffer(offset);
+
+    let search_range = if forward {
+        buffer_offset..buffer.len()
+    } else {
+        0..(buffer_offset + 1).min(buffer.len())
+    };
+    let mut boundaries = buffer
+        .text_object_ranges(search_range)
+        .filter(|(range, object)| *object == target && !range.is_empty())
+        .map(|(range, _)| if start { range.start } else { range.end - 1 })
+        .filter(|boundary| {
+            if forward {
+                *boundary > buffer_offset
+            } else {
+                *boundary < buffer_offset
+            }
+        })
+        .collect::<Vec<_>>();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let boundary = if forward {
+        boundaries.into_iter().nth(times - 1)
+    } else {
+        boundaries.into_iter().rev().nth(times - 1)
+    };
+    match boundary {
+        Some(boundary) if excerpt.contains_buffer_range(boundary..boundary) => excerpt
+            .map_range_from_buffer(boundary..boundary)
+            .start
+            .to_display_point(map),
+        _ => display_point,
+    }
+}
+
 fn find_forward(
     map: &DisplaySnapshot,
     from: DisplayPoint,
@@ -1759,7 +1888,10 @@ fn window_bottom(
 #[cfg(test)]
 mod test {
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use indoc::indoc;
 
     #[gpui::test]
@@ -2270,4 +2402,119 @@ mod test {
             }ˇ»
         "});
     }
+
+    #[gpui::test]
+    async fn test_method_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇfn a() {
+                    1
+                }
+
+                fn b() {
+                    2
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] m");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    1
+                }
+
+                ˇfn b() {
+                    2
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] shift-m");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    1
+                }
+
+                fn b() {
+                    2
+                ˇ}
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ shift-m");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    1
+                ˇ}
+
+                fn b() {
+                    2
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ m");
+        cx.assert_state(
+            indoc! {"
+                ˇfn a() {
+                    1
+                }
+
+                fn b() {
+                    2
+                }
+            "},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("d ] m");
+        cx.assert_state(
+            indoc! {"
+                ˇfn b() {
+                    2
+                }
+            "},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_comment_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                // one
+                fn a() {}
+                ˇ// two
+                fn b() {}
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ /");
+        cx.assert_state(
+            indoc! {"
+                ˇ// one
+                fn a() {}
+                // two
+                fn b() {}
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] /");
+        cx.assert_state(
+            indoc! {"
+                // one
+                fn a() {}
+                ˇ// two
+                fn b() {}
+            "},
+            Mode::Normal,
+        );
+    }
 }
@@ -3,7 +3,7 @@ mod change;
 mod delete;
 mod increment;
 pub(crate) mod mark;
-mod paste;
+pub(crate) mod paste;
 pub(crate) mod repeat;
 mod scroll;
 pub(crate) mod search;
@@ -17,6 +17,7 @@ use crate::{
     object::Object,
     state::{Mode, Operator},
     surrounds::{check_and_move_to_valid_bracket_pair, SurroundsType},
+    visual::visual_block_insert,
     Vim,
 };
 use collections::BTreeSet;
@@ -248,6 +249,10 @@ pub(crate) fn move_cursor(
 
 fn insert_after(_: &mut Workspace, _: &InsertAfter, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
+        if vim.state().mode == Mode::VisualBlock {
+            visual_block_insert(vim, true, cx);
+            return;
+        }
         vim.start_recording(cx);
         vim.switch_mode(Mode::Insert, false, cx);
         vim.update_active_editor(cx, |_, editor, cx| {
@@ -260,6 +265,10 @@ fn insert_after(_: &mut Workspace, _: &InsertAfter, cx: &mut ViewContext<Workspa
 
 fn insert_before(_: &mut Workspace, _: &InsertBefore, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
+        if vim.state().mode == Mode::VisualBlock {
+            visual_block_insert(vim, false, cx);
+            return;
+        }
         vim.start_recording(cx);
         vim.switch_mode(Mode::Insert, false, cx);
     });
@@ -56,7 +56,7 @@ where
                         ranges.push(start..end);
                         cursor_positions.push(start..start);
                     }
-                    Mode::Visual => {
+                    Mode::Visual | Mode::HelixNormal | Mode::HelixSelect => {
                         ranges.push(selection.start..selection.end);
                         cursor_positions.push(selection.start..selection.start);
                     }
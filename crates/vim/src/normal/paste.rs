@@ -5,12 +5,16 @@ use editor::{
     RowExt,
 };
 use gpui::{impl_actions, AppContext, ViewContext};
-use language::{Bias, SelectionGoal};
+use language::SelectionGoal;
 use serde::Deserialize;
 use settings::Settings;
 use workspace::Workspace;
 
-use crate::{state::Mode, utils::copy_selections_content, UseSystemClipboard, Vim, VimSettings};
+use crate::{
+    state::{Mode, Register},
+    utils::copy_selections_content,
+    UseSystemClipboard, Vim, VimSettings,
+};
 
 #[derive(Clone, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -30,13 +34,34 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
 fn system_clipboard_is_newer(vim: &Vim, cx: &mut AppContext) -> bool {
     cx.read_from_clipboard().is_some_and(|item| {
         if let Some(last_state) = vim.workspace_state.registers.get(".system.") {
-            last_state != item.text()
+            &last_state.text != item.text()
         } else {
             true
         }
     })
 }
 
+/// The text that a paste would insert, taking the system clipboard into account.
+pub(crate) fn register_contents(vim: &Vim, cx: &mut AppContext) -> Register {
+    if VimSettings::get_global(cx).use_system_clipboard == UseSystemClipboard::Never
+        || VimSettings::get_global(cx).use_system_clipboard == UseSystemClipboard::OnYank
+            && !system_clipboard_is_newer(vim, cx)
+    {
+        vim.workspace_state
+            .registers
+            .get("\"")
+            .cloned()
+            .unwrap_or_default()
+    } else if let Some(item) = cx.read_from_clipboard() {
+        Register {
+            text: item.text().clone(),
+            clipboard_selections: item.metadata::<Vec<ClipboardSelection>>(),
+        }
+    } else {
+        Register::default()
+    }
+}
+
 fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
         vim.record_current_action(cx);
@@ -46,33 +71,13 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let (clipboard_text, clipboard_selections): (String, Option<_>) =
-                    if VimSettings::get_global(cx).use_system_clipboard == UseSystemClipboard::Never
-                        || VimSettings::get_global(cx).use_system_clipboard
-                            == UseSystemClipboard::OnYank
-                            && !system_clipboard_is_newer(vim, cx)
-                    {
-                        (
-                            vim.workspace_state
-                                .registers
-                                .get("\"")
-                                .cloned()
-                                .unwrap_or_else(|| "".to_string()),
-                            None,
-                        )
-                    } else {
-                        if let Some(item) = cx.read_from_clipboard() {
-                            let clipboard_selections = item
-                                .metadata::<Vec<ClipboardSelection>>()
-                                .filter(|clipboard_selections| {
-                                    clipboard_selections.len() > 1
-                                        && vim.state().mode != Mode::VisualLine
-                                });
-                            (item.text().clone(), clipboard_selections)
-                        } else {
-                            ("".into(), None)
-                        }
-                    };
+                let Register {
+                    text: clipboard_text,
+                    clipboard_selections,
+                } = register_contents(vim, cx);
+                let clipboard_selections = clipboard_selections.filter(|clipboard_selections| {
+                    clipboard_selections.len() > 1 && vim.state().mode != Mode::VisualLine
+                });
 
                 if clipboard_text.is_empty() {
                     return;
@@ -82,6 +87,28 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                     copy_selections_content(vim, editor, vim.state().mode == Mode::VisualLine, cx);
                 }
 
+                // a block that extends past the end of the buffer is pasted onto new lines.
+                if let Some(clipboard_selections) = clipboard_selections.as_ref() {
+                    let (display_map, current_selections) =
+                        editor.selections.all_adjusted_display(cx);
+                    let last_row = current_selections.last().unwrap().end.row().0
+                        + clipboard_selections
+                            .len()
+                            .saturating_sub(current_selections.len())
+                            as u32;
+                    let max_point = display_map.max_point();
+                    if last_row > max_point.row().0 {
+                        let end = max_point.to_point(&display_map);
+                        editor.edit(
+                            [(
+                                end..end,
+                                "\n".repeat((last_row - max_point.row().0) as usize),
+                            )],
+                            cx,
+                        );
+                    }
+                }
+
                 let (display_map, current_selections) = editor.selections.all_adjusted_display(cx);
 
                 // unlike zed, if you have a multi-cursor selection from vim block mode,
@@ -90,8 +117,11 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                 let mut selections_to_process = Vec::new();
                 let mut i = 0;
                 while i < current_selections.len() {
-                    selections_to_process
-                        .push((current_selections[i].start..current_selections[i].end, true));
+                    selections_to_process.push((
+                        current_selections[i].start..current_selections[i].end,
+                        true,
+                        None,
+                    ));
                     i += 1;
                 }
                 if let Some(clipboard_selections) = clipboard_selections.as_ref() {
@@ -100,11 +130,18 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                         .map(|selection| cmp::min(selection.start.column(), selection.end.column()))
                         .min()
                         .unwrap();
+                    let column = if action.before || vim.state().mode.selections_cover_char() {
+                        left
+                    } else {
+                        left + 1
+                    };
                     let mut row = current_selections.last().unwrap().end.row().next_row();
                     while i < clipboard_selections.len() {
-                        let cursor =
-                            display_map.clip_point(DisplayPoint::new(row, left), Bias::Left);
-                        selections_to_process.push((cursor..cursor, false));
+                        // like vim, lines that are too short for the block are padded with spaces.
+                        let line_len = display_map.line_len(row);
+                        let cursor = DisplayPoint::new(row, cmp::min(line_len, column));
+                        let padding = " ".repeat(column.saturating_sub(line_len) as usize);
+                        selections_to_process.push((cursor..cursor, false, Some(padding)));
                         i += 1;
                         row.0 += 1;
                     }
@@ -123,7 +160,8 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                 let mut original_indent_columns = Vec::new();
                 let mut start_offset = 0;
 
-                for (ix, (selection, preserve)) in selections_to_process.iter().enumerate() {
+                for (ix, (selection, preserve, padding)) in selections_to_process.iter().enumerate()
+                {
                     let (mut to_insert, original_indent_column) =
                         if let Some(clipboard_selections) = &clipboard_selections {
                             if let Some(clipboard_selection) = clipboard_selections.get(ix) {
@@ -151,7 +189,7 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                         to_insert = to_insert + "\n";
                     }
 
-                    let display_range = if !selection.is_empty() {
+                    let display_range = if !selection.is_empty() || padding.is_some() {
                         selection.start..selection.end
                     } else if line_mode {
                         let point = if before {
@@ -180,7 +218,11 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                     if *preserve {
                         new_selections.push((anchor, line_mode, is_multiline));
                     }
-                    edits.push((point_range, to_insert.repeat(count)));
+                    let mut to_insert = to_insert.repeat(count);
+                    if let Some(padding) = padding {
+                        to_insert.insert_str(0, padding);
+                    }
+                    edits.push((point_range, to_insert));
                     original_indent_columns.extend(original_indent_column);
                 }
 
@@ -528,6 +570,42 @@ mod test {
             the lzy dog"});
     }
 
+    #[gpui::test]
+    async fn test_paste_visual_block_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Never)
+            });
+        });
+
+        // the register remembers that it was yanked block-wise
+        cx.set_shared_state(indoc! {"
+            The ˇquick brown
+            fox jumps over
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v j y p").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The qˇquick brown
+            fox jjumps over
+            the lazy dog"});
+
+        // blocks are pasted as a rectangle, even past the end of the buffer
+        cx.set_shared_state(indoc! {"
+            ˇab
+            cd
+            x"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v j l y 2 j p").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ab
+            cd
+            xaˇb
+             cd"});
+    }
+
     #[gpui::test]
     async fn test_paste_indent(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new_typescript(cx).await;
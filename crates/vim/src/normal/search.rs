@@ -8,6 +8,7 @@ use serde_derive::Deserialize;
 use workspace::{searchable::Direction, Workspace};
 
 use crate::{
+    helix,
     motion::{search_motion, Motion},
     normal::move_cursor,
     state::{Mode, SearchState},
@@ -131,6 +132,7 @@ fn search(workspace: &mut Workspace, action: &Search, cx: &mut ViewContext<Works
                         direction,
                         count,
                         initial_query: query.clone(),
+                        helix_regex: None,
                         prior_selections,
                         prior_operator: vim.active_operator(),
                         prior_mode: vim.state().mode,
@@ -148,6 +150,10 @@ fn search_deploy(_: &mut Workspace, _: &buffer_search::Deploy, cx: &mut ViewCont
 }
 
 fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewContext<Workspace>) {
+    if let Some(operation) = Vim::read(cx).workspace_state.search.helix_regex {
+        helix::regex_prompt_submit(workspace, operation, cx);
+        return;
+    }
     let mut motion = None;
     Vim::update(cx, |vim, cx| {
         let pane = workspace.active_pane().clone();
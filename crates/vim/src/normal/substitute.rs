@@ -31,11 +31,14 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
 pub fn substitute(vim: &mut Vim, count: Option<usize>, line_mode: bool, cx: &mut WindowContext) {
     vim.update_active_editor(cx, |vim, editor, cx| {
         editor.set_clip_at_line_ends(false, cx);
+        // in visual block mode, lines that end inside the block have empty selections, which
+        // should not be expanded to the following character.
+        let is_visual_block = vim.state().mode == Mode::VisualBlock;
         editor.transact(cx, |editor, cx| {
             let text_layout_details = editor.text_layout_details(cx);
             editor.change_selections(None, cx, |s| {
                 s.move_with(|map, selection| {
-                    if selection.start == selection.end {
+                    if selection.start == selection.end && !is_visual_block {
                         Motion::Right.expand_selection(
                             map,
                             selection,
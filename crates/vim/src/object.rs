@@ -1,8 +1,12 @@
 use std::ops::Range;
 
 use crate::{
-    motion::right, normal::normal_object, state::Mode, utils::coerce_punctuation,
-    visual::visual_object, Vim,
+    motion::right,
+    normal::normal_object,
+    state::{Mode, Operator},
+    utils::coerce_punctuation,
+    visual::visual_object,
+    Vim,
 };
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
@@ -13,7 +17,7 @@ use editor::{
 use itertools::Itertools;
 
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, BufferSnapshot, CharKind, Point, Selection};
+use language::{char_kind, BufferSnapshot, CharKind, Point, Selection, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 use workspace::Workspace;
@@ -32,6 +36,9 @@ pub enum Object {
     CurlyBrackets,
     AngleBrackets,
     Argument,
+    Method,
+    Class,
+    Comment,
     Tag,
 }
 
@@ -58,6 +65,9 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
+        Method,
+        Class,
+        Comment,
         Tag
     ]
 );
@@ -96,12 +106,28 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Comment, cx: _| object(Object::Comment, cx));
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
     match Vim::read(cx).state().mode {
         Mode::Normal => normal_object(object, cx),
-        Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_object(object, cx),
+        // helix's `mr` picks the surrounding pair to change the same way vim's `cs` does.
+        Mode::HelixNormal | Mode::HelixSelect
+            if matches!(
+                Vim::read(cx).active_operator(),
+                Some(Operator::ChangeSurrounds { .. })
+            ) =>
+        {
+            normal_object(object, cx)
+        }
+        Mode::Visual
+        | Mode::VisualLine
+        | Mode::VisualBlock
+        | Mode::HelixNormal
+        | Mode::HelixSelect => visual_object(object, cx),
         Mode::Insert | Mode::Replace => {
             // Shouldn't execute a text object in insert mode. Ignoring
         }
@@ -123,13 +149,22 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => true,
         }
     }
 
     pub fn always_expands_both_ways(self) -> bool {
         match self {
-            Object::Word { .. } | Object::Sentence | Object::Paragraph | Object::Argument => false,
+            Object::Word { .. }
+            | Object::Sentence
+            | Object::Paragraph
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => false,
             Object::Quotes
             | Object::BackQuotes
             | Object::DoubleQuotes
@@ -143,6 +178,9 @@ impl Object {
     }
 
     pub fn target_visual_mode(self, current_mode: Mode) -> Mode {
+        if current_mode.is_helix() {
+            return current_mode;
+        }
         match self {
             Object::Word { .. }
             | Object::Sentence
@@ -161,7 +199,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => Mode::Visual,
             Object::Paragraph => Mode::VisualLine,
         }
     }
@@ -209,6 +250,33 @@ impl Object {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
             Object::Argument => argument(map, relative_to, around),
+            Object::Method => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundFunction
+                } else {
+                    TextObject::InsideFunction
+                },
+            ),
+            Object::Class => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundClass
+                } else {
+                    TextObject::InsideClass
+                },
+            ),
+            Object::Comment => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundComment
+                } else {
+                    TextObject::InsideComment
+                },
+            ),
         }
     }
 
@@ -552,7 +620,22 @@ fn argument(
         Some(start..end)
     }
 
-    let result = comma_delimited_range_at(buffer, excerpt.map_offset_to_buffer(offset), around)?;
+    let offset = excerpt.map_offset_to_buffer(offset);
+
+    // Prefer the arguments described by the language's textobjects query, unless the bracket
+    // based search finds a smaller argument that the query doesn't know about (e.g. within a
+    // tuple or a macro invocation).
+    let bracket_argument = comma_delimited_range_at(buffer, offset, false);
+    let result = match argument_text_object(buffer, offset, around) {
+        Some((inside, range))
+            if bracket_argument.as_ref().map_or(true, |argument| {
+                *argument == inside || argument.start < inside.start || argument.end > inside.end
+            }) =>
+        {
+            range
+        }
+        _ => comma_delimited_range_at(buffer, offset, around)?,
+    };
 
     if excerpt.contains_buffer_range(result.clone()) {
         let result = excerpt.map_range_from_buffer(result);
@@ -562,6 +645,110 @@ fn argument(
     }
 }
 
+/// Returns the innermost argument containing `offset` according to the textobjects query,
+/// along with the range to select for it.
+fn argument_text_object(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    around: bool,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let inside = buffer
+        .text_object_ranges(offset..(offset + 1).min(buffer.len()))
+        .filter(|(range, object)| {
+            *object == TextObject::InsideArgument && range.start <= offset && offset < range.end
+        })
+        .map(|(range, _)| range)
+        .min_by_key(|range| range.len())?;
+    if !around {
+        return Some((inside.clone(), inside));
+    }
+
+    // The around capture covers the argument and its trailing comma, if there is one.
+    let around_end = buffer
+        .text_object_ranges(inside.clone())
+        .filter(|(range, object)| {
+            *object == TextObject::AroundArgument && range.start == inside.start
+        })
+        .map(|(range, _)| range.end)
+        .max()
+        .unwrap_or(inside.end);
+
+    let range = if around_end > inside.end {
+        let whitespace = buffer
+            .chars_at(around_end)
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+        inside.start..around_end + whitespace
+    } else {
+        // The last argument has no trailing comma, so remove the preceding one instead.
+        let whitespace = buffer
+            .reversed_chars_at(inside.start)
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+        let start = inside.start - whitespace;
+        if buffer.reversed_chars_at(start).next() == Some(',') {
+            start - 1..inside.end
+        } else {
+            inside.clone()
+        }
+    };
+    Some((inside, range))
+}
+
+/// Returns the innermost function, class or comment containing `relative_to`, as described
+/// by the language's textobjects query.
+///
+/// Inside objects are looked up within the innermost around object, so that e.g. `if` also
+/// works from a function's signature.
+fn text_object(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let offset = relative_to.to_offset(map, Bias::Left);
+
+    let excerpt = snapshot.excerpt_containing(offset..offset)?;
+    let buffer = excerpt.buffer();
+    let offset = excerpt.map_offset_to_buffer(offset);
+
+    let around = target.around().unwrap_or(target);
+    let around_range = buffer
+        .text_object_ranges(offset..(offset + 1).min(buffer.len()))
+        .filter(|(range, object)| *object == around && range.start <= offset && offset < range.end)
+        .map(|(range, _)| range)
+        .min_by_key(|range| range.len())?;
+
+    let range = if around == target {
+        around_range
+    } else {
+        let inside_range = buffer
+            .text_object_ranges(around_range.clone())
+            .filter(|(range, object)| {
+                *object == target
+                    && around_range.start <= range.start
+                    && range.end <= around_range.end
+            })
+            .map(|(range, _)| range)
+            .max_by_key(|range| range.len());
+        match inside_range {
+            Some(range) => range,
+            // Most languages don't distinguish the contents of a comment from its delimiters.
+            None if target == TextObject::InsideComment => around_range,
+            None => return None,
+        }
+    };
+
+    if excerpt.contains_buffer_range(range.clone()) {
+        let range = excerpt.map_range_from_buffer(range);
+        Some(range.start.to_display_point(map)..range.end.to_display_point(map))
+    } else {
+        None
+    }
+}
+
 fn sentence(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -1434,6 +1621,118 @@ mod test {
         cx.assert_state("let a = [«test::call(first_arg)ˇ»]", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_text_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // Function
+        cx.set_state(
+            indoc! {"
+                fn boop() {
+                    let a = 1;
+                    ˇlet b = 2;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            indoc! {"
+                fn boop() {
+                    «let a = 1;
+                    let b = 2;ˇ»
+                }
+            "},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                fn boˇop() {
+                    let a = 1;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a f");
+        cx.assert_state(
+            indoc! {"
+                «fn boop() {
+                    let a = 1;
+                }ˇ»
+            "},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                fn boˇop() {
+                    let a = 1;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c i f");
+        cx.assert_state(
+            indoc! {"
+                fn boop() {
+                    ˇ
+                }
+            "},
+            Mode::Insert,
+        );
+
+        // Class
+        cx.set_state(
+            indoc! {"
+                struct Foo {
+                    ˇa: u32,
+                    b: u32,
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i c");
+        cx.assert_state(
+            indoc! {"
+                struct Foo {
+                    «a: u32,
+                    b: u32,ˇ»
+                }
+            "},
+            Mode::Visual,
+        );
+
+        // Comment
+        cx.set_state(
+            indoc! {"
+                // one
+                // twˇo
+                fn boop() {}
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a /");
+        cx.assert_state(
+            indoc! {"
+                «// one
+                // twoˇ»
+                fn boop() {}
+            "},
+            Mode::Visual,
+        );
+
+        // Argument
+        cx.set_state("fn boop(a: u32, ˇb: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes("d a a");
+        cx.assert_state("fn boop(a: u32ˇ) {}", Mode::Normal);
+
+        // No function around the cursor
+        cx.set_state("let ˇa = 1;", Mode::Normal);
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state("let «aˇ» = 1;", Mode::Visual);
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
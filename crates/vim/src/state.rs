@@ -1,10 +1,11 @@
 use std::{fmt::Display, ops::Range, sync::Arc};
 
+use crate::helix::HelixRegexOperation;
 use crate::surrounds::SurroundsType;
 use crate::{motion::Motion, object::Object};
 use collections::HashMap;
-use editor::Anchor;
-use gpui::{Action, KeyContext};
+use editor::{Anchor, ClipboardSelection};
+use gpui::{Action, KeyContext, Keystroke};
 use language::{CursorShape, Selection, TransactionId};
 use serde::{Deserialize, Serialize};
 use workspace::searchable::Direction;
@@ -17,6 +18,8 @@ pub enum Mode {
     Visual,
     VisualLine,
     VisualBlock,
+    HelixNormal,
+    HelixSelect,
 }
 
 impl Display for Mode {
@@ -28,6 +31,8 @@ impl Display for Mode {
             Mode::Visual => write!(f, "VISUAL"),
             Mode::VisualLine => write!(f, "VISUAL LINE"),
             Mode::VisualBlock => write!(f, "VISUAL BLOCK"),
+            Mode::HelixNormal => write!(f, "NORMAL"),
+            Mode::HelixSelect => write!(f, "SELECT"),
         }
     }
 }
@@ -35,10 +40,33 @@ impl Display for Mode {
 impl Mode {
     pub fn is_visual(&self) -> bool {
         match self {
-            Mode::Normal | Mode::Insert | Mode::Replace => false,
+            Mode::Normal | Mode::Insert | Mode::Replace | Mode::HelixNormal | Mode::HelixSelect => {
+                false
+            }
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => true,
         }
     }
+
+    /// Whether the selections in this mode always cover at least one character.
+    pub fn selections_cover_char(&self) -> bool {
+        self.is_visual() || self.is_helix()
+    }
+
+    pub fn is_helix(&self) -> bool {
+        matches!(self, Mode::HelixNormal | Mode::HelixSelect)
+    }
+
+    /// The value of `vim_mode` in the keymap context while in this mode.
+    pub fn keymap_context_name(&self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::Visual | Mode::VisualLine | Mode::VisualBlock => "visual",
+            Mode::Insert => "insert",
+            Mode::Replace => "replace",
+            Mode::HelixNormal => "helix_normal",
+            Mode::HelixSelect => "helix_select",
+        }
+    }
 }
 
 impl Default for Mode {
@@ -82,6 +110,9 @@ pub struct EditorState {
 
     pub current_tx: Option<TransactionId>,
     pub current_anchor: Option<Selection<Anchor>>,
+    /// The top-left corner of the block that `I` or `A` is inserting into, where the
+    /// cursor returns once insert mode ends.
+    pub block_insert_start: Option<Anchor>,
     pub undo_modes: HashMap<TransactionId, Mode>,
 }
 
@@ -117,7 +148,30 @@ pub struct WorkspaceState {
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
 
-    pub registers: HashMap<String, String>,
+    pub registers: HashMap<String, Register>,
+
+    pub mapped_keystrokes: Vec<(Keystroke, bool)>,
+    pub dispatching_mapping: bool,
+    pub dispatching_noremap: bool,
+}
+
+/// The contents of a vim register.
+///
+/// `clipboard_selections` records how the text was split into selections when it was
+/// copied, so that a block-wise yank is pasted back as a block.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Register {
+    pub(crate) text: String,
+    pub(crate) clipboard_selections: Option<Vec<ClipboardSelection>>,
+}
+
+impl From<String> for Register {
+    fn from(text: String) -> Self {
+        Register {
+            text,
+            clipboard_selections: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -149,6 +203,7 @@ pub struct SearchState {
     pub direction: Direction,
     pub count: usize,
     pub initial_query: String,
+    pub helix_regex: Option<HelixRegexOperation>,
 
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
@@ -166,7 +221,11 @@ impl EditorState {
                 }
             }
             Mode::Replace => CursorShape::Underscore,
-            Mode::Visual | Mode::VisualLine | Mode::VisualBlock => CursorShape::Block,
+            Mode::Visual
+            | Mode::VisualLine
+            | Mode::VisualBlock
+            | Mode::HelixNormal
+            | Mode::HelixSelect => CursorShape::Block,
             Mode::Insert => CursorShape::Bar,
         }
     }
@@ -191,9 +250,13 @@ impl EditorState {
 
     pub fn clip_at_line_ends(&self) -> bool {
         match self.mode {
-            Mode::Insert | Mode::Visual | Mode::VisualLine | Mode::VisualBlock | Mode::Replace => {
-                false
-            }
+            Mode::Insert
+            | Mode::Visual
+            | Mode::VisualLine
+            | Mode::VisualBlock
+            | Mode::Replace
+            | Mode::HelixNormal
+            | Mode::HelixSelect => false,
             Mode::Normal => true,
         }
     }
@@ -204,15 +267,7 @@ impl EditorState {
 
     pub fn keymap_context_layer(&self) -> KeyContext {
         let mut context = KeyContext::new_with_defaults();
-        context.set(
-            "vim_mode",
-            match self.mode {
-                Mode::Normal => "normal",
-                Mode::Visual | Mode::VisualLine | Mode::VisualBlock => "visual",
-                Mode::Insert => "insert",
-                Mode::Replace => "replace",
-            },
-        );
+        context.set("vim_mode", self.mode.keymap_context_name());
 
         if self.vim_controlled() {
             context.add("VimControl");
@@ -13,15 +13,20 @@ use std::sync::Arc;
 pub enum SurroundsType {
     Motion(Motion),
     Object(Object),
+    /// The current selections, as used by helix's `ms`.
+    Selection,
 }
 
 // This exists so that we can have Deserialize on Operators, but not on Motions.
 impl<'de> Deserialize<'de> for SurroundsType {
-    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        Err(serde::de::Error::custom("Cannot deserialize SurroundsType"))
+        match String::deserialize(deserializer)?.as_str() {
+            "Selection" => Ok(SurroundsType::Selection),
+            _ => Err(serde::de::Error::custom("Cannot deserialize SurroundsType")),
+        }
     }
 }
 
@@ -50,6 +55,7 @@ pub fn add_surrounds(text: Arc<str>, target: SurroundsType, cx: &mut WindowConte
 
                 for selection in &display_selections {
                     let range = match &target {
+                        SurroundsType::Selection => Some(selection.range()),
                         SurroundsType::Object(object) => {
                             object.range(&display_map, selection.clone(), false)
                         }
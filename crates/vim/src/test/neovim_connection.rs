@@ -416,7 +416,11 @@ impl NeovimConnection {
                     }
                 }
             }
-            Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
+            Mode::Visual
+            | Mode::VisualLine
+            | Mode::VisualBlock
+            | Mode::HelixNormal
+            | Mode::HelixSelect => {
                 if (selection_row, selection_col) > (cursor_row, cursor_col) {
                     let selection_line_length =
                         self.read_position("echo strlen(getline(line('v')))").await;
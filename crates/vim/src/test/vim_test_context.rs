@@ -110,6 +110,14 @@ impl VimTestContext {
         })
     }
 
+    pub fn enable_helix(&mut self) {
+        self.cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<HelixModeSetting>(cx, |s| *s = Some(true));
+            });
+        })
+    }
+
     pub fn disable_vim(&mut self) {
         self.cx.update(|cx| {
             SettingsStore::update_global(cx, |store, cx| {
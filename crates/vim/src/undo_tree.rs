@@ -0,0 +1,460 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use editor::Editor;
+use gpui::{
+    actions, impl_actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Model, Task, View, WeakView,
+};
+use language::{Buffer, TransactionId, UndoTreeNode};
+use picker::{Picker, PickerDelegate};
+use serde_derive::Deserialize;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::Vim;
+
+/// How far to move through the undo tree, either by a number of states or by the
+/// time the states were created.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoDistance {
+    Steps(usize),
+    Seconds(u64),
+}
+
+impl UndoDistance {
+    /// Parses the argument of `:earlier` and `:later`, e.g. `3`, `10s`, `5m`, `1h` or `2d`.
+    pub fn parse(argument: &str) -> Option<Self> {
+        let argument = argument.trim();
+        if argument.is_empty() {
+            return Some(Self::Steps(1));
+        }
+
+        let (number, multiplier) = match argument.char_indices().last()? {
+            (ix, 's') => (&argument[..ix], 1),
+            (ix, 'm') => (&argument[..ix], 60),
+            (ix, 'h') => (&argument[..ix], 60 * 60),
+            (ix, 'd') => (&argument[..ix], 24 * 60 * 60),
+            _ => return argument.parse().ok().map(Self::Steps),
+        };
+        number
+            .parse::<u64>()
+            .ok()
+            .map(|number| Self::Seconds(number * multiplier))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Earlier {
+    pub distance: UndoDistance,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Later {
+    pub distance: UndoDistance,
+}
+
+impl_actions!(vim, [Earlier, Later]);
+
+actions!(vim, [UndoStateOlder, UndoStateNewer, ToggleUndoTree]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_, _: &UndoStateOlder, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            travel(vim, UndoDistance::Steps(count), false, cx);
+        })
+    });
+    workspace.register_action(|_, _: &UndoStateNewer, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            travel(vim, UndoDistance::Steps(count), true, cx);
+        })
+    });
+    workspace.register_action(|_, action: &Earlier, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            travel(vim, action.distance, false, cx);
+        })
+    });
+    workspace.register_action(|_, action: &Later, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            travel(vim, action.distance, true, cx);
+        })
+    });
+    workspace.register_action(|workspace, _: &ToggleUndoTree, cx| {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        workspace.toggle_modal(cx, |cx| UndoTreeView::new(editor, buffer, cx));
+    });
+}
+
+fn travel(vim: &mut Vim, distance: UndoDistance, forward: bool, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let current = buffer.current_undo_state();
+        let target = undo_state_at_distance(
+            &buffer.undo_tree(),
+            current,
+            distance,
+            forward,
+            Instant::now(),
+        );
+        if target != current {
+            editor.travel_to_undo_state(target, cx);
+        }
+    });
+}
+
+/// Finds the state `distance` away from `current` in the chronological order of the
+/// undo tree, regardless of which branch it is on. `None` is the original text.
+fn undo_state_at_distance(
+    nodes: &[UndoTreeNode],
+    current: Option<TransactionId>,
+    distance: UndoDistance,
+    forward: bool,
+    now: Instant,
+) -> Option<TransactionId> {
+    // Position 0 is the original text, position `ix + 1` is `nodes[ix]`.
+    let position = current
+        .and_then(|current| nodes.iter().position(|node| node.transaction_id == current))
+        .map_or(0, |ix| ix + 1);
+
+    let target_position = match distance {
+        UndoDistance::Steps(steps) => {
+            if forward {
+                (position + steps).min(nodes.len())
+            } else {
+                position.saturating_sub(steps)
+            }
+        }
+        UndoDistance::Seconds(seconds) => {
+            let duration = Duration::from_secs(seconds);
+            if forward {
+                let Some(base) = position
+                    .checked_sub(1)
+                    .map(|ix| nodes[ix].edited_at)
+                    .or_else(|| nodes.first().map(|node| node.edited_at))
+                else {
+                    return current;
+                };
+                let limit = base + duration;
+                let newest = nodes
+                    .iter()
+                    .rposition(|node| node.edited_at <= limit)
+                    .map_or(0, |ix| ix + 1);
+                newest.max(position)
+            } else {
+                let base = position
+                    .checked_sub(1)
+                    .map_or(now, |ix| nodes[ix].edited_at);
+                let Some(limit) = base.checked_sub(duration) else {
+                    return None;
+                };
+                let newest = nodes
+                    .iter()
+                    .rposition(|node| node.edited_at <= limit)
+                    .map_or(0, |ix| ix + 1);
+                newest.min(position)
+            }
+        }
+    };
+
+    target_position
+        .checked_sub(1)
+        .map(|ix| nodes[ix].transaction_id)
+}
+
+/// A modal listing every state of the active buffer's undo tree. Moving the selection
+/// previews a state, confirming keeps it, and dismissing restores the original state.
+pub struct UndoTreeView {
+    picker: View<Picker<UndoTreeDelegate>>,
+}
+
+impl FocusableView for UndoTreeView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for UndoTreeView {}
+impl ModalView for UndoTreeView {}
+
+impl Render for UndoTreeView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(24.)).child(self.picker.clone())
+    }
+}
+
+impl UndoTreeView {
+    fn new(editor: View<Editor>, buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let original_state = buffer.read(cx).current_undo_state();
+        let entries = undo_tree_entries(&buffer.read(cx).undo_tree(), Instant::now());
+        let delegate = UndoTreeDelegate {
+            view: cx.view().downgrade(),
+            editor,
+            original_state,
+            confirmed: false,
+            matches: (0..entries.len()).collect(),
+            entries,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UndoTreeEntry {
+    transaction_id: Option<TransactionId>,
+    sequence: usize,
+    depth: usize,
+    age: Option<Duration>,
+}
+
+/// Lays out the undo tree newest first. The most recent branch of each state stays in
+/// the same column, older branches are indented one level further.
+fn undo_tree_entries(nodes: &[UndoTreeNode], now: Instant) -> Vec<UndoTreeEntry> {
+    let mut depths: Vec<usize> = Vec::with_capacity(nodes.len());
+    for (ix, node) in nodes.iter().enumerate() {
+        let parent_ix = node.parent.and_then(|parent| {
+            nodes[..ix]
+                .iter()
+                .position(|node| node.transaction_id == parent)
+        });
+        let is_newest_child = !nodes[ix + 1..]
+            .iter()
+            .any(|sibling| sibling.parent == node.parent);
+        let parent_depth = parent_ix.map_or(0, |parent_ix| depths[parent_ix]);
+        depths.push(parent_depth + usize::from(!is_newest_child));
+    }
+
+    let mut entries = nodes
+        .iter()
+        .zip(depths)
+        .enumerate()
+        .rev()
+        .map(|(ix, (node, depth))| UndoTreeEntry {
+            transaction_id: Some(node.transaction_id),
+            sequence: ix + 1,
+            depth,
+            age: Some(now.saturating_duration_since(node.edited_at)),
+        })
+        .collect::<Vec<_>>();
+    entries.push(UndoTreeEntry {
+        transaction_id: None,
+        sequence: 0,
+        depth: 0,
+        age: None,
+    });
+    entries
+}
+
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s ago")
+    } else if seconds < 60 * 60 {
+        format!("{}m ago", seconds / 60)
+    } else if seconds < 24 * 60 * 60 {
+        format!("{}h ago", seconds / (60 * 60))
+    } else {
+        format!("{}d ago", seconds / (24 * 60 * 60))
+    }
+}
+
+struct UndoTreeDelegate {
+    view: WeakView<UndoTreeView>,
+    editor: View<Editor>,
+    original_state: Option<TransactionId>,
+    confirmed: bool,
+    entries: Vec<UndoTreeEntry>,
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl UndoTreeDelegate {
+    fn preview(&self, cx: &mut WindowContext) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|ix| &self.entries[*ix])
+        {
+            let target = entry.transaction_id;
+            self.editor
+                .update(cx, |editor, cx| editor.travel_to_undo_state(target, cx));
+        }
+    }
+}
+
+impl PickerDelegate for UndoTreeDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Go to undo state...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+        self.preview(cx);
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.trim();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.sequence.to_string().starts_with(query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = if query.is_empty() {
+            self.matches
+                .iter()
+                .position(|ix| self.entries[*ix].transaction_id == self.original_state)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        self.preview(cx);
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.confirmed = true;
+        self.editor.update(cx, |editor, cx| editor.focus(cx));
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if !self.confirmed {
+            let original_state = self.original_state;
+            self.editor.update(cx, |editor, cx| {
+                editor.travel_to_undo_state(original_state, cx);
+            });
+        }
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.entries[*self.matches.get(ix)?];
+        let is_original_state = entry.transaction_id == self.original_state;
+        let label = match entry.age {
+            Some(age) => format!("{} ({})", entry.sequence, format_age(age)),
+            None => format!("{} (original)", entry.sequence),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .pl(rems(entry.depth as f32))
+                        .gap_2()
+                        .child(Label::new(if is_original_state { "●" } else { "○" }))
+                        .child(Label::new(label)),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+
+    use super::UndoDistance;
+
+    #[test]
+    fn test_parse_undo_distance() {
+        assert_eq!(UndoDistance::parse(""), Some(UndoDistance::Steps(1)));
+        assert_eq!(UndoDistance::parse("3"), Some(UndoDistance::Steps(3)));
+        assert_eq!(UndoDistance::parse("10s"), Some(UndoDistance::Seconds(10)));
+        assert_eq!(UndoDistance::parse("5m"), Some(UndoDistance::Seconds(300)));
+        assert_eq!(UndoDistance::parse("1h"), Some(UndoDistance::Seconds(3600)));
+        assert_eq!(
+            UndoDistance::parse("2d"),
+            Some(UndoDistance::Seconds(172800))
+        );
+        assert_eq!(UndoDistance::parse("2f"), None);
+        assert_eq!(UndoDistance::parse("m"), None);
+    }
+
+    #[gpui::test]
+    async fn test_undo_tree_branches(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("A space t w o escape");
+        cx.simulate_keystrokes("A space t h r e e escape");
+        cx.assert_state("one two threˇe", Mode::Normal);
+
+        // Undoing and then editing creates a new branch.
+        cx.simulate_keystrokes("u");
+        cx.simulate_keystrokes("A space f o u r escape");
+        cx.assert_state("one two fouˇr", Mode::Normal);
+        cx.simulate_keystrokes("u ctrl-r");
+        assert_eq!(cx.buffer_text(), "one two four");
+
+        // g- walks back through every state, including the abandoned branch.
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "one two three");
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "one two");
+        cx.simulate_keystrokes("2 g -");
+        assert_eq!(cx.buffer_text(), "one");
+        assert_eq!(cx.mode(), Mode::Normal);
+
+        cx.simulate_keystrokes("g +");
+        assert_eq!(cx.buffer_text(), "one two");
+        cx.simulate_keystrokes("2 g +");
+        assert_eq!(cx.buffer_text(), "one two four");
+    }
+
+    #[gpui::test]
+    async fn test_earlier_later_commands(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("A space t w o escape");
+        cx.simulate_keystrokes("A space t h r e e escape");
+
+        cx.simulate_keystrokes(": e a r l i e r space 2 enter");
+        assert_eq!(cx.buffer_text(), "one");
+        cx.simulate_keystrokes(": l a t e r enter");
+        assert_eq!(cx.buffer_text(), "one two");
+
+        // Every edit happened within the last hour.
+        cx.simulate_keystrokes(": l a t e r space 1 h enter");
+        assert_eq!(cx.buffer_text(), "one two three");
+        cx.simulate_keystrokes(": e a r l i e r space 1 h enter");
+        assert_eq!(cx.buffer_text(), "one");
+    }
+}
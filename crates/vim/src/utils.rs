@@ -6,7 +6,10 @@ use language::{CharKind, Point};
 use multi_buffer::MultiBufferRow;
 use settings::Settings;
 
-use crate::{state::Mode, UseSystemClipboard, Vim, VimSettings};
+use crate::{
+    state::{Mode, Register},
+    UseSystemClipboard, Vim, VimSettings,
+};
 
 pub struct HighlightOnYank;
 
@@ -104,19 +107,28 @@ fn copy_selections_content_internal(
 
     let setting = VimSettings::get_global(cx).use_system_clipboard;
     if setting == UseSystemClipboard::Always || setting == UseSystemClipboard::OnYank && is_yank {
-        cx.write_to_clipboard(ClipboardItem::new(text.clone()).with_metadata(clipboard_selections));
+        cx.write_to_clipboard(
+            ClipboardItem::new(text.clone()).with_metadata(clipboard_selections.clone()),
+        );
         vim.workspace_state
             .registers
-            .insert(".system.".to_string(), text.clone());
+            .insert(".system.".to_string(), text.clone().into());
     } else {
         vim.workspace_state.registers.insert(
             ".system.".to_string(),
             cx.read_from_clipboard()
                 .map(|item| item.text().clone())
-                .unwrap_or_default(),
+                .unwrap_or_default()
+                .into(),
         );
     }
-    vim.workspace_state.registers.insert("\"".to_string(), text);
+    vim.workspace_state.registers.insert(
+        "\"".to_string(),
+        Register {
+            text,
+            clipboard_selections: Some(clipboard_selections),
+        },
+    );
     if !is_yank || vim.state().mode == Mode::Visual {
         return;
     }
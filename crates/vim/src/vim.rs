@@ -6,6 +6,7 @@ mod test;
 mod change_list;
 mod command;
 mod editor_events;
+mod helix;
 mod insert;
 mod mode_indicator;
 mod motion;
@@ -14,7 +15,9 @@ mod object;
 mod replace;
 mod state;
 mod surrounds;
+mod undo_tree;
 mod utils;
+mod vimrc;
 mod visual;
 
 use anyhow::Result;
@@ -45,6 +48,7 @@ use state::{EditorState, Mode, Operator, RecordedSelection, WorkspaceState};
 use std::{ops::Range, sync::Arc};
 use surrounds::{add_surrounds, change_surrounds, delete_surrounds};
 use ui::BorrowAppContext;
+pub use vimrc::Vimrc;
 use visual::{visual_block_motion, visual_replace};
 use workspace::{self, Workspace};
 
@@ -55,6 +59,12 @@ use crate::state::ReplayableAction;
 /// Default: false
 pub struct VimModeSetting(pub bool);
 
+/// Whether or not to enable Helix mode, where selections come first and
+/// motions select the text they move over.
+///
+/// Default: false
+pub struct HelixModeSetting(pub bool);
+
 /// An Action to Switch between modes
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SwitchMode(pub Mode);
@@ -92,6 +102,7 @@ impl_actions!(vim, [SwitchMode, PushOperator, Number]);
 pub fn init(cx: &mut AppContext) {
     cx.set_global(Vim::default());
     VimModeSetting::register(cx);
+    HelixModeSetting::register(cx);
     VimSettings::register(cx);
 
     cx.observe_keystrokes(observe_keystrokes).detach();
@@ -106,17 +117,18 @@ pub fn init(cx: &mut AppContext) {
     CommandPaletteFilter::update_global(cx, |filter, _| {
         filter.hide_namespace(Vim::NAMESPACE);
     });
-    Vim::update_global(cx, |vim, cx| {
-        vim.set_enabled(VimModeSetting::get_global(cx).0, cx)
-    });
+    Vim::update_global(cx, |vim, cx| vim.set_enabled(modal_editing_enabled(cx), cx));
     cx.observe_global::<SettingsStore>(|cx| {
-        Vim::update_global(cx, |vim, cx| {
-            vim.set_enabled(VimModeSetting::get_global(cx).0, cx)
-        });
+        Vim::update_global(cx, |vim, cx| vim.set_enabled(modal_editing_enabled(cx), cx));
     })
     .detach();
 }
 
+/// Whether either vim or helix key bindings are enabled.
+pub fn modal_editing_enabled(cx: &AppContext) -> bool {
+    VimModeSetting::get_global(cx).0 || HelixModeSetting::get_global(cx).0
+}
+
 fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, &SwitchMode(mode): &SwitchMode, cx| {
         Vim::update(cx, |vim, cx| vim.switch_mode(mode, false, cx))
@@ -155,6 +167,7 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     });
 
     normal::register(workspace, cx);
+    helix::register(workspace, cx);
     insert::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
@@ -162,6 +175,8 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     object::register(workspace, cx);
     visual::register(workspace, cx);
     change_list::register(workspace, cx);
+    undo_tree::register(workspace, cx);
+    vimrc::register(workspace, cx);
 }
 
 /// Called whenever an keystroke is typed so vim can observe all actions
@@ -276,6 +291,12 @@ impl Vim {
         let newest_selection_empty = editor.selections.newest::<usize>(cx).is_empty();
 
         if editor_mode == EditorMode::Full
+            && self.state().mode == Mode::Normal
+            && HelixModeSetting::get_global(cx).0
+            && editor.leader_peer_id().is_none()
+        {
+            self.switch_mode(Mode::HelixNormal, true, cx);
+        } else if editor_mode == EditorMode::Full
                 && !newest_selection_empty
                 && self.state().mode == Mode::Normal
                 // When following someone, don't switch vim mode.
@@ -409,16 +430,24 @@ impl Vim {
     }
 
     fn switch_mode(&mut self, mode: Mode, leave_selections: bool, cx: &mut WindowContext) {
+        // In helix mode every command that returns to normal mode lands in helix's normal mode.
+        let mode = if mode == Mode::Normal && HelixModeSetting::get_global(cx).0 {
+            Mode::HelixNormal
+        } else {
+            mode
+        };
         let state = self.state();
         let last_mode = state.mode;
         let prior_mode = state.last_mode;
         let prior_tx = state.current_tx;
+        let block_insert_start = state.block_insert_start;
         self.update_state(|state| {
             state.last_mode = last_mode;
             state.mode = mode;
             state.operator_stack.clear();
             state.current_tx.take();
             state.current_anchor.take();
+            state.block_insert_start.take();
         });
         if mode != Mode::Insert {
             self.take_count(cx);
@@ -432,6 +461,9 @@ impl Vim {
         }
 
         if leave_selections {
+            if mode.is_helix() {
+                helix::select_cursor_characters(self, cx);
+            }
             return;
         }
 
@@ -461,13 +493,16 @@ impl Vim {
                     && prior_mode == Mode::VisualBlock
                     && mode != Mode::VisualBlock
                 {
-                    let pos = s.first_anchor().head();
+                    let pos = block_insert_start.unwrap_or_else(|| s.first_anchor().head());
                     s.select_anchor_ranges(vec![pos..pos])
                 }
 
                 let snapshot = s.display_map();
                 if let Some(pending) = s.pending.as_mut() {
-                    if pending.selection.reversed && mode.is_visual() && !last_mode.is_visual() {
+                    if pending.selection.reversed
+                        && mode.selections_cover_char()
+                        && !last_mode.selections_cover_char()
+                    {
                         let mut end = pending.selection.end.to_point(&snapshot.buffer_snapshot);
                         end = snapshot
                             .buffer_snapshot
@@ -477,13 +512,15 @@ impl Vim {
                 }
 
                 s.move_with(|map, selection| {
-                    if last_mode.is_visual() && !mode.is_visual() {
+                    if last_mode.selections_cover_char() && !mode.selections_cover_char() {
                         let mut point = selection.head();
                         if !selection.reversed && !selection.is_empty() {
                             point = movement::left(map, selection.head());
                         }
                         selection.collapse_to(point, selection.goal)
-                    } else if !last_mode.is_visual() && mode.is_visual() {
+                    } else if mode.selections_cover_char()
+                        && (!last_mode.selections_cover_char() || mode.is_helix())
+                    {
                         if selection.is_empty() {
                             selection.end = movement::right(map, selection.start);
                         }
@@ -655,6 +692,8 @@ impl Vim {
         {
             self.switch_mode(Mode::Normal, true, cx);
             is_visual = false;
+        } else if newest.start == newest.end && state.mode.is_helix() {
+            helix::select_cursor_characters(self, cx);
         }
 
         if is_visual {
@@ -703,11 +742,15 @@ impl Vim {
             }
             Some(Operator::Replace) => match Vim::read(cx).state().mode {
                 Mode::Normal => normal_replace(text, cx),
-                Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
+                Mode::Visual
+                | Mode::VisualLine
+                | Mode::VisualBlock
+                | Mode::HelixNormal
+                | Mode::HelixSelect => visual_replace(text, cx),
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
             Some(Operator::AddSurrounds { target }) => match Vim::read(cx).state().mode {
-                Mode::Normal => {
+                Mode::Normal | Mode::HelixNormal | Mode::HelixSelect => {
                     if let Some(target) = target {
                         add_surrounds(text, target, cx);
                         Vim::update(cx, |vim, cx| vim.clear_operator(cx));
@@ -716,7 +759,7 @@ impl Vim {
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
             Some(Operator::ChangeSurrounds { target }) => match Vim::read(cx).state().mode {
-                Mode::Normal => {
+                Mode::Normal | Mode::HelixNormal | Mode::HelixSelect => {
                     if let Some(target) = target {
                         change_surrounds(text, target, cx);
                         Vim::update(cx, |vim, cx| vim.clear_operator(cx));
@@ -725,7 +768,7 @@ impl Vim {
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
             Some(Operator::DeleteSurrounds) => match Vim::read(cx).state().mode {
-                Mode::Normal => {
+                Mode::Normal | Mode::HelixNormal | Mode::HelixSelect => {
                     delete_surrounds(text, cx);
                     Vim::update(cx, |vim, cx| vim.clear_operator(cx));
                 }
@@ -847,6 +890,18 @@ impl Settings for VimModeSetting {
     }
 }
 
+impl Settings for HelixModeSetting {
+    const KEY: Option<&'static str> = Some("helix_mode");
+
+    type FileContent = Option<bool>;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        Ok(Self(sources.user.copied().flatten().unwrap_or(
+            sources.default.ok_or_else(Self::missing_default)?,
+        )))
+    }
+}
+
 /// Controls when to use system clipboard.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -0,0 +1,574 @@
+//! Support for a restricted subset of vimrc syntax, so that mappings can be
+//! shared with (neo)vim.
+//!
+//! Only key mappings (`map`, `nnoremap`, `vnoremap`, `inoremap`, ...) and
+//! `let mapleader` are understood. Each mapping becomes a key binding whose
+//! context matches the vim mode it was defined for, and whose action replays
+//! the right-hand side as keystrokes. Everything else is reported back as
+//! unsupported so that it can be surfaced to the user.
+
+use anyhow::{anyhow, Result};
+use collections::BTreeMap;
+use gpui::{impl_actions, AsyncWindowContext, Keystroke, ViewContext};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use settings::{KeymapAction, KeymapBlock, KeymapFile};
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::{state::Mode, Vim};
+
+/// The maximum number of keystrokes a mapping may expand to, which guards
+/// against recursive mappings.
+const MAX_MAPPED_KEYSTROKES: usize = 100;
+
+/// Replays the right-hand side of a vimrc mapping.
+///
+/// When `noremap` is set, the keystrokes are dispatched without triggering
+/// any other vimrc mappings, mirroring vim's `noremap` family of commands.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Mapping {
+    keystrokes: String,
+    #[serde(default)]
+    noremap: bool,
+}
+
+impl_actions!(vim, [Mapping]);
+
+pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(mapping);
+}
+
+fn mapping(_: &mut Workspace, action: &Mapping, cx: &mut ViewContext<Workspace>) {
+    let mut keystrokes: Vec<(Keystroke, bool)> = action
+        .keystrokes
+        .split(' ')
+        .flat_map(|k| Keystroke::parse(k).log_err())
+        .map(|keystroke| (keystroke, action.noremap))
+        .collect();
+    keystrokes.reverse();
+
+    let start_dispatching = Vim::update(cx, |vim, _| {
+        let state = &mut vim.workspace_state;
+        // While the right-hand side of a `noremap` is being dispatched, let the
+        // keystrokes fall through to the bindings this mapping shadows.
+        if state.dispatching_noremap {
+            return None;
+        }
+        // Keystrokes from nested mappings go on top of the stack, so that they
+        // are dispatched before the rest of the outer mapping.
+        state.mapped_keystrokes.append(&mut keystrokes);
+        Some(!std::mem::replace(&mut state.dispatching_mapping, true))
+    });
+
+    match start_dispatching {
+        None => cx.propagate(),
+        Some(false) => {}
+        Some(true) => cx
+            .window_context()
+            .spawn(|mut cx| async move {
+                let result = dispatch_mapped_keystrokes(&mut cx);
+                cx.update(|cx| {
+                    Vim::update(cx, |vim, _| {
+                        let state = &mut vim.workspace_state;
+                        state.mapped_keystrokes.clear();
+                        state.dispatching_mapping = false;
+                        state.dispatching_noremap = false;
+                    })
+                })
+                .ok();
+                result
+            })
+            .detach_and_log_err(cx),
+    }
+}
+
+fn dispatch_mapped_keystrokes(cx: &mut AsyncWindowContext) -> Result<()> {
+    for _ in 0..MAX_MAPPED_KEYSTROKES {
+        let dispatched = cx.update(|cx| {
+            let Some((keystroke, noremap)) = Vim::update(cx, |vim, _| {
+                let state = &mut vim.workspace_state;
+                let next = state.mapped_keystrokes.pop();
+                state.dispatching_noremap = next.as_ref().map_or(false, |(_, noremap)| *noremap);
+                next
+            }) else {
+                return false;
+            };
+
+            let focused = cx.focused();
+            cx.dispatch_keystroke(keystroke);
+            if cx.focused() != focused {
+                // As in `Workspace::send_keystrokes`, focus changes need to be
+                // flushed before the next keystroke is dispatched.
+                cx.draw();
+            }
+            true
+        })?;
+        if !dispatched {
+            return Ok(());
+        }
+    }
+    Err(anyhow!(
+        "mapping expanded to more than {MAX_MAPPED_KEYSTROKES} keystrokes, is it recursive?"
+    ))
+}
+
+/// The modes a vimrc mapping can apply to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl MapMode {
+    /// The keymap context in which a mapping for this mode should be active.
+    /// These line up with the contexts produced by `EditorState::keymap_context_layer`.
+    fn keymap_context(&self) -> String {
+        match self {
+            MapMode::Normal => format!(
+                "Editor && vim_mode == {} && vim_operator == none && !VimWaiting",
+                Mode::Normal.keymap_context_name()
+            ),
+            MapMode::Visual => format!(
+                "Editor && vim_mode == {} && vim_operator == none && !VimWaiting",
+                Mode::Visual.keymap_context_name()
+            ),
+            MapMode::OperatorPending => "Editor && vim_operator != none && !VimWaiting".into(),
+            MapMode::Insert => format!(
+                "Editor && vim_mode == {}",
+                Mode::Insert.keymap_context_name()
+            ),
+        }
+    }
+}
+
+/// A single mapping read from a vimrc, with both sides translated into
+/// space-separated keystrokes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VimrcMapping {
+    pub mode: MapMode,
+    pub lhs: String,
+    /// `None` when the mapping is `<Nop>`, which unbinds the keys.
+    pub rhs: Option<String>,
+    pub noremap: bool,
+}
+
+/// A line of the vimrc that could not be translated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedLine {
+    /// The one-based line number.
+    pub row: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vimrc {
+    pub mappings: Vec<VimrcMapping>,
+    pub unsupported: Vec<UnsupportedLine>,
+}
+
+impl Vimrc {
+    pub fn parse(content: &str) -> Self {
+        let mut vimrc = Self::default();
+        let mut leaders = Leaders::default();
+
+        for (ix, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('"') {
+                continue;
+            }
+
+            let result = match line.split_once(char::is_whitespace) {
+                Some(("let", assignment)) => parse_let(assignment.trim(), &mut leaders),
+                Some((command, args)) => parse_map_command(command, args.trim(), &leaders)
+                    .map(|mappings| vimrc.mappings.extend(mappings)),
+                None => Err(format!("`{line}` is not a mapping")),
+            };
+
+            if let Err(reason) = result {
+                vimrc.unsupported.push(UnsupportedLine {
+                    row: ix + 1,
+                    text: line.to_string(),
+                    reason,
+                });
+            }
+        }
+
+        vimrc
+    }
+
+    /// Converts the mappings into keymap blocks, one per mode.
+    pub fn keymap(&self) -> KeymapFile {
+        let mut blocks: Vec<(MapMode, BTreeMap<String, KeymapAction>)> = Vec::new();
+        for mapping in &self.mappings {
+            let action = match &mapping.rhs {
+                Some(rhs) => json!([
+                    "vim::Mapping",
+                    { "keystrokes": rhs, "noremap": mapping.noremap }
+                ]),
+                None => Value::Null,
+            };
+            let bindings = match blocks.iter_mut().find(|(mode, _)| *mode == mapping.mode) {
+                Some((_, bindings)) => bindings,
+                None => {
+                    blocks.push((mapping.mode, BTreeMap::default()));
+                    &mut blocks.last_mut().unwrap().1
+                }
+            };
+            bindings.insert(mapping.lhs.clone(), action.into());
+        }
+
+        blocks
+            .into_iter()
+            .map(|(mode, bindings)| KeymapBlock::new(Some(mode.keymap_context()), bindings))
+            .collect()
+    }
+}
+
+/// The keys that `<leader>` and `<localleader>` expand to.
+struct Leaders {
+    leader: Vec<String>,
+    local_leader: Vec<String>,
+}
+
+impl Default for Leaders {
+    fn default() -> Self {
+        Self {
+            leader: vec!["\\".into()],
+            local_leader: vec!["\\".into()],
+        }
+    }
+}
+
+fn parse_let(assignment: &str, leaders: &mut Leaders) -> Result<(), String> {
+    let (name, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| "expected `let {name} = {value}`".to_string())?;
+    let leader = match name.trim() {
+        "mapleader" | "g:mapleader" => &mut leaders.leader,
+        "maplocalleader" | "g:maplocalleader" => &mut leaders.local_leader,
+        name => {
+            return Err(format!(
+                "cannot set `{name}`, only the leader keys are supported"
+            ))
+        }
+    };
+
+    let value = value.trim();
+    let value = if let Some(value) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        // Double quoted strings use `\<Key>` for special keys.
+        value.replace("\\<", "<").replace("\\\\", "\\")
+    } else if let Some(value) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        value.to_string()
+    } else {
+        return Err(format!("expected a quoted string, got `{value}`"));
+    };
+
+    let keys = parse_keys(&value, &Leaders::default())?;
+    if keys.is_empty() {
+        return Err("the leader cannot be empty".into());
+    }
+    *leader = keys;
+    Ok(())
+}
+
+fn parse_map_command(
+    command: &str,
+    args: &str,
+    leaders: &Leaders,
+) -> Result<Vec<VimrcMapping>, String> {
+    use MapMode::*;
+
+    let (modes, noremap): (&[MapMode], bool) = match command {
+        "map" => (&[Normal, Visual, OperatorPending], false),
+        "no" | "nor" | "nore" | "norem" | "norema" | "noremap" => {
+            (&[Normal, Visual, OperatorPending], true)
+        }
+        "nm" | "nma" | "nmap" => (&[Normal], false),
+        "nn" | "nno" | "nnor" | "nnore" | "nnorem" | "nnorema" | "nnoremap" => (&[Normal], true),
+        "vm" | "vma" | "vmap" | "xm" | "xma" | "xmap" => (&[Visual], false),
+        "vn" | "vno" | "vnor" | "vnore" | "vnorem" | "vnorema" | "vnoremap" | "xn" | "xno"
+        | "xnor" | "xnore" | "xnorem" | "xnorema" | "xnoremap" => (&[Visual], true),
+        "om" | "oma" | "omap" => (&[OperatorPending], false),
+        "ono" | "onor" | "onore" | "onorem" | "onorema" | "onoremap" => (&[OperatorPending], true),
+        "im" | "ima" | "imap" => (&[Insert], false),
+        "ino" | "inor" | "inore" | "inorem" | "inorema" | "inoremap" => (&[Insert], true),
+        _ => return Err(format!("`{command}` is not supported")),
+    };
+
+    let mut args = args;
+    loop {
+        let Some(rest) = args.strip_prefix('<') else {
+            break;
+        };
+        let Some((argument, rest)) = rest.split_once('>') else {
+            break;
+        };
+        match argument.to_ascii_lowercase().as_str() {
+            "silent" | "nowait" | "unique" | "special" => args = rest.trim_start(),
+            "buffer" | "expr" | "script" => {
+                return Err(format!("the `<{argument}>` argument is not supported"))
+            }
+            _ => break,
+        }
+    }
+
+    let (lhs, rhs) = args
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| "expected both sides of a mapping".to_string())?;
+    let rhs = rhs.trim();
+    if rhs.is_empty() {
+        return Err("expected both sides of a mapping".into());
+    }
+
+    let lhs = parse_keys(lhs, leaders)?.join(" ");
+    let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+        None
+    } else {
+        Some(parse_keys(rhs, leaders)?.join(" "))
+    };
+
+    Ok(modes
+        .iter()
+        .map(|mode| VimrcMapping {
+            mode: *mode,
+            lhs: lhs.clone(),
+            rhs: rhs.clone(),
+            noremap,
+        })
+        .collect())
+}
+
+/// Translates vim key notation (e.g. `<C-w>j`) into zed keystrokes (e.g. `["ctrl-w", "j"]`).
+fn parse_keys(notation: &str, leaders: &Leaders) -> Result<Vec<String>, String> {
+    let mut keys = Vec::new();
+    let mut chars = notation.char_indices().peekable();
+    while let Some((ix, ch)) = chars.next() {
+        match ch {
+            '<' => {
+                let Some(len) = notation[ix + 1..].find('>') else {
+                    keys.push("<".to_string());
+                    continue;
+                };
+                let name = &notation[ix + 1..ix + 1 + len];
+                match parse_special_key(name, leaders)? {
+                    Some(special) => {
+                        keys.extend(special);
+                        while chars.next_if(|(next, _)| *next <= ix + 1 + len).is_some() {}
+                    }
+                    None => keys.push("<".to_string()),
+                }
+            }
+            '\\' if chars.peek().map(|(_, ch)| *ch) == Some('|') => {
+                chars.next();
+                keys.push("|".to_string());
+            }
+            '|' => return Err("multiple commands separated by `|` are not supported".into()),
+            _ => keys.push(keystroke_for_char(ch, &[])),
+        }
+    }
+    Ok(keys)
+}
+
+/// Parses the contents of a `<...>` key. Returns `None` if `name` is not a key
+/// name, in which case vim treats the `<` literally.
+fn parse_special_key(name: &str, leaders: &Leaders) -> Result<Option<Vec<String>>, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch.is_ascii_punctuation())
+    {
+        return Ok(None);
+    }
+
+    match name.to_ascii_lowercase().as_str() {
+        "leader" => return Ok(Some(leaders.leader.clone())),
+        "localleader" => return Ok(Some(leaders.local_leader.clone())),
+        "lt" => return Ok(Some(vec!["<".into()])),
+        "bar" => return Ok(Some(vec!["|".into()])),
+        "bslash" => return Ok(Some(vec!["\\".into()])),
+        _ => {}
+    }
+
+    let mut modifiers = Vec::new();
+    let mut rest = name;
+    while let Some((modifier, remainder)) = rest.split_once('-') {
+        if remainder.is_empty() {
+            // `<C-->` maps the `-` key.
+            break;
+        }
+        let modifier = match modifier.to_ascii_lowercase().as_str() {
+            "c" => "ctrl",
+            "a" | "m" => "alt",
+            "s" => "shift",
+            "d" => "cmd",
+            _ => return Err(format!("unsupported key `<{name}>`")),
+        };
+        modifiers.push(modifier);
+        rest = remainder;
+    }
+
+    let mut chars = rest.chars();
+    let key = match (chars.next(), chars.next()) {
+        // Without modifiers, vim treats `<x>` literally.
+        (Some(_), None) if modifiers.is_empty() => return Ok(None),
+        (Some(ch), None) => {
+            if modifiers.contains(&"ctrl") {
+                // vim does not distinguish between `<C-w>` and `<C-W>`.
+                keystroke_for_char(ch.to_ascii_lowercase(), &modifiers)
+            } else {
+                keystroke_for_char(ch, &modifiers)
+            }
+        }
+        _ => {
+            let key = match rest.to_ascii_lowercase().as_str() {
+                "cr" | "enter" | "return" => "enter".to_string(),
+                "esc" => "escape".to_string(),
+                "space" => "space".to_string(),
+                "tab" => "tab".to_string(),
+                "bs" => "backspace".to_string(),
+                "del" => "delete".to_string(),
+                key @ ("up" | "down" | "left" | "right" | "home" | "end" | "pageup"
+                | "pagedown") => key.to_string(),
+                key if key.starts_with('f')
+                    && key[1..]
+                        .parse::<u8>()
+                        .map_or(false, |n| (1..=12).contains(&n)) =>
+                {
+                    key.to_string()
+                }
+                _ if modifiers.is_empty() && rest.starts_with(|ch: char| !ch.is_alphabetic()) => {
+                    return Ok(None)
+                }
+                _ => return Err(format!("unsupported key `<{name}>`")),
+            };
+            with_modifiers(&modifiers, key)
+        }
+    };
+
+    Ok(Some(vec![key]))
+}
+
+fn keystroke_for_char(ch: char, modifiers: &[&str]) -> String {
+    let key = match ch {
+        ' ' => "space".to_string(),
+        ch if ch.is_ascii_uppercase() => {
+            let mut modifiers = modifiers.to_vec();
+            if !modifiers.contains(&"shift") {
+                modifiers.push("shift");
+            }
+            return with_modifiers(&modifiers, ch.to_ascii_lowercase().to_string());
+        }
+        ch => ch.to_string(),
+    };
+    with_modifiers(modifiers, key)
+}
+
+fn with_modifiers(modifiers: &[&str], key: String) -> String {
+    let mut keystroke = String::new();
+    for modifier in ["ctrl", "alt", "shift", "cmd"] {
+        if modifiers.contains(&modifier) {
+            keystroke.push_str(modifier);
+            keystroke.push('-');
+        }
+    }
+    keystroke.push_str(&key);
+    keystroke
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::{MapMode, UnsupportedLine, Vimrc, VimrcMapping};
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn mapping(mode: MapMode, lhs: &str, rhs: Option<&str>, noremap: bool) -> VimrcMapping {
+        VimrcMapping {
+            mode,
+            lhs: lhs.into(),
+            rhs: rhs.map(Into::into),
+            noremap,
+        }
+    }
+
+    #[test]
+    fn test_parse_vimrc() {
+        let vimrc = Vimrc::parse(indoc! {r#"
+            " comments are ignored
+            nnoremap <C-h> <C-w>h
+            nmap Y y$
+            let mapleader = "\<Space>"
+            map <leader>w :w<CR>
+            inoremap <silent> jk <Esc>
+            vnoremap < <gv
+            xnoremap <lt> <Nop>
+            set number
+            nnoremap <buffer> x y
+            nnoremap <Plug>(foo) x
+        "#});
+
+        assert_eq!(
+            vimrc.mappings,
+            vec![
+                mapping(MapMode::Normal, "ctrl-h", Some("ctrl-w h"), true),
+                mapping(MapMode::Normal, "shift-y", Some("y $"), false),
+                mapping(MapMode::Normal, "space w", Some(": w enter"), false),
+                mapping(MapMode::Visual, "space w", Some(": w enter"), false),
+                mapping(
+                    MapMode::OperatorPending,
+                    "space w",
+                    Some(": w enter"),
+                    false
+                ),
+                mapping(MapMode::Insert, "j k", Some("escape"), true),
+                mapping(MapMode::Visual, "<", Some("< g v"), true),
+                mapping(MapMode::Visual, "<", None, true),
+            ]
+        );
+        assert_eq!(
+            vimrc
+                .unsupported
+                .iter()
+                .map(|UnsupportedLine { row, .. }| *row)
+                .collect::<Vec<_>>(),
+            vec![9, 10, 11]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_mappings(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let vimrc = Vimrc::parse(indoc! {"
+            nnoremap j k
+            nnoremap k j
+            nmap Q jj
+            inoremap jk <Esc>
+        "});
+        assert!(vimrc.unsupported.is_empty());
+        cx.update(|cx| vimrc.keymap().add_to_cx(cx).unwrap());
+
+        cx.set_state("one\ntwo\nˇthree", Mode::Normal);
+        cx.simulate_keystrokes("j");
+        cx.assert_state("one\nˇtwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("k");
+        cx.assert_state("one\ntwo\nˇthree", Mode::Normal);
+
+        // recursive mappings expand into other mappings
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇone\ntwo\nthree", Mode::Normal);
+
+        cx.simulate_keystrokes("i j k");
+        cx.assert_state("ˇone\ntwo\nthree", Mode::Normal);
+    }
+}
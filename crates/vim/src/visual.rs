@@ -416,6 +416,67 @@ pub fn yank(vim: &mut Vim, cx: &mut WindowContext) {
     vim.switch_mode(Mode::Normal, true, cx);
 }
 
+/// Starts insert mode on every line of the block, before it (`I`) or after it (`A`), so that
+/// the inserted text is repeated on each line.
+pub(crate) fn visual_block_insert(vim: &mut Vim, append: bool, cx: &mut WindowContext) {
+    vim.start_recording(cx);
+    let block_insert_start = vim.update_active_editor(cx, |_, editor, cx| {
+        let (map, selections) = editor.selections.all_adjusted_display(cx);
+        let (Some(first), Some(last)) = (selections.first(), selections.last()) else {
+            return None;
+        };
+        let block_start = map.display_point_to_anchor(first.start, Bias::Left);
+
+        let mut cursors = Vec::new();
+        let mut edits = Vec::new();
+        if append {
+            // After `$` each line is appended to at its own end, otherwise lines that are
+            // too short to reach the block are padded with spaces, like in vim.
+            let ragged = selections
+                .iter()
+                .all(|selection| selection.end.column() == map.line_len(selection.end.row()));
+            let column = selections
+                .iter()
+                .map(|selection| selection.end.column())
+                .max()
+                .unwrap_or_default();
+
+            let mut row = first.start.row();
+            while row <= last.end.row() {
+                if let Some(selection) = selections
+                    .iter()
+                    .find(|selection| selection.start.row() == row)
+                {
+                    cursors.push(map.display_point_to_anchor(selection.end, Bias::Left));
+                } else {
+                    let line_len = map.line_len(row);
+                    let end = DisplayPoint::new(row, line_len);
+                    cursors.push(map.display_point_to_anchor(end, Bias::Right));
+                    if !ragged && line_len < column {
+                        let end = end.to_point(&map);
+                        edits.push((end..end, " ".repeat((column - line_len) as usize)));
+                    }
+                }
+                row.0 += 1;
+            }
+        } else {
+            cursors.extend(
+                selections
+                    .iter()
+                    .map(|selection| map.display_point_to_anchor(selection.start, Bias::Left)),
+            );
+        }
+
+        editor.edit(edits, cx);
+        editor.change_selections(None, cx, |s| {
+            s.select_anchor_ranges(cursors.into_iter().map(|cursor| cursor..cursor))
+        });
+        Some(block_start)
+    });
+    vim.switch_mode(Mode::Insert, true, cx);
+    vim.update_state(|state| state.block_insert_start = block_insert_start.flatten());
+}
+
 pub(crate) fn visual_replace(text: Arc<str>, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         vim.stop_recording();
@@ -1081,6 +1142,73 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_visual_block_insert_append(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            The ˇquick brown
+            fox jumps over
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v l j shift-i x y escape")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The ˇxyquick brown
+            fox xyjumps over
+            the lazy dog"});
+
+        cx.set_shared_state(indoc! {"
+            The ˇquick brown
+            fox jumps over
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v l j shift-a x escape")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The ˇquxick brown
+            fox juxmps over
+            the lazy dog"});
+
+        cx.set_shared_state(indoc! {"
+            The ˇquick brown
+            fox jumps over
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v l j c x y escape")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The xˇyick brown
+            fox xymps over
+            the lazy dog"});
+
+        // lines that are too short are padded
+        cx.set_shared_state(indoc! {"
+            The ˇquick
+            fo
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v l 2 j shift-a ; escape")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            The ˇqu;ick
+            fo    ;
+            the la;zy dog"});
+
+        // unless the block extends to the end of each line
+        cx.set_shared_state(indoc! {"
+            ˇThe quick
+            fox
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes("ctrl-v 2 j $ shift-a ; escape")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ˇThe quick;
+            fox;
+            the lazy dog;"});
+    }
+
     #[gpui::test]
     async fn test_visual_object(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -4,7 +4,7 @@ use project::TaskSourceKind;
 use task::{ResolvedTask, TaskContext, TaskTemplate};
 use ui::{ViewContext, WindowContext};
 
-use crate::Workspace;
+use crate::{notifications::NotificationId, Toast, Workspace};
 
 pub fn task_cwd(workspace: &Workspace, cx: &mut WindowContext) -> anyhow::Result<Option<PathBuf>> {
     let project = workspace.project().read(cx);
@@ -42,7 +42,7 @@ pub fn task_cwd(workspace: &Workspace, cx: &mut WindowContext) -> anyhow::Result
 }
 
 pub fn schedule_task(
-    workspace: &Workspace,
+    workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
     task_to_resolve: &TaskTemplate,
     task_cx: &TaskContext,
@@ -63,21 +63,52 @@ pub fn schedule_task(
 }
 
 pub fn schedule_resolved_task(
-    workspace: &Workspace,
+    workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
     mut resolved_task: ResolvedTask,
     omit_history: bool,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
     if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
+        resolved_task.resolved = Some(spawn_in_terminal.clone());
+        let task_plan = if resolved_task.original_task().depends_on.is_empty() {
+            None
+        } else {
+            let task_plan = workspace
+                .project()
+                .read(cx)
+                .task_inventory()
+                .read(cx)
+                .task_plan(&task_source_kind, &resolved_task);
+            match task_plan {
+                Ok(task_plan) => Some(task_plan),
+                Err(e) => {
+                    struct TaskPlanError;
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<TaskPlanError>(),
+                            format!("Cannot run task `{}`: {e}", resolved_task.resolved_label),
+                        ),
+                        cx,
+                    );
+                    return;
+                }
+            }
+        };
+
         if !omit_history {
-            resolved_task.resolved = Some(spawn_in_terminal.clone());
             workspace.project().update(cx, |project, cx| {
                 project.task_inventory().update(cx, |inventory, _| {
                     inventory.task_scheduled(task_source_kind, resolved_task);
                 })
             });
         }
-        cx.emit(crate::Event::SpawnTask(spawn_in_terminal));
+        match task_plan {
+            Some(task_plan) => cx.emit(crate::Event::SpawnTaskPlan(task_plan)),
+            None if spawn_in_terminal.debug.is_some() => {
+                cx.emit(crate::Event::StartDebugSession(spawn_in_terminal))
+            }
+            None => cx.emit(crate::Event::SpawnTask(spawn_in_terminal)),
+        }
     }
 }
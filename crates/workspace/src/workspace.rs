@@ -75,7 +75,7 @@ use std::{
     sync::{atomic::AtomicUsize, Arc, Weak},
     time::Duration,
 };
-use task::SpawnInTerminal;
+use task::{SpawnInTerminal, TaskPlan};
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
@@ -539,6 +539,8 @@ pub enum Event {
     ContactRequestedJoin(u64),
     WorkspaceCreated(WeakView<Workspace>),
     SpawnTask(SpawnInTerminal),
+    SpawnTaskPlan(TaskPlan),
+    StartDebugSession(SpawnInTerminal),
     OpenBundledFile {
         text: Cow<'static, str>,
         title: &'static str,
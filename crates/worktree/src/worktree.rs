@@ -42,6 +42,7 @@ use postage::{
     prelude::{Sink as _, Stream as _},
     watch,
 };
+use prost::Message as _;
 use serde::Serialize;
 use settings::{Settings, SettingsLocation, SettingsStore};
 use smol::channel::{self, Sender};
@@ -65,7 +66,7 @@ use std::{
 use sum_tree::{Bias, Edit, SeekTarget, SumTree, TreeMap, TreeSet};
 use text::BufferId;
 use util::{
-    paths::{PathMatcher, HOME},
+    paths::{self, PathMatcher, HOME},
     ResultExt,
 };
 
@@ -782,6 +783,33 @@ fn path_matchers(values: Option<&[String]>, context: &'static str) -> Vec<PathMa
         .collect()
 }
 
+/// The file that the undo history of the file at `abs_path` is saved to, named after its
+/// full path like vim's `undofile`.
+fn undo_history_path(abs_path: &Path) -> PathBuf {
+    paths::UNDO_DIR.join(abs_path.to_string_lossy().replace(['/', '\\', ':'], "%"))
+}
+
+/// Rebuilds a freshly loaded buffer from its saved undo history, which only applies if
+/// replaying the history's operations yields the buffer's current text.
+fn restore_undo_history(
+    text_buffer: &text::Buffer,
+    undo_history: proto::UndoHistory,
+) -> Result<text::Buffer> {
+    let (base_text, operations, history) = language::proto::deserialize_undo_history(undo_history)?;
+    let mut restored = text::Buffer::new_normalized(
+        text_buffer.replica_id(),
+        text_buffer.remote_id(),
+        text_buffer.line_ending(),
+        Rope::from(base_text),
+    );
+    restored.apply_ops(operations)?;
+    if restored.deferred_ops_len() > 0 || !restored.chars().eq(text_buffer.chars()) {
+        return Err(anyhow!("the file changed since its undo history was saved"));
+    }
+    restored.restore_history(history)?;
+    Ok(restored)
+}
+
 impl LocalWorktree {
     pub fn contains_abs_path(&self, path: &Path) -> bool {
         path.starts_with(&self.abs_path)
@@ -799,9 +827,23 @@ impl LocalWorktree {
             let (file, contents, diff_base) = this
                 .update(&mut cx, |t, cx| t.as_local().unwrap().load(&path, cx))?
                 .await?;
+            let undo_history = this
+                .update(&mut cx, |t, cx| {
+                    t.as_local().unwrap().load_undo_history(&file, cx)
+                })?
+                .await;
             let text_buffer = cx
                 .background_executor()
-                .spawn(async move { text::Buffer::new(0, buffer_id, contents) })
+                .spawn(async move {
+                    let text_buffer = text::Buffer::new(0, buffer_id, contents);
+                    undo_history
+                        .and_then(|undo_history| {
+                            restore_undo_history(&text_buffer, undo_history)
+                                .context("restoring undo history")
+                                .log_err()
+                        })
+                        .unwrap_or(text_buffer)
+                })
                 .await;
             cx.insert_model(reservation, |_| {
                 Buffer::build(
@@ -1194,6 +1236,39 @@ impl LocalWorktree {
         })
     }
 
+    /// Loads the undo history saved for the given file, if persisting it is enabled.
+    fn load_undo_history(
+        &self,
+        file: &File,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Option<proto::UndoHistory>> {
+        if !WorktreeSettings::get_global(cx)
+            .persistent_undo
+            .unwrap_or_default()
+            || file.is_private
+        {
+            return Task::ready(None);
+        }
+        let Some(abs_path) = self.absolutize(&file.path).log_err() else {
+            return Task::ready(None);
+        };
+
+        let fs = self.fs.clone();
+        cx.background_executor().spawn(async move {
+            let undo_history_path = undo_history_path(&abs_path);
+            if !fs.is_file(&undo_history_path).await {
+                return None;
+            }
+            let mut bytes = Vec::new();
+            fs.open_sync(&undo_history_path)
+                .await
+                .log_err()?
+                .read_to_end(&mut bytes)
+                .log_err()?;
+            proto::UndoHistory::decode(bytes.as_slice()).log_err()
+        })
+    }
+
     pub fn save_buffer(
         &self,
         buffer_handle: Model<Buffer>,
@@ -1211,12 +1286,23 @@ impl LocalWorktree {
             has_changed_file = true;
         }
 
+        let is_private = self.snapshot.is_path_private(&path);
+        let undo_history = (WorktreeSettings::get_global(cx)
+            .persistent_undo
+            .unwrap_or_default()
+            && !is_private)
+            .then(|| {
+                (
+                    buffer.base_text().clone(),
+                    buffer.operations().clone(),
+                    buffer.saved_history(),
+                )
+            });
         let text = buffer.as_rope().clone();
         let version = buffer.version();
         let save = self.write_file(path.as_ref(), text, buffer.line_ending(), cx);
         let fs = Arc::clone(&self.fs);
         let abs_path = self.absolutize(&path);
-        let is_private = self.snapshot.is_path_private(&path);
 
         cx.spawn(move |this, mut cx| async move {
             let entry = save.await?;
@@ -1281,6 +1367,24 @@ impl LocalWorktree {
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
+            if let Some((base_text, operations, history)) = undo_history {
+                let undo_history = language::proto::serialize_undo_history(
+                    &base_text,
+                    operations.values(),
+                    &history,
+                );
+                let undo_history_path = undo_history_path(&abs_path);
+                async {
+                    fs.create_dir(&paths::UNDO_DIR).await?;
+                    let mut bytes = futures::io::Cursor::new(undo_history.encode_to_vec());
+                    fs.create_file_with(&undo_history_path, Pin::new(&mut bytes))
+                        .await
+                }
+                .await
+                .with_context(|| format!("saving undo history to {undo_history_path:?}"))
+                .log_err();
+            }
+
             Ok(())
         })
     }
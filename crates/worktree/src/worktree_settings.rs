@@ -23,6 +23,12 @@ pub struct WorktreeSettings {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Whether to save the undo history of files when they are saved, and restore it
+    /// when they are opened again, as long as they were not changed in the meantime.
+    /// Private files never have their undo history saved.
+    /// Default: false
+    pub persistent_undo: Option<bool>,
 }
 
 impl Settings for WorktreeSettings {
@@ -2580,6 +2580,137 @@ async fn test_propagate_git_statuses(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_persistent_undo(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |project_settings| {
+                project_settings.persistent_undo = Some(true);
+            });
+        });
+    });
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/project",
+        json!({
+            "a.txt": "123456",
+            ".env": "SECRET=1",
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        build_client(cx),
+        "/project".as_ref(),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let buffer = tree
+        .update(cx, |tree, cx| {
+            tree.as_local_mut()
+                .unwrap()
+                .load_buffer("a.txt".as_ref(), cx)
+        })
+        .await
+        .unwrap();
+    let undo_tree = buffer.update(cx, |buffer, cx| {
+        for (ix, text) in ["a", "b"].into_iter().enumerate() {
+            buffer.edit([(ix..ix + 1, text)], None, cx);
+            buffer.finalize_last_transaction();
+        }
+        buffer.undo(cx);
+        buffer.edit([(2..3, "c")], None, cx);
+        assert_eq!(buffer.text(), "a2c456");
+        buffer.undo_tree()
+    });
+    tree.update(cx, |tree, cx| {
+        tree.as_local()
+            .unwrap()
+            .save_buffer(buffer.clone(), Path::new("a.txt").into(), false, cx)
+    })
+    .await
+    .unwrap();
+
+    // Reopening the file brings back every branch of the undo tree.
+    let reopened = tree
+        .update(cx, |tree, cx| {
+            tree.as_local_mut()
+                .unwrap()
+                .load_buffer("a.txt".as_ref(), cx)
+        })
+        .await
+        .unwrap();
+    reopened.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "a2c456");
+        assert_eq!(
+            buffer
+                .undo_tree()
+                .into_iter()
+                .map(|node| (node.transaction_id, node.parent))
+                .collect::<Vec<_>>(),
+            undo_tree
+                .iter()
+                .map(|node| (node.transaction_id, node.parent))
+                .collect::<Vec<_>>(),
+        );
+        assert!(!buffer.is_dirty());
+        buffer.travel_to_undo_state(Some(undo_tree[1].transaction_id), cx);
+        assert_eq!(buffer.text(), "ab3456");
+        buffer.undo(cx);
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "123456");
+    });
+
+    // The history is dropped if the file changed since it was saved.
+    fs.insert_file("/project/a.txt", "changed".into()).await;
+    let reopened = tree
+        .update(cx, |tree, cx| {
+            tree.as_local_mut()
+                .unwrap()
+                .load_buffer("a.txt".as_ref(), cx)
+        })
+        .await
+        .unwrap();
+    reopened.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "changed");
+        assert!(buffer.undo_tree().is_empty());
+    });
+
+    // The history of private files is never saved.
+    let buffer = tree
+        .update(cx, |tree, cx| {
+            tree.as_local_mut()
+                .unwrap()
+                .load_buffer(".env".as_ref(), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "#")], None, cx));
+    tree.update(cx, |tree, cx| {
+        tree.as_local()
+            .unwrap()
+            .save_buffer(buffer.clone(), Path::new(".env").into(), false, cx)
+    })
+    .await
+    .unwrap();
+    assert!(
+        !fs.is_file(&crate::undo_history_path(Path::new("/project/.env")))
+            .await
+    );
+    assert!(
+        fs.is_file(&crate::undo_history_path(Path::new("/project/a.txt")))
+            .await
+    );
+}
+
 fn build_client(cx: &mut TestAppContext) -> Arc<Client> {
     let clock = Arc::new(FakeSystemClock::default());
     let http_client = FakeHttpClient::with_404_response();
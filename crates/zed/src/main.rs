@@ -7,6 +7,7 @@ mod reliability;
 mod zed;
 
 use anyhow::{anyhow, Context as _, Result};
+use assistant_slash_command::SlashCommandRegistry;
 use clap::{command, Parser};
 use cli::FORCE_CLI_MODE_ENV_VAR_NAME;
 use client::{parse_zed_link, Client, DevServerToken, UserStore};
@@ -149,6 +150,8 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     project_symbols::init(cx);
     project_panel::init(Assets, cx);
     tasks_ui::init(cx);
+    test_explorer::init(cx);
+    debugger_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
     vim::init(cx);
@@ -178,6 +181,7 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
 
     inline_completion_registry::init(app_state.client.telemetry().clone(), cx);
 
+    assistant_slash_command::init(cx);
     assistant::init(app_state.client.clone(), cx);
 
     cx.observe_global::<SettingsStore>({
@@ -216,6 +220,9 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
         app_state.node_runtime.clone(),
         app_state.languages.clone(),
         ThemeRegistry::global(cx),
+        SlashCommandRegistry::global(cx),
+        project::TaskProviderRegistry::default_global(cx),
+        project::ExternalToolRegistry::default_global(cx),
         cx,
     );
 
@@ -304,6 +311,8 @@ fn main() {
         fs.clone(),
         paths::KEYMAP.clone(),
     );
+    let user_vimrc_file_rx =
+        watch_config_file(&app.background_executor(), fs.clone(), paths::VIMRC.clone());
 
     let login_shell_env_loaded = if stdout_is_a_pty() {
         Task::ready(())
@@ -342,7 +351,7 @@ fn main() {
 
         settings::init(cx);
         handle_settings_file_changes(user_settings_file_rx, cx);
-        handle_keymap_file_changes(user_keymap_file_rx, cx);
+        handle_keymap_file_changes(user_keymap_file_rx, user_vimrc_file_rx, cx);
 
         client::init_settings(cx);
         let client = Client::production(cx);
@@ -962,7 +971,7 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
         while (events.next().await).is_some() {
             cx.update(|cx| {
                 FileIcons::update_global(cx, |file_types, _cx| {
-                    *file_types = file_icons::FileIcons::new(Assets);
+                    file_types.reload_default_theme(Assets);
                 });
             })
             .ok();
@@ -9,6 +9,7 @@ use breadcrumbs::Breadcrumbs;
 use client::ZED_URL_SCHEME;
 use collections::VecDeque;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
+use extension::ExtensionStore;
 use gpui::{
     actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, PromptLevel,
     TitlebarOptions, View, ViewContext, VisualContext, WindowKind, WindowOptions,
@@ -17,6 +18,7 @@ pub use open_listener::*;
 
 use anyhow::Context as _;
 use assets::Assets;
+use debugger_ui::DebuggerPanel;
 use futures::{channel::mpsc, select_biased, StreamExt};
 use project::TaskSourceKind;
 use project_panel::ProjectPanel;
@@ -33,14 +35,16 @@ use task::static_source::{StaticSource, TrackedFile};
 use theme::ActiveTheme;
 use workspace::notifications::NotificationId;
 
+use extensions_ui::ExtensionPanel;
 use terminal_view::terminal_panel::{self, TerminalPanel};
+use test_explorer::TestExplorerPanel;
 use util::{
     asset_str,
     paths::{self, LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH},
     ResultExt,
 };
 use uuid::Uuid;
-use vim::VimModeSetting;
+use vim::{modal_editing_enabled, HelixModeSetting, VimModeSetting, Vimrc};
 use welcome::BaseKeymap;
 use workspace::{
     create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
@@ -186,6 +190,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 assistant::AssistantPanel::load(workspace_handle.clone(), cx.clone());
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
+            let test_explorer_panel = TestExplorerPanel::load(workspace_handle.clone(), cx.clone());
+            let debugger_panel = DebuggerPanel::load(workspace_handle.clone(), cx.clone());
+            let extension_panel = ExtensionPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
             let chat_panel =
@@ -198,6 +205,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             let (
                 project_panel,
                 terminal_panel,
+                test_explorer_panel,
+                debugger_panel,
+                extension_panel,
                 assistant_panel,
                 channels_panel,
                 chat_panel,
@@ -205,6 +215,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
+                test_explorer_panel,
+                debugger_panel,
+                extension_panel,
                 assistant_panel,
                 channels_panel,
                 chat_panel,
@@ -214,6 +227,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             workspace_handle.update(&mut cx, |workspace, cx| {
                 workspace.add_panel(assistant_panel, cx);
                 workspace.add_panel(project_panel, cx);
+                workspace.add_panel(test_explorer_panel, cx);
+                workspace.add_panel(debugger_panel, cx);
+                workspace.add_panel(extension_panel, cx);
                 {
                     let project = workspace.project().read(cx);
                     if project.is_local()
@@ -622,17 +638,30 @@ fn open_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
 
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
+    mut user_vimrc_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
 ) {
     BaseKeymap::register(cx);
     VimModeSetting::register(cx);
+    HelixModeSetting::register(cx);
 
     let (base_keymap_tx, mut base_keymap_rx) = mpsc::unbounded();
+
+    if let Some(extension_store) = ExtensionStore::try_global(cx) {
+        let base_keymap_tx = base_keymap_tx.clone();
+        cx.subscribe(&extension_store, move |_, event, _| {
+            if let extension::Event::KeymapsUpdated = event {
+                base_keymap_tx.unbounded_send(()).ok();
+            }
+        })
+        .detach();
+    }
+
     let mut old_base_keymap = *BaseKeymap::get_global(cx);
-    let mut old_vim_enabled = VimModeSetting::get_global(cx).0;
+    let mut old_vim_enabled = modal_editing_enabled(cx);
     cx.observe_global::<SettingsStore>(move |cx| {
         let new_base_keymap = *BaseKeymap::get_global(cx);
-        let new_vim_enabled = VimModeSetting::get_global(cx).0;
+        let new_vim_enabled = modal_editing_enabled(cx);
 
         if new_base_keymap != old_base_keymap || new_vim_enabled != old_vim_enabled {
             old_base_keymap = new_base_keymap;
@@ -646,6 +675,7 @@ pub fn handle_keymap_file_changes(
 
     cx.spawn(move |cx| async move {
         let mut user_keymap = KeymapFile::default();
+        let mut user_vimrc_keymap = KeymapFile::default();
         loop {
             select_biased! {
                 _ = base_keymap_rx.next() => {}
@@ -658,16 +688,45 @@ pub fn handle_keymap_file_changes(
                         }
                     }
                 }
+                user_vimrc_content = user_vimrc_file_rx.next() => {
+                    if let Some(user_vimrc_content) = user_vimrc_content {
+                        let vimrc = Vimrc::parse(&user_vimrc_content);
+                        for line in &vimrc.unsupported {
+                            log::warn!(
+                                "ignoring line {} of {:?} ({}): {}",
+                                line.row,
+                                paths::VIMRC.as_path(),
+                                line.text,
+                                line.reason
+                            );
+                        }
+                        user_vimrc_keymap = vimrc.keymap();
+                    }
+                }
             }
-            cx.update(|cx| reload_keymaps(cx, &user_keymap)).ok();
+            cx.update(|cx| reload_keymaps(cx, &user_keymap, &user_vimrc_keymap))
+                .ok();
         }
     })
     .detach();
 }
 
-fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
+fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile, vimrc_content: &KeymapFile) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
+    if let Some(extension_store) = ExtensionStore::try_global(cx) {
+        let extension_keymaps = extension_store
+            .read(cx)
+            .keymaps()
+            .cloned()
+            .collect::<Vec<_>>();
+        for keymap in extension_keymaps {
+            keymap.add_to_cx(cx).log_err();
+        }
+    }
+    if modal_editing_enabled(cx) {
+        vimrc_content.clone().add_to_cx(cx).log_err();
+    }
     keymap_content.clone().add_to_cx(cx).log_err();
     cx.set_menus(app_menus());
 }
@@ -679,7 +738,7 @@ pub fn load_default_keymap(cx: &mut AppContext) {
     }
 
     KeymapFile::load_asset(DEFAULT_KEYMAP_PATH, cx).unwrap();
-    if VimModeSetting::get_global(cx).0 {
+    if modal_editing_enabled(cx) {
         KeymapFile::load_asset("keymaps/vim.json", cx).unwrap();
     }
 
@@ -2824,8 +2883,10 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
             handle_settings_file_changes(settings_rx, cx);
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
         workspace
             .update(cx, |workspace, cx| {
@@ -2963,9 +3024,11 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
 
             handle_settings_file_changes(settings_rx, cx);
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
 
         cx.background_executor.run_until_parked();
@@ -3102,6 +3165,9 @@ mod tests {
             terminal_view::init(cx);
             assistant::init(app_state.client.clone(), cx);
             tasks_ui::init(cx);
+            test_explorer::init(cx);
+            debugger_ui::init(cx);
+            extensions_ui::init_panel_settings(cx);
             initialize_workspace(app_state.clone(), cx);
             app_state
         })